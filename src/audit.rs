@@ -0,0 +1,259 @@
+//! # Audit Log
+//!
+//! For operational auditing, the CLI can append a record of every operation
+//! that creates, changes, deletes or verifies stored manifests to an audit
+//! log (`--audit-log <path>`). This is separate from the provenance recorded
+//! in the manifests themselves: it records who did what, when, and whether
+//! it succeeded.
+//!
+//! The log is a JSON Lines file. Each entry carries `prev_hash`, the SHA-256
+//! of the previous line as written ([`GENESIS_HASH`] for the first entry), so
+//! the entries form a hash chain: editing, removing or reordering any entry
+//! breaks the chain at the next one, which [`AuditLog::verify`] (`audit
+//! verify`) detects. Removing entries from the end leaves a valid chain, so
+//! keep the last hash reported by `audit verify` somewhere else to detect
+//! truncation.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::audit::AuditLog;
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let log = AuditLog::new(dir.path().join("audit.jsonl"));
+//! log.append("create", Some("urn:c2pa:1234"), &Ok::<_, atlas_cli::Error>(())).unwrap();
+//! assert_eq!(log.verify().unwrap().entries, 1);
+//! ```
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+/// `prev_hash` of the first entry of a log
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Whether an audited operation succeeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditResult {
+    Success,
+    Failure,
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// RFC 3339 time the entry was written
+    pub timestamp: String,
+    /// Login name of the user running the CLI, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// e.g. `create`, `verify`, `sign` or `delete`
+    pub operation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_id: Option<String>,
+    pub result: AuditResult,
+    /// Error message of a failed operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// SHA-256 of the previous line, or [`GENESIS_HASH`]
+    pub prev_hash: String,
+}
+
+/// Result of checking an audit log's hash chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditSummary {
+    pub entries: usize,
+    /// SHA-256 of the last line, the `prev_hash` the next entry will record
+    pub last_hash: String,
+}
+
+/// An append-only, hash-chained audit log file
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends an entry recording the outcome of `operation`, creating the
+    /// log if needed
+    pub fn append<T>(
+        &self,
+        operation: &str,
+        manifest_id: Option<&str>,
+        outcome: &Result<T>,
+    ) -> Result<AuditEntry> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&self.path)
+            .map_err(|e| self.error(format!("cannot open: {e}")))?;
+        // Concurrent writers must not chain to the same previous entry
+        file.lock()
+            .map_err(|e| self.error(format!("cannot lock: {e}")))?;
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(|e| self.error(format!("cannot read: {e}")))?;
+        let prev_hash = content
+            .lines()
+            .rfind(|line| !line.is_empty())
+            .map_or_else(|| GENESIS_HASH.to_string(), line_hash);
+
+        let entry = AuditEntry {
+            timestamp: OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .map_err(|e| Error::Serialization(e.to_string()))?,
+            user: current_user(),
+            operation: operation.to_string(),
+            manifest_id: manifest_id.map(str::to_string),
+            result: match outcome {
+                Ok(_) => AuditResult::Success,
+                Err(_) => AuditResult::Failure,
+            },
+            error: outcome.as_ref().err().map(ToString::to_string),
+            prev_hash,
+        };
+        let mut line =
+            serde_json::to_string(&entry).map_err(|e| Error::Serialization(e.to_string()))?;
+        // Continue a log whose last line lacks its newline on a new line
+        if !content.is_empty() && !content.ends_with('\n') {
+            line.insert(0, '\n');
+        }
+        line.push('\n');
+        file.write_all(line.as_bytes())
+            .and_then(|()| file.flush())
+            .map_err(|e| self.error(format!("cannot write: {e}")))?;
+        Ok(entry)
+    }
+
+    /// Checks that every entry is valid and records the hash of the entry
+    /// before it
+    pub fn verify(&self) -> Result<AuditSummary> {
+        let content = std::fs::read_to_string(&self.path)
+            .map_err(|e| self.error(format!("cannot read: {e}")))?;
+
+        let mut entries = 0;
+        let mut last_hash = GENESIS_HASH.to_string();
+        for (index, line) in content.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let line_number = index + 1;
+            let entry: AuditEntry = serde_json::from_str(line).map_err(|e| {
+                Error::Validation(format!(
+                    "Audit log {} line {line_number} is not a valid entry: {e}",
+                    self.path.display()
+                ))
+            })?;
+            if entry.prev_hash != last_hash {
+                return Err(Error::Validation(format!(
+                    "Audit log {} is broken at line {line_number}: it records previous hash {} but the previous entry hashes to {last_hash}",
+                    self.path.display(),
+                    entry.prev_hash
+                )));
+            }
+            last_hash = line_hash(line);
+            entries += 1;
+        }
+        Ok(AuditSummary { entries, last_hash })
+    }
+
+    fn error(&self, message: String) -> Error {
+        Error::Storage(format!("Audit log {}: {message}", self.path.display()))
+    }
+}
+
+/// Records the outcome of an operation in the audit log at `log`; does
+/// nothing if no log is configured
+pub fn record<T>(
+    log: Option<&Path>,
+    operation: &str,
+    manifest_id: Option<&str>,
+    outcome: &Result<T>,
+) -> Result<()> {
+    if let Some(log) = log {
+        AuditLog::new(log).append(operation, manifest_id, outcome)?;
+    }
+    Ok(())
+}
+
+/// Records the outcome of an operation and passes it on, so a failure to
+/// write the log is only reported if the operation itself succeeded
+pub fn audited<T>(
+    log: Option<&Path>,
+    operation: &str,
+    manifest_id: Option<&str>,
+    outcome: Result<T>,
+) -> Result<T> {
+    let recorded = record(log, operation, manifest_id, &outcome);
+    let value = outcome?;
+    recorded?;
+    Ok(value)
+}
+
+fn line_hash(line: &str) -> String {
+    hex::encode(Sha256::digest(line.as_bytes()))
+}
+
+fn current_user() -> Option<String> {
+    ["USER", "USERNAME"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok())
+        .filter(|user| !user.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_audit_log_hash_chain() -> Result<()> {
+        let dir = tempdir()?;
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+
+        let first = log.append("create", Some("urn:c2pa:a"), &Ok::<_, Error>(()))?;
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        let failed: Result<()> = Err(Error::Validation("hash mismatch".to_string()));
+        let second = log.append("verify", Some("urn:c2pa:a"), &failed)?;
+        assert_eq!(second.result, AuditResult::Failure);
+        assert!(second.error.as_deref().unwrap().contains("hash mismatch"));
+        log.append("delete", Some("urn:c2pa:a"), &Ok::<_, Error>(()))?;
+
+        let summary = log.verify()?;
+        assert_eq!(summary.entries, 3);
+        let content = std::fs::read_to_string(log.path())?;
+        assert_eq!(
+            summary.last_hash,
+            line_hash(content.lines().last().unwrap())
+        );
+
+        // Rewriting an earlier entry breaks the chain at the next one
+        let tampered = content.replacen("\"failure\"", "\"success\"", 1);
+        std::fs::write(log.path(), &tampered)?;
+        let err = log.verify().unwrap_err().to_string();
+        assert!(err.contains("line 3"), "{err}");
+
+        // So does removing one
+        let lines: Vec<&str> = content.lines().collect();
+        std::fs::write(log.path(), format!("{}\n{}\n", lines[0], lines[2]))?;
+        assert!(log.verify().is_err());
+
+        Ok(())
+    }
+}
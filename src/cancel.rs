@@ -0,0 +1,80 @@
+//! # Cancellation
+//!
+//! Long-running operations (hashing large files, creating a manifest from
+//! many files, migrating or verifying a whole storage backend) check a
+//! cancellation token between hashed chunks and between files or manifests,
+//! and stop with [`Error::Cancelled`] once it is cancelled. Work is only
+//! abandoned at those points, never halfway through writing a manifest, so
+//! storage is left holding complete manifests only.
+//!
+//! The library checks the process-wide [`token`]. The CLI cancels it on the
+//! first Ctrl-C (see [`install_handler`]); a second Ctrl-C exits at once.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::cancel::CancellationToken;
+//! use atlas_cli::error::Error;
+//!
+//! let token = CancellationToken::new();
+//! assert!(token.check().is_ok());
+//! token.cancel();
+//! assert!(matches!(token.check(), Err(Error::Cancelled)));
+//! ```
+
+use crate::error::{EXIT_CANCELLED, Error, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+
+/// Shared flag telling long-running operations to stop
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every operation checking this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Returns [`Error::Cancelled`] if the token has been cancelled
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+static TOKEN: LazyLock<CancellationToken> = LazyLock::new(CancellationToken::new);
+
+/// The process-wide token checked by long-running operations
+pub fn token() -> &'static CancellationToken {
+    &TOKEN
+}
+
+/// Returns [`Error::Cancelled`] if the process-wide token has been cancelled
+pub fn check() -> Result<()> {
+    token().check()
+}
+
+/// Cancels the process-wide token on Ctrl-C (SIGINT), so the running
+/// operation stops at its next check. A second Ctrl-C exits immediately, for
+/// operations blocked where they cannot check the token.
+pub fn install_handler() -> Result<()> {
+    ctrlc::set_handler(|| {
+        if token().is_cancelled() {
+            std::process::exit(EXIT_CANCELLED.into());
+        }
+        eprintln!("Cancelling... (press Ctrl-C again to exit immediately)");
+        token().cancel();
+    })
+    .map_err(|e| Error::InitializationError(format!("Failed to install Ctrl-C handler: {e}")))
+}
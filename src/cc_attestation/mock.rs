@@ -5,12 +5,24 @@ use tdx_workload_attestation::provider::AttestationProvider;
 
 pub struct MockAttestationProvider {
     platform: String,
+    nonce: Option<Vec<u8>>,
 }
 
 impl MockAttestationProvider {
     pub fn new(platform: &str) -> Self {
         Self {
             platform: platform.to_string(),
+            nonce: None,
+        }
+    }
+
+    /// Creates a provider whose reports embed `nonce` (hex-encoded), so
+    /// reports for different nonces differ, as real reports binding a
+    /// nonce in their report data do
+    pub fn with_nonce(platform: &str, nonce: &[u8]) -> Self {
+        Self {
+            platform: platform.to_string(),
+            nonce: Some(nonce.to_vec()),
         }
     }
 }
@@ -18,7 +30,7 @@ impl MockAttestationProvider {
 impl AttestationProvider for MockAttestationProvider {
     fn get_attestation_report(&self) -> Result<String> {
         // Create a mock attestation report with platform info
-        let mock_report = json!({
+        let mut mock_report = json!({
             "type": "mock_attestation",
             "platform": self.platform,
             "timestamp": chrono::Utc::now().to_rfc3339(),
@@ -28,6 +40,9 @@ impl AttestationProvider for MockAttestationProvider {
                 "message": "This is a mock attestation report for non-Linux or unsupported platforms"
             }
         });
+        if let Some(nonce) = &self.nonce {
+            mock_report["nonce"] = json!(hex::encode(nonce));
+        }
 
         // Serialize to JSON string
         Ok(serde_json::to_string_pretty(&mock_report).unwrap_or_else(|_| "{}".to_string()))
@@ -3,7 +3,10 @@ use crate::error::{Error, Result};
 pub mod mock;
 use mock::MockAttestationProvider;
 
-use tdx_workload_attestation::get_platform_name;
+#[cfg(feature = "with-sev")]
+pub mod sev;
+
+use subtle::ConstantTimeEq;
 use tdx_workload_attestation::provider::AttestationProvider;
 
 #[cfg(feature = "with-tdx")]
@@ -13,14 +16,96 @@ use tdx_workload_attestation::host::TeeHost;
 #[cfg(feature = "with-tdx")]
 use tdx_workload_attestation::tdx::LinuxTdxProvider;
 
-pub fn get_report(show: bool) -> Result<String> {
-    // Select the appropriate provider based on platform and current OS
-    let platform = get_platform_name().map_err(|e| Error::CCAttestationError(e.to_string()))?;
+#[cfg(feature = "with-sev")]
+use sev::{SEV_SNP_PLATFORM, SevSnpHost, SevSnpProvider};
+
+/// Names of the CC platforms [`get_platform_name`] can return. CC
+/// attestation assertions are labelled with one of them; assertions the mock
+/// provider records on other hosts are labelled with the operating system
+/// name, which could as well be an ordinary platform label, and are not
+/// treated as attestations.
+pub const ATTESTATION_PLATFORMS: &[&str] = &["tdx-linux", "sev-snp-linux"];
+
+/// Whether a custom assertion label is that of a CC attestation assertion
+pub fn is_attestation_label(label: &str) -> bool {
+    ATTESTATION_PLATFORMS.contains(&label)
+}
+
+/// Returns the name of the current confidential computing platform.
+///
+/// Extends `tdx_workload_attestation::get_platform_name` with SEV-SNP
+/// detection when built with the `with-sev` feature.
+pub fn get_platform_name() -> Result<String> {
+    #[cfg(feature = "with-sev")]
+    if sev::is_sev_snp_guest() {
+        return Ok(SEV_SNP_PLATFORM.to_string());
+    }
+
+    tdx_workload_attestation::get_platform_name()
+        .map_err(|e| Error::CCAttestationError(e.to_string()))
+}
+
+/// Extracts the launch measurement recorded in a parsed attestation report.
+///
+/// Understands TDX reports (`td_info.mrtd`) and SEV-SNP reports
+/// (`measurement`). Returns `None` for reports that carry no measurement,
+/// such as mock reports.
+pub fn report_launch_measurement(report: &serde_json::Value) -> Option<[u8; 48]> {
+    if let Some(mrtd) = report["td_info"]["mrtd"].as_array() {
+        let bytes: Vec<u8> = mrtd
+            .iter()
+            .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect::<Option<_>>()?;
+        return bytes.try_into().ok();
+    }
+
+    if let Some(measurement) = report["measurement"].as_str() {
+        return hex::decode(measurement).ok()?.try_into().ok();
+    }
 
-    let provider: Box<dyn AttestationProvider> = match platform.as_str() {
+    None
+}
+
+/// Returns true if the platform name denotes real CC hardware rather than
+/// a mock provider
+pub fn is_cc_platform(platform: &str) -> bool {
+    match platform {
+        "tdx-linux" => true,
+        #[cfg(feature = "with-sev")]
+        SEV_SNP_PLATFORM => true,
+        _ => false,
+    }
+}
+
+fn get_provider(platform: &str) -> Box<dyn AttestationProvider> {
+    match platform {
         #[cfg(feature = "with-tdx")]
         "tdx-linux" => Box::new(LinuxTdxProvider::new()),
-        _ => Box::new(MockAttestationProvider::new(&platform)), // Use mock for non-Linux
+        #[cfg(feature = "with-sev")]
+        SEV_SNP_PLATFORM => Box::new(SevSnpProvider::new()),
+        _ => Box::new(MockAttestationProvider::new(platform)), // Use mock for non-Linux, non-CC
+    }
+}
+
+pub fn get_report(show: bool) -> Result<String> {
+    get_report_with_nonce(show, None)
+}
+
+/// Gets an attestation report that embeds `nonce`, if given.
+///
+/// Only the mock provider can embed a nonce so far; on real CC hardware a
+/// nonce is rejected rather than silently left out of the report.
+pub fn get_report_with_nonce(show: bool, nonce: Option<&[u8]>) -> Result<String> {
+    // Select the appropriate provider based on platform and current OS
+    let platform = get_platform_name()?;
+    let provider = match nonce {
+        None => get_provider(&platform),
+        Some(_) if is_cc_platform(&platform) => {
+            return Err(Error::CCAttestationError(format!(
+                "Embedding a nonce in the report is not supported on {platform}"
+            )));
+        }
+        Some(nonce) => Box::new(MockAttestationProvider::with_nonce(&platform, nonce)),
     };
 
     // Get the attestation report from the provider
@@ -37,13 +122,8 @@ pub fn get_report(show: bool) -> Result<String> {
 
 pub fn get_launch_measurement() -> Result<[u8; 48]> {
     // Select the appropriate provider based on platform and current OS
-    let platform = get_platform_name().map_err(|e| Error::CCAttestationError(e.to_string()))?;
-
-    let provider: Box<dyn AttestationProvider> = match platform.as_str() {
-        #[cfg(feature = "with-tdx")]
-        "tdx-linux" => Box::new(LinuxTdxProvider::new()),
-        _ => Box::new(MockAttestationProvider::new(&platform)), // Use mock for non-Linux, non-CC
-    };
+    let platform = get_platform_name()?;
+    let provider = get_provider(&platform);
 
     // Get the measurement from the provider
     let measurement = provider
@@ -54,18 +134,63 @@ pub fn get_launch_measurement() -> Result<[u8; 48]> {
 }
 
 pub fn verify_launch_endorsement(host_platform: &str) -> Result<bool> {
+    // Get the launch measurement for the current platform
+    let measurement = get_launch_measurement()?;
+    verify_host_endorsement(host_platform, &measurement)
+}
+
+/// Verifies the launch endorsement after comparing the live launch
+/// measurement against an expected value, e.g. one pinned in a manifest's
+/// CC assertion at creation time.
+///
+/// Returns `Error::LaunchMeasurementMismatch` if the platform has drifted
+/// from the expected measurement.
+pub fn verify_launch_endorsement_against(host_platform: &str, expected: &[u8; 48]) -> Result<bool> {
+    let measurement = get_launch_measurement()?;
+    check_measurement(expected, &measurement)?;
+    verify_host_endorsement(host_platform, &measurement)
+}
+
+/// Name of the launch check performed for a host platform. SEV-SNP hosts
+/// only match the measurement against a reference, without validating the
+/// report's VCEK signature, so the check is not called an endorsement.
+pub fn launch_check_name(host_platform: &str) -> &'static str {
+    match host_platform {
+        "sev-snp" => "launch measurement match (report signature not verified)",
+        _ => "launch endorsement verification",
+    }
+}
+
+pub(crate) fn check_measurement(expected: &[u8; 48], actual: &[u8; 48]) -> Result<()> {
+    if bool::from(expected.ct_eq(actual)) {
+        Ok(())
+    } else {
+        Err(Error::LaunchMeasurementMismatch {
+            expected: hex::encode(expected),
+            actual: hex::encode(actual),
+        })
+    }
+}
+
+#[allow(unused_variables)]
+fn verify_host_endorsement(host_platform: &str, measurement: &[u8; 48]) -> Result<bool> {
     // Get the launch endorsement from the specific host, if possible
     match host_platform {
         #[cfg(feature = "with-tdx")]
         "gcp-tdx" => {
-            // Get the launch measurement for the current platform
-            let measurement =
-                get_launch_measurement().map_err(|e| Error::CCAttestationError(e.to_string()))?;
-            let gcp_host = GcpTdxHost::new(&measurement);
+            let gcp_host = GcpTdxHost::new(measurement);
             Ok(gcp_host
                 .verify_launch_endorsement()
                 .map_err(|e| Error::CCAttestationError(e.to_string()))?)
         }
+        // Measurement match only: the report's VCEK signature is not checked
+        #[cfg(feature = "with-sev")]
+        "sev-snp" => {
+            let sev_host = SevSnpHost::new(measurement);
+            Ok(sev_host
+                .measurement_matches_reference()
+                .map_err(|e| Error::CCAttestationError(e.to_string()))?)
+        }
         _ => Err(Error::CCAttestationError(format!(
             "Launch endorsement verification not supported for platform {host_platform}"
         ))),
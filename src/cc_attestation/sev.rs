@@ -0,0 +1,178 @@
+use openssl::memcmp;
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tdx_workload_attestation::error::{Error, Result};
+use tdx_workload_attestation::host::TeeHost;
+use tdx_workload_attestation::provider::AttestationProvider;
+
+/// Platform name reported for AMD SEV-SNP Linux guests
+pub const SEV_SNP_PLATFORM: &str = "sev-snp-linux";
+
+/// Environment variable holding the hex-encoded reference launch measurement
+pub const SEV_SNP_REFERENCE_MEASUREMENT_ENV: &str = "ATLAS_SEV_SNP_REFERENCE_MEASUREMENT";
+
+const SEV_GUEST_DEVICE: &str = "/dev/sev-guest";
+const TSM_REPORT_DIR: &str = "/sys/kernel/config/tsm/report";
+const TSM_SEV_PROVIDER: &str = "sev_guest";
+
+/// Size of the SNP attestation report body (excluding the signature)
+const SNP_REPORT_MIN_LEN: usize = 0x2A0;
+/// Offset and length of the MEASUREMENT field in the SNP attestation report
+const SNP_MEASUREMENT_OFFSET: usize = 0x90;
+const SNP_MEASUREMENT_LEN: usize = 48;
+/// Length of the caller-supplied REPORT_DATA blob
+const SNP_REPORT_DATA_LEN: usize = 64;
+
+/// Returns true if the current machine is an AMD SEV-SNP guest
+pub fn is_sev_snp_guest() -> bool {
+    Path::new(SEV_GUEST_DEVICE).exists()
+}
+
+/// Attestation provider for AMD SEV-SNP Linux guests.
+///
+/// Reports are requested through the kernel's configfs-tsm interface, which
+/// proxies the request to the `sev-guest` driver.
+pub struct SevSnpProvider {
+    report_root: PathBuf,
+}
+
+impl SevSnpProvider {
+    pub fn new() -> Self {
+        Self {
+            report_root: PathBuf::from(TSM_REPORT_DIR),
+        }
+    }
+
+    fn get_raw_report(&self) -> Result<Vec<u8>> {
+        if !is_sev_snp_guest() {
+            return Err(Error::NotSupported(
+                "SEV-SNP guest device not found".to_string(),
+            ));
+        }
+
+        let entry = self
+            .report_root
+            .join(format!("atlas-{}", uuid::Uuid::new_v4()));
+        fs::create_dir(&entry)?;
+
+        let result = (|| {
+            let provider = fs::read_to_string(entry.join("provider"))?;
+            if provider.trim() != TSM_SEV_PROVIDER {
+                return Err(Error::NotSupported(format!(
+                    "Unexpected configfs-tsm provider: {}",
+                    provider.trim()
+                )));
+            }
+
+            fs::write(entry.join("inblob"), [0u8; SNP_REPORT_DATA_LEN])?;
+            let report = fs::read(entry.join("outblob"))?;
+
+            if report.len() < SNP_REPORT_MIN_LEN {
+                return Err(Error::QuoteError(format!(
+                    "SEV-SNP report too short: {} bytes",
+                    report.len()
+                )));
+            }
+
+            Ok(report)
+        })();
+
+        // The configfs entry must be removed even if the request failed
+        let _ = fs::remove_dir(&entry);
+
+        result
+    }
+}
+
+impl Default for SevSnpProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extracts the launch measurement from a raw SNP attestation report
+pub fn measurement_from_report(report: &[u8]) -> Result<[u8; 48]> {
+    let end = SNP_MEASUREMENT_OFFSET + SNP_MEASUREMENT_LEN;
+    if report.len() < end {
+        return Err(Error::ParseError(format!(
+            "SEV-SNP report too short to contain a measurement: {} bytes",
+            report.len()
+        )));
+    }
+
+    let mut measurement = [0u8; SNP_MEASUREMENT_LEN];
+    measurement.copy_from_slice(&report[SNP_MEASUREMENT_OFFSET..end]);
+    Ok(measurement)
+}
+
+impl AttestationProvider for SevSnpProvider {
+    fn get_attestation_report(&self) -> Result<String> {
+        let report = self.get_raw_report()?;
+        let measurement = measurement_from_report(&report)?;
+
+        let report_json = json!({
+            "type": "sev_snp_attestation",
+            "platform": SEV_SNP_PLATFORM,
+            "measurement": hex::encode(measurement),
+            "report": hex::encode(&report),
+        });
+
+        serde_json::to_string(&report_json).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+
+    fn get_launch_measurement(&self) -> Result<[u8; 48]> {
+        let report = self.get_raw_report()?;
+        measurement_from_report(&report)
+    }
+}
+
+/// Host interface for SEV-SNP guests.
+///
+/// Only matches the guest launch measurement against a reference measurement
+/// supplied by the operator via `ATLAS_SEV_SNP_REFERENCE_MEASUREMENT`. The
+/// report's VCEK signature is not validated against AMD's certificate chain,
+/// so a match does not prove that the report came from genuine SEV-SNP
+/// hardware.
+pub struct SevSnpHost {
+    measurement: [u8; 48],
+}
+
+impl SevSnpHost {
+    pub fn new(measurement: &[u8; 48]) -> Self {
+        Self {
+            measurement: *measurement,
+        }
+    }
+
+    fn reference_measurement(&self) -> Result<[u8; 48]> {
+        let value = std::env::var(SEV_SNP_REFERENCE_MEASUREMENT_ENV).map_err(|_| {
+            Error::NotSupported(format!(
+                "{SEV_SNP_REFERENCE_MEASUREMENT_ENV} is not set; no reference measurement available"
+            ))
+        })?;
+
+        let bytes = hex::decode(value.trim()).map_err(|e| Error::ParseError(e.to_string()))?;
+        bytes.try_into().map_err(|b: Vec<u8>| {
+            Error::ParseError(format!(
+                "Reference measurement must be {SNP_MEASUREMENT_LEN} bytes, got {}",
+                b.len()
+            ))
+        })
+    }
+
+    /// Compares the launch measurement with the reference measurement in
+    /// constant time. The report signature is not checked.
+    pub fn measurement_matches_reference(&self) -> Result<bool> {
+        let reference = self.reference_measurement()?;
+        Ok(memcmp::eq(&reference, &self.measurement))
+    }
+}
+
+impl TeeHost for SevSnpHost {
+    /// A measurement match only; see [`SevSnpHost::measurement_matches_reference`]
+    fn verify_launch_endorsement(&self) -> Result<bool> {
+        self.measurement_matches_reference()
+    }
+}
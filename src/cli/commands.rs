@@ -1,24 +1,189 @@
+use crate::error::{Error, Result};
+use crate::hash::DigestAlgorithm;
+use crate::manifest::utils::{parse_asset_type, parse_relationship};
+use atlas_c2pa_lib::asset_type::AssetType;
 use clap::Subcommand;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, clap::ValueEnum)]
 pub enum HashAlgorithmChoice {
     Sha256,
     Sha384,
     Sha512,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
 }
 
 impl HashAlgorithmChoice {
-    pub fn to_cose_algorithm(&self) -> atlas_c2pa_lib::cose::HashAlgorithm {
+    pub fn to_digest_algorithm(&self) -> DigestAlgorithm {
         match self {
-            HashAlgorithmChoice::Sha256 => atlas_c2pa_lib::cose::HashAlgorithm::Sha256,
-            HashAlgorithmChoice::Sha384 => atlas_c2pa_lib::cose::HashAlgorithm::Sha384,
-            HashAlgorithmChoice::Sha512 => atlas_c2pa_lib::cose::HashAlgorithm::Sha512,
+            HashAlgorithmChoice::Sha256 => DigestAlgorithm::Sha256,
+            HashAlgorithmChoice::Sha384 => DigestAlgorithm::Sha384,
+            HashAlgorithmChoice::Sha512 => DigestAlgorithm::Sha512,
+            HashAlgorithmChoice::Sha3_256 => DigestAlgorithm::Sha3_256,
+            HashAlgorithmChoice::Sha3_384 => DigestAlgorithm::Sha3_384,
+            HashAlgorithmChoice::Sha3_512 => DigestAlgorithm::Sha3_512,
         }
     }
 }
 
+impl FromStr for HashAlgorithmChoice {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "sha256" => Ok(HashAlgorithmChoice::Sha256),
+            "sha384" => Ok(HashAlgorithmChoice::Sha384),
+            "sha512" => Ok(HashAlgorithmChoice::Sha512),
+            "sha3-256" => Ok(HashAlgorithmChoice::Sha3_256),
+            "sha3-384" => Ok(HashAlgorithmChoice::Sha3_384),
+            "sha3-512" => Ok(HashAlgorithmChoice::Sha3_512),
+            _ => Err(Error::Validation(format!(
+                "Invalid hash algorithm '{s}'. Valid options are: sha256, sha384, sha512, sha3-256, sha3-384, sha3-512"
+            ))),
+        }
+    }
+}
+
+/// Key types offered by `keygen`
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum KeyTypeChoice {
+    /// 3072-bit RSA
+    Rsa,
+    /// ECDSA on the NIST P-256 curve
+    Ec,
+}
+
+impl KeyTypeChoice {
+    pub fn to_key_type(self) -> crate::signing::keygen::KeyType {
+        match self {
+            KeyTypeChoice::Rsa => crate::signing::keygen::KeyType::Rsa3072,
+            KeyTypeChoice::Ec => crate::signing::keygen::KeyType::EcP256,
+        }
+    }
+}
+
+/// Arguments for generating a signing key pair
+#[derive(Debug, clap::Args)]
+pub struct KeygenArgs {
+    /// Type of key to generate
+    #[arg(long = "type", value_enum, default_value = "rsa")]
+    pub key_type: KeyTypeChoice,
+
+    /// Path for the private key; the public key is written next to it as NAME.pub.pem
+    #[arg(long = "output", short = 'o')]
+    pub output: PathBuf,
+
+    /// Overwrite existing key files
+    #[arg(long = "force")]
+    pub force: bool,
+}
+
+/// Arguments for printing the public key of a signing key
+#[derive(Debug, clap::Args)]
+pub struct PubkeyArgs {
+    /// Private signing key (default: ATLAS_CLI_KEY_PATH, then the OS keyring)
+    #[arg(long = "key")]
+    pub key: Option<PathBuf>,
+
+    /// Write the public key PEM to this file instead of stdout
+    #[arg(long = "out", short = 'o')]
+    pub out: Option<PathBuf>,
+
+    /// Overwrite an existing output file
+    #[arg(long = "force")]
+    pub force: bool,
+}
+
+/// Arguments for copying manifests between storage backends
+#[derive(Debug, clap::Args)]
+pub struct MigrateArgs {
+    /// Source storage as TYPE:URL, e.g. local-fs:./manifests
+    #[arg(long = "from")]
+    pub from: String,
+
+    /// Destination storage as TYPE:URL, e.g. database:http://localhost:8080
+    #[arg(long = "to")]
+    pub to: String,
+
+    /// Leave manifests that already exist in the destination untouched
+    #[arg(long = "skip-existing")]
+    pub skip_existing: bool,
+}
+
 #[derive(Debug, Subcommand)]
+pub enum ConfigCommands {
+    /// Print the effective settings and where each value comes from
+    Show {
+        /// Command whose flags to show, e.g. `model create`; global settings only if omitted
+        command: Vec<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AuditCommands {
+    /// Check the hash chain of the audit log
+    Verify {
+        /// Audit log to check (default: the --audit-log path)
+        #[arg(long = "log", value_name = "PATH")]
+        log: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BatchCommands {
+    /// Create and store a manifest for each entry of a JSON spec file
+    Create(BatchCreateArgs),
+}
+
+/// Arguments for creating manifests from a spec file
+#[derive(Debug, clap::Args)]
+pub struct BatchCreateArgs {
+    /// JSON array of manifest entries (type, name, paths, ingredient_names, author_org, ...)
+    #[arg(long = "spec")]
+    pub spec: PathBuf,
+
+    /// Path to private key file used to sign every manifest (default: ATLAS_CLI_KEY_PATH, then the OS keyring)
+    #[arg(long = "key")]
+    pub key: Option<PathBuf>,
+
+    /// Hash algorithm to use for signing: sha256, sha384, sha512, sha3-256, sha3-384 or sha3-512 (default: sha384)
+    #[arg(long = "hash-alg", value_parser = HashAlgorithmChoice::from_str, default_value = "sha384")]
+    pub hash_alg: HashAlgorithmChoice,
+
+    /// Reject RSA signing keys smaller than this many bits
+    #[arg(long = "min-key-bits", default_value = "2048")]
+    pub min_key_bits: u32,
+
+    /// Author organization recorded for entries that do not set their own
+    #[arg(long = "author-org")]
+    pub author_org: Option<String>,
+
+    /// Tag to record in every manifest, e.g. env=prod (can be repeated)
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+
+    /// Store manifests without validating them against the bundled JSON Schema
+    #[arg(long = "no-schema-check")]
+    pub no_schema_check: bool,
+
+    /// Derive manifest and ingredient IDs from the inputs for reproducible output
+    #[arg(long = "deterministic")]
+    pub deterministic: bool,
+
+    /// Storage backend (database, local-fs or rekor)
+    #[arg(long = "storage-type", default_value = "database")]
+    pub storage_type: String,
+
+    /// Storage URL
+    #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+    pub storage_url: String,
+}
+
+#[derive(Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum DatasetCommands {
     /// Create a new dataset manifest
     Create {
@@ -29,6 +194,14 @@ pub enum DatasetCommands {
         #[arg(long = "ingredient-names", num_args = 1.., value_delimiter = ',')]
         ingredient_names: Vec<String>,
 
+        /// C2PA relationship of each ingredient (componentOf, parentOf or inputTo; comma-separated, default: componentOf)
+        #[arg(long = "relationships", value_delimiter = ',', value_parser = parse_relationship)]
+        relationships: Vec<String>,
+
+        /// URL to record for each ingredient instead of its local file:// URL, e.g. where it is published (comma-separated; a directory's URL is the base of its files' URLs)
+        #[arg(long = "ingredient-urls", value_delimiter = ',')]
+        ingredient_urls: Vec<String>,
+
         /// Dataset name
         #[arg(long = "name")]
         name: String,
@@ -49,22 +222,98 @@ pub enum DatasetCommands {
         #[arg(long = "linked-manifests")]
         linked_manifests: Option<Vec<String>>,
 
-        /// Path to private key file for signing (PEM format)
+        /// Path to private key file for signing (PEM or DER format; default: ATLAS_CLI_KEY_PATH, then the OS keyring)
         #[arg(long = "key")]
         key: Option<PathBuf>,
 
-        /// Hash algorithm to use for signing (default: sha384)
-        #[arg(long = "hash-alg", value_enum, default_value = "sha384")]
+        /// Sign with a cloud KMS key instead of a key file: awskms://<key>, gcpkms://<key version> or azurekms://<vault>/<key>
+        #[arg(long = "kms-key", conflicts_with = "key")]
+        kms_key: Option<String>,
+
+        /// Hash algorithm to use for signing: sha256, sha384, sha512, sha3-256, sha3-384 or sha3-512 (default: sha384)
+        #[arg(long = "hash-alg", value_parser = HashAlgorithmChoice::from_str, default_value = "sha384")]
         hash_alg: HashAlgorithmChoice,
 
+        /// URL of an RFC 3161 Time Stamp Authority used to timestamp the signature
+        #[arg(long = "tsa-url")]
+        tsa_url: Option<String>,
+
+        /// Reject RSA signing keys smaller than this many bits
+        #[arg(long = "min-key-bits", default_value = "2048")]
+        min_key_bits: u32,
+
+        /// Record the OS, architecture, hostname and CLI version in the manifest
+        #[arg(long = "record-environment")]
+        record_environment: bool,
+
+        /// With --record-environment, leave the hostname out
+        #[arg(long = "redact-hostname", requires = "record_environment")]
+        redact_hostname: bool,
+
+        /// Creative work type to record instead of the default for this asset kind
+        #[arg(long = "creative-type")]
+        creative_type: Option<String>,
+
+        /// Digital source type URI to record instead of the default for this asset kind
+        #[arg(long = "digital-source-type")]
+        digital_source_type: Option<String>,
+
+        /// Validate inputs and report what would be created without hashing or storing
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+
+        /// Store the manifest without validating it against the bundled JSON Schema
+        #[arg(long = "no-schema-check")]
+        no_schema_check: bool,
+
+        /// Detect ingredient types from file extensions only, without inspecting file contents
+        #[arg(long = "no-sniff")]
+        no_sniff: bool,
+
+        /// Tag to record in the manifest, e.g. env=prod (can be repeated)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
         /// Only print manifest without storing
         #[arg(long = "print")]
         print: bool,
 
-        /// Output format (json or cbor)
+        /// Output format (json, cbor or yaml)
         #[arg(long = "format", default_value = "json")]
         format: String,
 
+        /// Write the printed manifest to this file instead of stdout
+        #[arg(long = "output")]
+        output: Option<PathBuf>,
+
+        /// Print CBOR output as hex for debugging
+        #[arg(long = "hex")]
+        hex: bool,
+
+        /// Derive manifest and ingredient IDs from the inputs for reproducible output
+        #[arg(long = "deterministic")]
+        deterministic: bool,
+
+        /// Hash the decompressed content of .gz and .zst inputs so the hash does not depend on compression
+        #[arg(long = "hash-decompressed")]
+        hash_decompressed: bool,
+
+        /// Hash only the size, first and last MiB and 64 sampled blocks of each file: a fast sanity check for huge files, not a full integrity guarantee
+        #[arg(long = "quick-hash")]
+        quick_hash: bool,
+
+        /// Creation time to record (YYYY-MM-DD or RFC 3339) instead of now
+        #[arg(long = "created-at")]
+        created_at: Option<String>,
+
+        /// Start of the period in which the manifest is valid (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "valid-from")]
+        valid_from: Option<String>,
+
+        /// End of the period in which the manifest is valid (YYYY-MM-DD or RFC 3339); verification fails afterwards
+        #[arg(long = "valid-until")]
+        valid_until: Option<String>,
+
         /// Storage backend (local or rekor)
         #[arg(long = "storage-type", default_value = "database")]
         storage_type: Box<String>,
@@ -76,6 +325,18 @@ pub enum DatasetCommands {
         /// Collect the underlying TDX attestation, if available
         #[arg(long = "with-tdx", default_value = "false")]
         with_tdx: bool,
+
+        /// Force the ingredient asset type (e.g. dataset.pytorch), overriding extension detection
+        #[arg(long = "asset-type", value_parser = parse_asset_type)]
+        asset_type: Option<AssetType>,
+
+        /// Recursively include the files in directory inputs
+        #[arg(long = "recursive", default_value = "false")]
+        recursive: bool,
+
+        /// Comma-separated names or `*.ext` patterns to skip when walking directories
+        #[arg(long = "ignore", value_delimiter = ',')]
+        ignore: Vec<String>,
     },
     /// List all dataset manifests
     List {
@@ -86,11 +347,27 @@ pub enum DatasetCommands {
         /// Storage URL
         #[arg(long = "storage-url", default_value = "http://localhost:8080")]
         storage_url: Box<String>,
+
+        /// Only list manifests with this tag (can be repeated)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Only list manifests created at or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "since")]
+        since: Option<String>,
+
+        /// Only list manifests created before this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "until")]
+        until: Option<String>,
     },
     Verify {
         /// Manifest ID to verify
-        #[arg(long = "id")]
-        id: String,
+        #[arg(long = "id", required_unless_present = "file", conflicts_with = "file")]
+        id: Option<String>,
+
+        /// Verify a manifest file (JSON, or CBOR with a .cbor extension) instead of a stored manifest; storage is only used to resolve its cross-references
+        #[arg(long = "file")]
+        file: Option<PathBuf>,
         /// Storage backend (local or rekor)
         #[arg(long = "storage-type", default_value = "database")]
         storage_type: Box<String>,
@@ -98,20 +375,65 @@ pub enum DatasetCommands {
         /// Storage URL
         #[arg(long = "storage-url", default_value = "http://localhost:8080")]
         storage_url: Box<String>,
+
+        /// Fail if a CC attestation cannot be re-validated on this platform
+        #[arg(long = "strict-cc")]
+        strict_cc: bool,
+
+        /// PEM file of trusted TSA certificates used to validate signature timestamps
+        #[arg(long = "tsa-ca")]
+        tsa_ca: Option<PathBuf>,
+
+        /// Warn instead of failing when a cross-reference target is missing
+        #[arg(long = "allow-dangling")]
+        allow_dangling: bool,
+
+        /// Do not download remote ingredients; skip them with a warning
+        #[arg(long = "offline")]
+        offline: bool,
+
+        /// File of trusted signer public keys (PEM) or SHA-256 fingerprints; fail unless every signer is listed
+        #[arg(long = "trust-list")]
+        trust_list: Option<PathBuf>,
+
+        /// Warn instead of failing when the manifest's validity window has ended
+        #[arg(long = "allow-expired")]
+        allow_expired: bool,
+
+        /// JSON file of policy rules (JSONPath expressions) the manifest must satisfy
+        #[arg(long = "policy")]
+        policy: Option<PathBuf>,
+
+        /// Fail instead of warning when an ingredient's recorded format or type does not match its file
+        #[arg(long = "strict")]
+        strict: bool,
+
+        /// Check every ingredient before failing and list all that failed, instead of stopping at the first
+        #[arg(long = "continue-on-error")]
+        continue_on_error: bool,
     },
 }
 
 #[derive(Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum ModelCommands {
     /// Create a new model manifest
     Create {
-        /// Path to model file
+        /// Path to model file (`-` reads it from stdin)
         #[arg(long = "paths", num_args = 1.., value_delimiter = ',')]
         paths: Vec<PathBuf>,
 
         /// Names for each ingredient (comma-separated)
         #[arg(long = "ingredient-names", num_args = 1.., value_delimiter = ',')]
         ingredient_names: Vec<String>,
+
+        /// C2PA relationship of each ingredient (componentOf, parentOf or inputTo; comma-separated, default: componentOf)
+        #[arg(long = "relationships", value_delimiter = ',', value_parser = parse_relationship)]
+        relationships: Vec<String>,
+
+        /// URL to record for each ingredient instead of its local file:// URL, e.g. where it is published (comma-separated; a directory's URL is the base of its files' URLs)
+        #[arg(long = "ingredient-urls", value_delimiter = ',')]
+        ingredient_urls: Vec<String>,
         /// Model name
         #[arg(long = "name")]
         name: String,
@@ -132,22 +454,110 @@ pub enum ModelCommands {
         #[arg(long = "linked-manifests")]
         linked_manifests: Option<Vec<String>>,
 
-        /// Path to private key file for signing (PEM format)
+        /// Manifest ID of a dataset the model was trained on (can be repeated); recorded with its hash and checked on verify
+        #[arg(long = "trained-on")]
+        trained_on: Vec<String>,
+
+        /// Model card or other documentation of the model: a local file, hashed as a documentOf ingredient, or an http(s):// or s3:// URL
+        #[arg(long = "model-card", value_name = "PATH_OR_URL")]
+        model_card: Option<String>,
+
+        /// Expected SHA-256, SHA-384 or SHA-512 hex digest of the content at a --model-card URL
+        #[arg(long = "model-card-hash", requires = "model_card")]
+        model_card_hash: Option<String>,
+
+        /// Path to private key file for signing (PEM or DER format; default: ATLAS_CLI_KEY_PATH, then the OS keyring)
         #[arg(long = "key")]
         key: Option<PathBuf>,
 
-        /// Hash algorithm to use for signing (default: sha384)
-        #[arg(long = "hash-alg", value_enum, default_value = "sha384")]
+        /// Sign with a cloud KMS key instead of a key file: awskms://<key>, gcpkms://<key version> or azurekms://<vault>/<key>
+        #[arg(long = "kms-key", conflicts_with = "key")]
+        kms_key: Option<String>,
+
+        /// Hash algorithm to use for signing: sha256, sha384, sha512, sha3-256, sha3-384 or sha3-512 (default: sha384)
+        #[arg(long = "hash-alg", value_parser = HashAlgorithmChoice::from_str, default_value = "sha384")]
         hash_alg: HashAlgorithmChoice,
 
+        /// URL of an RFC 3161 Time Stamp Authority used to timestamp the signature
+        #[arg(long = "tsa-url")]
+        tsa_url: Option<String>,
+
+        /// Reject RSA signing keys smaller than this many bits
+        #[arg(long = "min-key-bits", default_value = "2048")]
+        min_key_bits: u32,
+
+        /// Record the OS, architecture, hostname and CLI version in the manifest
+        #[arg(long = "record-environment")]
+        record_environment: bool,
+
+        /// With --record-environment, leave the hostname out
+        #[arg(long = "redact-hostname", requires = "record_environment")]
+        redact_hostname: bool,
+
+        /// Creative work type to record instead of the default for this asset kind
+        #[arg(long = "creative-type")]
+        creative_type: Option<String>,
+
+        /// Digital source type URI to record instead of the default for this asset kind
+        #[arg(long = "digital-source-type")]
+        digital_source_type: Option<String>,
+
+        /// Validate inputs and report what would be created without hashing or storing
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+
+        /// Store the manifest without validating it against the bundled JSON Schema
+        #[arg(long = "no-schema-check")]
+        no_schema_check: bool,
+
+        /// Detect ingredient types from file extensions only, without inspecting file contents
+        #[arg(long = "no-sniff")]
+        no_sniff: bool,
+
+        /// Tag to record in the manifest, e.g. env=prod (can be repeated)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
         /// Only print manifest without storing
         #[arg(long = "print")]
         print: bool,
 
-        /// Output format (json or cbor)
+        /// Output format (json, cbor or yaml)
         #[arg(long = "format", default_value = "json")]
         format: String,
 
+        /// Write the printed manifest to this file instead of stdout
+        #[arg(long = "output")]
+        output: Option<PathBuf>,
+
+        /// Print CBOR output as hex for debugging
+        #[arg(long = "hex")]
+        hex: bool,
+
+        /// Derive manifest and ingredient IDs from the inputs for reproducible output
+        #[arg(long = "deterministic")]
+        deterministic: bool,
+
+        /// Hash the decompressed content of .gz and .zst inputs so the hash does not depend on compression
+        #[arg(long = "hash-decompressed")]
+        hash_decompressed: bool,
+
+        /// Hash only the size, first and last MiB and 64 sampled blocks of each file: a fast sanity check for huge files, not a full integrity guarantee
+        #[arg(long = "quick-hash")]
+        quick_hash: bool,
+
+        /// Creation time to record (YYYY-MM-DD or RFC 3339) instead of now
+        #[arg(long = "created-at")]
+        created_at: Option<String>,
+
+        /// Start of the period in which the manifest is valid (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "valid-from")]
+        valid_from: Option<String>,
+
+        /// End of the period in which the manifest is valid (YYYY-MM-DD or RFC 3339); verification fails afterwards
+        #[arg(long = "valid-until")]
+        valid_until: Option<String>,
+
         /// Storage backend (local or rekor)
         #[arg(long = "storage-type", default_value = "database")]
         storage_type: Box<String>,
@@ -159,6 +569,104 @@ pub enum ModelCommands {
         /// Collect the underlying CC attestation, if available
         #[arg(long = "with-tdx", default_value = "false")]
         with_tdx: bool,
+
+        /// Force the ingredient asset type (e.g. model.onnx, dataset), overriding extension detection
+        #[arg(long = "asset-type", value_parser = parse_asset_type)]
+        asset_type: Option<AssetType>,
+
+        /// Recursively include the files in directory inputs
+        #[arg(long = "recursive", default_value = "false")]
+        recursive: bool,
+
+        /// Comma-separated names or `*.ext` patterns to skip when walking directories
+        #[arg(long = "ignore", value_delimiter = ',')]
+        ignore: Vec<String>,
+    },
+    /// Create a model manifest derived from a base model's manifest, e.g. for a fine-tune
+    Derive {
+        /// Manifest ID of the base model (can be repeated, e.g. for merged models)
+        #[arg(long = "base", required = true)]
+        base: Vec<String>,
+
+        /// Path to the derived model file
+        #[arg(long = "paths", num_args = 1.., value_delimiter = ',')]
+        paths: Vec<PathBuf>,
+
+        /// Names for each ingredient (comma-separated)
+        #[arg(long = "ingredient-names", num_args = 1.., value_delimiter = ',')]
+        ingredient_names: Vec<String>,
+
+        /// Model name
+        #[arg(long = "name")]
+        name: String,
+
+        /// Author organization name
+        #[arg(long = "author-org")]
+        author_org: Option<String>,
+
+        /// Author name
+        #[arg(long = "author-name")]
+        author_name: Option<String>,
+
+        /// Optional description
+        #[arg(long = "description")]
+        description: Option<String>,
+
+        /// Optional linked manifest IDs besides the base models
+        #[arg(long = "linked-manifests")]
+        linked_manifests: Option<Vec<String>>,
+
+        /// Manifest ID of a dataset the model was trained on (can be repeated); recorded with its hash and checked on verify
+        #[arg(long = "trained-on")]
+        trained_on: Vec<String>,
+
+        /// Model card or other documentation of the model: a local file, hashed as a documentOf ingredient, or an http(s):// or s3:// URL
+        #[arg(long = "model-card", value_name = "PATH_OR_URL")]
+        model_card: Option<String>,
+
+        /// Expected SHA-256, SHA-384 or SHA-512 hex digest of the content at a --model-card URL
+        #[arg(long = "model-card-hash", requires = "model_card")]
+        model_card_hash: Option<String>,
+
+        /// Path to private key file for signing (PEM or DER format; default: ATLAS_CLI_KEY_PATH, then the OS keyring)
+        #[arg(long = "key")]
+        key: Option<PathBuf>,
+
+        /// Sign with a cloud KMS key instead of a key file: awskms://<key>, gcpkms://<key version> or azurekms://<vault>/<key>
+        #[arg(long = "kms-key", conflicts_with = "key")]
+        kms_key: Option<String>,
+
+        /// Hash algorithm to use for signing: sha256, sha384, sha512, sha3-256, sha3-384 or sha3-512 (default: sha384)
+        #[arg(long = "hash-alg", value_parser = HashAlgorithmChoice::from_str, default_value = "sha384")]
+        hash_alg: HashAlgorithmChoice,
+
+        /// Reject RSA signing keys smaller than this many bits
+        #[arg(long = "min-key-bits", default_value = "2048")]
+        min_key_bits: u32,
+
+        /// Record the OS, architecture, hostname and CLI version in the manifest
+        #[arg(long = "record-environment")]
+        record_environment: bool,
+
+        /// With --record-environment, leave the hostname out
+        #[arg(long = "redact-hostname", requires = "record_environment")]
+        redact_hostname: bool,
+
+        /// Tag to record in the manifest, e.g. env=prod (can be repeated)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Store the manifest without validating it against the bundled JSON Schema
+        #[arg(long = "no-schema-check")]
+        no_schema_check: bool,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
     },
     /// List all model manifests
     List {
@@ -169,11 +677,27 @@ pub enum ModelCommands {
         /// Storage URL
         #[arg(long = "storage-url", default_value = "http://localhost:8080")]
         storage_url: Box<String>,
+
+        /// Only list manifests with this tag (can be repeated)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Only list manifests created at or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "since")]
+        since: Option<String>,
+
+        /// Only list manifests created before this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "until")]
+        until: Option<String>,
     },
     Verify {
         /// Manifest ID to verify
-        #[arg(long = "id")]
-        id: String,
+        #[arg(long = "id", required_unless_present = "file", conflicts_with = "file")]
+        id: Option<String>,
+
+        /// Verify a manifest file (JSON, or CBOR with a .cbor extension) instead of a stored manifest; storage is only used to resolve its cross-references
+        #[arg(long = "file")]
+        file: Option<PathBuf>,
         /// Storage backend (local or rekor)
         #[arg(long = "storage-type", default_value = "database")]
         storage_type: Box<String>,
@@ -181,6 +705,42 @@ pub enum ModelCommands {
         /// Storage URL
         #[arg(long = "storage-url", default_value = "http://localhost:8080")]
         storage_url: Box<String>,
+
+        /// Fail if a CC attestation cannot be re-validated on this platform
+        #[arg(long = "strict-cc")]
+        strict_cc: bool,
+
+        /// PEM file of trusted TSA certificates used to validate signature timestamps
+        #[arg(long = "tsa-ca")]
+        tsa_ca: Option<PathBuf>,
+
+        /// Warn instead of failing when a cross-reference target is missing
+        #[arg(long = "allow-dangling")]
+        allow_dangling: bool,
+
+        /// Do not download remote ingredients; skip them with a warning
+        #[arg(long = "offline")]
+        offline: bool,
+
+        /// File of trusted signer public keys (PEM) or SHA-256 fingerprints; fail unless every signer is listed
+        #[arg(long = "trust-list")]
+        trust_list: Option<PathBuf>,
+
+        /// Warn instead of failing when the manifest's validity window has ended
+        #[arg(long = "allow-expired")]
+        allow_expired: bool,
+
+        /// JSON file of policy rules (JSONPath expressions) the manifest must satisfy
+        #[arg(long = "policy")]
+        policy: Option<PathBuf>,
+
+        /// Fail instead of warning when an ingredient's recorded format or type does not match its file
+        #[arg(long = "strict")]
+        strict: bool,
+
+        /// Check every ingredient before failing and list all that failed, instead of stopping at the first
+        #[arg(long = "continue-on-error")]
+        continue_on_error: bool,
     },
     LinkDataset {
         /// Model manifest ID
@@ -222,12 +782,78 @@ pub enum ManifestCommands {
         storage_url: Box<String>,
     },
 
+    /// Add a countersignature to a stored manifest
+    Sign {
+        /// Manifest ID to sign
+        #[arg(short, long)]
+        id: String,
+
+        /// Private key of the signer (default: ATLAS_CLI_KEY_PATH, then the OS keyring)
+        #[arg(long = "key")]
+        key: Option<PathBuf>,
+
+        /// Identity recorded with the signature, e.g. an email address
+        #[arg(long = "identity")]
+        identity: String,
+
+        /// Hash algorithm to use for signing: sha256, sha384, sha512, sha3-256, sha3-384 or sha3-512 (default: sha384)
+        #[arg(long = "hash-alg", value_parser = HashAlgorithmChoice::from_str, default_value = "sha384")]
+        hash_alg: HashAlgorithmChoice,
+
+        /// Reject RSA signing keys smaller than this many bits
+        #[arg(long = "min-key-bits", default_value = "2048")]
+        min_key_bits: u32,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
+
+    /// Re-hash a manifest's local file ingredients and store the updated hashes
+    Refresh {
+        /// Manifest ID to refresh
+        #[arg(short, long)]
+        id: String,
+
+        /// Private key to re-sign the manifest with (default: ATLAS_CLI_KEY_PATH, then the OS keyring)
+        #[arg(long = "key")]
+        key: Option<PathBuf>,
+
+        /// Hash algorithm to use for signing: sha256, sha384, sha512, sha3-256, sha3-384 or sha3-512 (default: sha384)
+        #[arg(long = "hash-alg", value_parser = HashAlgorithmChoice::from_str, default_value = "sha384")]
+        hash_alg: HashAlgorithmChoice,
+
+        /// Reject RSA signing keys smaller than this many bits
+        #[arg(long = "min-key-bits", default_value = "2048")]
+        min_key_bits: u32,
+
+        /// Store the refreshed manifest; without this only the changed hashes are shown
+        #[arg(long = "confirm")]
+        confirm: bool,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
+
     /// Show manifest details
     Show {
         /// Manifest ID to show
         #[arg(short, long)]
         id: String,
 
+        /// Output format (text, json, cbor or yaml)
+        #[arg(long = "format", default_value = "text")]
+        format: String,
+
         /// Storage backend (local or rekor)
         #[arg(long = "storage-type", default_value = "database")]
         storage_type: Box<String>,
@@ -270,6 +896,77 @@ pub enum ManifestCommands {
         #[arg(long = "storage-url", default_value = "http://localhost:8080")]
         storage_url: Box<String>,
     },
+    /// Verify every manifest in a storage backend
+    VerifyAll {
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+
+        /// Fail instead of warning when a CC attestation cannot be re-validated
+        #[arg(long = "strict-cc", default_value = "false")]
+        strict_cc: bool,
+
+        /// PEM file of trusted TSA certificates used to validate signature timestamps
+        #[arg(long = "tsa-ca")]
+        tsa_ca: Option<PathBuf>,
+
+        /// Warn instead of failing when a cross-reference target is missing
+        #[arg(long = "allow-dangling")]
+        allow_dangling: bool,
+
+        /// Do not download remote ingredients; skip them with a warning
+        #[arg(long = "offline")]
+        offline: bool,
+
+        /// File of trusted signer public keys (PEM) or SHA-256 fingerprints; fail unless every signer is listed
+        #[arg(long = "trust-list")]
+        trust_list: Option<PathBuf>,
+
+        /// Warn instead of failing when the manifest's validity window has ended
+        #[arg(long = "allow-expired")]
+        allow_expired: bool,
+
+        /// JSON file of policy rules (JSONPath expressions) the manifest must satisfy
+        #[arg(long = "policy")]
+        policy: Option<PathBuf>,
+
+        /// Fail instead of warning when an ingredient's recorded format or type does not match its file
+        #[arg(long = "strict")]
+        strict: bool,
+
+        /// Check every ingredient before failing and list all that failed, instead of stopping at the first
+        #[arg(long = "continue-on-error")]
+        continue_on_error: bool,
+
+        /// Only verify signatures (timestamps, countersignatures and --trust-list), fetching just each manifest's claim; claim signatures require --trust-list
+        #[arg(
+            long = "signature-only",
+            conflicts_with_all = ["strict_cc", "allow_dangling", "offline", "allow_expired", "policy", "strict", "continue_on_error"]
+        )]
+        signature_only: bool,
+    },
+    /// Delete every manifest of a type, or the manifests with the given IDs
+    Prune {
+        /// Manifest type to delete (dataset, model, software, evaluation or unknown)
+        #[arg(long = "type", conflicts_with = "ids", required_unless_present = "ids")]
+        manifest_type: Option<String>,
+
+        /// Manifest ID to delete (can be repeated)
+        #[arg(long = "id")]
+        ids: Vec<String>,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
     /// Export provenance graph information
     Export {
         /// Manifest ID to export provenance for
@@ -296,9 +993,268 @@ pub enum ManifestCommands {
         #[arg(long = "max-depth", default_value = "10")]
         max_depth: u32,
     },
+
+    /// Print the ancestry of a derived model, following its base manifests
+    Lineage {
+        /// Manifest ID to show the lineage of
+        #[arg(short, long)]
+        id: String,
+
+        /// Output format (text or json)
+        #[arg(long = "format", default_value = "text")]
+        format: String,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
+
+    /// Summarize a storage backend: manifests per type, total size and creation times
+    Stats {
+        /// Output format (text or json)
+        #[arg(long = "format", default_value = "text")]
+        format: String,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
+
+    /// Show the differences between two manifests
+    Diff {
+        /// First (old) manifest ID
+        #[arg(long = "old")]
+        old: String,
+
+        /// Second (new) manifest ID
+        #[arg(long = "new")]
+        new: String,
+
+        /// Output format (text or json)
+        #[arg(long = "format", default_value = "text")]
+        format: String,
+
+        /// Also compare volatile fields such as instance_id and created_at
+        #[arg(long = "include-volatile")]
+        include_volatile: bool,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
+
+    /// Export a manifest and all transitively linked manifests to a directory
+    ExportBundle {
+        /// Root manifest ID of the bundle
+        #[arg(short, long)]
+        id: String,
+
+        /// Output directory for the bundle
+        #[arg(long = "out-dir")]
+        out_dir: PathBuf,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
+
+    /// Write the ingredient hashes of a manifest as a checksum file, checkable with `sha256sum -c`
+    ExportChecksums {
+        /// Manifest ID to export ingredient hashes for
+        #[arg(short, long)]
+        id: String,
+
+        /// Hash algorithm to list; ingredients hashed with another algorithm are skipped (default: sha256)
+        #[arg(long = "alg", value_parser = HashAlgorithmChoice::from_str, default_value = "sha256")]
+        alg: HashAlgorithmChoice,
+
+        /// Output file path (defaults to stdout if not provided)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
+
+    /// Re-verify a manifest whenever one of its local file ingredients changes, until Ctrl-C
+    Watch {
+        /// Manifest ID to watch
+        #[arg(short, long)]
+        id: String,
+
+        /// Milliseconds the files must stay unchanged before verifying again
+        #[arg(long = "debounce-ms", default_value = "500")]
+        debounce_ms: u64,
+
+        /// Milliseconds between checks of the files
+        #[arg(long = "interval-ms", default_value = "250")]
+        interval_ms: u64,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+
+        /// Warn instead of failing when a cross-reference target is missing
+        #[arg(long = "allow-dangling")]
+        allow_dangling: bool,
+
+        /// Fail instead of warning when an ingredient's recorded format or type does not match its file
+        #[arg(long = "strict")]
+        strict: bool,
+
+        /// Check every ingredient before failing and list all that failed, instead of stopping at the first
+        #[arg(long = "continue-on-error")]
+        continue_on_error: bool,
+    },
+
+    /// Import a manifest from a JSON or CBOR file into a storage backend
+    Import {
+        /// Manifest file to import, e.g. from `create --output`
+        #[arg(long = "file")]
+        file: PathBuf,
+
+        /// Input format (json or cbor); defaults to cbor for .cbor files and json otherwise
+        #[arg(long = "format")]
+        format: Option<String>,
+
+        /// Verify countersignatures before storing
+        #[arg(long = "verify-signatures")]
+        verify_signatures: bool,
+
+        /// File of trusted signer public keys (PEM) or SHA-256 fingerprints; fail unless every signer is listed
+        #[arg(long = "trust-list")]
+        trust_list: Option<PathBuf>,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
+
+    /// Embed a manifest into an asset (PNG or JPEG), or write a .c2pa sidecar
+    Embed {
+        /// Asset to embed the manifest into
+        #[arg(long = "asset")]
+        asset: PathBuf,
+
+        /// ID of a stored manifest to embed
+        #[arg(short, long, conflicts_with = "manifest")]
+        id: Option<String>,
+
+        /// JSON manifest file to embed, e.g. from `create --print`
+        #[arg(long = "manifest")]
+        manifest: Option<PathBuf>,
+
+        /// Write the embedded asset here instead of modifying it in place
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Write a .c2pa sidecar next to the asset instead of embedding
+        #[arg(long = "sidecar")]
+        sidecar: bool,
+
+        /// Storage backend (local or rekor)
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+    },
+
+    /// Extract the manifest embedded in an asset or its .c2pa sidecar
+    Extract {
+        /// Asset to extract the manifest from
+        #[arg(long = "asset")]
+        asset: PathBuf,
+
+        /// Output format (json, cbor or yaml)
+        #[arg(long = "format", default_value = "json")]
+        format: String,
+    },
+
+    /// Verify the manifest embedded in an asset or its .c2pa sidecar
+    VerifyEmbedded {
+        /// Asset to verify
+        #[arg(long = "asset")]
+        asset: PathBuf,
+
+        /// Storage backend used to resolve cross-references
+        #[arg(long = "storage-type", default_value = "database")]
+        storage_type: Box<String>,
+
+        /// Storage URL
+        #[arg(long = "storage-url", default_value = "http://localhost:8080")]
+        storage_url: Box<String>,
+
+        /// Fail instead of warning when a CC attestation cannot be re-validated
+        #[arg(long = "strict-cc", default_value = "false")]
+        strict_cc: bool,
+
+        /// PEM file of trusted TSA certificates used to validate signature timestamps
+        #[arg(long = "tsa-ca")]
+        tsa_ca: Option<PathBuf>,
+
+        /// Warn instead of failing when a cross-reference target is missing
+        #[arg(long = "allow-dangling")]
+        allow_dangling: bool,
+
+        /// Do not download remote ingredients; skip them with a warning
+        #[arg(long = "offline")]
+        offline: bool,
+
+        /// File of trusted signer public keys (PEM) or SHA-256 fingerprints; fail unless every signer is listed
+        #[arg(long = "trust-list")]
+        trust_list: Option<PathBuf>,
+
+        /// Warn instead of failing when the manifest's validity window has ended
+        #[arg(long = "allow-expired")]
+        allow_expired: bool,
+
+        /// JSON file of policy rules (JSONPath expressions) the manifest must satisfy
+        #[arg(long = "policy")]
+        policy: Option<PathBuf>,
+
+        /// Fail instead of warning when an ingredient's recorded format or type does not match its file
+        #[arg(long = "strict")]
+        strict: bool,
+
+        /// Check every ingredient before failing and list all that failed, instead of stopping at the first
+        #[arg(long = "continue-on-error")]
+        continue_on_error: bool,
+    },
 }
 /// Commands for evaluation results
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum EvaluationCommands {
     /// Create a new evaluation result manifest
     Create {
@@ -318,7 +1274,7 @@ pub enum EvaluationCommands {
         #[arg(long = "dataset-id")]
         dataset_id: String,
 
-        /// Evaluation metrics (key=value pairs)
+        /// Evaluation metrics as key=value pairs (numbers keep their type) or @file.json
         #[arg(long = "metrics", num_args = 1.., value_delimiter = ',')]
         metrics: Vec<String>,
 
@@ -334,22 +1290,94 @@ pub enum EvaluationCommands {
         #[arg(long = "description")]
         description: Option<String>,
 
-        /// Path to private key file for signing (PEM format)
+        /// Path to private key file for signing (PEM or DER format; default: ATLAS_CLI_KEY_PATH, then the OS keyring)
         #[arg(long = "key")]
         key: Option<PathBuf>,
 
-        /// Hash algorithm to use for signing (default: sha384)
-        #[arg(long = "hash-alg", value_enum, default_value = "sha384")]
+        /// Sign with a cloud KMS key instead of a key file: awskms://<key>, gcpkms://<key version> or azurekms://<vault>/<key>
+        #[arg(long = "kms-key", conflicts_with = "key")]
+        kms_key: Option<String>,
+
+        /// Hash algorithm to use for signing: sha256, sha384, sha512, sha3-256, sha3-384 or sha3-512 (default: sha384)
+        #[arg(long = "hash-alg", value_parser = HashAlgorithmChoice::from_str, default_value = "sha384")]
         hash_alg: HashAlgorithmChoice,
 
+        /// URL of an RFC 3161 Time Stamp Authority used to timestamp the signature
+        #[arg(long = "tsa-url")]
+        tsa_url: Option<String>,
+
+        /// Reject RSA signing keys smaller than this many bits
+        #[arg(long = "min-key-bits", default_value = "2048")]
+        min_key_bits: u32,
+
+        /// Record the OS, architecture, hostname and CLI version in the manifest
+        #[arg(long = "record-environment")]
+        record_environment: bool,
+
+        /// With --record-environment, leave the hostname out
+        #[arg(long = "redact-hostname", requires = "record_environment")]
+        redact_hostname: bool,
+
+        /// Creative work type to record instead of the default for this asset kind
+        #[arg(long = "creative-type")]
+        creative_type: Option<String>,
+
+        /// Digital source type URI to record instead of the default for this asset kind
+        #[arg(long = "digital-source-type")]
+        digital_source_type: Option<String>,
+
+        /// Validate inputs and report what would be created without hashing or storing
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+
+        /// Store the manifest without validating it against the bundled JSON Schema
+        #[arg(long = "no-schema-check")]
+        no_schema_check: bool,
+
+        /// Tag to record in the manifest, e.g. env=prod (can be repeated)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
         /// Only print manifest without storing
         #[arg(long = "print")]
         print: bool,
 
-        /// Output format (json or cbor)
+        /// Output format (json, cbor or yaml)
         #[arg(long = "format", default_value = "json")]
         format: String,
 
+        /// Write the printed manifest to this file instead of stdout
+        #[arg(long = "output")]
+        output: Option<PathBuf>,
+
+        /// Print CBOR output as hex for debugging
+        #[arg(long = "hex")]
+        hex: bool,
+
+        /// Derive manifest and ingredient IDs from the inputs for reproducible output
+        #[arg(long = "deterministic")]
+        deterministic: bool,
+
+        /// Hash the decompressed content of .gz and .zst inputs so the hash does not depend on compression
+        #[arg(long = "hash-decompressed")]
+        hash_decompressed: bool,
+
+        /// Hash only the size, first and last MiB and 64 sampled blocks of each file: a fast sanity check for huge files, not a full integrity guarantee
+        #[arg(long = "quick-hash")]
+        quick_hash: bool,
+
+        /// Creation time to record (YYYY-MM-DD or RFC 3339) instead of now
+        #[arg(long = "created-at")]
+        created_at: Option<String>,
+
+        /// Start of the period in which the manifest is valid (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "valid-from")]
+        valid_from: Option<String>,
+
+        /// End of the period in which the manifest is valid (YYYY-MM-DD or RFC 3339); verification fails afterwards
+        #[arg(long = "valid-until")]
+        valid_until: Option<String>,
+
         /// Storage backend (local or rekor)
         #[arg(long = "storage-type", default_value = "database")]
         storage_type: Box<String>,
@@ -368,13 +1396,29 @@ pub enum EvaluationCommands {
         /// Storage URL
         #[arg(long = "storage-url", default_value = "http://localhost:8080")]
         storage_url: Box<String>,
+
+        /// Only list manifests with this tag (can be repeated)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Only list manifests created at or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "since")]
+        since: Option<String>,
+
+        /// Only list manifests created before this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "until")]
+        until: Option<String>,
     },
 
     /// Verify an evaluation result manifest
     Verify {
         /// Evaluation result manifest ID to verify
-        #[arg(long = "id")]
-        id: String,
+        #[arg(long = "id", required_unless_present = "file", conflicts_with = "file")]
+        id: Option<String>,
+
+        /// Verify a manifest file (JSON, or CBOR with a .cbor extension) instead of a stored manifest; storage is only used to resolve its cross-references
+        #[arg(long = "file")]
+        file: Option<PathBuf>,
 
         /// Storage backend (local or rekor)
         #[arg(long = "storage-type", default_value = "database")]
@@ -383,6 +1427,42 @@ pub enum EvaluationCommands {
         /// Storage URL
         #[arg(long = "storage-url", default_value = "http://localhost:8080")]
         storage_url: Box<String>,
+
+        /// Fail if a CC attestation cannot be re-validated on this platform
+        #[arg(long = "strict-cc")]
+        strict_cc: bool,
+
+        /// PEM file of trusted TSA certificates used to validate signature timestamps
+        #[arg(long = "tsa-ca")]
+        tsa_ca: Option<PathBuf>,
+
+        /// Warn instead of failing when a cross-reference target is missing
+        #[arg(long = "allow-dangling")]
+        allow_dangling: bool,
+
+        /// Do not download remote ingredients; skip them with a warning
+        #[arg(long = "offline")]
+        offline: bool,
+
+        /// File of trusted signer public keys (PEM) or SHA-256 fingerprints; fail unless every signer is listed
+        #[arg(long = "trust-list")]
+        trust_list: Option<PathBuf>,
+
+        /// Warn instead of failing when the manifest's validity window has ended
+        #[arg(long = "allow-expired")]
+        allow_expired: bool,
+
+        /// JSON file of policy rules (JSONPath expressions) the manifest must satisfy
+        #[arg(long = "policy")]
+        policy: Option<PathBuf>,
+
+        /// Fail instead of warning when an ingredient's recorded format or type does not match its file
+        #[arg(long = "strict")]
+        strict: bool,
+
+        /// Check every ingredient before failing and list all that failed, instead of stopping at the first
+        #[arg(long = "continue-on-error")]
+        continue_on_error: bool,
     },
 }
 
@@ -399,10 +1479,15 @@ pub enum CCAttestationCommands {
         /// VM host platform (determines download command and format)
         #[arg(long = "host-platform", default_value = "gcp-tdx")]
         host_platform: String,
+
+        /// Expected launch measurement (hex) to compare against before verifying
+        #[arg(long = "expected-measurement")]
+        expected_measurement: Option<String>,
     },
 }
 
 #[derive(Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum SoftwareCommands {
     /// Create a new software component manifest
     Create {
@@ -413,6 +1498,14 @@ pub enum SoftwareCommands {
         #[arg(long = "ingredient-names", num_args = 1.., value_delimiter = ',')]
         ingredient_names: Vec<String>,
 
+        /// C2PA relationship of each ingredient (componentOf, parentOf or inputTo; comma-separated, default: componentOf)
+        #[arg(long = "relationships", value_delimiter = ',', value_parser = parse_relationship)]
+        relationships: Vec<String>,
+
+        /// URL to record for each ingredient instead of its local file:// URL, e.g. where it is published (comma-separated; a directory's URL is the base of its files' URLs)
+        #[arg(long = "ingredient-urls", value_delimiter = ',')]
+        ingredient_urls: Vec<String>,
+
         /// Software name
         #[arg(long = "name")]
         name: String,
@@ -441,22 +1534,94 @@ pub enum SoftwareCommands {
         #[arg(long = "linked-manifests")]
         linked_manifests: Option<Vec<String>>,
 
-        /// Path to private key file for signing (PEM format)
+        /// Path to private key file for signing (PEM or DER format; default: ATLAS_CLI_KEY_PATH, then the OS keyring)
         #[arg(long = "key")]
         key: Option<PathBuf>,
 
-        /// Hash algorithm to use for signing (default: sha384)
-        #[arg(long = "hash-alg", value_enum, default_value = "sha384")]
+        /// Sign with a cloud KMS key instead of a key file: awskms://<key>, gcpkms://<key version> or azurekms://<vault>/<key>
+        #[arg(long = "kms-key", conflicts_with = "key")]
+        kms_key: Option<String>,
+
+        /// Hash algorithm to use for signing: sha256, sha384, sha512, sha3-256, sha3-384 or sha3-512 (default: sha384)
+        #[arg(long = "hash-alg", value_parser = HashAlgorithmChoice::from_str, default_value = "sha384")]
         hash_alg: HashAlgorithmChoice,
 
+        /// URL of an RFC 3161 Time Stamp Authority used to timestamp the signature
+        #[arg(long = "tsa-url")]
+        tsa_url: Option<String>,
+
+        /// Reject RSA signing keys smaller than this many bits
+        #[arg(long = "min-key-bits", default_value = "2048")]
+        min_key_bits: u32,
+
+        /// Record the OS, architecture, hostname and CLI version in the manifest
+        #[arg(long = "record-environment")]
+        record_environment: bool,
+
+        /// With --record-environment, leave the hostname out
+        #[arg(long = "redact-hostname", requires = "record_environment")]
+        redact_hostname: bool,
+
+        /// Creative work type to record instead of the default for this asset kind
+        #[arg(long = "creative-type")]
+        creative_type: Option<String>,
+
+        /// Digital source type URI to record instead of the default for this asset kind
+        #[arg(long = "digital-source-type")]
+        digital_source_type: Option<String>,
+
+        /// Validate inputs and report what would be created without hashing or storing
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+
+        /// Store the manifest without validating it against the bundled JSON Schema
+        #[arg(long = "no-schema-check")]
+        no_schema_check: bool,
+
+        /// Tag to record in the manifest, e.g. env=prod (can be repeated)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
         /// Only print manifest without storing
         #[arg(long = "print")]
         print: bool,
 
-        /// Output format (json or cbor)
+        /// Output format (json, cbor or yaml)
         #[arg(long = "format", default_value = "json")]
         format: String,
 
+        /// Write the printed manifest to this file instead of stdout
+        #[arg(long = "output")]
+        output: Option<PathBuf>,
+
+        /// Print CBOR output as hex for debugging
+        #[arg(long = "hex")]
+        hex: bool,
+
+        /// Derive manifest and ingredient IDs from the inputs for reproducible output
+        #[arg(long = "deterministic")]
+        deterministic: bool,
+
+        /// Hash the decompressed content of .gz and .zst inputs so the hash does not depend on compression
+        #[arg(long = "hash-decompressed")]
+        hash_decompressed: bool,
+
+        /// Hash only the size, first and last MiB and 64 sampled blocks of each file: a fast sanity check for huge files, not a full integrity guarantee
+        #[arg(long = "quick-hash")]
+        quick_hash: bool,
+
+        /// Creation time to record (YYYY-MM-DD or RFC 3339) instead of now
+        #[arg(long = "created-at")]
+        created_at: Option<String>,
+
+        /// Start of the period in which the manifest is valid (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "valid-from")]
+        valid_from: Option<String>,
+
+        /// End of the period in which the manifest is valid (YYYY-MM-DD or RFC 3339); verification fails afterwards
+        #[arg(long = "valid-until")]
+        valid_until: Option<String>,
+
         /// Storage backend (local or rekor)
         #[arg(long = "storage-type", default_value = "database")]
         storage_type: Box<String>,
@@ -468,6 +1633,18 @@ pub enum SoftwareCommands {
         /// Collect the underlying CC attestation, if available
         #[arg(long = "with-tdx", default_value = "false")]
         with_tdx: bool,
+
+        /// Force the ingredient asset type (e.g. model.onnx, dataset), overriding extension detection
+        #[arg(long = "asset-type", value_parser = parse_asset_type)]
+        asset_type: Option<AssetType>,
+
+        /// Recursively include the files in directory inputs
+        #[arg(long = "recursive", default_value = "false")]
+        recursive: bool,
+
+        /// Comma-separated names or `*.ext` patterns to skip when walking directories
+        #[arg(long = "ignore", value_delimiter = ',')]
+        ignore: Vec<String>,
     },
     /// List all software component manifests
     List {
@@ -478,12 +1655,28 @@ pub enum SoftwareCommands {
         /// Storage URL
         #[arg(long = "storage-url", default_value = "http://localhost:8080")]
         storage_url: Box<String>,
+
+        /// Only list manifests with this tag (can be repeated)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Only list manifests created at or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "since")]
+        since: Option<String>,
+
+        /// Only list manifests created before this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long = "until")]
+        until: Option<String>,
     },
     /// Verify a software component manifest
     Verify {
         /// Manifest ID to verify
-        #[arg(long = "id")]
-        id: String,
+        #[arg(long = "id", required_unless_present = "file", conflicts_with = "file")]
+        id: Option<String>,
+
+        /// Verify a manifest file (JSON, or CBOR with a .cbor extension) instead of a stored manifest; storage is only used to resolve its cross-references
+        #[arg(long = "file")]
+        file: Option<PathBuf>,
         /// Storage backend (local or rekor)
         #[arg(long = "storage-type", default_value = "database")]
         storage_type: Box<String>,
@@ -491,6 +1684,42 @@ pub enum SoftwareCommands {
         /// Storage URL
         #[arg(long = "storage-url", default_value = "http://localhost:8080")]
         storage_url: Box<String>,
+
+        /// Fail if a CC attestation cannot be re-validated on this platform
+        #[arg(long = "strict-cc")]
+        strict_cc: bool,
+
+        /// PEM file of trusted TSA certificates used to validate signature timestamps
+        #[arg(long = "tsa-ca")]
+        tsa_ca: Option<PathBuf>,
+
+        /// Warn instead of failing when a cross-reference target is missing
+        #[arg(long = "allow-dangling")]
+        allow_dangling: bool,
+
+        /// Do not download remote ingredients; skip them with a warning
+        #[arg(long = "offline")]
+        offline: bool,
+
+        /// File of trusted signer public keys (PEM) or SHA-256 fingerprints; fail unless every signer is listed
+        #[arg(long = "trust-list")]
+        trust_list: Option<PathBuf>,
+
+        /// Warn instead of failing when the manifest's validity window has ended
+        #[arg(long = "allow-expired")]
+        allow_expired: bool,
+
+        /// JSON file of policy rules (JSONPath expressions) the manifest must satisfy
+        #[arg(long = "policy")]
+        policy: Option<PathBuf>,
+
+        /// Fail instead of warning when an ingredient's recorded format or type does not match its file
+        #[arg(long = "strict")]
+        strict: bool,
+
+        /// Check every ingredient before failing and list all that failed, instead of stopping at the first
+        #[arg(long = "continue-on-error")]
+        continue_on_error: bool,
     },
     /// Link software to a model
     LinkModel {
@@ -0,0 +1,330 @@
+//! # Defaults File
+//!
+//! Flags passed to almost every command, such as `--author-org`, `--key` or
+//! `--storage-type`, can be set once in a TOML defaults file instead. Keys
+//! are long flag names without the dashes, and a value applies to every
+//! command that has the flag:
+//!
+//! ```toml
+//! author-org = "Example Org"
+//! key = "/home/me/keys/signing.pem"
+//! storage-type = "local-fs"
+//! storage-url = "/var/lib/atlas/manifests"
+//! tag = ["team=vision"]
+//! ```
+//!
+//! The file is read from `--config <path>` if given, otherwise from
+//! `$XDG_CONFIG_HOME/atlas-cli/defaults.toml` (or
+//! `~/.config/atlas-cli/defaults.toml`) if it exists. Precedence, highest
+//! first: command-line flags, the defaults file, built-in defaults. The file
+//! values become the flags' defaults before parsing, so they are validated
+//! exactly like command-line values.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::cli::defaults::Defaults;
+//! use clap::{Arg, Command};
+//!
+//! let defaults = Defaults::parse("storage-type = \"local-fs\"", None).unwrap();
+//! let command = defaults
+//!     .apply(Command::new("atlas-cli").arg(
+//!         Arg::new("storage_type").long("storage-type").default_value("database"),
+//!     ))
+//!     .unwrap();
+//! let matches = command.get_matches_from(["atlas-cli"]);
+//! assert_eq!(matches.get_one::<String>("storage_type").unwrap(), "local-fs");
+//! ```
+
+use crate::error::{Error, Result};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Command};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Name of the defaults file in the configuration directory
+pub const DEFAULTS_FILE: &str = "defaults.toml";
+
+/// Flag values read from a defaults file, keyed by long flag name
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Defaults {
+    /// File the values were read from; `None` if no file was loaded
+    pub path: Option<PathBuf>,
+    pub values: BTreeMap<String, Vec<String>>,
+}
+
+impl Defaults {
+    /// Loads the defaults file named by `--config`, or the default file if it
+    /// exists. An explicitly named file must exist.
+    pub fn load(explicit: Option<&Path>) -> Result<Self> {
+        let path = match explicit {
+            Some(path) => path.to_path_buf(),
+            None => match default_path() {
+                Some(path) if path.is_file() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            Error::Validation(format!("Cannot read defaults file {}: {e}", path.display()))
+        })?;
+        Self::parse(&content, Some(path))
+    }
+
+    /// Parses the content of a defaults file
+    pub fn parse(content: &str, path: Option<PathBuf>) -> Result<Self> {
+        let source = path
+            .as_deref()
+            .map_or("defaults".to_string(), |path| path.display().to_string());
+        let table: toml::Table = toml::from_str(content)
+            .map_err(|e| Error::Validation(format!("Invalid defaults file {source}: {e}")))?;
+
+        let mut values = BTreeMap::new();
+        for (key, value) in table {
+            let invalid = || {
+                Error::Validation(format!(
+                    "Invalid value for '{key}' in {source}: expected a string, number, boolean or array of them"
+                ))
+            };
+            let value = match value {
+                toml::Value::Array(items) => items
+                    .into_iter()
+                    .map(|item| scalar_to_string(item).ok_or_else(invalid))
+                    .collect::<Result<Vec<_>>>()?,
+                scalar => vec![scalar_to_string(scalar).ok_or_else(invalid)?],
+            };
+            values.insert(key, value);
+        }
+        Ok(Self { path, values })
+    }
+
+    /// Sets the file values as the defaults of the matching flags of
+    /// `command` and all its subcommands.
+    ///
+    /// Fails if a key matches no flag, so typos are not silently ignored.
+    pub fn apply(&self, command: Command) -> Result<Command> {
+        let mut known = BTreeSet::new();
+        collect_long_flags(&command, &mut known);
+        // The file cannot name another defaults file
+        known.remove("config");
+        if let Some(unknown) = self.values.keys().find(|key| !known.contains(key.as_str())) {
+            return Err(Error::Validation(format!(
+                "Unknown setting '{unknown}' in {}: keys are long flag names such as author-org",
+                self.source()
+            )));
+        }
+        Ok(self.apply_to(command))
+    }
+
+    fn apply_to(&self, command: Command) -> Command {
+        let subcommands: Vec<String> = command
+            .get_subcommands()
+            .map(|subcommand| subcommand.get_name().to_string())
+            .collect();
+        let mut command =
+            command.mut_args(
+                |arg| match arg.get_long().and_then(|long| self.values.get(long)) {
+                    Some(values) => arg.default_values(values.clone()),
+                    None => arg,
+                },
+            );
+        for name in subcommands {
+            command = command.mut_subcommand(name, |subcommand| self.apply_to(subcommand));
+        }
+        command
+    }
+
+    fn source(&self) -> String {
+        self.path
+            .as_deref()
+            .map_or("the defaults file".to_string(), |path| {
+                path.display().to_string()
+            })
+    }
+}
+
+fn scalar_to_string(value: toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn collect_long_flags(command: &Command, known: &mut BTreeSet<String>) {
+    known.extend(
+        command
+            .get_arguments()
+            .filter_map(|arg| arg.get_long().map(str::to_string)),
+    );
+    for subcommand in command.get_subcommands() {
+        collect_long_flags(subcommand, known);
+    }
+}
+
+/// `$XDG_CONFIG_HOME/atlas-cli/defaults.toml`, falling back to
+/// `~/.config/atlas-cli/defaults.toml`
+pub fn default_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("atlas-cli").join(DEFAULTS_FILE))
+}
+
+/// Finds the value of `--config` in raw arguments, which must be known
+/// before the command line can be parsed with the file's defaults
+pub fn config_path_from_args(args: &[OsString]) -> Option<PathBuf> {
+    let mut args = args.iter().skip(1).map(|arg| arg.to_string_lossy());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == "--config" {
+            return args.next().map(|path| PathBuf::from(path.as_ref()));
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Renders the effective settings of a command: every flag with a value,
+/// and whether it came from the command line, the defaults file or the
+/// built-in default.
+///
+/// `matches` are the parsed matches of `command`, if it was the one run.
+pub fn format_effective(
+    command: &Command,
+    matches: Option<&ArgMatches>,
+    defaults: &Defaults,
+) -> String {
+    let mut out = String::new();
+    for arg in command.get_arguments() {
+        let Some(long) = arg.get_long() else {
+            continue;
+        };
+        if long == "help" || long == "version" {
+            continue;
+        }
+        let id = arg.get_id().as_str();
+        let from_command_line = matches
+            .is_some_and(|matches| matches.value_source(id) == Some(ValueSource::CommandLine));
+        let values: Vec<String> = match matches.filter(|_| from_command_line) {
+            Some(matches) => matches
+                .get_raw(id)
+                .into_iter()
+                .flatten()
+                .map(|value| value.to_string_lossy().to_string())
+                .collect(),
+            None => arg
+                .get_default_values()
+                .iter()
+                .map(|value| value.to_string_lossy().to_string())
+                .collect(),
+        };
+        let source = if from_command_line {
+            "command line"
+        } else if defaults.values.contains_key(long) {
+            "defaults file"
+        } else if values.is_empty() {
+            continue;
+        } else {
+            "built-in default"
+        };
+        out.push_str(&format!("--{long} = {} ({source})\n", values.join(",")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::commands::ModelCommands;
+    use clap::{FromArgMatches, Subcommand};
+
+    fn atlas_command() -> Command {
+        Command::new("atlas-cli").subcommand(ModelCommands::augment_subcommands(
+            Command::new("model").subcommand_required(true),
+        ))
+    }
+
+    #[test]
+    fn test_defaults_precedence() -> Result<()> {
+        let defaults = Defaults::parse(
+            r#"
+            author-org = "File Org"
+            storage-type = "local-fs"
+            min-key-bits = 3072
+            tag = ["team=vision", "env=dev"]
+            "#,
+            None,
+        )?;
+        let command = defaults.apply(atlas_command())?;
+
+        let matches = command.try_get_matches_from([
+            "atlas-cli",
+            "model",
+            "create",
+            "--paths=model.onnx",
+            "--ingredient-names=Model",
+            "--name=Model",
+            "--storage-type=database",
+        ]);
+        let matches = matches.unwrap();
+        let (_, model) = matches.subcommand().unwrap();
+        let ModelCommands::Create {
+            author_org,
+            storage_type,
+            min_key_bits,
+            tags,
+            ..
+        } = ModelCommands::from_arg_matches(model).unwrap()
+        else {
+            panic!("expected model create");
+        };
+        // File values replace built-in defaults; flags override both
+        assert_eq!(author_org.as_deref(), Some("File Org"));
+        assert_eq!(min_key_bits, 3072);
+        assert_eq!(tags, ["team=vision", "env=dev"]);
+        assert_eq!(*storage_type, "database");
+
+        let (_, create) = model.subcommand().unwrap();
+        let model_create = atlas_command()
+            .find_subcommand("model")
+            .and_then(|model| model.find_subcommand("create"))
+            .cloned()
+            .unwrap();
+        let effective = format_effective(&defaults.apply(model_create)?, Some(create), &defaults);
+        assert!(effective.contains("--author-org = File Org (defaults file)"));
+        assert!(effective.contains("--storage-type = database (command line)"));
+        assert!(effective.contains("--hash-alg = sha384 (built-in default)"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_defaults_reject_unknown_keys() -> Result<()> {
+        let defaults = Defaults::parse("author-organisation = \"Typo\"", None)?;
+        assert!(defaults.apply(atlas_command()).is_err());
+        assert!(Defaults::parse("author-org = { name = \"x\" }", None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_path_from_args() {
+        let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(
+            config_path_from_args(&args(&["atlas-cli", "--config", "a.toml", "model"])),
+            Some(PathBuf::from("a.toml"))
+        );
+        assert_eq!(
+            config_path_from_args(&args(&["atlas-cli", "model", "--config=b.toml"])),
+            Some(PathBuf::from("b.toml"))
+        );
+        assert_eq!(config_path_from_args(&args(&["atlas-cli", "model"])), None);
+    }
+}
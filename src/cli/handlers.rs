@@ -1,25 +1,39 @@
 use crate::error::{Error, Result};
 
 use super::commands::{
-    CCAttestationCommands, DatasetCommands, EvaluationCommands, ManifestCommands, ModelCommands,
+    AuditCommands, BatchCommands, CCAttestationCommands, ConfigCommands, DatasetCommands,
+    EvaluationCommands, KeygenArgs, ManifestCommands, MigrateArgs, ModelCommands, PubkeyArgs,
     SoftwareCommands,
 };
+use super::defaults::{self, Defaults};
+use super::progress;
+use crate::audit::{self, AuditLog};
 use crate::cc_attestation;
 use crate::manifest;
-use crate::manifest::config::ManifestCreationConfig;
+use crate::manifest::config::{ManifestCreationConfig, VerificationConfig};
 use crate::manifest::dataset::list_dataset_manifests;
-use crate::storage::database::DatabaseStorage;
-use crate::storage::filesystem::FilesystemStorage;
+use crate::manifest::report::{CheckStatus, VerificationReport};
+use crate::signing;
+use crate::signing::kms::KmsSigner;
+use crate::signing::signer::Signer;
+use crate::storage;
+use crate::storage::create_storage;
 use crate::storage::rekor::RekorStorage;
+use crate::storage::traits::{ManifestFilter, parse_time_bound};
 
-use crate::StorageBackend;
+use crate::{Config, StorageBackend};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
-pub fn handle_dataset_command(cmd: DatasetCommands) -> Result<()> {
+pub fn handle_dataset_command(cmd: DatasetCommands, cli_config: &Config) -> Result<()> {
     let _storage = RekorStorage::new()?;
     match cmd {
         DatasetCommands::Create {
             paths,
             ingredient_names,
+            relationships,
+            ingredient_urls,
             name,
             author_org,
             author_name,
@@ -29,25 +43,39 @@ pub fn handle_dataset_command(cmd: DatasetCommands) -> Result<()> {
             storage_url,
             print,
             format,
+            output,
+            hex,
+            deterministic,
+            hash_decompressed,
+            quick_hash,
+            created_at,
+            valid_from,
+            valid_until,
             key,
+            kms_key,
             hash_alg,
+            tsa_url,
+            min_key_bits,
+            record_environment,
+            redact_hostname,
+            creative_type,
+            digital_source_type,
+            dry_run,
+            no_schema_check,
+            no_sniff,
+            tags,
             with_tdx,
+            asset_type,
+            recursive,
+            ignore,
         } => {
-            let storage: Option<&'static dyn StorageBackend> = match storage_type.as_str() {
-                "database" => {
-                    let db_storage = Box::new(DatabaseStorage::new(*storage_url.clone())?);
-                    Some(Box::leak(db_storage))
-                }
-                "rekor" => {
-                    let rekor_storage = Box::new(RekorStorage::new_with_url(*storage_url.clone())?);
-                    Some(Box::leak(rekor_storage))
-                }
-                "local-fs" => {
-                    let fs_storage = Box::new(FilesystemStorage::new(storage_url.as_str())?);
-                    Some(Box::leak(fs_storage))
-                }
-                _ => None,
-            };
+            let (paths, ingredient_names) = expand_path_patterns(paths, ingredient_names)?;
+
+            let storage: Option<&'static dyn StorageBackend> = Some(Box::leak(create_storage(
+                &storage_type,
+                *storage_url,
+                &cli_config.storage_config,
+            )?));
 
             let config = ManifestCreationConfig {
                 paths,
@@ -60,12 +88,46 @@ pub fn handle_dataset_command(cmd: DatasetCommands) -> Result<()> {
                 storage,
                 print,
                 output_format: format,
-                key_path: key,
-                hash_alg: hash_alg.to_cose_algorithm(),
+                key_path: signing_key_path(key.as_deref(), kms_key.as_deref())?,
+                hash_alg: hash_alg.to_digest_algorithm(),
                 with_cc: with_tdx,
                 software_type: None,
                 version: None,
                 custom_fields: None,
+                asset_type,
+                recursive,
+                ignore_patterns: ignore,
+                show_progress: progress::is_enabled(cli_config),
+                tsa_url,
+                dry_run,
+                tags,
+                min_key_bits,
+                creative_type_override: creative_type,
+                digital_source_type_override: digital_source_type,
+                skip_schema_check: no_schema_check,
+                no_sniff,
+                output_path: output,
+                hex_output: hex,
+                deterministic,
+                hash_decompressed,
+                created_at: created_at.as_deref().map(parse_time_bound).transpose()?,
+                ingredient_relationships: relationships,
+                ingredient_urls,
+                base_manifests: Vec::new(),
+                claim_generator: None,
+                valid_from: valid_from.as_deref().map(parse_time_bound).transpose()?,
+                valid_until: valid_until.as_deref().map(parse_time_bound).transpose()?,
+                quick_hash,
+                follow_symlinks: cli_config.follow_symlinks,
+                trained_on: Vec::new(),
+                record_environment,
+                redact_hostname,
+                signer: kms_signer(kms_key.as_deref())?,
+                model_card: None,
+                model_card_hash: None,
+                symlink_roots: cli_config.symlink_roots.clone(),
+                hash_chunk_size: cli_config.hash_chunk_size,
+                audit_log: cli_config.audit_log.clone(),
             };
 
             manifest::create_dataset_manifest(config)
@@ -73,67 +135,113 @@ pub fn handle_dataset_command(cmd: DatasetCommands) -> Result<()> {
         DatasetCommands::List {
             storage_type,
             storage_url,
+            tags,
+            since,
+            until,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            let filter = ManifestFilter::new(tags, since.as_deref(), until.as_deref())?;
 
-            list_dataset_manifests(storage.as_ref())
+            list_dataset_manifests(storage.as_ref(), &filter)
         }
         DatasetCommands::Verify {
             id,
+            file,
             storage_type,
             storage_url,
+            strict_cc,
+            tsa_ca,
+            allow_dangling,
+            offline,
+            trust_list,
+            allow_expired,
+            policy,
+            strict,
+            continue_on_error,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
-            manifest::verify_dataset_manifest(&id, storage.as_ref())
+            let config = VerificationConfig {
+                strict_cc,
+                tsa_ca,
+                allow_dangling,
+                offline,
+                trust_list,
+                allow_expired,
+                policy,
+                strict,
+                continue_on_error,
+                follow_symlinks: cli_config.follow_symlinks,
+                symlink_roots: cli_config.symlink_roots.clone(),
+            };
+            let report = verify_stored_or_file(
+                id,
+                file,
+                storage.as_ref(),
+                &config,
+                manifest::verify_dataset_manifest_with_config,
+                cli_config.audit_log.as_deref(),
+            )?;
+            print_verification_report(&report, cli_config);
+            Ok(())
         }
     }
 }
 
-pub fn handle_model_command(cmd: ModelCommands) -> Result<()> {
+pub fn handle_model_command(cmd: ModelCommands, cli_config: &Config) -> Result<()> {
     let _storage = RekorStorage::new()?;
     match cmd {
         ModelCommands::Create {
             paths,
             ingredient_names,
+            relationships,
+            ingredient_urls,
             name,
             author_org,
             author_name,
             description,
             linked_manifests,
+            trained_on,
+            model_card,
+            model_card_hash,
             storage_type,
             storage_url,
             print,
             format,
+            output,
+            hex,
+            deterministic,
+            hash_decompressed,
+            quick_hash,
+            created_at,
+            valid_from,
+            valid_until,
             key,
+            kms_key,
             hash_alg,
+            tsa_url,
+            min_key_bits,
+            record_environment,
+            redact_hostname,
+            creative_type,
+            digital_source_type,
+            dry_run,
+            no_schema_check,
+            no_sniff,
+            tags,
             with_tdx,
+            asset_type,
+            recursive,
+            ignore,
         } => {
-            let storage: Option<&'static dyn StorageBackend> = match storage_type.as_str() {
-                "database" => {
-                    let db_storage = Box::new(DatabaseStorage::new(*storage_url.clone())?);
-                    Some(Box::leak(db_storage))
-                }
-                "rekor" => {
-                    let rekor_storage = Box::new(RekorStorage::new_with_url(*storage_url.clone())?);
-                    Some(Box::leak(rekor_storage))
-                }
-                "local-fs" => {
-                    let fs_storage = Box::new(FilesystemStorage::new(storage_url.as_str())?);
-                    Some(Box::leak(fs_storage))
-                }
-                _ => None,
-            };
+            let (paths, ingredient_names) = expand_path_patterns(paths, ingredient_names)?;
+
+            let storage: Option<&'static dyn StorageBackend> = Some(Box::leak(create_storage(
+                &storage_type,
+                *storage_url,
+                &cli_config.storage_config,
+            )?));
 
             let config = ManifestCreationConfig {
                 paths,
@@ -146,42 +254,187 @@ pub fn handle_model_command(cmd: ModelCommands) -> Result<()> {
                 storage,
                 print,
                 output_format: format,
-                key_path: key,
-                hash_alg: hash_alg.to_cose_algorithm(),
+                key_path: signing_key_path(key.as_deref(), kms_key.as_deref())?,
+                hash_alg: hash_alg.to_digest_algorithm(),
                 with_cc: with_tdx,
                 software_type: None,
                 version: None,
                 custom_fields: None,
+                asset_type,
+                recursive,
+                ignore_patterns: ignore,
+                show_progress: progress::is_enabled(cli_config),
+                tsa_url,
+                dry_run,
+                tags,
+                min_key_bits,
+                creative_type_override: creative_type,
+                digital_source_type_override: digital_source_type,
+                skip_schema_check: no_schema_check,
+                no_sniff,
+                output_path: output,
+                hex_output: hex,
+                deterministic,
+                hash_decompressed,
+                created_at: created_at.as_deref().map(parse_time_bound).transpose()?,
+                ingredient_relationships: relationships,
+                ingredient_urls,
+                base_manifests: Vec::new(),
+                claim_generator: None,
+                valid_from: valid_from.as_deref().map(parse_time_bound).transpose()?,
+                valid_until: valid_until.as_deref().map(parse_time_bound).transpose()?,
+                quick_hash,
+                follow_symlinks: cli_config.follow_symlinks,
+                trained_on,
+                record_environment,
+                redact_hostname,
+                signer: kms_signer(kms_key.as_deref())?,
+                model_card,
+                model_card_hash,
+                symlink_roots: cli_config.symlink_roots.clone(),
+                hash_chunk_size: cli_config.hash_chunk_size,
+                audit_log: cli_config.audit_log.clone(),
             };
 
             manifest::create_model_manifest(config)
         }
-        ModelCommands::List {
+        ModelCommands::Derive {
+            base,
+            paths,
+            ingredient_names,
+            name,
+            author_org,
+            author_name,
+            description,
+            linked_manifests,
+            trained_on,
+            model_card,
+            model_card_hash,
+            key,
+            kms_key,
+            hash_alg,
+            min_key_bits,
+            record_environment,
+            redact_hostname,
+            tags,
+            no_schema_check,
             storage_type,
             storage_url,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
+            let storage: &'static dyn StorageBackend = Box::leak(create_storage(
+                &storage_type,
+                *storage_url,
+                &cli_config.storage_config,
+            )?);
+
+            let config = ManifestCreationConfig {
+                paths,
+                ingredient_names,
+                name,
+                author_org,
+                author_name,
+                description,
+                linked_manifests,
+                base_manifests: base,
+                storage: Some(storage),
+                print: false,
+                output_format: "json".to_string(),
+                key_path: signing_key_path(key.as_deref(), kms_key.as_deref())?,
+                hash_alg: hash_alg.to_digest_algorithm(),
+                with_cc: false,
+                software_type: None,
+                version: None,
+                custom_fields: None,
+                asset_type: None,
+                recursive: false,
+                ignore_patterns: Vec::new(),
+                show_progress: progress::is_enabled(cli_config),
+                tsa_url: None,
+                dry_run: false,
+                tags,
+                min_key_bits,
+                creative_type_override: None,
+                digital_source_type_override: None,
+                skip_schema_check: no_schema_check,
+                no_sniff: false,
+                output_path: None,
+                hex_output: false,
+                deterministic: false,
+                created_at: None,
+                hash_decompressed: false,
+                ingredient_relationships: Vec::new(),
+                claim_generator: None,
+                valid_from: None,
+                valid_until: None,
+                quick_hash: false,
+                follow_symlinks: cli_config.follow_symlinks,
+                trained_on,
+                ingredient_urls: Vec::new(),
+                record_environment,
+                redact_hostname,
+                signer: kms_signer(kms_key.as_deref())?,
+                model_card,
+                model_card_hash,
+                symlink_roots: cli_config.symlink_roots.clone(),
+                hash_chunk_size: cli_config.hash_chunk_size,
+                audit_log: cli_config.audit_log.clone(),
             };
 
-            manifest::list_model_manifest(storage.as_ref())
+            manifest::create_model_manifest(config)
+        }
+        ModelCommands::List {
+            storage_type,
+            storage_url,
+            tags,
+            since,
+            until,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            let filter = ManifestFilter::new(tags, since.as_deref(), until.as_deref())?;
+
+            manifest::list_model_manifest(storage.as_ref(), &filter)
         }
         ModelCommands::Verify {
             id,
+            file,
             storage_type,
             storage_url,
+            strict_cc,
+            tsa_ca,
+            allow_dangling,
+            offline,
+            trust_list,
+            allow_expired,
+            policy,
+            strict,
+            continue_on_error,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
-            manifest::verify_model_manifest(&id, storage.as_ref())
+            let config = VerificationConfig {
+                strict_cc,
+                tsa_ca,
+                allow_dangling,
+                offline,
+                trust_list,
+                allow_expired,
+                policy,
+                strict,
+                continue_on_error,
+                follow_symlinks: cli_config.follow_symlinks,
+                symlink_roots: cli_config.symlink_roots.clone(),
+            };
+            let report = verify_stored_or_file(
+                id,
+                file,
+                storage.as_ref(),
+                &config,
+                manifest::verify_model_manifest_with_config,
+                cli_config.audit_log.as_deref(),
+            )?;
+            print_verification_report(&report, cli_config);
+            Ok(())
         }
         ModelCommands::LinkDataset {
             model_id,
@@ -189,12 +442,7 @@ pub fn handle_model_command(cmd: ModelCommands) -> Result<()> {
             storage_type,
             storage_url,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
             let updated_manifest =
                 manifest::linking::link_dataset_to_model(&model_id, &dataset_id, storage.as_ref())?;
@@ -207,7 +455,7 @@ pub fn handle_model_command(cmd: ModelCommands) -> Result<()> {
     }
 }
 
-pub fn handle_manifest_command(cmd: ManifestCommands) -> Result<()> {
+pub fn handle_manifest_command(cmd: ManifestCommands, cli_config: &Config) -> Result<()> {
     match cmd {
         ManifestCommands::Link {
             source,
@@ -215,42 +463,148 @@ pub fn handle_manifest_command(cmd: ManifestCommands) -> Result<()> {
             storage_type,
             storage_url,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            audit::audited(
+                cli_config.audit_log.as_deref(),
+                "link",
+                Some(&source),
+                manifest::link_manifests(&source, &target, &*storage),
+            )
+        }
+        ManifestCommands::Sign {
+            id,
+            key,
+            identity,
+            hash_alg,
+            min_key_bits,
+            storage_type,
+            storage_url,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            let key = signing::key_path::resolve_key_path(key.as_deref())?;
+            let private_key = signing::load_private_key_with_min_bits(&key, min_key_bits)?;
+            let mut manifest = storage.retrieve_manifest(&id)?;
+            manifest::signatures::add_signature_with_algorithm(
+                &mut manifest,
+                &private_key,
+                &identity,
+                hash_alg.to_digest_algorithm(),
+            )?;
+            let updated_id = audit::audited(
+                cli_config.audit_log.as_deref(),
+                "sign",
+                Some(&id),
+                storage.update_manifest(&manifest),
+            )?;
+            println!("Manifest {updated_id} countersigned by {identity}");
+            Ok(())
+        }
+        ManifestCommands::Refresh {
+            id,
+            key,
+            hash_alg,
+            min_key_bits,
+            confirm,
+            storage_type,
+            storage_url,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            let mut manifest = storage.retrieve_manifest(&id)?;
+            let preview = manifest::refresh::find_hash_changes(&manifest)?;
+            for title in &preview.skipped {
+                log::warn!("Skipping {title}: not a local file");
+            }
+            if preview.changes.is_empty() {
+                println!("All ingredient hashes of manifest {id} are up to date");
+                return Ok(());
+            }
+            println!("Changed ingredient hashes:");
+            for change in &preview.changes {
+                println!(
+                    "  {}: {} -> {}",
+                    change.title, change.old_hash, change.new_hash
+                );
+            }
+            if !confirm {
+                return Err(Error::Validation(
+                    "Re-run with --confirm to store the refreshed manifest".to_string(),
+                ));
+            }
+
+            // Only sign a manifest that was signed before, unless a key is given
+            let signed = manifest
+                .claim_v2
+                .as_ref()
+                .unwrap_or(&manifest.claim)
+                .signature
+                .is_some();
+            let private_key = if signed || key.is_some() {
+                match signing::key_path::find_key_path(key.as_deref())? {
+                    Some((path, _)) => Some(signing::load_private_key_with_min_bits(
+                        &path,
+                        min_key_bits,
+                    )?),
+                    None => None,
+                }
+            } else {
+                None
             };
+            let algorithm = hash_alg.to_digest_algorithm();
+            let summary = manifest::refresh::refresh_ingredient_hashes(
+                &mut manifest,
+                private_key.as_ref().map(|key| (key, algorithm)),
+            )?;
 
-            manifest::link_manifests(&source, &target, &*storage)
+            if summary.removed_countersignatures > 0 {
+                println!(
+                    "Removed {} countersignature(s) covering the old hashes",
+                    summary.removed_countersignatures
+                );
+            }
+            let updated_id = audit::audited(
+                cli_config.audit_log.as_deref(),
+                "refresh",
+                Some(&id),
+                storage.update_manifest(&manifest),
+            )?;
+            println!(
+                "Manifest {updated_id} refreshed: {} ingredient hash(es) updated",
+                summary.changes.len()
+            );
+            Ok(())
         }
         ManifestCommands::Show {
             id,
+            format,
             storage_type,
             storage_url,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
-            manifest::show_manifest(&id, &*storage)
+            manifest::show_manifest_with_format(&id, &*storage, &format)
         }
         ManifestCommands::Validate {
             id,
             storage_type,
             storage_url,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
-            manifest::validate_linked_manifests(&id, &*storage)
+            let checks = manifest::validate_linked_manifests(&id, &*storage)?;
+            if checks.is_empty() {
+                println!("No cross-references found in manifest");
+            }
+            for check in &checks {
+                let glyph = match check.status {
+                    CheckStatus::Verified => "✓",
+                    CheckStatus::Warning => "⚠",
+                };
+                println!("{glyph} {}: {}", check.subject, check.message);
+            }
+            Ok(())
         }
         ManifestCommands::VerifyLink {
             source,
@@ -258,21 +612,103 @@ pub fn handle_manifest_command(cmd: ManifestCommands) -> Result<()> {
             storage_type,
             storage_url,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
             let result = manifest::verify_manifest_link(&source, &target, &*storage)?;
             if result {
-                println!("Link verification successful");
+                log::info!("Link verification successful");
                 Ok(())
             } else {
                 Err(Error::Validation("Link verification failed".to_string()))
             }
         }
+        ManifestCommands::VerifyAll {
+            storage_type,
+            storage_url,
+            strict_cc,
+            tsa_ca,
+            allow_dangling,
+            offline,
+            trust_list,
+            allow_expired,
+            policy,
+            strict,
+            continue_on_error,
+            signature_only,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            let config = VerificationConfig {
+                strict_cc,
+                tsa_ca,
+                allow_dangling,
+                offline,
+                trust_list,
+                allow_expired,
+                policy,
+                strict,
+                continue_on_error,
+                follow_symlinks: cli_config.follow_symlinks,
+                symlink_roots: cli_config.symlink_roots.clone(),
+            };
+            let summary = if signature_only {
+                manifest::common::verify_all_signatures(storage.as_ref(), &config)?
+            } else {
+                manifest::common::verify_all_with_config(storage.as_ref(), &config)?
+            };
+
+            println!(
+                "Verified {} manifests: {} passed, {} failed",
+                summary.total,
+                summary.passed,
+                summary.failed.len()
+            );
+            for (id, error) in &summary.failed {
+                println!("  ✗ {id}: {error}");
+            }
+
+            let outcome = if summary.all_passed() {
+                Ok(())
+            } else {
+                Err(Error::Validation(format!(
+                    "{} of {} manifests failed verification",
+                    summary.failed.len(),
+                    summary.total
+                )))
+            };
+            audit::audited(cli_config.audit_log.as_deref(), "verify-all", None, outcome)
+        }
+        ManifestCommands::Prune {
+            manifest_type,
+            ids,
+            storage_type,
+            storage_url,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            let deleted = match manifest_type {
+                Some(manifest_type) => audit::audited(
+                    cli_config.audit_log.as_deref(),
+                    &format!("delete-type:{manifest_type}"),
+                    None,
+                    storage.delete_by_type(&manifest_type.parse()?),
+                )?,
+                None => {
+                    let deleted = storage.delete_many(&ids);
+                    for id in &ids {
+                        audit::record(
+                            cli_config.audit_log.as_deref(),
+                            "delete",
+                            Some(id),
+                            &deleted,
+                        )?;
+                    }
+                    deleted?
+                }
+            };
+            println!("Deleted {deleted} manifest(s)");
+            Ok(())
+        }
         ManifestCommands::Export {
             id,
             storage_type,
@@ -281,12 +717,7 @@ pub fn handle_manifest_command(cmd: ManifestCommands) -> Result<()> {
             output,
             max_depth,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
             manifest::export_provenance(
                 &id,
@@ -296,10 +727,245 @@ pub fn handle_manifest_command(cmd: ManifestCommands) -> Result<()> {
                 max_depth,
             )
         }
+        ManifestCommands::Lineage {
+            id,
+            format,
+            storage_type,
+            storage_url,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+            let lineage = manifest::lineage::build_lineage(&id, storage.as_ref())?;
+            match format.to_lowercase().as_str() {
+                "text" => print!("{}", manifest::lineage::format_lineage(&lineage)),
+                "json" => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&lineage)
+                        .map_err(|e| Error::Serialization(e.to_string()))?
+                ),
+                _ => {
+                    return Err(Error::Validation(format!(
+                        "Invalid output format '{format}'. Valid options are: text, json"
+                    )));
+                }
+            }
+            Ok(())
+        }
+        ManifestCommands::Stats {
+            format,
+            storage_type,
+            storage_url,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+            let stats = storage::stats::collect_stats(storage.as_ref())?;
+            match format.to_lowercase().as_str() {
+                "text" => print!("{}", storage::stats::format_stats(&stats)),
+                "json" => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&stats)
+                        .map_err(|e| Error::Serialization(e.to_string()))?
+                ),
+                _ => {
+                    return Err(Error::Validation(format!(
+                        "Invalid output format '{format}'. Valid options are: text, json"
+                    )));
+                }
+            }
+            Ok(())
+        }
+        ManifestCommands::Diff {
+            old,
+            new,
+            format,
+            include_volatile,
+            storage_type,
+            storage_url,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            manifest::diff::diff_stored_manifests(&old, &new, &*storage, include_volatile, &format)
+                .map(|_| ())
+        }
+        ManifestCommands::ExportBundle {
+            id,
+            out_dir,
+            storage_type,
+            storage_url,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            manifest::export_bundle(&id, &*storage, &out_dir).map(|_| ())
+        }
+        ManifestCommands::ExportChecksums {
+            id,
+            alg,
+            output,
+            storage_type,
+            storage_url,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+            let stored = storage.retrieve_manifest(&id)?;
+            let checksums =
+                manifest::checksums::export_checksums(&stored, alg.to_digest_algorithm());
+            for (title, reason) in &checksums.skipped {
+                log::warn!("Skipping ingredient {title}: it {reason}");
+            }
+            if checksums.listed == 0 {
+                return Err(Error::Validation(format!(
+                    "Manifest {id} has no ingredients with a {} hash of a local file",
+                    alg.to_digest_algorithm().as_str()
+                )));
+            }
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, &checksums.content)?;
+                    println!("Wrote {} checksums to {}", checksums.listed, path.display());
+                }
+                None => print!("{}", checksums.content),
+            }
+            Ok(())
+        }
+        ManifestCommands::Watch {
+            id,
+            debounce_ms,
+            interval_ms,
+            storage_type,
+            storage_url,
+            allow_dangling,
+            strict,
+            continue_on_error,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+            let config = VerificationConfig {
+                allow_dangling,
+                strict,
+                continue_on_error,
+                follow_symlinks: cli_config.follow_symlinks,
+                ..Default::default()
+            };
+            watch_manifest(
+                &id,
+                storage.as_ref(),
+                &config,
+                Duration::from_millis(interval_ms),
+                Duration::from_millis(debounce_ms),
+            )
+        }
+        ManifestCommands::Import {
+            file,
+            format,
+            verify_signatures,
+            trust_list,
+            storage_type,
+            storage_url,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+            let imported = manifest::import_manifest(
+                &file,
+                format.as_deref(),
+                storage.as_ref(),
+                verify_signatures,
+                trust_list.as_deref(),
+            );
+            let imported_id = imported.as_ref().ok().cloned();
+            let id = audit::audited(
+                cli_config.audit_log.as_deref(),
+                "import",
+                imported_id.as_deref(),
+                imported,
+            )?;
+            println!("Manifest imported with ID: {id}");
+            Ok(())
+        }
+        ManifestCommands::Embed {
+            asset,
+            id,
+            manifest: manifest_file,
+            output,
+            sidecar,
+            storage_type,
+            storage_url,
+        } => {
+            let manifest = match (id, manifest_file) {
+                (Some(id), _) => {
+                    let storage =
+                        create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+                    storage.retrieve_manifest(&id)?
+                }
+                (None, Some(path)) => {
+                    let content = std::fs::read_to_string(&path)?;
+                    serde_json::from_str(&content)
+                        .map_err(Error::from)
+                        .and_then(manifest::version::migrate_manifest)
+                        .map_err(|e| {
+                            Error::Validation(format!(
+                                "Invalid manifest file {}: {e}",
+                                path.display()
+                            ))
+                        })?
+                }
+                (None, None) => {
+                    return Err(Error::Validation(
+                        "Either --id or --manifest is required".to_string(),
+                    ));
+                }
+            };
+
+            if sidecar {
+                let path = manifest::embed::write_sidecar(&asset, &manifest)?;
+                println!("Manifest written to sidecar: {}", path.display());
+            } else {
+                let output = output.unwrap_or_else(|| asset.clone());
+                let format = manifest::embed::embed_manifest(&asset, &manifest, &output)?;
+                println!("Manifest embedded in {} ({format:?})", output.display());
+            }
+            Ok(())
+        }
+        ManifestCommands::Extract { asset, format } => {
+            let (manifest, _) = manifest::embed::extract_manifest(&asset)?;
+            println!(
+                "{}",
+                manifest::common::serialize_manifest(&manifest, &format)?
+            );
+            Ok(())
+        }
+        ManifestCommands::VerifyEmbedded {
+            asset,
+            storage_type,
+            storage_url,
+            strict_cc,
+            tsa_ca,
+            allow_dangling,
+            offline,
+            trust_list,
+            allow_expired,
+            policy,
+            strict,
+            continue_on_error,
+        } => {
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            let config = VerificationConfig {
+                strict_cc,
+                tsa_ca,
+                allow_dangling,
+                offline,
+                trust_list,
+                allow_expired,
+                policy,
+                strict,
+                continue_on_error,
+                follow_symlinks: cli_config.follow_symlinks,
+                symlink_roots: cli_config.symlink_roots.clone(),
+            };
+            let report =
+                manifest::embed::verify_embedded_manifest(&asset, storage.as_ref(), &config)?;
+            print_verification_report(&report, cli_config);
+            Ok(())
+        }
     }
 }
 
-pub fn handle_evaluation_command(cmd: EvaluationCommands) -> Result<()> {
+pub fn handle_evaluation_command(cmd: EvaluationCommands, cli_config: &Config) -> Result<()> {
     match cmd {
         EvaluationCommands::Create {
             path,
@@ -314,24 +980,32 @@ pub fn handle_evaluation_command(cmd: EvaluationCommands) -> Result<()> {
             storage_url,
             print,
             format,
+            output,
+            hex,
+            deterministic,
+            hash_decompressed,
+            quick_hash,
+            created_at,
+            valid_from,
+            valid_until,
             key,
+            kms_key,
             hash_alg,
+            tsa_url,
+            min_key_bits,
+            record_environment,
+            redact_hostname,
+            creative_type,
+            digital_source_type,
+            dry_run,
+            no_schema_check,
+            tags,
         } => {
-            let storage: Option<&'static dyn StorageBackend> = match storage_type.as_str() {
-                "database" => {
-                    let db_storage = Box::new(DatabaseStorage::new(*storage_url.clone())?);
-                    Some(Box::leak(db_storage))
-                }
-                "rekor" => {
-                    let rekor_storage = Box::new(RekorStorage::new_with_url(*storage_url.clone())?);
-                    Some(Box::leak(rekor_storage))
-                }
-                "local-fs" => {
-                    let fs_storage = Box::new(FilesystemStorage::new(storage_url.as_str())?);
-                    Some(Box::leak(fs_storage))
-                }
-                _ => None,
-            };
+            let storage: Option<&'static dyn StorageBackend> = Some(Box::leak(create_storage(
+                &storage_type,
+                *storage_url,
+                &cli_config.storage_config,
+            )?));
 
             let config = ManifestCreationConfig {
                 paths: vec![path],
@@ -344,12 +1018,46 @@ pub fn handle_evaluation_command(cmd: EvaluationCommands) -> Result<()> {
                 storage,
                 print,
                 output_format: format,
-                key_path: key,
-                hash_alg: hash_alg.to_cose_algorithm(),
+                key_path: signing_key_path(key.as_deref(), kms_key.as_deref())?,
+                hash_alg: hash_alg.to_digest_algorithm(),
                 with_cc: false,
                 software_type: None,
                 version: None,
                 custom_fields: None, // Will be populated by create_manifest
+                asset_type: None,
+                recursive: false,
+                ignore_patterns: vec![],
+                show_progress: progress::is_enabled(cli_config),
+                tsa_url,
+                dry_run,
+                tags,
+                min_key_bits,
+                creative_type_override: creative_type,
+                digital_source_type_override: digital_source_type,
+                skip_schema_check: no_schema_check,
+                no_sniff: false,
+                output_path: output,
+                hex_output: hex,
+                deterministic,
+                hash_decompressed,
+                created_at: created_at.as_deref().map(parse_time_bound).transpose()?,
+                ingredient_relationships: Vec::new(),
+                base_manifests: Vec::new(),
+                claim_generator: None,
+                valid_from: valid_from.as_deref().map(parse_time_bound).transpose()?,
+                valid_until: valid_until.as_deref().map(parse_time_bound).transpose()?,
+                quick_hash,
+                follow_symlinks: cli_config.follow_symlinks,
+                trained_on: Vec::new(),
+                ingredient_urls: Vec::new(),
+                record_environment,
+                redact_hostname,
+                signer: kms_signer(kms_key.as_deref())?,
+                model_card: None,
+                model_card_hash: None,
+                symlink_roots: cli_config.symlink_roots.clone(),
+                hash_chunk_size: cli_config.hash_chunk_size,
+                audit_log: cli_config.audit_log.clone(),
             };
 
             manifest::evaluation::create_manifest(config, model_id, dataset_id, metrics)
@@ -357,29 +1065,56 @@ pub fn handle_evaluation_command(cmd: EvaluationCommands) -> Result<()> {
         EvaluationCommands::List {
             storage_type,
             storage_url,
+            tags,
+            since,
+            until,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            let filter = ManifestFilter::new(tags, since.as_deref(), until.as_deref())?;
 
-            manifest::evaluation::list_evaluation_manifests(storage.as_ref())
+            manifest::evaluation::list_evaluation_manifests(storage.as_ref(), &filter)
         }
         EvaluationCommands::Verify {
             id,
+            file,
             storage_type,
             storage_url,
+            strict_cc,
+            tsa_ca,
+            allow_dangling,
+            offline,
+            trust_list,
+            allow_expired,
+            policy,
+            strict,
+            continue_on_error,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
-            manifest::evaluation::verify_evaluation_manifest(&id, storage.as_ref())
+            let config = VerificationConfig {
+                strict_cc,
+                tsa_ca,
+                allow_dangling,
+                offline,
+                trust_list,
+                allow_expired,
+                policy,
+                strict,
+                continue_on_error,
+                follow_symlinks: cli_config.follow_symlinks,
+                symlink_roots: cli_config.symlink_roots.clone(),
+            };
+            let report = verify_stored_or_file(
+                id,
+                file,
+                storage.as_ref(),
+                &config,
+                manifest::evaluation::verify_evaluation_manifest_with_config,
+                cli_config.audit_log.as_deref(),
+            )?;
+            print_verification_report(&report, cli_config);
+            Ok(())
         }
     }
 }
@@ -397,27 +1132,39 @@ pub fn handle_cc_attestation_command(cmd: CCAttestationCommands) -> Result<()> {
             Ok(())
         }
 
-        CCAttestationCommands::VerifyLaunch { host_platform } => {
-            let result = cc_attestation::verify_launch_endorsement(&host_platform).unwrap();
+        CCAttestationCommands::VerifyLaunch {
+            host_platform,
+            expected_measurement,
+        } => {
+            let result = match expected_measurement {
+                Some(expected) => {
+                    let expected: [u8; 48] = hex::decode(expected.trim_start_matches("0x"))?
+                        .try_into()
+                        .map_err(|_| {
+                            Error::Validation("Expected measurement must be 48 bytes".to_string())
+                        })?;
+                    cc_attestation::verify_launch_endorsement_against(&host_platform, &expected)?
+                }
+                None => cc_attestation::verify_launch_endorsement(&host_platform).unwrap(),
+            };
+            let check = cc_attestation::launch_check_name(&host_platform);
             if result {
-                println!(
-                    "Passed: launch endorsement verification for {host_platform} host platform"
-                );
+                println!("Passed: {check} for {host_platform} host platform");
             } else {
-                println!(
-                    "Failed: launch endorsement verification for {host_platform} host platform"
-                );
+                println!("Failed: {check} for {host_platform} host platform");
             }
             Ok(())
         }
     }
 }
 
-pub fn handle_software_command(cmd: SoftwareCommands) -> Result<()> {
+pub fn handle_software_command(cmd: SoftwareCommands, cli_config: &Config) -> Result<()> {
     match cmd {
         SoftwareCommands::Create {
             paths,
             ingredient_names,
+            relationships,
+            ingredient_urls,
             name,
             software_type,
             version,
@@ -429,25 +1176,38 @@ pub fn handle_software_command(cmd: SoftwareCommands) -> Result<()> {
             storage_url,
             print,
             format,
+            output,
+            hex,
+            deterministic,
+            hash_decompressed,
+            quick_hash,
+            created_at,
+            valid_from,
+            valid_until,
             key,
+            kms_key,
             hash_alg,
+            tsa_url,
+            min_key_bits,
+            record_environment,
+            redact_hostname,
+            creative_type,
+            digital_source_type,
+            dry_run,
+            no_schema_check,
+            tags,
             with_tdx,
+            asset_type,
+            recursive,
+            ignore,
         } => {
-            let storage: Option<&'static dyn StorageBackend> = match storage_type.as_str() {
-                "database" => {
-                    let db_storage = Box::new(DatabaseStorage::new(*storage_url.clone())?);
-                    Some(Box::leak(db_storage))
-                }
-                "rekor" => {
-                    let rekor_storage = Box::new(RekorStorage::new_with_url(*storage_url.clone())?);
-                    Some(Box::leak(rekor_storage))
-                }
-                "local-fs" => {
-                    let fs_storage = Box::new(FilesystemStorage::new(storage_url.as_str())?);
-                    Some(Box::leak(fs_storage))
-                }
-                _ => None,
-            };
+            let (paths, ingredient_names) = expand_path_patterns(paths, ingredient_names)?;
+
+            let storage: Option<&'static dyn StorageBackend> = Some(Box::leak(create_storage(
+                &storage_type,
+                *storage_url,
+                &cli_config.storage_config,
+            )?));
 
             let config = ManifestCreationConfig {
                 paths,
@@ -460,12 +1220,46 @@ pub fn handle_software_command(cmd: SoftwareCommands) -> Result<()> {
                 storage,
                 print,
                 output_format: format,
-                key_path: key,
-                hash_alg: hash_alg.to_cose_algorithm(),
+                key_path: signing_key_path(key.as_deref(), kms_key.as_deref())?,
+                hash_alg: hash_alg.to_digest_algorithm(),
                 with_cc: with_tdx,
                 software_type: Some(software_type.clone()),
                 version: version.clone(),
                 custom_fields: None,
+                asset_type,
+                recursive,
+                ignore_patterns: ignore,
+                show_progress: progress::is_enabled(cli_config),
+                tsa_url,
+                dry_run,
+                tags,
+                min_key_bits,
+                creative_type_override: creative_type,
+                digital_source_type_override: digital_source_type,
+                skip_schema_check: no_schema_check,
+                no_sniff: false,
+                output_path: output,
+                hex_output: hex,
+                deterministic,
+                hash_decompressed,
+                created_at: created_at.as_deref().map(parse_time_bound).transpose()?,
+                ingredient_relationships: relationships,
+                ingredient_urls,
+                base_manifests: Vec::new(),
+                claim_generator: None,
+                valid_from: valid_from.as_deref().map(parse_time_bound).transpose()?,
+                valid_until: valid_until.as_deref().map(parse_time_bound).transpose()?,
+                quick_hash,
+                follow_symlinks: cli_config.follow_symlinks,
+                trained_on: Vec::new(),
+                record_environment,
+                redact_hostname,
+                signer: kms_signer(kms_key.as_deref())?,
+                model_card: None,
+                model_card_hash: None,
+                symlink_roots: cli_config.symlink_roots.clone(),
+                hash_chunk_size: cli_config.hash_chunk_size,
+                audit_log: cli_config.audit_log.clone(),
             };
 
             manifest::software::create_manifest(config, software_type, version)
@@ -473,29 +1267,56 @@ pub fn handle_software_command(cmd: SoftwareCommands) -> Result<()> {
         SoftwareCommands::List {
             storage_type,
             storage_url,
+            tags,
+            since,
+            until,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
+
+            let filter = ManifestFilter::new(tags, since.as_deref(), until.as_deref())?;
 
-            manifest::software::list_software_manifests(storage.as_ref())
+            manifest::software::list_software_manifests(storage.as_ref(), &filter)
         }
         SoftwareCommands::Verify {
             id,
+            file,
             storage_type,
             storage_url,
+            strict_cc,
+            tsa_ca,
+            allow_dangling,
+            offline,
+            trust_list,
+            allow_expired,
+            policy,
+            strict,
+            continue_on_error,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
-            manifest::software::verify_software_manifest(&id, storage.as_ref())
+            let config = VerificationConfig {
+                strict_cc,
+                tsa_ca,
+                allow_dangling,
+                offline,
+                trust_list,
+                allow_expired,
+                policy,
+                strict,
+                continue_on_error,
+                follow_symlinks: cli_config.follow_symlinks,
+                symlink_roots: cli_config.symlink_roots.clone(),
+            };
+            let report = verify_stored_or_file(
+                id,
+                file,
+                storage.as_ref(),
+                &config,
+                manifest::software::verify_software_manifest_with_config,
+                cli_config.audit_log.as_deref(),
+            )?;
+            print_verification_report(&report, cli_config);
+            Ok(())
         }
         SoftwareCommands::LinkModel {
             software_id,
@@ -503,12 +1324,7 @@ pub fn handle_software_command(cmd: SoftwareCommands) -> Result<()> {
             storage_type,
             storage_url,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
             // Link software to model
             manifest::link_manifests(&model_id, &software_id, storage.as_ref())
@@ -519,15 +1335,386 @@ pub fn handle_software_command(cmd: SoftwareCommands) -> Result<()> {
             storage_type,
             storage_url,
         } => {
-            let storage: Box<dyn StorageBackend> = match storage_type.as_str() {
-                "database" => Box::new(DatabaseStorage::new(*storage_url.clone())?),
-                "rekor" => Box::new(RekorStorage::new_with_url(*storage_url.clone())?),
-                "local-fs" => Box::new(FilesystemStorage::new(storage_url.as_str())?),
-                _ => return Err(Error::Validation("Invalid storage type".to_string())),
-            };
+            let storage = create_storage(&storage_type, *storage_url, &cli_config.storage_config)?;
 
             // Link software to dataset
             manifest::link_manifests(&dataset_id, &software_id, storage.as_ref())
         }
     }
 }
+
+/// Prints a verification report with one line per check, unless `--quiet`
+/// was given
+fn print_verification_report(report: &VerificationReport, cli_config: &Config) {
+    if !cli_config.quiet {
+        print!("{}", format_verification_report(report));
+    }
+}
+
+/// Renders a verification report with a `✓` or `⚠` line per check
+pub(crate) fn format_verification_report(report: &VerificationReport) -> String {
+    let mut output = format!("Verified manifest: {}\n", report.manifest_id);
+    for check in report.checks() {
+        let glyph = match check.status {
+            CheckStatus::Verified => "✓",
+            CheckStatus::Warning => "⚠",
+        };
+        output.push_str(&format!("{glyph} {}: {}\n", check.subject, check.message));
+    }
+    match report.status() {
+        CheckStatus::Verified => output.push_str("✓ Manifest verification successful\n"),
+        CheckStatus::Warning => output.push_str(&format!(
+            "⚠ Manifest verification successful with {} warning(s)\n",
+            report.warnings().count()
+        )),
+    }
+    output
+}
+
+/// Expands glob patterns in `--paths` into the matching files.
+///
+/// Matches are sorted so the ingredient order is stable. Each match is named
+/// `<ingredient-name>/<path relative to the pattern's fixed prefix>`, and paths
+/// without glob characters are passed through unchanged.
+pub(crate) fn expand_path_patterns(
+    paths: Vec<PathBuf>,
+    ingredient_names: Vec<String>,
+) -> Result<(Vec<PathBuf>, Vec<String>)> {
+    let mut expanded_paths = Vec::new();
+    let mut expanded_names = Vec::new();
+
+    for (path, name) in paths.into_iter().zip(ingredient_names) {
+        let pattern = path.to_string_lossy().to_string();
+        if !pattern.contains(['*', '?', '[']) {
+            expanded_paths.push(path);
+            expanded_names.push(name);
+            continue;
+        }
+
+        let mut matches = glob::glob(&pattern)
+            .map_err(|e| Error::Validation(format!("Invalid path pattern '{pattern}': {e}")))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::Io(e.into()))?;
+        matches.sort();
+
+        if matches.is_empty() {
+            return Err(Error::Validation(format!(
+                "Path pattern '{pattern}' did not match any files"
+            )));
+        }
+        log::info!("Pattern '{pattern}' matched {} files", matches.len());
+
+        // The part of the pattern before the first wildcard component
+        let prefix: PathBuf = path
+            .components()
+            .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+            .collect();
+
+        for matched in matches {
+            let relative = matched.strip_prefix(&prefix).unwrap_or(&matched);
+            expanded_names.push(format!("{name}/{}", relative.to_string_lossy()));
+            expanded_paths.push(matched);
+        }
+    }
+
+    Ok((expanded_paths, expanded_names))
+}
+
+pub fn handle_keygen_command(args: KeygenArgs) -> Result<()> {
+    let key = signing::keygen::generate_private_key(args.key_type.to_key_type())?;
+    let public_path = signing::keygen::write_key_pair(&key, &args.output, args.force)?;
+    println!("Private key written to {}", args.output.display());
+    println!("Public key written to {}", public_path.display());
+    Ok(())
+}
+
+/// Resolves the signing key file, unless a KMS key signs instead
+fn signing_key_path(key: Option<&Path>, kms_key: Option<&str>) -> Result<Option<PathBuf>> {
+    if kms_key.is_some() {
+        return Ok(None);
+    }
+    Ok(signing::key_path::find_key_path(key)?.map(|(path, _)| path))
+}
+
+/// The signer for a `--kms-key` URI
+fn kms_signer(kms_key: Option<&str>) -> Result<Option<Arc<dyn Signer>>> {
+    kms_key
+        .map(|uri| -> Result<Arc<dyn Signer>> { Ok(Arc::new(KmsSigner::from_uri(uri)?)) })
+        .transpose()
+}
+
+/// Verifies the manifest file if one is given, otherwise the stored manifest
+/// with the given ID
+/// Verifies `id` now and again after every change to its local file
+/// ingredients, printing the outcome each time, until cancelled
+fn watch_manifest(
+    id: &str,
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+    poll_interval: Duration,
+    debounce: Duration,
+) -> Result<()> {
+    let stored = storage.retrieve_manifest(id)?;
+    let paths = manifest::watch::ingredient_paths(&stored);
+    if paths.is_empty() {
+        return Err(Error::Validation(format!(
+            "Manifest {id} has no local file ingredients to watch"
+        )));
+    }
+    println!(
+        "Watching {} files of manifest {id}; press Ctrl-C to stop",
+        paths.len()
+    );
+
+    let mut watcher = manifest::watch::FileWatcher::new(paths);
+    loop {
+        match manifest::verify_manifest_with_config(id, storage, config) {
+            Ok(report) if report.status() == CheckStatus::Warning => {
+                println!("PASS with {} warnings: {id}", report.warnings().count())
+            }
+            Ok(_) => println!("PASS: {id}"),
+            Err(Error::Cancelled) => return Ok(()),
+            Err(e) => println!("FAIL: {e}"),
+        }
+
+        let changes = match watcher.wait(poll_interval, debounce) {
+            Ok(changes) => changes,
+            Err(Error::Cancelled) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        for change in changes {
+            println!("{} {}", change.kind.as_str(), change.path.display());
+        }
+    }
+}
+
+fn verify_stored_or_file(
+    id: Option<String>,
+    file: Option<PathBuf>,
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+    verify_stored: fn(&str, &dyn StorageBackend, &VerificationConfig) -> Result<VerificationReport>,
+    audit_log: Option<&Path>,
+) -> Result<VerificationReport> {
+    match (id, file) {
+        (_, Some(file)) => {
+            let manifest = manifest::read_manifest_file(&file, None)?;
+            audit::audited(
+                audit_log,
+                "verify",
+                Some(&manifest.instance_id),
+                manifest::verify_manifest_value_with_config(&manifest, Some(storage), config),
+            )
+        }
+        (Some(id), None) => audit::audited(
+            audit_log,
+            "verify",
+            Some(&id),
+            verify_stored(&id, storage, config),
+        ),
+        (None, None) => Err(Error::Validation(
+            "Either --id or --file is required".to_string(),
+        )),
+    }
+}
+
+pub fn handle_pubkey_command(args: PubkeyArgs) -> Result<()> {
+    let key_path = signing::key_path::resolve_key_path(args.key.as_deref())?;
+    let public_key = signing::keygen::public_key(&signing::load_private_key(&key_path)?)?;
+    let fingerprint = signing::trust::public_key_fingerprint(&public_key)?;
+    match &args.out {
+        Some(out) => {
+            signing::keygen::write_public_key(&public_key, out, args.force)?;
+            println!("Public key written to {}", out.display());
+        }
+        None => print!(
+            "{}",
+            String::from_utf8_lossy(&signing::keygen::public_key_pem(&public_key)?)
+        ),
+    }
+    // Printed on its own line, a valid trust list entry like the PEM above
+    println!("sha256:{fingerprint}");
+    Ok(())
+}
+
+pub fn handle_migrate_command(args: MigrateArgs, cli_config: &Config) -> Result<()> {
+    let (from_type, from_url) = storage::migrate::parse_storage_spec(&args.from)?;
+    let (to_type, to_url) = storage::migrate::parse_storage_spec(&args.to)?;
+    let source = create_storage(&from_type, from_url, &cli_config.storage_config)?;
+    let destination = create_storage(&to_type, to_url, &cli_config.storage_config)?;
+
+    let summary = audit::audited(
+        cli_config.audit_log.as_deref(),
+        "migrate",
+        None,
+        storage::migrate::migrate_manifests(
+            source.as_ref(),
+            destination.as_ref(),
+            args.skip_existing,
+            progress::is_enabled(cli_config),
+        ),
+    )?;
+
+    for (source_id, stored_id) in &summary.renamed {
+        println!("Manifest {source_id} stored as {stored_id}");
+    }
+    println!(
+        "Migrated {} manifest(s), skipped {} already present",
+        summary.copied, summary.skipped
+    );
+    Ok(())
+}
+
+/// Handles `config` commands; `root` is the full command line definition with
+/// the defaults file applied and `matches` the parsed command line
+pub fn handle_config_command(
+    cmd: ConfigCommands,
+    root: &clap::Command,
+    matches: &clap::ArgMatches,
+    defaults: &Defaults,
+) -> Result<()> {
+    match cmd {
+        ConfigCommands::Show { command } => {
+            match &defaults.path {
+                Some(path) => println!("Defaults file: {}", path.display()),
+                None => match defaults::default_path() {
+                    Some(path) => println!("Defaults file: {} (not found)", path.display()),
+                    None => println!("Defaults file: none"),
+                },
+            }
+
+            let mut target = root;
+            for name in &command {
+                target = target.find_subcommand(name).ok_or_else(|| {
+                    Error::Validation(format!("Unknown command: {}", command.join(" ")))
+                })?;
+            }
+
+            println!("\nGlobal settings:");
+            print!(
+                "{}",
+                defaults::format_effective(root, Some(matches), defaults)
+            );
+            if !command.is_empty() {
+                println!("\n{} settings:", command.join(" "));
+                print!("{}", defaults::format_effective(target, None, defaults));
+            }
+            Ok(())
+        }
+    }
+}
+
+pub fn handle_audit_command(cmd: AuditCommands, cli_config: &Config) -> Result<()> {
+    match cmd {
+        AuditCommands::Verify { log } => {
+            let log = log
+                .or_else(|| cli_config.audit_log.clone())
+                .map(AuditLog::new)
+                .ok_or_else(|| {
+                    Error::Validation("No audit log given; use --log or --audit-log".to_string())
+                })?;
+            let summary = log.verify()?;
+            println!(
+                "Audit log {} is intact: {} entries",
+                log.path().display(),
+                summary.entries
+            );
+            println!("Last entry hash: {}", summary.last_hash);
+            Ok(())
+        }
+    }
+}
+
+pub fn handle_batch_command(cmd: BatchCommands, cli_config: &Config) -> Result<()> {
+    match cmd {
+        BatchCommands::Create(args) => {
+            let entries = manifest::batch::load_spec(&args.spec)?;
+            let storage: &'static dyn StorageBackend = Box::leak(create_storage(
+                &args.storage_type,
+                args.storage_url,
+                &cli_config.storage_config,
+            )?);
+
+            let base = ManifestCreationConfig {
+                paths: Vec::new(),
+                ingredient_names: Vec::new(),
+                name: String::new(),
+                author_org: args.author_org,
+                author_name: None,
+                description: None,
+                linked_manifests: None,
+                storage: Some(storage),
+                print: false,
+                output_format: "json".to_string(),
+                key_path: signing::key_path::find_key_path(args.key.as_deref())?
+                    .map(|(path, _)| path),
+                hash_alg: args.hash_alg.to_digest_algorithm(),
+                with_cc: false,
+                software_type: None,
+                version: None,
+                custom_fields: None,
+                asset_type: None,
+                recursive: false,
+                ignore_patterns: Vec::new(),
+                show_progress: progress::is_enabled(cli_config),
+                tsa_url: None,
+                dry_run: false,
+                tags: args.tags,
+                min_key_bits: args.min_key_bits,
+                creative_type_override: None,
+                digital_source_type_override: None,
+                skip_schema_check: args.no_schema_check,
+                no_sniff: false,
+                output_path: None,
+                hex_output: false,
+                deterministic: args.deterministic,
+                created_at: None,
+                hash_decompressed: false,
+                ingredient_relationships: Vec::new(),
+                base_manifests: Vec::new(),
+                claim_generator: None,
+                valid_from: None,
+                valid_until: None,
+                quick_hash: false,
+                follow_symlinks: cli_config.follow_symlinks,
+                trained_on: Vec::new(),
+                ingredient_urls: Vec::new(),
+                record_environment: false,
+                redact_hostname: false,
+                signer: None,
+                model_card: None,
+                model_card_hash: None,
+                symlink_roots: cli_config.symlink_roots.clone(),
+                hash_chunk_size: cli_config.hash_chunk_size,
+                audit_log: cli_config.audit_log.clone(),
+            };
+
+            let base_dir = args.spec.parent().map(PathBuf::from).unwrap_or_default();
+            let total = entries.len();
+            let summary = manifest::batch::create_batch(entries, &base, &base_dir)?;
+
+            for (name, id) in &summary.created {
+                println!("{name}: {id}");
+            }
+            println!(
+                "Created {} of {total} manifest(s), {} failed",
+                summary.created.len(),
+                summary.failed.len()
+            );
+            if summary.failed.is_empty() {
+                Ok(())
+            } else {
+                let names: Vec<&str> = summary
+                    .failed
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect();
+                Err(Error::Validation(format!(
+                    "{} batch entries failed: {}",
+                    summary.failed.len(),
+                    names.join(", ")
+                )))
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod commands;
+pub mod defaults;
 pub mod handlers;
 use crate::error::Error;
 
@@ -26,13 +27,18 @@ pub fn format_error(error: &Error) -> String {
         Error::InitializationError(msg) => format!("Initialization error: {msg}"),
         Error::HexDecode(err) => format!("Hex decode error: {err}"),
         Error::CCAttestationError(msg) => format!("CC attestation error: {msg}"),
+        Error::LaunchMeasurementMismatch { expected, actual } => {
+            format!("Launch measurement mismatch: expected 0x{expected}, got 0x{actual}")
+        }
         Error::Json(err) => format!("JSON error: {err}"),
+        Error::Cancelled => "Operation cancelled".to_string(),
+        Error::SigningFailure(e) => e.to_string(),
     }
 }
 
-/// Helper function to print validation warnings to the user
+/// Helper function to log validation warnings for the user
 pub fn print_validation_warning(message: &str) {
-    eprintln!("Warning: {message}");
+    log::warn!("{message}");
 }
 
 /// Helper function to confirm actions with the user
@@ -53,7 +59,7 @@ pub fn confirm_action(prompt: &str) -> bool {
 /// Function to initialize any CLI-specific requirements
 pub fn initialize() -> Result<(), crate::error::Error> {
     // Set up logging if needed
-    env_logger::init();
+    crate::init_logging()?;
 
     // Check for required environment variables
     if std::env::var("REKOR_URL").is_err() {
@@ -65,16 +71,16 @@ pub fn initialize() -> Result<(), crate::error::Error> {
 
 // Shared functionality for progress indication
 pub mod progress {
-    use indicatif::{ProgressBar, ProgressStyle};
+    use crate::Config;
+    use std::io::IsTerminal;
 
-    pub fn create_progress_bar(len: u64) -> ProgressBar {
-        let pb = ProgressBar::new(len);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
-                .expect("Invalid progress bar template")
-                .progress_chars("=>-"),
-        );
-        pb
+    pub use crate::progress::{
+        create_bytes_progress_bar, create_files_progress_bar, create_progress_bar,
+    };
+
+    /// Returns true if progress bars are enabled in `config` and stderr is a
+    /// terminal
+    pub fn is_enabled(config: &Config) -> bool {
+        config.show_progress && !config.quiet && std::io::stderr().is_terminal()
     }
 }
@@ -1,6 +1,9 @@
 mod types;
 
-pub use types::{Error, Result};
+pub use types::{
+    EXIT_CANCELLED, EXIT_FAILURE, EXIT_SIGNING, EXIT_STORAGE, EXIT_SUCCESS, EXIT_USAGE,
+    EXIT_VALIDATION, Error, Result, SigningError,
+};
 
 /// Format an error for display to the user
 ///
@@ -29,8 +29,129 @@ pub enum Error {
     #[error("CC Attestation error: {0}")]
     CCAttestationError(String),
 
+    #[error("Launch measurement mismatch: expected {expected}, got {actual}")]
+    LaunchMeasurementMismatch { expected: String, actual: String },
+
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    #[error(transparent)]
+    SigningFailure(#[from] SigningError),
+}
+
+/// Signing and signature failures that callers may want to tell apart,
+/// e.g. to ask for a key rather than report a tampered manifest
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SigningError {
+    /// No signing key was configured, or the key file does not exist
+    #[error("Signing error: {0}")]
+    KeyNotFound(String),
+
+    /// A signature does not match the data it signs
+    #[error("Signature verification failed: {0}")]
+    VerificationFailed(String),
+}
+
+impl SigningError {
+    /// Stable machine-readable code of this failure
+    pub fn code(&self) -> &'static str {
+        match self {
+            SigningError::KeyNotFound(_) => "signing.key_not_found",
+            SigningError::VerificationFailed(_) => "signing.verification_failed",
+        }
+    }
+}
+
+/// Process exit code for a successful run
+pub const EXIT_SUCCESS: u8 = 0;
+/// Process exit code for errors without a more specific code
+pub const EXIT_FAILURE: u8 = 1;
+/// Process exit code for validation and verification failures
+pub const EXIT_VALIDATION: u8 = 2;
+/// Process exit code for storage backend errors
+pub const EXIT_STORAGE: u8 = 3;
+/// Process exit code for signing and key errors
+pub const EXIT_SIGNING: u8 = 4;
+/// Process exit code for invalid command line usage
+pub const EXIT_USAGE: u8 = 64;
+/// Process exit code for an operation cancelled with Ctrl-C (128 + SIGINT)
+pub const EXIT_CANCELLED: u8 = 130;
+
+impl Error {
+    /// Process exit code the CLI exits with for this error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atlas_cli::error::{EXIT_STORAGE, EXIT_VALIDATION, Error};
+    ///
+    /// assert_eq!(Error::Validation("hash mismatch".into()).exit_code(), EXIT_VALIDATION);
+    /// assert_eq!(Error::Storage("not found".into()).exit_code(), EXIT_STORAGE);
+    /// ```
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Validation(_) | Error::Manifest(_) | Error::LaunchMeasurementMismatch { .. } => {
+                EXIT_VALIDATION
+            }
+            Error::Storage(_) => EXIT_STORAGE,
+            Error::Signing(_) | Error::SigningFailure(SigningError::KeyNotFound(_)) => EXIT_SIGNING,
+            Error::SigningFailure(SigningError::VerificationFailed(_)) => EXIT_VALIDATION,
+            Error::Cancelled => EXIT_CANCELLED,
+            Error::Io(_)
+            | Error::Serialization(_)
+            | Error::InitializationError(_)
+            | Error::HexDecode(_)
+            | Error::CCAttestationError(_)
+            | Error::Json(_) => EXIT_FAILURE,
+        }
+    }
+
+    /// Stable machine-readable code for this error, for callers that handle
+    /// failures programmatically instead of matching on messages. Codes name
+    /// the error category, e.g. `validation` or `storage`, or a specific
+    /// failure within it, e.g. `signing.key_not_found`, and do not change
+    /// between releases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atlas_cli::error::{Error, SigningError};
+    ///
+    /// assert_eq!(Error::Validation("hash mismatch".into()).code(), "validation");
+    /// let missing = Error::from(SigningError::KeyNotFound("key.pem".into()));
+    /// assert_eq!(missing.code(), "signing.key_not_found");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Io(_) => "io",
+            Error::Storage(_) => "storage",
+            Error::Validation(_) => "validation",
+            Error::Manifest(_) => "manifest",
+            Error::Signing(_) => "signing",
+            Error::Serialization(_) => "serialization",
+            Error::InitializationError(_) => "initialization",
+            Error::HexDecode(_) => "hex_decode",
+            Error::CCAttestationError(_) => "cc_attestation",
+            Error::LaunchMeasurementMismatch { .. } => "launch_measurement_mismatch",
+            Error::Json(_) => "json",
+            Error::Cancelled => "cancelled",
+            Error::SigningFailure(e) => e.code(),
+        }
+    }
+
+    /// Prefixes a serialization error with the operation being performed and
+    /// the manifest it concerns; other errors are returned unchanged
+    pub fn with_manifest_context(self, operation: &str, title: &str, id: &str) -> Self {
+        match self {
+            Error::Serialization(msg) => {
+                Error::Serialization(format!("{operation} '{title}' ({id}): {msg}"))
+            }
+            other => other,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,113 @@
+//! # Digest Algorithms
+//!
+//! [`HashAlgorithm`] from `atlas-c2pa-lib` only covers SHA-2. [`DigestAlgorithm`]
+//! adds the SHA3 family for regimes that require it, and is accepted wherever
+//! a `HashAlgorithm` is, both for content hashes and signature digests.
+//!
+//! The algorithm name is recorded in `IngredientData.alg` and in
+//! countersignatures (`sha384`, `sha3-256`, ...), so verification re-hashes
+//! and checks signatures with the same algorithm.
+
+use crate::error::{Error, Result};
+use atlas_c2pa_lib::cose::HashAlgorithm;
+use std::fmt;
+use std::str::FromStr;
+
+/// Digest algorithms for content hashes and signatures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+}
+
+impl DigestAlgorithm {
+    /// Every supported algorithm
+    pub const ALL: [Self; 6] = [
+        Self::Sha256,
+        Self::Sha384,
+        Self::Sha512,
+        Self::Sha3_256,
+        Self::Sha3_384,
+        Self::Sha3_512,
+    ];
+
+    /// Name recorded in manifests
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atlas_cli::hash::DigestAlgorithm;
+    ///
+    /// assert_eq!(DigestAlgorithm::Sha384.as_str(), "sha384");
+    /// assert_eq!(DigestAlgorithm::Sha3_256.as_str(), "sha3-256");
+    /// assert_eq!("sha3-512".parse::<DigestAlgorithm>().unwrap(), DigestAlgorithm::Sha3_512);
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+            Self::Sha3_256 => "sha3-256",
+            Self::Sha3_384 => "sha3-384",
+            Self::Sha3_512 => "sha3-512",
+        }
+    }
+
+    /// The equivalent `atlas-c2pa-lib` algorithm; `None` for SHA3
+    pub fn to_hash_algorithm(&self) -> Option<HashAlgorithm> {
+        match self {
+            Self::Sha256 => Some(HashAlgorithm::Sha256),
+            Self::Sha384 => Some(HashAlgorithm::Sha384),
+            Self::Sha512 => Some(HashAlgorithm::Sha512),
+            Self::Sha3_256 | Self::Sha3_384 | Self::Sha3_512 => None,
+        }
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DigestAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.as_str() == s)
+            .ok_or_else(|| {
+                Error::Validation(format!(
+                    "Unsupported hash algorithm '{s}'. Valid options are: {}",
+                    Self::ALL.map(|algorithm| algorithm.as_str()).join(", ")
+                ))
+            })
+    }
+}
+
+impl From<HashAlgorithm> for DigestAlgorithm {
+    fn from(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Self::Sha256,
+            HashAlgorithm::Sha384 => Self::Sha384,
+            HashAlgorithm::Sha512 => Self::Sha512,
+        }
+    }
+}
+
+impl From<&HashAlgorithm> for DigestAlgorithm {
+    fn from(algorithm: &HashAlgorithm) -> Self {
+        algorithm.clone().into()
+    }
+}
+
+impl From<&DigestAlgorithm> for DigestAlgorithm {
+    fn from(algorithm: &DigestAlgorithm) -> Self {
+        *algorithm
+    }
+}
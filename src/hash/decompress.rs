@@ -0,0 +1,136 @@
+//! # Decompressed Hashing
+//!
+//! Hashes the content of gzip and zstd files rather than their compressed
+//! bytes, so a recorded hash stays the same when a file is recompressed with
+//! different settings. Compression is recognised from the file extension.
+
+use super::{DigestAlgorithm, calculate_reader_hash_with_algorithm};
+use crate::error::{Error, Result};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Compression formats that can be hashed transparently
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Recognises `.gz` and `.zst` files
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atlas_cli::hash::decompress::Compression;
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(Compression::from_path(Path::new("model.pt.zst")), Some(Compression::Zstd));
+    /// assert_eq!(Compression::from_path(Path::new("data.json.gz")), Some(Compression::Gzip));
+    /// assert_eq!(Compression::from_path(Path::new("model.onnx")), None);
+    /// ```
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Some(Self::Gzip),
+            Some("zst") => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Name recorded in manifests
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Parses a name recorded by [`Compression::as_str`]
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            other => Err(Error::Validation(format!(
+                "Unsupported compression '{other}'. Valid options are: gzip, zstd"
+            ))),
+        }
+    }
+
+    /// Wraps a reader of compressed bytes in a decoder
+    pub fn decoder<'a, R: Read + 'a>(&self, reader: R) -> Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            Self::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+            Self::Zstd => Box::new(zstd::stream::read::Decoder::new(reader)?),
+        })
+    }
+}
+
+/// The path with its compression extension removed, e.g. `model.pt` for
+/// `model.pt.zst`, used to detect the type of the compressed content
+pub fn decompressed_path(path: &Path) -> PathBuf {
+    match Compression::from_path(path) {
+        Some(_) => path.with_extension(""),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Hashes the decompressed content of a file
+pub fn calculate_decompressed_file_hash(
+    path: &Path,
+    compression: Compression,
+    algorithm: impl Into<DigestAlgorithm>,
+) -> Result<String> {
+    let file = std::fs::File::open(path)?;
+    let decoder = compression.decoder(file)?;
+    calculate_reader_hash_with_algorithm(decoder, algorithm).map_err(|e| match e {
+        Error::Io(e) => Error::Validation(format!(
+            "Failed to decompress {} as {}: {e}",
+            path.display(),
+            compression.as_str()
+        )),
+        other => other,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::calculate_hash_with_algorithm;
+    use atlas_c2pa_lib::cose::HashAlgorithm;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_decompressed_hash_ignores_compression() -> Result<()> {
+        let dir = tempdir()?;
+        let content = b"model weights".repeat(100);
+        let expected = calculate_hash_with_algorithm(&content, &HashAlgorithm::Sha384);
+
+        let gz_path = dir.path().join("model.pt.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&gz_path)?,
+            flate2::Compression::best(),
+        );
+        encoder.write_all(&content)?;
+        encoder.finish()?;
+
+        let zst_path = dir.path().join("model.pt.zst");
+        std::fs::write(&zst_path, zstd::encode_all(&content[..], 3)?)?;
+
+        for path in [&gz_path, &zst_path] {
+            let compression = Compression::from_path(path).unwrap();
+            let hash = calculate_decompressed_file_hash(path, compression, &HashAlgorithm::Sha384)?;
+            assert_eq!(hash, expected);
+        }
+
+        // Content that is not actually compressed is rejected
+        let fake = dir.path().join("fake.gz");
+        std::fs::write(&fake, b"plain text")?;
+        assert!(
+            calculate_decompressed_file_hash(&fake, Compression::Gzip, &HashAlgorithm::Sha384)
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
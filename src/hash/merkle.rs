@@ -0,0 +1,243 @@
+//! # Merkle Trees
+//!
+//! Builds SHA-384 Merkle trees over a list of leaf hashes so that a manifest can
+//! record a single root for many files and later prove that one file hash is a
+//! member of the set without the other files.
+//!
+//! ## Tree layout
+//!
+//! - Leaves are used in the order given; callers must sort them (e.g. by path)
+//!   if they need a stable root.
+//! - Each leaf node is `SHA-384(0x00 || leaf)` and each internal node is
+//!   `SHA-384(0x01 || left || right)`, so leaves cannot be confused with
+//!   internal nodes.
+//! - When a level has an odd number of nodes, the last node is promoted to the
+//!   next level unchanged.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::hash::calculate_hash;
+//! use atlas_cli::hash::merkle::{build_merkle_proof, build_merkle_root, verify_merkle_proof};
+//!
+//! let leaves: Vec<String> = [b"shard-0", b"shard-1", b"shard-2"]
+//!     .iter()
+//!     .map(|data| calculate_hash(*data))
+//!     .collect();
+//!
+//! let root = build_merkle_root(&leaves).unwrap();
+//! let proof = build_merkle_proof(&leaves, 1).unwrap();
+//! assert!(verify_merkle_proof(&leaves[1], &proof, &root).unwrap());
+//! ```
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha384};
+use subtle::ConstantTimeEq;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Position of a sibling hash relative to the node being proven
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MerkleSide {
+    Left,
+    Right,
+}
+
+/// One step of an inclusion proof, from the leaf towards the root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProofStep {
+    /// Hex-encoded sibling node hash
+    pub hash: String,
+    /// Which side of the current node the sibling is on
+    pub side: MerkleSide,
+}
+
+fn hash_leaf(leaf: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha384::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(leaf);
+    hasher.finalize().to_vec()
+}
+
+fn hash_node(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha384::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().to_vec()
+}
+
+fn leaf_nodes(leaf_hashes: &[String]) -> Result<Vec<Vec<u8>>> {
+    if leaf_hashes.is_empty() {
+        return Err(Error::Validation(
+            "Cannot build a Merkle tree without leaves".to_string(),
+        ));
+    }
+
+    leaf_hashes
+        .iter()
+        .map(|leaf| Ok(hash_leaf(&hex::decode(leaf).map_err(Error::HexDecode)?)))
+        .collect()
+}
+
+fn next_level(level: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_node(left, right),
+            [single] => single.clone(),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Compute the Merkle root of a list of hex-encoded leaf hashes
+///
+/// # Arguments
+///
+/// * `leaf_hashes` - Hex-encoded leaf hashes, in tree order
+///
+/// # Returns
+///
+/// * `Ok(String)` - The hex-encoded SHA-384 root
+/// * `Err(Error)` - If the list is empty or a leaf is invalid hexadecimal
+pub fn build_merkle_root(leaf_hashes: &[String]) -> Result<String> {
+    let mut level = leaf_nodes(leaf_hashes)?;
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    Ok(hex::encode(&level[0]))
+}
+
+/// Build an inclusion proof for the leaf at `index`
+///
+/// The proof lists the sibling hashes from the leaf up to the root; levels
+/// where the node is promoted without a sibling contribute no step.
+pub fn build_merkle_proof(leaf_hashes: &[String], index: usize) -> Result<Vec<MerkleProofStep>> {
+    let mut level = leaf_nodes(leaf_hashes)?;
+    if index >= level.len() {
+        return Err(Error::Validation(format!(
+            "Leaf index {index} out of range for {} leaves",
+            level.len()
+        )));
+    }
+
+    let mut proof = Vec::new();
+    let mut position = index;
+    while level.len() > 1 {
+        let sibling = position ^ 1;
+        if sibling < level.len() {
+            proof.push(MerkleProofStep {
+                hash: hex::encode(&level[sibling]),
+                side: if sibling < position {
+                    MerkleSide::Left
+                } else {
+                    MerkleSide::Right
+                },
+            });
+        }
+        level = next_level(&level);
+        position /= 2;
+    }
+
+    Ok(proof)
+}
+
+/// Verify that `leaf_hash` is a member of the tree with the given root
+///
+/// # Returns
+///
+/// * `Ok(true)` - If the proof leads from the leaf to `root`
+/// * `Ok(false)` - If the proof does not match
+/// * `Err(Error)` - If any hash is invalid hexadecimal
+pub fn verify_merkle_proof(leaf_hash: &str, proof: &[MerkleProofStep], root: &str) -> Result<bool> {
+    let mut current = hash_leaf(&hex::decode(leaf_hash).map_err(Error::HexDecode)?);
+    for step in proof {
+        let sibling = hex::decode(&step.hash).map_err(Error::HexDecode)?;
+        current = match step.side {
+            MerkleSide::Left => hash_node(&sibling, &current),
+            MerkleSide::Right => hash_node(&current, &sibling),
+        };
+    }
+
+    let root = hex::decode(root).map_err(Error::HexDecode)?;
+    Ok(current.ct_eq(&root).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::calculate_hash;
+
+    fn leaves(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| calculate_hash(format!("shard-{i}").as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn test_merkle_root_known_tree() -> Result<()> {
+        let leaves = leaves(3);
+        let decoded: Vec<Vec<u8>> = leaves.iter().map(|l| hex::decode(l).unwrap()).collect();
+
+        // root = H(1 || H(1 || L0 || L1) || L2), with Ln = H(0 || leaf n)
+        let l0 = hash_leaf(&decoded[0]);
+        let l1 = hash_leaf(&decoded[1]);
+        let l2 = hash_leaf(&decoded[2]);
+        let expected = hex::encode(hash_node(&hash_node(&l0, &l1), &l2));
+
+        assert_eq!(build_merkle_root(&leaves)?, expected);
+
+        // A single leaf is its own (leaf-hashed) root
+        assert_eq!(build_merkle_root(&leaves[..1])?, hex::encode(l0));
+
+        // Leaf order is significant
+        let reversed: Vec<String> = leaves.iter().rev().cloned().collect();
+        assert_ne!(build_merkle_root(&reversed)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merkle_proofs() -> Result<()> {
+        for count in 1..=7 {
+            let leaves = leaves(count);
+            let root = build_merkle_root(&leaves)?;
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = build_merkle_proof(&leaves, index)?;
+                assert!(verify_merkle_proof(leaf, &proof, &root)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merkle_proof_rejects_non_member() -> Result<()> {
+        let leaves = leaves(4);
+        let root = build_merkle_root(&leaves)?;
+        let proof = build_merkle_proof(&leaves, 2)?;
+
+        let outsider = calculate_hash(b"not a shard");
+        assert!(!verify_merkle_proof(&outsider, &proof, &root)?);
+
+        // Proof for the wrong position does not verify
+        assert!(!verify_merkle_proof(&leaves[3], &proof, &root)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merkle_invalid_inputs() {
+        assert!(matches!(build_merkle_root(&[]), Err(Error::Validation(_))));
+        assert!(matches!(
+            build_merkle_proof(&leaves(2), 2),
+            Err(Error::Validation(_))
+        ));
+        assert!(build_merkle_root(&["zz".to_string()]).is_err());
+    }
+}
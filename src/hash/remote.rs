@@ -0,0 +1,209 @@
+//! # Remote Content Hashing
+//!
+//! Hashes ingredients that live in object storage by streaming them over
+//! HTTP(S). `s3://bucket/key` URLs are fetched through the bucket's HTTPS
+//! endpoint, `https://<bucket>.s3.amazonaws.com/<key>` by default, or
+//! `<endpoint>/<bucket>/<key>` when `ATLAS_S3_ENDPOINT` is set (for example
+//! for MinIO). Only objects readable without credentials can be fetched.
+//!
+//! Downloads are bounded by a timeout and a size cap so a slow or oversized
+//! object cannot stall verification.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::hash::remote::{is_remote_url, resolve_url};
+//!
+//! assert!(is_remote_url("s3://models/resnet.onnx"));
+//! assert!(!is_remote_url("file:///models/resnet.onnx"));
+//! # if std::env::var("ATLAS_S3_ENDPOINT").is_err() {
+//! assert_eq!(
+//!     resolve_url("s3://models/resnet.onnx").unwrap(),
+//!     "https://models.s3.amazonaws.com/resnet.onnx"
+//! );
+//! # }
+//! ```
+
+use super::{DigestAlgorithm, calculate_reader_hash_with_algorithm};
+use crate::error::{Error, Result};
+use std::io::Read;
+use std::time::Duration;
+
+/// Environment variable overriding the endpoint used for `s3://` URLs
+pub const S3_ENDPOINT_ENV: &str = "ATLAS_S3_ENDPOINT";
+
+/// Bounds applied when downloading remote content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchLimits {
+    /// Maximum time for the whole download
+    pub timeout: Duration,
+    /// Maximum number of bytes read before the download is aborted
+    pub max_bytes: u64,
+}
+
+impl Default for FetchLimits {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(600),
+            max_bytes: 64 * 1024 * 1024 * 1024,
+        }
+    }
+}
+
+/// Returns true for URLs that can be fetched by [`hash_remote`]
+pub fn is_remote_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://") || url.starts_with("s3://")
+}
+
+/// Maps `s3://` URLs to their HTTPS endpoint and returns other URLs unchanged
+pub fn resolve_url(url: &str) -> Result<String> {
+    let Some(location) = url.strip_prefix("s3://") else {
+        return Ok(url.to_string());
+    };
+
+    let (bucket, key) = location
+        .split_once('/')
+        .filter(|(bucket, key)| !bucket.is_empty() && !key.is_empty())
+        .ok_or_else(|| {
+            Error::Validation(format!("Invalid S3 URL {url}: expected s3://bucket/key"))
+        })?;
+
+    Ok(match std::env::var(S3_ENDPOINT_ENV) {
+        Ok(endpoint) => format!("{}/{bucket}/{key}", endpoint.trim_end_matches('/')),
+        Err(_) => format!("https://{bucket}.s3.amazonaws.com/{key}"),
+    })
+}
+
+/// Downloads `url` and returns the hex hash of its content
+pub fn hash_remote(
+    url: &str,
+    algorithm: impl Into<DigestAlgorithm>,
+    limits: &FetchLimits,
+) -> Result<String> {
+    let resolved = resolve_url(url)?;
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(limits.timeout)
+        .build()
+        .map_err(|e| Error::Validation(format!("Failed to create HTTP client: {e}")))?;
+
+    let response = client
+        .get(&resolved)
+        .send()
+        .map_err(|e| Error::Validation(format!("Failed to fetch {url}: {e}")))?;
+
+    if !response.status().is_success() {
+        return Err(Error::Validation(format!(
+            "Fetching {url} returned status {}",
+            response.status()
+        )));
+    }
+
+    let too_large = || {
+        Error::Validation(format!(
+            "Remote content at {url} exceeds the {} byte limit",
+            limits.max_bytes
+        ))
+    };
+    if response
+        .content_length()
+        .is_some_and(|length| length > limits.max_bytes)
+    {
+        return Err(too_large());
+    }
+
+    let mut reader = CappedReader {
+        inner: response,
+        remaining: limits.max_bytes,
+        exceeded: false,
+    };
+    let hash = calculate_reader_hash_with_algorithm(&mut reader, algorithm);
+
+    match hash {
+        _ if reader.exceeded => Err(too_large()),
+        Ok(hash) => Ok(hash),
+        Err(e) => Err(Error::Validation(format!("Failed to read {url}: {e}"))),
+    }
+}
+
+// Fails the read once more than `remaining` bytes have been produced
+struct CappedReader<R> {
+    inner: R,
+    remaining: u64,
+    exceeded: bool,
+}
+
+impl<R: Read> Read for CappedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read as u64 > self.remaining {
+            self.exceeded = true;
+            return Err(std::io::Error::other("size limit exceeded"));
+        }
+        self.remaining -= read as u64;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::calculate_hash_with_algorithm;
+    use atlas_c2pa_lib::cose::HashAlgorithm;
+
+    #[test]
+    fn test_hash_remote_streams_content() -> Result<()> {
+        let mut server = mockito::Server::new();
+        let body = vec![7u8; 100_000];
+        let _mock = server
+            .mock("GET", "/models/weights.bin")
+            .with_body(&body)
+            .create();
+
+        let url = format!("{}/models/weights.bin", server.url());
+        let hash = hash_remote(&url, &HashAlgorithm::Sha384, &FetchLimits::default())?;
+        assert_eq!(
+            hash,
+            calculate_hash_with_algorithm(&body, &HashAlgorithm::Sha384)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_remote_enforces_limits_and_status() {
+        let mut server = mockito::Server::new();
+        let _large = server
+            .mock("GET", "/large")
+            .with_body(vec![0u8; 4096])
+            .create();
+        let _missing = server.mock("GET", "/missing").with_status(404).create();
+
+        let limits = FetchLimits {
+            max_bytes: 1024,
+            ..Default::default()
+        };
+        match hash_remote(
+            &format!("{}/large", server.url()),
+            &HashAlgorithm::Sha256,
+            &limits,
+        ) {
+            Err(Error::Validation(msg)) => assert!(msg.contains("1024 byte limit"), "{msg}"),
+            other => panic!("expected a size limit error, got {other:?}"),
+        }
+
+        match hash_remote(
+            &format!("{}/missing", server.url()),
+            &HashAlgorithm::Sha256,
+            &FetchLimits::default(),
+        ) {
+            Err(Error::Validation(msg)) => assert!(msg.contains("404"), "{msg}"),
+            other => panic!("expected a status error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_invalid_s3_url() {
+        assert!(resolve_url("s3://bucket-only").is_err());
+        assert!(resolve_url("s3:///key").is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! # Sampled Hashing
+//!
+//! Hashing terabyte-scale files in full can take hours, which is too slow for
+//! a quick sanity check. A sampled hash covers only part of the file: its
+//! size, the first and last [`EDGE_BYTES`] and [`SAMPLE_BLOCKS`] blocks of
+//! [`BLOCK_BYTES`] spread evenly in between, all fed into one SHA-256.
+//!
+//! A sampled hash detects truncation, appended data, a replaced file and
+//! changes to the sampled regions, but **not** a change anywhere else. It
+//! is therefore recorded with its own algorithm name, [`SAMPLE_ALG`], so it
+//! can never be mistaken for a full hash, and verification reports a
+//! matching sampled hash as a warning rather than as verified. Files no
+//! larger than the sampled regions are hashed in full under the same scheme.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::hash::sample::calculate_sampled_file_hash;
+//! use std::io::Write;
+//!
+//! let mut file = tempfile::NamedTempFile::new().unwrap();
+//! file.write_all(b"small file").unwrap();
+//! let hash = calculate_sampled_file_hash(file.path()).unwrap();
+//! assert_eq!(hash.len(), 64);
+//! ```
+
+use crate::error::Result;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Algorithm name recorded for sampled hashes
+pub const SAMPLE_ALG: &str = "sample-sha256";
+
+/// Bytes hashed at the start and at the end of the file
+pub const EDGE_BYTES: u64 = 1024 * 1024;
+
+/// Number of blocks sampled between the start and the end
+pub const SAMPLE_BLOCKS: u64 = 64;
+
+/// Size of each sampled block
+pub const BLOCK_BYTES: u64 = 64 * 1024;
+
+/// Returns true if an ingredient's `alg` marks a sampled hash
+pub fn is_sampled(alg: &str) -> bool {
+    alg == SAMPLE_ALG
+}
+
+/// Calculates the sampled hash of a file as lowercase hex
+pub fn calculate_sampled_file_hash(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+
+    let sampled_bytes = 2 * EDGE_BYTES + SAMPLE_BLOCKS * BLOCK_BYTES;
+    if size <= sampled_bytes {
+        let mut content = Vec::with_capacity(size as usize);
+        file.read_to_end(&mut content)?;
+        hasher.update(&content);
+        return Ok(hex::encode(hasher.finalize()));
+    }
+
+    let mut regions = vec![(0, EDGE_BYTES)];
+    // Blocks are centred in equal slices of the middle of the file
+    let middle = size - 2 * EDGE_BYTES;
+    let slice = middle / SAMPLE_BLOCKS;
+    for block in 0..SAMPLE_BLOCKS {
+        let offset = EDGE_BYTES + block * slice + (slice - BLOCK_BYTES) / 2;
+        regions.push((offset, BLOCK_BYTES));
+    }
+    regions.push((size - EDGE_BYTES, EDGE_BYTES));
+
+    let mut buffer = vec![0; EDGE_BYTES as usize];
+    for (offset, length) in regions {
+        crate::cancel::check()?;
+        let buffer = &mut buffer[..length as usize];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(buffer)?;
+        hasher.update(offset.to_le_bytes());
+        hasher.update(&*buffer);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sampled_hash_covers_edges_and_samples() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("large.bin");
+        let size = 2 * EDGE_BYTES + SAMPLE_BLOCKS * BLOCK_BYTES * 4;
+        let content: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &content)?;
+        let original = calculate_sampled_file_hash(&path)?;
+
+        // A change in the last bytes is detected
+        let mut changed = content.clone();
+        changed[size as usize - 1] ^= 1;
+        std::fs::write(&path, &changed)?;
+        assert_ne!(calculate_sampled_file_hash(&path)?, original);
+
+        // A change between the sampled blocks is not; that is the tradeoff
+        let slice = (size - 2 * EDGE_BYTES) / SAMPLE_BLOCKS;
+        let mut unsampled = content.clone();
+        unsampled[(EDGE_BYTES + slice / 8) as usize] ^= 1;
+        std::fs::write(&path, &unsampled)?;
+        assert_eq!(calculate_sampled_file_hash(&path)?, original);
+
+        // Appending data changes the size and the tail
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path)?;
+        file.write_all(b"extra")?;
+        assert_ne!(calculate_sampled_file_hash(&path)?, original);
+
+        Ok(())
+    }
+}
@@ -1,21 +1,41 @@
+use super::{
+    DEFAULT_CHUNK_SIZE, DigestAlgorithm, calculate_reader_hash_with_chunk_size, hash_reader,
+};
 use crate::error::Error;
+use crate::progress;
 use crate::utils::safe_open_file;
-use sha2::{Digest, Sha256};
-use std::io::Read;
+use sha2::Sha256;
 use std::path::Path;
 
+/// Calculate the SHA-256 hash of a file
 pub fn calculate_file_hash<P: AsRef<Path>>(path: P) -> Result<String, Error> {
-    let mut file = safe_open_file(path.as_ref(), false)?;
-    let mut hasher = Sha256::new();
-    let mut buffer = [0; 8192];
+    let file = safe_open_file(path.as_ref(), false)?;
+    hash_reader::<Sha256, _>(file, DEFAULT_CHUNK_SIZE)
+}
+
+/// Calculate the hash of a file with the given algorithm, reading
+/// `chunk_size` bytes at a time and showing a bytes-processed progress bar
+/// while hashing when `show_progress` is set
+pub fn calculate_file_hash_with_progress<P: AsRef<Path>>(
+    path: P,
+    algorithm: impl Into<DigestAlgorithm>,
+    chunk_size: usize,
+    show_progress: bool,
+) -> Result<String, Error> {
+    let file = std::fs::File::open(path.as_ref())?;
+    let len = file.metadata()?.len();
+
+    let pb = progress::create_bytes_progress_bar(len, show_progress);
+    pb.set_message(
+        path.as_ref()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    );
+    let reader = pb.wrap_read(file);
 
-    loop {
-        let bytes_read = file.read(&mut buffer).map_err(Error::Io)?;
-        if bytes_read == 0 {
-            break;
-        }
-        hasher.update(&buffer[..bytes_read]);
-    }
+    let hash = calculate_reader_hash_with_chunk_size(reader, algorithm, chunk_size);
+    pb.finish_and_clear();
 
-    Ok(hex::encode(hasher.finalize()))
+    hash
 }
@@ -24,6 +24,29 @@
 //!     --print
 //! ```
 //!
+//! ## Library Usage
+//!
+//! Verification functions return a [`VerificationReport`] rather than printing,
+//! so they can be called directly against any [`StorageBackend`]:
+//!
+//! ```
+//! use atlas_cli::storage::MemoryStorage;
+//! use atlas_cli::{VerificationConfig, verify_manifest_with_config};
+//!
+//! let storage = MemoryStorage::new();
+//! let config = VerificationConfig {
+//!     offline: true,
+//!     ..Default::default()
+//! };
+//! // Nothing has been stored yet, so there is nothing to verify
+//! assert!(verify_manifest_with_config("urn:c2pa:missing", &storage, &config).is_err());
+//! ```
+//!
+//! Likewise, [`build_manifest`] assembles and signs a manifest from a
+//! [`ManifestCreationConfig`] without printing or storing it, so callers can
+//! inspect or post-process the [`Manifest`](atlas_c2pa_lib::manifest::Manifest)
+//! before handing it to a backend.
+//!
 //! For more examples and detailed documentation, see:
 //! - [User Guide](https://github.com/IntelLabs/atlas-cli/blob/main/docs/USER_GUIDE.md)
 //! - [Examples](https://github.com/IntelLabs/atlas-cli/blob/main/docs/EXAMPLES.md)
@@ -31,11 +54,14 @@
 
 #![doc(html_root_url = "https://docs.rs/atlas-cli/0.1.0")]
 
+pub mod audit;
+pub mod cancel;
 pub mod cc_attestation;
 pub mod cli;
 pub mod error;
 pub mod hash;
 pub mod manifest;
+pub mod progress;
 pub mod signing;
 pub mod storage;
 #[cfg(test)]
@@ -57,6 +83,18 @@ pub struct Config {
     pub storage_config: StorageConfig,
     /// Whether to show progress bars
     pub show_progress: bool,
+    /// Directories symlinked inputs may point into; empty allows only the
+    /// current working directory
+    pub symlink_roots: Vec<PathBuf>,
+    /// Hash symlinked input files whose targets resolve into `symlink_roots`
+    /// instead of rejecting them
+    pub follow_symlinks: bool,
+    /// Only print results and problems, not progress or per-check status lines
+    pub quiet: bool,
+    /// Bytes read at a time while hashing the inputs of new manifests
+    pub hash_chunk_size: usize,
+    /// Audit log appended to by operations on stored manifests
+    pub audit_log: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -65,12 +103,22 @@ impl Default for Config {
             key_path: None,
             storage_config: StorageConfig::default(),
             show_progress: true,
+            symlink_roots: Vec::new(),
+            follow_symlinks: false,
+            quiet: false,
+            hash_chunk_size: hash::DEFAULT_CHUNK_SIZE,
+            audit_log: None,
         }
     }
 }
 
 /// Initialize logging for the CLI
 ///
+/// Progress messages are logged at `info` and problems at `warn`, both to
+/// stderr so that stdout only carries results such as a printed manifest.
+/// `RUST_LOG` sets the verbosity and defaults to `info`, e.g. `RUST_LOG=warn`
+/// keeps only warnings and `RUST_LOG=debug` adds diagnostics.
+///
 /// # Examples
 ///
 /// ```
@@ -82,8 +130,28 @@ impl Default for Config {
 /// assert!(result.is_ok() || result.is_err());
 /// ```
 pub fn init_logging() -> Result<()> {
-    env_logger::try_init().map_err(|e| Error::InitializationError(e.to_string()))
+    use std::io::Write;
+
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .format(|buf, record| match record.level() {
+            log::Level::Info => writeln!(buf, "{}", record.args()),
+            log::Level::Warn => writeln!(buf, "Warning: {}", record.args()),
+            log::Level::Error => writeln!(buf, "Error: {}", record.args()),
+            level => writeln!(buf, "[{level} {}] {}", record.target(), record.args()),
+        })
+        .try_init()
+        .map_err(|e| Error::InitializationError(e.to_string()))
 }
 
 // Re-export commonly used types and traits
-pub use storage::traits::StorageBackend;
+pub use manifest::report::{CheckStatus, VerificationCheck, VerificationReport};
+pub use manifest::utils::determine_manifest_type;
+pub use manifest::{
+    AssetKind, ManifestCreationConfig, VerificationConfig, VerifyAllSummary, build_manifest,
+    verify_all, verify_all_with_config, verify_dataset_manifest,
+    verify_dataset_manifest_with_config, verify_embedded_manifest, verify_evaluation_manifest,
+    verify_evaluation_manifest_with_config, verify_manifest, verify_manifest_with_config,
+    verify_model_manifest, verify_model_manifest_with_config, verify_software_manifest,
+    verify_software_manifest_with_config,
+};
+pub use storage::traits::{ArtifactLocation, ManifestType, StorageBackend};
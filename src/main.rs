@@ -2,17 +2,84 @@ use atlas_cli::{
     cli::{
         self,
         commands::{
-            CCAttestationCommands, DatasetCommands, EvaluationCommands, ManifestCommands,
-            ModelCommands, SoftwareCommands,
+            AuditCommands, BatchCommands, CCAttestationCommands, ConfigCommands, DatasetCommands,
+            EvaluationCommands, KeygenArgs, ManifestCommands, MigrateArgs, ModelCommands,
+            PubkeyArgs, SoftwareCommands,
         },
+        defaults::{self, Defaults},
     },
-    error::Result,
+    error::EXIT_USAGE,
+    storage::config::StorageConfig,
 };
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(author, version, about)]
 pub struct Cli {
+    /// Defaults file to read instead of ~/.config/atlas-cli/defaults.toml; command-line flags override its values
+    #[arg(long = "config", global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Disable progress bars
+    #[arg(long = "no-progress", global = true)]
+    no_progress: bool,
+
+    /// Only print results and problems: no progress messages, progress bars or verification status lines
+    #[arg(short = 'q', long = "quiet", global = true)]
+    quiet: bool,
+
+    /// Directory symlinked files may point into (repeatable; defaults to the current directory)
+    #[arg(long = "allow-symlink-root", global = true, value_name = "DIR")]
+    allow_symlink_roots: Vec<PathBuf>,
+
+    /// Hash symlinked input files whose targets are inside an allowed root instead of rejecting them
+    #[arg(long = "follow-symlinks", global = true)]
+    follow_symlinks: bool,
+
+    /// Fail instead of warning when a deprecated storage type (local, filesystem) is used
+    #[arg(long = "deny-deprecated-storage", global = true)]
+    deny_deprecated_storage: bool,
+
+    /// Seconds allowed to connect to the database storage service
+    #[arg(
+        long = "connect-timeout",
+        global = true,
+        value_name = "SECS",
+        default_value_t = 5
+    )]
+    connect_timeout: u64,
+
+    /// Seconds allowed for a whole request to the database storage service
+    #[arg(
+        long = "request-timeout",
+        global = true,
+        value_name = "SECS",
+        default_value_t = 30
+    )]
+    request_timeout: u64,
+
+    /// PEM public key of the Rekor log, to check its signed tree heads (built in for rekor.sigstore.dev)
+    #[arg(long = "rekor-public-key", global = true, value_name = "PATH")]
+    rekor_public_key: Option<PathBuf>,
+
+    /// KiB read at a time while hashing; larger chunks (e.g. 4096) can be faster on fast storage
+    #[arg(
+        long = "hash-chunk-size",
+        global = true,
+        value_name = "KIB",
+        default_value_t = 64,
+        value_parser = clap::value_parser!(u32).range(1..=65536)
+    )]
+    hash_chunk_size: u32,
+
+    /// Append a hash-chained record of every create, verify, update and delete of stored manifests to this file
+    #[arg(long = "audit-log", global = true, value_name = "PATH")]
+    audit_log: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -49,32 +116,125 @@ enum Commands {
         #[command(subcommand)]
         command: CCAttestationCommands,
     },
+    /// Generate a signing key pair
+    Keygen(KeygenArgs),
+    /// Print the public key and fingerprint of a signing key
+    Pubkey(PubkeyArgs),
+    /// Copy every manifest from one storage backend to another
+    Migrate(MigrateArgs),
+    /// Create many manifests at once
+    Batch {
+        #[command(subcommand)]
+        command: BatchCommands,
+    },
+    /// Inspect the settings read from the defaults file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Check the audit log
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     // Initialize logging
-    atlas_cli::init_logging()?;
+    if let Err(e) = atlas_cli::init_logging() {
+        eprintln!("{}", cli::format_error(&e));
+        return ExitCode::from(e.exit_code());
+    }
 
-    // Parse command line arguments
-    let cli = Cli::parse();
+    // Values from the defaults file become the flags' defaults, so flags on
+    // the command line take precedence over them
+    let args: Vec<OsString> = std::env::args_os().collect();
+    let defaults = match Defaults::load(defaults::config_path_from_args(&args).as_deref()) {
+        Ok(defaults) => defaults,
+        Err(e) => {
+            eprintln!("{}", cli::format_error(&e));
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    let command_line = match defaults.apply(Cli::command()) {
+        Ok(command_line) => command_line,
+        Err(e) => {
+            eprintln!("{}", cli::format_error(&e));
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    // Parse command line arguments; usage errors get their own exit code so
+    // they can't be mistaken for validation failures
+    let parsed = command_line
+        .clone()
+        .try_get_matches_from(args)
+        .and_then(|matches| Cli::from_arg_matches(&matches).map(|cli| (cli, matches)));
+    let (cli, matches) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let _ = e.print();
+            return if e.use_stderr() {
+                ExitCode::from(EXIT_USAGE)
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
+
+    let config = atlas_cli::Config {
+        show_progress: !cli.no_progress,
+        symlink_roots: cli.allow_symlink_roots,
+        follow_symlinks: cli.follow_symlinks,
+        quiet: cli.quiet,
+        hash_chunk_size: cli.hash_chunk_size as usize * 1024,
+        audit_log: cli.audit_log,
+        storage_config: StorageConfig {
+            connect_timeout: Duration::from_secs(cli.connect_timeout),
+            request_timeout: Duration::from_secs(cli.request_timeout),
+            deny_deprecated: cli.deny_deprecated_storage,
+            rekor_public_key: cli.rekor_public_key,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    // Quiet mode also drops log messages below `warn`
+    if config.quiet {
+        log::set_max_level(log::LevelFilter::Warn.min(log::max_level()));
+    }
+    if let Err(e) = atlas_cli::cancel::install_handler() {
+        log::warn!("{e}");
+    }
 
     // Handle commands
     let result = match cli.command {
-        Commands::Dataset { command } => cli::handlers::handle_dataset_command(command),
-        Commands::Model { command } => cli::handlers::handle_model_command(command),
-        Commands::Software { command } => cli::handlers::handle_software_command(command),
+        Commands::Dataset { command } => cli::handlers::handle_dataset_command(command, &config),
+        Commands::Model { command } => cli::handlers::handle_model_command(command, &config),
+        Commands::Software { command } => cli::handlers::handle_software_command(command, &config),
 
-        Commands::Manifest { command } => cli::handlers::handle_manifest_command(command),
-        Commands::Evaluation { command } => cli::handlers::handle_evaluation_command(command),
+        Commands::Manifest { command } => cli::handlers::handle_manifest_command(command, &config),
+        Commands::Evaluation { command } => {
+            cli::handlers::handle_evaluation_command(command, &config)
+        }
         Commands::CCAttestation { command } => {
             cli::handlers::handle_cc_attestation_command(command)
         }
+        Commands::Keygen(args) => cli::handlers::handle_keygen_command(args),
+        Commands::Pubkey(args) => cli::handlers::handle_pubkey_command(args),
+        Commands::Migrate(args) => cli::handlers::handle_migrate_command(args, &config),
+        Commands::Batch { command } => cli::handlers::handle_batch_command(command, &config),
+        Commands::Config { command } => {
+            cli::handlers::handle_config_command(command, &command_line, &matches, &defaults)
+        }
+        Commands::Audit { command } => cli::handlers::handle_audit_command(command, &config),
     };
 
-    // Format and display any errors
-    if let Err(ref e) = result {
-        eprintln!("{}", cli::format_error(e));
+    // Format and display any errors, exiting with a code for their kind
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", cli::format_error(&e));
+            ExitCode::from(e.exit_code())
+        }
     }
-
-    result
 }
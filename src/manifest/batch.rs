@@ -0,0 +1,224 @@
+//! # Batch Manifest Creation
+//!
+//! Creates many manifests from a single spec file, e.g. to onboard an
+//! existing model registry without invoking the CLI once per model. The spec
+//! is a JSON array of entries, each describing one manifest:
+//!
+//! ```json
+//! [
+//!   {"type": "dataset", "name": "Training Data", "paths": ["data/train.csv"]},
+//!   {
+//!     "type": "model",
+//!     "name": "Classifier",
+//!     "paths": ["models/classifier.onnx"],
+//!     "ingredient_names": ["Classifier Weights"],
+//!     "author_org": "Example Org",
+//!     "linked_manifests": ["Training Data"]
+//!   }
+//! ]
+//! ```
+//!
+//! Signing, hashing and storage settings are shared by every entry. A failing
+//! entry is recorded in the [`BatchSummary`] and the remaining entries are
+//! still created. Linked manifests may name an earlier entry of the same
+//! spec, which is replaced with the ID it was stored under.
+
+use crate::audit;
+use crate::error::{Error, Result};
+use crate::manifest::common::{AssetKind, build_manifest};
+use crate::manifest::config::ManifestCreationConfig;
+use crate::manifest::schema;
+use crate::manifest::software::with_software_fields;
+use crate::manifest::utils::parse_asset_type;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Kind of manifest a spec entry creates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchEntryKind {
+    #[default]
+    Model,
+    Dataset,
+    Software,
+}
+
+/// One manifest described in a batch spec
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchEntry {
+    /// Kind of manifest; defaults to `model`
+    #[serde(rename = "type", default)]
+    pub kind: BatchEntryKind,
+    pub name: String,
+    pub paths: Vec<PathBuf>,
+    /// Ingredient names; defaults to the file names of `paths`
+    #[serde(default)]
+    pub ingredient_names: Vec<String>,
+    /// Relationship of each ingredient; defaults to `componentOf`
+    #[serde(default)]
+    pub relationships: Vec<String>,
+    /// URL of each ingredient; defaults to the `file://` URL of its path
+    #[serde(default)]
+    pub ingredient_urls: Vec<String>,
+    pub author_org: Option<String>,
+    pub author_name: Option<String>,
+    pub description: Option<String>,
+    /// Manifest IDs, or names of earlier entries in the spec
+    #[serde(default)]
+    pub linked_manifests: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub asset_type: Option<String>,
+    /// Required for software entries
+    pub software_type: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Outcome of a batch, in spec order
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    /// Name and stored ID of each created manifest
+    pub created: Vec<(String, String)>,
+    /// Name of each failed entry and why it failed
+    pub failed: Vec<(String, Error)>,
+}
+
+/// Reads a batch spec file
+pub fn load_spec(path: &Path) -> Result<Vec<BatchEntry>> {
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| {
+        Error::Validation(format!(
+            "Invalid batch spec {}: {e}. Expected a JSON array of manifest entries",
+            path.display()
+        ))
+    })
+}
+
+/// Creates and stores a manifest for each entry, continuing past failures.
+///
+/// Each entry starts from `base`, which must have storage configured and
+/// supplies the settings entries do not override (key, hash algorithm, ...).
+/// Relative entry paths are resolved against `base_dir`, normally the
+/// directory containing the spec file.
+pub fn create_batch(
+    entries: Vec<BatchEntry>,
+    base: &ManifestCreationConfig,
+    base_dir: &Path,
+) -> Result<BatchSummary> {
+    let storage = base.storage.ok_or_else(|| {
+        Error::Validation("Batch creation requires a storage backend".to_string())
+    })?;
+
+    let mut summary = BatchSummary::default();
+    let mut stored_ids: HashMap<String, String> = HashMap::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        crate::cancel::check()?;
+        let name = entry.name.clone();
+        log::info!("Creating manifest {} ({name})", index + 1);
+
+        let result =
+            entry_config(entry, base, base_dir, &stored_ids).and_then(|(config, asset_kind)| {
+                let manifest = build_manifest(&config, asset_kind)?;
+                if !config.skip_schema_check {
+                    schema::validate_manifest_schema(&manifest)?;
+                }
+                let stored = storage.store_manifest(&manifest);
+                let audit_id = stored.as_ref().unwrap_or(&manifest.instance_id).clone();
+                audit::audited(
+                    config.audit_log.as_deref(),
+                    "create",
+                    Some(&audit_id),
+                    stored,
+                )
+            });
+        match result {
+            Ok(id) => {
+                stored_ids.insert(name.clone(), id.clone());
+                summary.created.push((name, id));
+            }
+            Err(Error::Cancelled) => {
+                log::warn!(
+                    "Batch cancelled after creating {} manifest(s)",
+                    summary.created.len()
+                );
+                return Err(Error::Cancelled);
+            }
+            Err(e) => {
+                log::warn!("Failed to create manifest {} ({name}): {e}", index + 1);
+                summary.failed.push((name, e));
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Builds the creation config for one entry
+fn entry_config(
+    entry: BatchEntry,
+    base: &ManifestCreationConfig,
+    base_dir: &Path,
+    stored_ids: &HashMap<String, String>,
+) -> Result<(ManifestCreationConfig, AssetKind)> {
+    if entry.paths.is_empty() {
+        return Err(Error::Validation("Entry has no paths".to_string()));
+    }
+    let ingredient_names = if entry.ingredient_names.is_empty() {
+        entry
+            .paths
+            .iter()
+            .map(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .ok_or_else(|| {
+                        Error::Validation(format!("Path {} has no file name", path.display()))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else if entry.ingredient_names.len() == entry.paths.len() {
+        entry.ingredient_names
+    } else {
+        return Err(Error::Validation(format!(
+            "Entry has {} paths but {} ingredient names",
+            entry.paths.len(),
+            entry.ingredient_names.len()
+        )));
+    };
+
+    let linked_manifests = entry
+        .linked_manifests
+        .into_iter()
+        .map(|link| stored_ids.get(&link).cloned().unwrap_or(link))
+        .collect::<Vec<_>>();
+
+    let mut config = base.clone_without_storage();
+    config.paths = entry.paths.iter().map(|path| base_dir.join(path)).collect();
+    config.ingredient_names = ingredient_names;
+    config.ingredient_relationships = entry.relationships;
+    config.ingredient_urls = entry.ingredient_urls;
+    config.name = entry.name;
+    config.author_org = entry.author_org.or_else(|| base.author_org.clone());
+    config.author_name = entry.author_name.or_else(|| base.author_name.clone());
+    config.description = entry.description;
+    config.linked_manifests = (!linked_manifests.is_empty()).then_some(linked_manifests);
+    config.tags.extend(entry.tags);
+    if let Some(asset_type) = entry.asset_type {
+        config.asset_type = Some(parse_asset_type(&asset_type)?);
+    }
+
+    Ok(match entry.kind {
+        BatchEntryKind::Model => (config, AssetKind::Model),
+        BatchEntryKind::Dataset => (config, AssetKind::Dataset),
+        BatchEntryKind::Software => {
+            let software_type = entry.software_type.ok_or_else(|| {
+                Error::Validation("Software entries require a software_type".to_string())
+            })?;
+            (
+                with_software_fields(config, software_type, entry.version),
+                AssetKind::Software,
+            )
+        }
+    })
+}
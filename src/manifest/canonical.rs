@@ -0,0 +1,218 @@
+//! # Canonical Serialization
+//!
+//! Deterministic encodings used wherever manifest bytes are signed or hashed,
+//! so that semantically identical values always produce identical bytes
+//! regardless of map insertion order or serde version.
+//!
+//! - CBOR follows the RFC 7049 canonical form: map keys are sorted shortest
+//!   first and then bytewise, and integers use their shortest encoding.
+//! - JSON is compact with object keys sorted lexicographically.
+//!
+//! Cross-references created before canonical JSON was introduced hash the
+//! target's plain `serde_json` serialization instead.
+//! [`match_cross_reference_hash`] still accepts those hashes as
+//! [`CrossReferenceHashScheme::Legacy`], so verification can warn rather
+//! than fail on them.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::canonical::to_canonical_json;
+//! use serde_json::json;
+//!
+//! let a = to_canonical_json(&json!({"b": 1, "a": [true, null]})).unwrap();
+//! assert_eq!(a, r#"{"a":[true,null],"b":1}"#);
+//! ```
+
+use crate::error::{Error, Result};
+use atlas_c2pa_lib::manifest::Manifest;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// How a cross-reference's `manifest_hash` was computed from its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossReferenceHashScheme {
+    /// SHA-256 of the target's canonical JSON
+    Canonical,
+    /// SHA-256 of the target's plain `serde_json` serialization, as recorded
+    /// by releases before canonical JSON
+    Legacy,
+}
+
+/// Serialize a value to canonical CBOR, as signed in claim signatures
+pub fn to_canonical_cbor<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    // serde_cbor::Value maps are ordered by the canonical key ordering
+    let value =
+        serde_cbor::value::to_value(value).map_err(|e| Error::Serialization(e.to_string()))?;
+    serde_cbor::to_vec(&value).map_err(|e| Error::Serialization(e.to_string()))
+}
+
+/// Serialize a value to canonical JSON, as hashed in cross-references
+pub fn to_canonical_json<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    let value = serde_json::to_value(value).map_err(|e| Error::Serialization(e.to_string()))?;
+    let mut out = String::new();
+    write_canonical_json(&value, &mut out)?;
+    Ok(out)
+}
+
+/// Hex SHA-256 of a cross-reference target's canonical JSON, the hash new
+/// cross-references record
+pub fn cross_reference_hash(target: &Manifest, id: &str) -> Result<String> {
+    let json = to_canonical_json(target).map_err(|e| {
+        e.with_manifest_context("hashing cross-reference target", &target.title, id)
+    })?;
+    Ok(hex::encode(Sha256::digest(json.as_bytes())))
+}
+
+/// The scheme `recorded` was computed with from `target`, or `None` if it
+/// matches neither
+pub fn match_cross_reference_hash(
+    target: &Manifest,
+    id: &str,
+    recorded: &str,
+) -> Result<Option<CrossReferenceHashScheme>> {
+    if cross_reference_hash(target, id)? == recorded {
+        return Ok(Some(CrossReferenceHashScheme::Canonical));
+    }
+    let legacy = serde_json::to_string(target).map_err(|e| {
+        Error::Serialization(e.to_string()).with_manifest_context(
+            "hashing cross-reference target",
+            &target.title,
+            id,
+        )
+    })?;
+    Ok((hex::encode(Sha256::digest(legacy.as_bytes())) == recorded)
+        .then_some(CrossReferenceHashScheme::Legacy))
+}
+
+fn write_canonical_json(value: &Value, out: &mut String) -> Result<()> {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            // Sort explicitly rather than relying on serde_json's map type,
+            // which keeps insertion order when `preserve_order` is enabled
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(
+                    &serde_json::to_string(key).map_err(|e| Error::Serialization(e.to_string()))?,
+                );
+                out.push(':');
+                write_canonical_json(item, out)?;
+            }
+            out.push('}');
+        }
+        scalar => out.push_str(
+            &serde_json::to_string(scalar).map_err(|e| Error::Serialization(e.to_string()))?,
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+    use atlas_c2pa_lib::claim::ClaimV2;
+    use atlas_c2pa_lib::datetime_wrapper::OffsetDateTimeWrapper;
+    use std::collections::HashMap;
+    use time::OffsetDateTime;
+
+    fn claim_with_data(data: Value) -> ClaimV2 {
+        ClaimV2 {
+            instance_id: "urn:c2pa:test".to_string(),
+            ingredients: vec![],
+            created_assertions: vec![Assertion::CustomAssertion(CustomAssertion {
+                label: "test.shuffled".to_string(),
+                data,
+            })],
+            claim_generator_info: "c2pa-cli".to_string(),
+            signature: None,
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::UNIX_EPOCH),
+        }
+    }
+
+    #[test]
+    fn test_canonical_serialization_ignores_insertion_order() -> Result<()> {
+        let keys = ["zeta", "alpha", "mu", "beta", "a", "longer_key"];
+
+        let mut forward = serde_json::Map::new();
+        let mut backward = serde_json::Map::new();
+        let mut forward_hash = HashMap::new();
+        let mut backward_hash = HashMap::new();
+        for (i, key) in keys.iter().enumerate() {
+            forward.insert(key.to_string(), Value::from(i as u64 * 1000));
+            forward_hash.insert(key.to_string(), i as u64 * 1000);
+        }
+        for (i, key) in keys.iter().enumerate().rev() {
+            backward.insert(key.to_string(), Value::from(i as u64 * 1000));
+            backward_hash.insert(key.to_string(), i as u64 * 1000);
+        }
+
+        let first = claim_with_data(Value::Object(forward));
+        let second = claim_with_data(Value::Object(backward));
+        assert_eq!(to_canonical_cbor(&first)?, to_canonical_cbor(&second)?);
+        assert_eq!(to_canonical_json(&first)?, to_canonical_json(&second)?);
+
+        // HashMap iteration order differs between instances
+        assert_eq!(
+            to_canonical_cbor(&forward_hash)?,
+            to_canonical_cbor(&backward_hash)?
+        );
+        assert_eq!(
+            to_canonical_json(&forward_hash)?,
+            to_canonical_json(&backward_hash)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_cbor_key_order() -> Result<()> {
+        let map: HashMap<&str, u8> = [("bb", 1), ("a", 2), ("c", 3)].into_iter().collect();
+        let encoded = to_canonical_cbor(&map)?;
+
+        // Shorter keys sort first, then bytewise: a, c, bb
+        assert_eq!(
+            encoded,
+            vec![
+                0xa3, 0x61, b'a', 0x02, 0x61, b'c', 0x03, 0x62, b'b', b'b', 0x01
+            ]
+        );
+
+        // Integers use their shortest encoding
+        assert_eq!(to_canonical_cbor(&23u64)?, vec![0x17]);
+        assert_eq!(to_canonical_cbor(&24u64)?, vec![0x18, 0x18]);
+        assert_eq!(to_canonical_cbor(&256u64)?, vec![0x19, 0x01, 0x00]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_json_nested() -> Result<()> {
+        let value = serde_json::json!({
+            "outer": {"y": [{"d": 1, "c": 2}], "x": "s\"q"},
+            "first": 1.5
+        });
+        assert_eq!(
+            to_canonical_json(&value)?,
+            r#"{"first":1.5,"outer":{"x":"s\"q","y":[{"c":2,"d":1}]}}"#
+        );
+        Ok(())
+    }
+}
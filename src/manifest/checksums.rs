@@ -0,0 +1,103 @@
+//! # Checksum Files
+//!
+//! To let artifacts be checked without C2PA tooling, `manifest
+//! export-checksums` writes the ingredient hashes of a manifest as a
+//! checksum file in the format of GNU `sha256sum` and friends: one
+//! `<hash>  <path>` line per ingredient, checkable with e.g. `sha256sum -c`.
+//!
+//! A checksum file holds hashes of a single algorithm, so only ingredients
+//! whose recorded `alg` is the requested one are listed. Ingredients whose
+//! hash cannot be reproduced by hashing a local file (sampled or
+//! decompressed-content hashes, stdin and remote inputs) are skipped as well.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::checksums::checksum_line;
+//!
+//! assert_eq!(checksum_line("ab12", "data/train.csv"), "ab12  data/train.csv");
+//! ```
+
+use crate::hash::sample::is_sampled;
+use crate::hash::{self, DigestAlgorithm};
+use crate::manifest::common::STDIN_URL_SCHEME;
+use crate::manifest::utils::decompressed_ingredients;
+use atlas_c2pa_lib::manifest::Manifest;
+
+/// Ingredient hashes of a manifest in checksum file format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksums {
+    /// Checksum file content, one line per listed ingredient
+    pub content: String,
+    pub listed: usize,
+    /// Title and reason for each ingredient that was left out
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Lists the ingredients of `manifest` hashed with `alg` as a checksum file
+pub fn export_checksums(manifest: &Manifest, alg: DigestAlgorithm) -> Checksums {
+    let decompressed = decompressed_ingredients(manifest);
+
+    let mut checksums = Checksums {
+        content: String::new(),
+        listed: 0,
+        skipped: Vec::new(),
+    };
+    for ingredient in &manifest.ingredients {
+        let data = &ingredient.data;
+        let skip_reason = if is_sampled(&data.alg) {
+            Some("has a sampled hash that only covers part of the file".to_string())
+        } else if decompressed.contains_key(&data.url) {
+            Some("has a hash of its decompressed content".to_string())
+        } else if data.url.starts_with(STDIN_URL_SCHEME) {
+            Some("was read from stdin".to_string())
+        } else if data.url.contains("://") && !data.url.starts_with("file://") {
+            Some(format!("is not a local file: {}", data.url))
+        } else if !data.alg.eq_ignore_ascii_case(alg.as_str()) {
+            Some(format!("is hashed with {}, not {}", data.alg, alg.as_str()))
+        } else if hash::get_hash_length(alg.as_str()) != data.hash.len() {
+            Some(format!(
+                "has a hash that is not a valid {} hash",
+                alg.as_str()
+            ))
+        } else {
+            None
+        };
+
+        match skip_reason {
+            Some(reason) => checksums.skipped.push((ingredient.title.clone(), reason)),
+            None => {
+                let path = data.url.trim_start_matches("file://");
+                checksums.content.push_str(&checksum_line(&data.hash, path));
+                checksums.content.push('\n');
+                checksums.listed += 1;
+            }
+        }
+    }
+    checksums
+}
+
+/// Formats one checksum file line, escaping paths with backslashes or
+/// newlines the way GNU coreutils does
+pub fn checksum_line(hash: &str, path: &str) -> String {
+    if path.contains(['\\', '\n', '\r']) {
+        let escaped = path
+            .replace('\\', "\\\\")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        format!("\\{hash}  {escaped}")
+    } else {
+        format!("{hash}  {path}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_line_escapes_special_paths() {
+        assert_eq!(checksum_line("ab", "a b.txt"), "ab  a b.txt");
+        assert_eq!(checksum_line("ab", "a\\b\nc"), "\\ab  a\\\\b\\nc");
+    }
+}
@@ -1,34 +1,87 @@
+use crate::audit;
+use crate::cancel;
 use crate::cc_attestation;
-use crate::error::{Error, Result};
-use crate::hash;
-
-use crate::hash::utils::calculate_file_hash;
-use crate::manifest::config::ManifestCreationConfig;
+use crate::error::{Error, Result, SigningError};
+use crate::hash::{self, DigestAlgorithm};
+use crate::progress;
+
+use crate::hash::decompress::{Compression, calculate_decompressed_file_hash, decompressed_path};
+use crate::hash::remote::{self, FetchLimits};
+use crate::hash::sample::{SAMPLE_ALG, calculate_sampled_file_hash, is_sampled};
+use crate::hash::utils::calculate_file_hash_with_progress;
+use crate::manifest::canonical::{self, CrossReferenceHashScheme};
+use crate::manifest::config::{ManifestCreationConfig, VerificationConfig};
+use crate::manifest::environment::Environment;
+use crate::manifest::lineage;
+use crate::manifest::model_card::{
+    self as model_card, MODEL_CARD_RELATIONSHIP, MODEL_CARD_TITLE, ModelCardLink,
+};
+use crate::manifest::policy::{self, Policy};
+use crate::manifest::report::{VerificationCheck, VerificationReport};
+use crate::manifest::schema;
+use crate::manifest::signatures;
+use crate::manifest::training;
 use crate::manifest::utils::{
-    determine_dataset_type, determine_format, determine_model_type, determine_software_type,
+    DEFAULT_RELATIONSHIP, decompressed_ingredients, detect_dataset_type, detect_model_type,
+    determine_format, determine_manifest_type, determine_software_type, parse_relationship,
+    sniff_content,
 };
+use crate::manifest::validity::{self, ValidityWindow};
+use crate::manifest::version;
 use crate::signing;
-use crate::storage::traits::{ArtifactLocation, StorageBackend};
+use crate::signing::signer::{LocalSigner, Signer};
+use crate::signing::timestamp;
+use crate::signing::trust::TrustList;
+use crate::storage::rekor::RekorStorage;
+use crate::storage::traits::{ArtifactLocation, ManifestFilter, ManifestType, StorageBackend};
+use crate::utils::safe_file_path_with_roots;
 use atlas_c2pa_lib::assertion::{
     Action, ActionAssertion, Assertion, Author, CreativeWorkAssertion, CustomAssertion,
 };
 use atlas_c2pa_lib::asset_type::AssetType;
 use atlas_c2pa_lib::claim::ClaimV2;
-use atlas_c2pa_lib::cose::HashAlgorithm;
 use atlas_c2pa_lib::cross_reference::CrossReference;
 use atlas_c2pa_lib::datetime_wrapper::OffsetDateTimeWrapper;
 use atlas_c2pa_lib::ingredient::{Ingredient, IngredientData};
 use atlas_c2pa_lib::manifest::Manifest;
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD;
+use serde::Serialize;
 use serde_json::to_string_pretty;
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
 use std::path::{Path, PathBuf};
-use tdx_workload_attestation::get_platform_name;
+use std::str::FromStr;
+use std::sync::Arc;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+/// Label of the assertion recording user-defined manifest tags
+pub const TAGS_LABEL: &str = "atlas.tags";
+
+/// Label of the assertion recording combined hashes of directory inputs
+pub const DIRECTORY_HASHES_LABEL: &str = "atlas.directory_hashes";
+
+/// Label of the assertion listing ingredients hashed after decompression
+pub const DECOMPRESSED_HASHES_LABEL: &str = "atlas.decompressed_hashes";
+
+/// Generator recorded in manifests unless the config names another one
+pub const DEFAULT_CLAIM_GENERATOR: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Input path meaning "read the ingredient's bytes from stdin"
+pub const STDIN_PATH: &str = "-";
+
+/// URL scheme recorded for ingredients read from stdin, followed by the
+/// ingredient title
+pub const STDIN_URL_SCHEME: &str = "stdin:";
+
+/// Directory entries that are always skipped when walking directory inputs
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[".git", ".hg", ".svn", "__pycache__", ".DS_Store"];
+
 /// Asset type enum to distinguish between models, datasets, software, and evaluations
+#[derive(Debug)]
 pub enum AssetKind {
     Model,
     Dataset,
@@ -38,31 +91,176 @@ pub enum AssetKind {
 
 /// Creates a manifest for a model, dataset, software, or evaluation
 pub fn create_manifest(config: ManifestCreationConfig, asset_kind: AssetKind) -> Result<()> {
-    // Create ingredients using the helper function
-    let mut ingredients = Vec::new();
-    for (path, ingredient_name) in config.paths.iter().zip(config.ingredient_names.iter()) {
-        // Determine asset type and format based on asset kind
-        let format = determine_format(path)?;
-        let asset_type = match asset_kind {
-            AssetKind::Model => determine_model_type(path)?,
-            AssetKind::Dataset => determine_dataset_type(path)?,
-            AssetKind::Software => determine_software_type(path)?,
-            AssetKind::Evaluation => AssetType::Dataset, // Use Dataset type for evaluation results
-        };
+    // Refuse to dump binary CBOR into a terminal before doing any work
+    let prints_to_stdout =
+        config.output_path.is_none() && (config.print || config.storage.is_none());
+    if prints_to_stdout
+        && !config.hex_output
+        && !config.dry_run
+        && config.output_format.eq_ignore_ascii_case("cbor")
+        && std::io::stdout().is_terminal()
+    {
+        return Err(Error::Validation(
+            "Refusing to write binary CBOR to a terminal; redirect stdout, use --output=<file> or pass --hex"
+                .to_string(),
+        ));
+    }
+
+    if config.dry_run {
+        let inputs = prepare_inputs(&config)?;
+        return report_dry_run(
+            &config,
+            &asset_kind,
+            &inputs.files,
+            inputs.directories.len(),
+        );
+    }
 
-        // Use the helper function to create the ingredient
-        let ingredient = create_ingredient_from_path_with_algorithm(
-            path,
-            ingredient_name,
-            asset_type,
-            format,
-            &config.hash_alg,
+    let manifest = build_manifest(&config, asset_kind)?;
+
+    // Output manifest if requested
+    if let Some(output_path) = &config.output_path {
+        write_manifest_file(&manifest, &config.output_format, output_path)?;
+        println!("Manifest written to {}", output_path.display());
+    } else if config.print || config.storage.is_none() {
+        let mut stdout = std::io::stdout().lock();
+        write_manifest(
+            &mut stdout,
+            &manifest,
+            &config.output_format,
+            config.hex_output,
         )?;
+        stdout.flush()?;
+    }
+
+    // Store manifest if storage is provided
+    if let Some(storage) = &config.storage
+        && !config.print
+    {
+        if !config.skip_schema_check {
+            schema::validate_manifest_schema(&manifest)?;
+        }
+        let stored = storage.store_manifest(&manifest);
+        let audit_id = stored.as_ref().unwrap_or(&manifest.instance_id).clone();
+        let id = audit::audited(
+            config.audit_log.as_deref(),
+            "create",
+            Some(&audit_id),
+            stored,
+        )?;
+        println!("Manifest stored successfully with ID: {id}");
+    }
+
+    Ok(())
+}
+
+/// Builds and signs a manifest for a model, dataset, software, or evaluation
+/// without printing or storing it.
+///
+/// `config.storage` is only read to resolve linked manifests; links that
+/// cannot be resolved are logged as warnings and left out. The dry-run and
+/// output settings are ignored.
+pub fn build_manifest(config: &ManifestCreationConfig, asset_kind: AssetKind) -> Result<Manifest> {
+    let (manifest, warnings) = assemble_manifest(config, asset_kind)?;
+    for cross_ref in &manifest.cross_references {
+        log::info!("Added link to manifest: {}", cross_ref.manifest_url);
+    }
+    for warning in warnings {
+        log::warn!("{warning}");
+    }
+    Ok(manifest)
+}
+
+/// Builds a manifest, returning it with warnings about unresolved links
+fn assemble_manifest(
+    config: &ManifestCreationConfig,
+    asset_kind: AssetKind,
+) -> Result<(Manifest, Vec<String>)> {
+    let PreparedInputs {
+        files: inputs,
+        relationships,
+        urls,
+        directories,
+        tags,
+    } = prepare_inputs(config)?;
+
+    // Create ingredients using the helper function. A single file shows the
+    // bytes hashed, while multi-file runs show the number of files completed.
+    let multi_file = inputs.len() > 1;
+    let pb = progress::create_files_progress_bar(
+        inputs.len() as u64,
+        config.show_progress && multi_file,
+    );
+    let mut ingredients = Vec::with_capacity(inputs.len());
+    let mut decompressed_hashes = Vec::new();
+    for (((path, title), relationship), url) in inputs.iter().zip(relationships).zip(urls) {
+        cancel::check()?;
+        let show_bytes = config.show_progress && !multi_file;
+        let mut ingredient =
+            create_ingredient_for_kind(path, title, &asset_kind, config, show_bytes)?;
+        ingredient.relationship = relationship;
+        if let Some(url) = url {
+            ingredient.data.url = url;
+        }
+        if config.hash_decompressed
+            && let Some(compression) = Compression::from_path(path)
+        {
+            decompressed_hashes.push(serde_json::json!({
+                "url": ingredient.data.url,
+                "compression": compression.as_str(),
+            }));
+        }
         ingredients.push(ingredient);
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+
+    // reference the model card: a local file is bound by its hash like the
+    // inputs, while a URL is only linked, with its expected hash if given
+    let mut model_card_link = None;
+    if let Some(model_card) = &config.model_card {
+        if remote::is_remote_url(model_card) {
+            model_card_link = Some(ModelCardLink::new(
+                model_card,
+                config.model_card_hash.as_deref(),
+            )?);
+        } else if config.model_card_hash.is_some() {
+            return Err(Error::Validation(
+                "A model card hash can only be given for a model card URL; local files are hashed"
+                    .to_string(),
+            ));
+        } else {
+            let mut ingredient = create_ingredient_for_kind(
+                Path::new(model_card),
+                MODEL_CARD_TITLE,
+                &asset_kind,
+                config,
+                false,
+            )?;
+            ingredient.relationship = MODEL_CARD_RELATIONSHIP.to_string();
+            ingredients.push(ingredient);
+        }
+    }
+
+    let mut directory_hashes = Vec::new();
+    for (path, ingredient_name, url, range) in directories {
+        let file_hashes: Vec<String> = ingredients[range]
+            .iter()
+            .map(|ingredient| ingredient.data.hash.clone())
+            .collect();
+        let hash_refs: Vec<&str> = file_hashes.iter().map(String::as_str).collect();
+        directory_hashes.push(serde_json::json!({
+            "title": ingredient_name,
+            "url": url.unwrap_or_else(|| format!("file://{}", path.to_string_lossy())),
+            "file_count": file_hashes.len(),
+            "alg": "sha384",
+            "hash": hash::combine_hashes(&hash_refs)?,
+            "merkle_root": hash::merkle::build_merkle_root(&file_hashes)?,
+        }));
     }
 
-    // Determine asset-specific values
-    let (creative_type, digital_source_type) = match asset_kind {
+    // Determine asset-specific values, unless overridden
+    let (default_creative_type, default_digital_source_type) = match asset_kind {
         AssetKind::Model => (
             "Model".to_string(),
             "http://cv.iptc.org/newscodes/digitalsourcetype/algorithmicMedia".to_string(),
@@ -80,6 +278,14 @@ pub fn create_manifest(config: ManifestCreationConfig, asset_kind: AssetKind) ->
             "http://cv.iptc.org/newscodes/digitalsourcetype/evaluationResult".to_string(),
         ),
     };
+    let creative_type = config
+        .creative_type_override
+        .clone()
+        .unwrap_or(default_creative_type);
+    let digital_source_type = config
+        .digital_source_type_override
+        .clone()
+        .unwrap_or(default_digital_source_type);
 
     // Create assertions
     let mut assertions = vec![
@@ -123,32 +329,31 @@ pub fn create_manifest(config: ManifestCreationConfig, asset_kind: AssetKind) ->
                         });
 
                         // Add evaluation-specific parameters if present
-                        if let Some(config_params) = &config.custom_fields {
-                            if let Some(eval_params) = config_params.get("evaluation") {
-                                if let Some(obj) = params.as_object_mut() {
-                                    obj.insert(
-                                        "model_id".to_string(),
-                                        eval_params
-                                            .get("model_id")
-                                            .cloned()
-                                            .unwrap_or(serde_json::Value::Null),
-                                    );
-                                    obj.insert(
-                                        "dataset_id".to_string(),
-                                        eval_params
-                                            .get("dataset_id")
-                                            .cloned()
-                                            .unwrap_or(serde_json::Value::Null),
-                                    );
-                                    obj.insert(
-                                        "metrics".to_string(),
-                                        eval_params
-                                            .get("metrics")
-                                            .cloned()
-                                            .unwrap_or(serde_json::Value::Null),
-                                    );
-                                }
-                            }
+                        if let Some(config_params) = &config.custom_fields
+                            && let Some(eval_params) = config_params.get("evaluation")
+                            && let Some(obj) = params.as_object_mut()
+                        {
+                            obj.insert(
+                                "model_id".to_string(),
+                                eval_params
+                                    .get("model_id")
+                                    .cloned()
+                                    .unwrap_or(serde_json::Value::Null),
+                            );
+                            obj.insert(
+                                "dataset_id".to_string(),
+                                eval_params
+                                    .get("dataset_id")
+                                    .cloned()
+                                    .unwrap_or(serde_json::Value::Null),
+                            );
+                            obj.insert(
+                                "metrics".to_string(),
+                                eval_params
+                                    .get("metrics")
+                                    .cloned()
+                                    .unwrap_or(serde_json::Value::Null),
+                            );
                         }
                         params
                     }
@@ -191,6 +396,63 @@ pub fn create_manifest(config: ManifestCreationConfig, asset_kind: AssetKind) ->
         }),
     ];
 
+    // record a combined hash for each directory input so the
+    // directory as a whole can be checked against the manifest
+    if !directory_hashes.is_empty() {
+        assertions.push(Assertion::CustomAssertion(CustomAssertion {
+            label: DIRECTORY_HASHES_LABEL.to_string(),
+            data: serde_json::Value::Array(directory_hashes),
+        }));
+    }
+
+    // record which ingredients were hashed after decompression so that
+    // verification decompresses them the same way
+    if !decompressed_hashes.is_empty() {
+        assertions.push(Assertion::CustomAssertion(CustomAssertion {
+            label: DECOMPRESSED_HASHES_LABEL.to_string(),
+            data: serde_json::Value::Array(decompressed_hashes),
+        }));
+    }
+
+    // record the schema and CLI versions so that later versions can
+    // migrate the manifest when its structure changes
+    assertions.push(version::version_assertion());
+
+    if let Some(window) = ValidityWindow::new(config.valid_from, config.valid_until)? {
+        assertions.push(window.to_assertion()?);
+    }
+
+    // record the manifests this one derives from; unlike other links they
+    // must resolve, since the lineage would otherwise point nowhere
+    if !config.base_manifests.is_empty() {
+        let storage = config.storage.ok_or_else(|| {
+            Error::Validation("Recording a base manifest requires a storage backend".to_string())
+        })?;
+        for base_id in &config.base_manifests {
+            retrieve_cross_reference_target(storage, base_id)?;
+        }
+        assertions.push(lineage::lineage_assertion(&config.base_manifests));
+    }
+
+    // record the datasets a model was trained on, bound to their hashes
+    if !config.trained_on.is_empty() {
+        let storage = config.storage.ok_or_else(|| {
+            Error::Validation("Recording training datasets requires a storage backend".to_string())
+        })?;
+        assertions.push(training::training_assertion(&config.trained_on, storage)?);
+    }
+
+    if let Some(link) = &model_card_link {
+        assertions.push(link.to_assertion()?);
+    }
+
+    if !tags.is_empty() {
+        assertions.push(Assertion::CustomAssertion(CustomAssertion {
+            label: TAGS_LABEL.to_string(),
+            data: serde_json::json!(tags),
+        }));
+    }
+
     // if we're creating the manifest in a CC environment, create
     // an assertion for the CC attestation
     if config.with_cc {
@@ -200,114 +462,388 @@ pub fn create_manifest(config: ManifestCreationConfig, asset_kind: AssetKind) ->
         assertions.push(Assertion::CustomAssertion(cc_assertion));
     }
 
+    // record the environment the manifest is created in, if requested
+    if config.record_environment {
+        assertions.push(Environment::capture(config.redact_hostname)?.to_assertion()?);
+    }
+
+    let claim_generator = match &config.claim_generator {
+        Some(generator) if generator.trim().is_empty() => {
+            return Err(Error::Validation(
+                "The claim generator must not be empty".to_string(),
+            ));
+        }
+        Some(generator) => generator.trim().to_string(),
+        None => DEFAULT_CLAIM_GENERATOR.to_string(),
+    };
+
+    let created_at = config.created_at.unwrap_or_else(OffsetDateTime::now_utc);
+    let (manifest_id, claim_id) = if config.deterministic {
+        let seed = deterministic_seed(config, &ingredients, &assertions)?;
+        for (index, ingredient) in ingredients.iter_mut().enumerate() {
+            let data = serde_json::to_vec(&ingredient.data)
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            let document_seed = hex::encode(Sha256::digest(data));
+            ingredient.document_id =
+                format!("uuid:{}", deterministic_id("document", &document_seed));
+            ingredient.instance_id = format!(
+                "uuid:{}",
+                deterministic_id(&format!("ingredient:{index}"), &seed)
+            );
+        }
+        (
+            format!("urn:c2pa:{}", deterministic_id("manifest", &seed)),
+            format!("urn:c2pa:{}", deterministic_id("claim", &seed)),
+        )
+    } else {
+        (
+            format!("urn:c2pa:{}", Uuid::new_v4()),
+            format!("urn:c2pa:{}", Uuid::new_v4()),
+        )
+    };
+
     // Create claim
     let mut claim = ClaimV2 {
-        instance_id: format!("urn:c2pa:{}", Uuid::new_v4()),
+        instance_id: claim_id,
         ingredients: ingredients.clone(),
         created_assertions: assertions,
-        claim_generator_info: "c2pa-cli".to_string(),
+        claim_generator_info: claim_generator_name(&claim_generator).to_string(),
         signature: None,
-        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        created_at: OffsetDateTimeWrapper(created_at),
     };
 
-    // Sign if key is provided
-    if let Some(key_file) = &config.key_path {
-        let private_key = signing::load_private_key(key_file)?;
-
-        // Serialize claim to CBOR for signing
-        let claim_cbor =
-            serde_cbor::to_vec(&claim).map_err(|e| Error::Serialization(e.to_string()))?;
+    // Sign if a key or signer is provided
+    if let Some(signer) = claim_signer(config)? {
+        // Sign the stable claim bytes, which countersignatures also cover
+        let claim_cbor = signatures::signed_claim_bytes(&claim).map_err(|e| {
+            e.with_manifest_context("signing claim of manifest", &config.name, &manifest_id)
+        })?;
 
-        // Use the signing module with the specified algorithm
-        let signature =
-            signing::sign_data_with_algorithm(&claim_cbor, &private_key, &config.hash_alg)?;
+        let signature = signer.sign(&claim_cbor, config.hash_alg)?;
 
         // Add signature to claim
         claim.signature = Some(STANDARD.encode(&signature));
+
+        // Timestamp the signature. The token is added after signing, so it
+        // is not covered by the claim signature.
+        if let Some(tsa_url) = &config.tsa_url {
+            let token = timestamp::request_timestamp(tsa_url, &signature)?;
+            claim
+                .created_assertions
+                .push(Assertion::CustomAssertion(CustomAssertion {
+                    label: timestamp::TIMESTAMP_ASSERTION_LABEL.to_string(),
+                    data: serde_json::json!({
+                        "tsa_url": tsa_url,
+                        "token": STANDARD.encode(&token),
+                    }),
+                }));
+        }
     }
 
     // Create the manifest
+    let mut warnings = Vec::new();
     let mut manifest = Manifest {
-        claim_generator: "c2pa-cli/0.1.0".to_string(),
+        claim_generator,
         title: config.name.clone(),
-        instance_id: format!("urn:c2pa:{}", Uuid::new_v4()),
+        instance_id: manifest_id,
         ingredients,
         claim: claim.clone(),
-        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        created_at: OffsetDateTimeWrapper(created_at),
         cross_references: vec![],
         claim_v2: Some(claim),
         is_active: true,
     };
 
-    if let Some(manifest_ids) = &config.linked_manifests {
+    // Base manifests are cross-referenced too, so their hashes are verified
+    let mut manifest_ids: Vec<&String> = config.linked_manifests.iter().flatten().collect();
+    for base_id in &config.base_manifests {
+        if !manifest_ids.contains(&base_id) {
+            manifest_ids.push(base_id);
+        }
+    }
+    if !manifest_ids.is_empty() {
         if let Some(storage_backend) = &config.storage {
             for linked_id in manifest_ids {
                 match storage_backend.retrieve_manifest(linked_id) {
                     Ok(linked_manifest) => {
-                        // Create a JSON representation of the linked manifest
-                        let linked_json = serde_json::to_string(&linked_manifest)
-                            .map_err(|e| Error::Serialization(e.to_string()))?;
-
                         // Create a hash of the linked manifest
-                        let linked_hash = hex::encode(Sha256::digest(linked_json.as_bytes()));
+                        let linked_hash =
+                            canonical::cross_reference_hash(&linked_manifest, linked_id)?;
 
                         // Create a cross-reference
                         let cross_ref = CrossReference {
-                            manifest_url: linked_id.clone(),
+                            manifest_url: linked_id.to_string(),
                             manifest_hash: linked_hash,
                             media_type: Some("application/json".to_string()),
                         };
 
                         // Add the cross-reference to the manifest
                         manifest.cross_references.push(cross_ref);
-
-                        println!("Added link to manifest: {linked_id}");
                     }
                     Err(e) => {
-                        println!("Warning: Could not link to manifest {linked_id}: {e}");
+                        warnings.push(format!("Could not link to manifest {linked_id}: {e}"));
                     }
                 }
             }
         } else {
-            println!("Warning: Cannot link manifests without a storage backend");
+            warnings.push("Cannot link manifests without a storage backend".to_string());
         }
     }
 
-    // Output manifest if requested
-    if config.print || config.storage.is_none() {
-        match config.output_format.to_lowercase().as_str() {
-            "json" => {
-                let manifest_json =
-                    to_string_pretty(&manifest).map_err(|e| Error::Serialization(e.to_string()))?;
-                println!("{manifest_json}");
-            }
-            "cbor" => {
-                let manifest_cbor = serde_cbor::to_vec(&manifest)
-                    .map_err(|e| Error::Serialization(e.to_string()))?;
-                println!("{}", hex::encode(&manifest_cbor));
+    Ok((manifest, warnings))
+}
+
+/// Files to hash, directory inputs and normalized tags of a creation request
+struct PreparedInputs<'a> {
+    files: Vec<(PathBuf, String)>,
+    // Relationship of each file, inherited from its input
+    relationships: Vec<String>,
+    // URL recorded for each file instead of its `file://` URL, if given
+    urls: Vec<Option<String>>,
+    // Each directory input with its ingredient name, URL override and the
+    // range of its files
+    directories: Vec<(
+        &'a PathBuf,
+        &'a String,
+        Option<String>,
+        std::ops::Range<usize>,
+    )>,
+    tags: Vec<String>,
+}
+
+/// Validates a creation request and expands directory inputs into the files
+/// they contain, remembering which ingredients belong to each directory
+fn prepare_inputs(config: &ManifestCreationConfig) -> Result<PreparedInputs<'_>> {
+    let input_relationships = if config.ingredient_relationships.is_empty() {
+        vec![DEFAULT_RELATIONSHIP.to_string(); config.ingredient_names.len()]
+    } else if config.ingredient_relationships.len() == config.ingredient_names.len() {
+        config
+            .ingredient_relationships
+            .iter()
+            .map(|relationship| parse_relationship(relationship))
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        return Err(Error::Validation(format!(
+            "Got {} ingredient relationships for {} ingredients; give one per ingredient or none",
+            config.ingredient_relationships.len(),
+            config.ingredient_names.len()
+        )));
+    };
+
+    let input_urls = if config.ingredient_urls.is_empty() {
+        vec![None; config.ingredient_names.len()]
+    } else if config.ingredient_urls.len() == config.ingredient_names.len() {
+        if config.quick_hash || config.hash_decompressed {
+            return Err(Error::Validation(
+                "Ingredient URLs cannot be combined with --quick-hash or --hash-decompressed, which are only verified from local files".to_string(),
+            ));
+        }
+        config
+            .ingredient_urls
+            .iter()
+            .map(|url| {
+                if remote::is_remote_url(url) || url.starts_with("file://") {
+                    Ok(Some(url.clone()))
+                } else {
+                    Err(Error::Validation(format!(
+                        "Invalid ingredient URL '{url}': expected an http://, https://, s3:// or file:// URL"
+                    )))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        return Err(Error::Validation(format!(
+            "Got {} ingredient URLs for {} ingredients; give one per ingredient or none",
+            config.ingredient_urls.len(),
+            config.ingredient_names.len()
+        )));
+    };
+
+    let mut inputs = Vec::new();
+    let mut relationships = Vec::new();
+    let mut urls = Vec::new();
+    let mut directories = Vec::new();
+    for (((path, ingredient_name), relationship), url) in config
+        .paths
+        .iter()
+        .zip(config.ingredient_names.iter())
+        .zip(input_relationships)
+        .zip(input_urls)
+    {
+        if path.is_dir() {
+            if !config.recursive {
+                return Err(Error::Validation(format!(
+                    "{} is a directory; use --recursive to include its contents",
+                    path.display()
+                )));
             }
-            _ => {
+
+            let files =
+                collect_directory_files(path, &config.ignore_patterns, config.follow_symlinks)?;
+            if files.is_empty() {
                 return Err(Error::Validation(format!(
-                    "Invalid output format '{}'. Valid options are: json, cbor",
-                    config.output_format
+                    "Directory {} contains no files to include",
+                    path.display()
                 )));
             }
+
+            let start = inputs.len();
+            for file in files {
+                let relative = file.strip_prefix(path).unwrap_or(&file);
+                let title = format!("{}/{}", ingredient_name, relative.to_string_lossy());
+                // The directory's URL is the base of its files' URLs
+                urls.push(url.as_ref().map(|base| {
+                    let relative: Vec<_> = relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect();
+                    format!("{}/{}", base.trim_end_matches('/'), relative.join("/"))
+                }));
+                inputs.push((file, title));
+            }
+            directories.push((path, ingredient_name, url, start..inputs.len()));
+        } else {
+            inputs.push((path.clone(), ingredient_name.clone()));
+            urls.push(url);
         }
+        relationships.resize(inputs.len(), relationship);
     }
 
-    // Store manifest if storage is provided
-    if let Some(storage) = &config.storage {
-        if !config.print {
-            let id = storage.store_manifest(&manifest)?;
-            println!("Manifest stored successfully with ID: {id}");
+    if inputs
+        .iter()
+        .filter(|(path, _)| is_stdin_path(path))
+        .count()
+        > 1
+    {
+        return Err(Error::Validation(format!(
+            "Only one input can be read from stdin ('{STDIN_PATH}')"
+        )));
+    }
+
+    if config.quick_hash {
+        if config.hash_decompressed {
+            return Err(Error::Validation(
+                "--quick-hash cannot be combined with --hash-decompressed".to_string(),
+            ));
+        }
+        if inputs.iter().any(|(path, _)| is_stdin_path(path)) {
+            return Err(Error::Validation(
+                "--quick-hash samples regions of seekable files and cannot hash stdin".to_string(),
+            ));
+        }
+    }
+
+    let tags = normalize_tags(&config.tags)?;
+
+    if config.key_path.is_some() && config.signer.is_some() {
+        return Err(Error::Validation(
+            "Both a signing key and a KMS key were given; sign with one of them".to_string(),
+        ));
+    }
+    if config.key_path.is_none() && config.signer.is_none() && config.tsa_url.is_some() {
+        return Err(Error::Validation(
+            "A TSA URL was given but the manifest is not signed; use --key or --kms-key to sign it"
+                .to_string(),
+        ));
+    }
+
+    for (value, name) in [
+        (&config.creative_type_override, "creative type"),
+        (&config.digital_source_type_override, "digital source type"),
+    ] {
+        if let Some(value) = value
+            && value.trim().is_empty()
+        {
+            return Err(Error::Validation(format!(
+                "The {name} override must not be empty"
+            )));
+        }
+    }
+
+    Ok(PreparedInputs {
+        files: inputs,
+        relationships,
+        urls,
+        directories,
+        tags,
+    })
+}
+
+/// Serialize a manifest in the given output format (`json`, `cbor` or `yaml`).
+///
+/// CBOR output is hex-encoded so that it can be printed.
+pub fn serialize_manifest(manifest: &Manifest, format: &str) -> Result<String> {
+    let bytes = serialize_manifest_bytes(manifest, format)?;
+    match format.to_lowercase().as_str() {
+        "cbor" => Ok(hex::encode(&bytes)),
+        _ => String::from_utf8(bytes).map_err(|e| Error::Serialization(e.to_string())),
+    }
+}
+
+/// Serialize a manifest in the given output format (`json`, `cbor` or `yaml`)
+/// as raw bytes, suitable for writing to a file
+pub fn serialize_manifest_bytes(manifest: &Manifest, format: &str) -> Result<Vec<u8>> {
+    let context = |e: Error| {
+        e.with_manifest_context(
+            &format!("writing {format} output for manifest"),
+            &manifest.title,
+            &manifest.instance_id,
+        )
+    };
+    match format.to_lowercase().as_str() {
+        "json" => to_string_pretty(manifest)
+            .map(String::into_bytes)
+            .map_err(|e| context(Error::Serialization(e.to_string()))),
+        "cbor" => {
+            serde_cbor::to_vec(manifest).map_err(|e| context(Error::Serialization(e.to_string())))
         }
+        "yaml" => serde_yaml::to_string(manifest)
+            .map(String::into_bytes)
+            .map_err(|e| context(Error::Serialization(e.to_string()))),
+        _ => Err(Error::Validation(format!(
+            "Invalid output format '{format}'. Valid options are: json, cbor, yaml"
+        ))),
     }
+}
 
+/// Writes a manifest in the given output format. CBOR is written as raw bytes
+/// unless `hex` is set; text output ends with a newline.
+pub fn write_manifest<W: Write>(
+    out: &mut W,
+    manifest: &Manifest,
+    format: &str,
+    hex: bool,
+) -> Result<()> {
+    match format.to_lowercase().as_str() {
+        "cbor" if hex => writeln!(out, "{}", serialize_manifest(manifest, format)?)?,
+        "cbor" => out.write_all(&serialize_manifest_bytes(manifest, format)?)?,
+        _ => {
+            let bytes = serialize_manifest_bytes(manifest, format)?;
+            out.write_all(&bytes)?;
+            if !bytes.ends_with(b"\n") {
+                out.write_all(b"\n")?;
+            }
+        }
+    }
     Ok(())
 }
 
-pub fn list_manifests(storage: &dyn StorageBackend, asset_kind: Option<AssetKind>) -> Result<()> {
-    let manifests = storage.list_manifests()?;
+/// Writes a manifest to `path` in the given output format. CBOR is written as
+/// raw bytes rather than hex.
+pub fn write_manifest_file(manifest: &Manifest, format: &str, path: &Path) -> Result<()> {
+    let bytes = serialize_manifest_bytes(manifest, format)?;
+    let mut file = crate::utils::safe_create_file(path, false)?;
+    file.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Lists manifests of the given kind that carry every tag in `tags`
+pub fn list_manifests(
+    storage: &dyn StorageBackend,
+    asset_kind: Option<AssetKind>,
+    filter: &ManifestFilter,
+) -> Result<()> {
+    let manifests = storage.list_manifests_filtered(filter)?;
 
     // Filter manifests by type if asset_kind is specified
     let filtered_manifests = if let Some(kind) = asset_kind {
@@ -325,10 +861,10 @@ pub fn list_manifests(storage: &dyn StorageBackend, asset_kind: Option<AssetKind
                     m.manifest_type,
                     crate::storage::traits::ManifestType::Software
                 ),
-                AssetKind::Evaluation => {
-                    // Check if manifest title or name contains "Evaluation"
-                    m.name.contains("Evaluation") || m.name.contains("evaluation")
-                }
+                AssetKind::Evaluation => matches!(
+                    m.manifest_type,
+                    crate::storage::traits::ManifestType::Evaluation
+                ),
             })
             .collect::<Vec<_>>()
     } else {
@@ -341,113 +877,665 @@ pub fn list_manifests(storage: &dyn StorageBackend, asset_kind: Option<AssetKind
             "Manifest: {} (ID: {}, Type: {:?}, Created: {})",
             metadata.name, metadata.id, metadata.manifest_type, metadata.created_at
         );
+        if !metadata.tags.is_empty() {
+            println!("  Tags: {}", metadata.tags.join(", "));
+        }
     }
 
     Ok(())
 }
 
+/// Per-manifest results of verifying every manifest in a storage backend
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VerifyAllSummary {
+    pub total: usize,
+    pub passed: usize,
+    /// IDs of manifests that failed verification, with the error message
+    pub failed: Vec<(String, String)>,
+}
+
+impl VerifyAllSummary {
+    /// Returns true if every manifest verified successfully
+    pub fn all_passed(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Verify every manifest held by a storage backend
+pub fn verify_all(storage: &dyn StorageBackend) -> Result<VerifyAllSummary> {
+    verify_all_with_config(storage, &VerificationConfig::default())
+}
+
+/// Verify every manifest held by a storage backend using the given options,
+/// collecting failures instead of stopping at the first one. Stops with
+/// [`Error::Cancelled`] if the operation is cancelled.
+pub fn verify_all_with_config(
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+) -> Result<VerifyAllSummary> {
+    verify_each(storage, |id| {
+        verify_manifest_with_config(id, storage, config)
+    })
+}
+
+/// Like [`verify_all_with_config`], checking only the signatures of each
+/// manifest with [`verify_signature_only`]
+pub fn verify_all_signatures(
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+) -> Result<VerifyAllSummary> {
+    verify_each(storage, |id| verify_signature_only(id, storage, config))
+}
+
+fn verify_each(
+    storage: &dyn StorageBackend,
+    verify: impl Fn(&str) -> Result<VerificationReport>,
+) -> Result<VerifyAllSummary> {
+    let manifests = storage.list_manifests()?;
+    let mut summary = VerifyAllSummary {
+        total: manifests.len(),
+        ..Default::default()
+    };
+
+    for metadata in manifests {
+        cancel::check()?;
+        match verify(&metadata.id) {
+            Ok(_) => summary.passed += 1,
+            // A cancelled verification is not a failure of the manifest
+            Err(Error::Cancelled) => return Err(Error::Cancelled),
+            Err(e) => summary.failed.push((metadata.id, e.to_string())),
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Validate the structure of a manifest.
+///
+/// `atlas_c2pa_lib::manifest::validate_manifest` rejects assertion kinds it
+/// has no validator for, which includes the `CustomAssertion`s this tool
+/// records (CC attestations, directory hashes, timestamps). Those are checked
+/// here for a non-empty label and removed before the library validation runs.
+pub fn validate_manifest_structure(manifest: &Manifest) -> Result<()> {
+    let is_custom = |assertion: &Assertion| matches!(assertion, Assertion::CustomAssertion(_));
+
+    let claims = std::iter::once(&manifest.claim).chain(manifest.claim_v2.as_ref());
+    for claim in claims {
+        for assertion in &claim.created_assertions {
+            if let Assertion::CustomAssertion(custom) = assertion
+                && custom.label.trim().is_empty()
+            {
+                return Err(Error::Validation(
+                    "Custom assertion must have a non-empty label".to_string(),
+                ));
+            }
+        }
+    }
+
+    let mut stripped = manifest.clone();
+    stripped.claim.created_assertions.retain(|a| !is_custom(a));
+    if let Some(claim) = stripped.claim_v2.as_mut() {
+        claim.created_assertions.retain(|a| !is_custom(a));
+    }
+
+    atlas_c2pa_lib::manifest::validate_manifest(&stripped).map_err(Error::Validation)
+}
+
 /// Verify a manifest
-pub fn verify_manifest(id: &str, storage: &dyn StorageBackend) -> Result<()> {
-    let manifest = storage.retrieve_manifest(id)?;
+pub fn verify_manifest(id: &str, storage: &dyn StorageBackend) -> Result<VerificationReport> {
+    verify_manifest_with_config(id, storage, &VerificationConfig::default())
+}
 
+/// Verify a manifest using the given verification options
+pub fn verify_manifest_with_config(
+    id: &str,
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+) -> Result<VerificationReport> {
+    // A manifest read from a transparency log is read from the same entry
+    // that is proven to be in the log
+    let rekor = storage.as_any().downcast_ref::<RekorStorage>();
+    let (manifest, inclusion) = match rekor {
+        Some(rekor) if !config.offline => {
+            let (manifest, proof) = rekor.retrieve_proven_manifest(id)?;
+            (manifest, Some(proof))
+        }
+        _ => (storage.retrieve_manifest(id)?, None),
+    };
+
+    let mut report = verify_loaded_manifest(&manifest, id, Some(storage), config, None)?;
+    match inclusion {
+        Some(proof) => report.transparency_log.push(VerificationCheck::verified(
+            id,
+            format!(
+                "included in the Rekor log at index {} of {} entries",
+                proof.log_index, proof.tree_size
+            ),
+        )),
+        None if rekor.is_some() => report.transparency_log.push(VerificationCheck::warning(
+            id,
+            "Rekor inclusion proof was not checked in offline mode",
+        )),
+        None => {}
+    }
+    Ok(report)
+}
+
+/// Verifies only the signatures of a stored manifest: its signature
+/// timestamps, its countersignatures and, with a trust list, that every
+/// signer is trusted.
+///
+/// Only the claim is retrieved (see [`StorageBackend::retrieve_claim`]), so
+/// ingredients, cross-references and everything else outside the claim are
+/// neither fetched nor checked. Fails if the manifest is not signed at all,
+/// or if it has a claim signature and no trust list is given, since the
+/// claim does not record the key it was signed with. Countersignatures
+/// verified without a trust list are reported as untrusted warnings.
+pub fn verify_signature_only(
+    id: &str,
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+) -> Result<VerificationReport> {
+    let claim = storage.retrieve_claim(id)?;
+    let countersignatures = signatures::verify_claim_signatures(&claim)?;
+    if claim.signature.is_none() && countersignatures == 0 {
+        return Err(Error::Validation(format!("Manifest {id} is not signed")));
+    }
+
+    let mut report = VerificationReport::new(id);
+    report.timestamps = verify_claim_timestamps(&claim, config)?;
+    report.countersignatures = signatures::claim_countersignatures(&claim)?
+        .into_iter()
+        .map(|s| VerificationCheck::verified(s.signer_identity, "signature matches the claim"))
+        .collect();
+    match &config.trust_list {
+        Some(trust_list) => {
+            report.trusted_signers =
+                signatures::verify_trusted_claim_signers(&claim, &TrustList::load(trust_list)?)?;
+        }
+        // The claim does not record its key, so only a trust list can check it
+        None if claim.signature.is_some() => {
+            return Err(Error::Validation(format!(
+                "The claim signature of manifest {id} cannot be checked without --trust-list"
+            )));
+        }
+        // Countersignatures verify with their recorded keys, which are not
+        // known to be trusted
+        None => {
+            report.trusted_signers = report
+                .countersignatures
+                .iter()
+                .map(|check| {
+                    VerificationCheck::warning(
+                        &check.subject,
+                        "signature is valid, but its key is not checked without a trust list",
+                    )
+                })
+                .collect();
+        }
+    }
+    Ok(report)
+}
+
+/// Verify a manifest read from a file rather than from storage.
+///
+/// Every check that does not need storage is run. Cross-references and
+/// training datasets are resolved against `storage` if one is given, and
+/// otherwise reported as warnings.
+pub fn verify_manifest_value(
+    manifest: &Manifest,
+    storage: Option<&dyn StorageBackend>,
+) -> Result<VerificationReport> {
+    verify_manifest_value_with_config(manifest, storage, &VerificationConfig::default())
+}
+
+/// Like [`verify_manifest_value`], using the given verification options
+pub fn verify_manifest_value_with_config(
+    manifest: &Manifest,
+    storage: Option<&dyn StorageBackend>,
+    config: &VerificationConfig,
+) -> Result<VerificationReport> {
+    verify_loaded_manifest(manifest, &manifest.instance_id, storage, config, None)
+}
+
+/// Verifies a manifest that has already been loaded.
+///
+/// `bound_asset_hash` is the hash of an asset the manifest was extracted
+/// from; the ingredient with that hash is treated as verified instead of
+/// being re-hashed from its recorded URL. Without `storage`, checks that
+/// need other manifests are reported as warnings.
+pub(crate) fn verify_loaded_manifest(
+    manifest: &Manifest,
+    id: &str,
+    storage: Option<&dyn StorageBackend>,
+    config: &VerificationConfig,
+    bound_asset_hash: Option<&str>,
+) -> Result<VerificationReport> {
     // Step 1: Verify the manifest structure
-    atlas_c2pa_lib::manifest::validate_manifest(&manifest)
-        .map_err(|e| crate::error::Error::Validation(e.to_string()))?;
+    validate_manifest_structure(manifest)?;
 
-    println!("Verifying manifest with ID: {id}");
+    let mut report = VerificationReport::new(id);
 
-    // Step 2: Verify each ingredient's hash
+    // Step 2: Verify each ingredient's hash. With `continue_on_error`, every
+    // ingredient is checked before the failures are reported together.
+    let decompressed = decompressed_ingredients(manifest);
+    let mut failures = Vec::new();
     for ingredient in &manifest.ingredients {
-        println!("Verifying ingredient: {}", ingredient.title);
-
-        if ingredient.data.url.starts_with("file://") {
-            let path = PathBuf::from(ingredient.data.url.trim_start_matches("file://"));
+        if bound_asset_hash == Some(ingredient.data.hash.as_str()) {
+            report.ingredients.push(VerificationCheck::verified(
+                &ingredient.title,
+                "hash matches the embedding asset",
+            ));
+            continue;
+        }
 
-            // Create ArtifactLocation for verification
-            let location = ArtifactLocation {
-                url: ingredient.data.url.clone(),
-                file_path: Some(path),
-                hash: ingredient.data.hash.clone(),
-            };
+        match verify_ingredient(ingredient, &decompressed, config) {
+            Ok(check) => report.ingredients.push(check),
+            Err(Error::Cancelled) => return Err(Error::Cancelled),
+            // A model card that was edited or removed is only a warning
+            Err(Error::Validation(message))
+                if !config.strict && model_card::is_model_card(ingredient) =>
+            {
+                report
+                    .ingredients
+                    .push(VerificationCheck::warning(&ingredient.title, message));
+            }
+            Err(e) if config.continue_on_error => failures.push(match e {
+                Error::Validation(message) => message,
+                other => format!("Error verifying component {}: {other}", ingredient.title),
+            }),
+            Err(e) => return Err(e),
+        }
+    }
+    if !failures.is_empty() {
+        return Err(Error::Validation(format!(
+            "{} of {} ingredients failed verification:\n  - {}",
+            failures.len(),
+            manifest.ingredients.len(),
+            failures.join("\n  - ")
+        )));
+    }
 
-            // Verify the hash and handle the result
-            match location.verify() {
-                Ok(true) => {
-                    println!(
-                        "✓ Successfully verified hash for component: {}",
-                        ingredient.title
-                    );
+    // Step 3: Verify cross-references if present. Every base manifest in the
+    // lineage must be among them, so the lineage is bound to their hashes.
+    let parent_ids = lineage::parent_ids(manifest);
+    if let Some(parent_id) = parent_ids.iter().find(|parent_id| {
+        !manifest
+            .cross_references
+            .iter()
+            .any(|cross_ref| &cross_ref.manifest_url == *parent_id)
+    }) {
+        return Err(Error::Validation(format!(
+            "Base manifest {parent_id} in the lineage is not cross-referenced"
+        )));
+    }
+    if let Some(storage) = storage {
+        for cross_ref in &manifest.cross_references {
+            let linked_manifest =
+                match retrieve_cross_reference_target(storage, &cross_ref.manifest_url) {
+                    Ok(linked_manifest) => linked_manifest,
+                    Err(e @ Error::Validation(_)) if config.allow_dangling => {
+                        report.cross_references.push(VerificationCheck::warning(
+                            &cross_ref.manifest_url,
+                            e.to_string(),
+                        ));
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                };
+            let scheme = canonical::match_cross_reference_hash(
+                &linked_manifest,
+                &cross_ref.manifest_url,
+                &cross_ref.manifest_hash,
+            )?;
+            match scheme {
+                Some(CrossReferenceHashScheme::Canonical) => {
+                    report.cross_references.push(VerificationCheck::verified(
+                        &cross_ref.manifest_url,
+                        if parent_ids.contains(&cross_ref.manifest_url) {
+                            "hash matches (base manifest)"
+                        } else {
+                            "hash matches"
+                        },
+                    ));
                 }
-                Ok(false) => {
-                    return Err(Error::Validation(format!(
-                        "Hash verification failed for component: {}. The file may have been modified.",
-                        ingredient.title
-                    )));
+                // Linked by an earlier release; re-linking records a
+                // canonical hash
+                Some(CrossReferenceHashScheme::Legacy) => {
+                    report.cross_references.push(VerificationCheck::warning(
+                        &cross_ref.manifest_url,
+                        "hash matches the legacy non-canonical JSON of the linked manifest; link it again to record a canonical hash",
+                    ));
                 }
-                Err(e) => {
+                None => {
                     return Err(Error::Validation(format!(
-                        "Error verifying component {}: {}. The file may be missing or inaccessible.",
-                        ingredient.title, e
+                        "Cross-reference verification failed for linked manifest: {}. Hash mismatch: stored={}, calculated={}",
+                        cross_ref.manifest_url,
+                        cross_ref.manifest_hash,
+                        canonical::cross_reference_hash(&linked_manifest, &cross_ref.manifest_url)?
                     )));
                 }
             }
-        } else {
-            // For non-file URLs, try direct hash verification
-            match calculate_file_hash(PathBuf::from(&ingredient.data.url)) {
-                Ok(calculated_hash) => {
-                    if calculated_hash != ingredient.data.hash {
-                        return Err(Error::Validation(format!(
-                            "Hash mismatch for ingredient: {}",
-                            ingredient.title
-                        )));
-                    }
-                    println!(
-                        "✓ Successfully verified hash for component: {}",
-                        ingredient.title
-                    );
-                }
-                Err(_) => {
-                    println!(
-                        "⚠ Warning: Component {} does not use file:// URL scheme and could not be verified directly",
+        }
+    } else {
+        for cross_ref in &manifest.cross_references {
+            report.cross_references.push(VerificationCheck::warning(
+                &cross_ref.manifest_url,
+                "not checked: no storage backend to retrieve the linked manifest from",
+            ));
+        }
+    }
+
+    // Step 4: Verify CC attestation assertions if present
+    report.cc_attestations = verify_cc_attestation(manifest, config.strict_cc)?;
+
+    // Step 5: Verify signature timestamps if present
+    report.timestamps = verify_timestamps(manifest, config)?;
+
+    // Step 6: Verify countersignatures if present
+    signatures::verify_signatures(manifest)?;
+    report.countersignatures = signatures::countersignatures(manifest)?
+        .into_iter()
+        .map(|s| VerificationCheck::verified(s.signer_identity, "signature matches the claim"))
+        .collect();
+
+    // Step 7: Check that every signer is trusted, if a trust list was given
+    if let Some(trust_list) = &config.trust_list {
+        let trust_list = TrustList::load(trust_list)?;
+        report.trusted_signers = signatures::verify_trusted_signers(manifest, &trust_list)?;
+    }
+
+    // Step 8: Check that the manifest is within its validity window
+    report.validity.extend(validity::check_validity(
+        manifest,
+        OffsetDateTime::now_utc(),
+        config.allow_expired,
+    )?);
+
+    // Step 9: Verify asset-specific requirements
+    report.asset_checks = verify_asset_specific_requirements(manifest)?;
+    if let Some(link) = ModelCardLink::from_manifest(manifest)? {
+        report.asset_checks.push(link.verify(config)?);
+    }
+    report
+        .asset_checks
+        .extend(verify_ingredient_formats(manifest, config)?);
+
+    // Step 10: Check the organization's policy rules, if a policy was given
+    if let Some(policy) = &config.policy {
+        report.policy = policy::check_policy(manifest, &Policy::load(policy)?)?;
+    }
+
+    // Step 11: Check that the datasets a model was trained on are unchanged
+    report.training_data = match storage {
+        Some(storage) => {
+            training::verify_training_datasets(manifest, storage, config.allow_dangling)?
+        }
+        None => training::training_datasets(manifest)?
+            .into_iter()
+            .map(|dataset| {
+                VerificationCheck::warning(
+                    dataset.manifest_id,
+                    "training dataset not checked: no storage backend to retrieve it from",
+                )
+            })
+            .collect(),
+    };
+
+    Ok(report)
+}
+
+/// Checks one ingredient's hash against its recorded source
+fn verify_ingredient(
+    ingredient: &Ingredient,
+    decompressed: &HashMap<String, Compression>,
+    config: &VerificationConfig,
+) -> Result<VerificationCheck> {
+    if is_sampled(&ingredient.data.alg) {
+        // A sampled hash only covers part of the file, so even a match is
+        // no guarantee that the file is unchanged
+        if !is_local_file_url(&ingredient.data.url) {
+            return Err(Error::Validation(format!(
+                "Component {} has a sampled hash but is not a local file",
+                ingredient.title
+            )));
+        }
+        let path = ingredient_file_path(ingredient, config)?;
+        let calculated_hash = calculate_sampled_file_hash(&path).map_err(|e| {
+            Error::Validation(format!(
+                "Error verifying component {}: {e}. The file may be missing or inaccessible.",
+                ingredient.title
+            ))
+        })?;
+        if calculated_hash != ingredient.data.hash {
+            return Err(Error::Validation(format!(
+                "Hash verification failed for component: {}. The file may have been modified.",
+                ingredient.title
+            )));
+        }
+        Ok(VerificationCheck::warning(
+            &ingredient.title,
+            "sampled hash matches (quick hash: only part of the file was checked)",
+        ))
+    } else if let Some(compression) = decompressed.get(&ingredient.data.url) {
+        let path = ingredient_file_path(ingredient, config)?;
+        let algorithm = DigestAlgorithm::from_str(&ingredient.data.alg)?;
+        let calculated_hash = calculate_decompressed_file_hash(&path, *compression, algorithm)
+            .map_err(|e| {
+                Error::Validation(format!(
+                    "Error verifying component {}: {e}. The file may be missing or inaccessible.",
+                    ingredient.title
+                ))
+            })?;
+        if calculated_hash != ingredient.data.hash {
+            return Err(Error::Validation(format!(
+                "Hash verification failed for component: {}. The decompressed content may have been modified.",
+                ingredient.title
+            )));
+        }
+        Ok(VerificationCheck::verified(
+            &ingredient.title,
+            format!("decompressed {} content hash matches", compression.as_str()),
+        ))
+    } else if is_local_file_url(&ingredient.data.url) {
+        let path = ingredient_file_path(ingredient, config)?;
+
+        // Create ArtifactLocation for verification
+        let location = ArtifactLocation {
+            url: ingredient.data.url.clone(),
+            file_path: Some(path),
+            hash: ingredient.data.hash.clone(),
+        };
+
+        // Verify with the recorded algorithm, falling back to the hash
+        // length for manifests whose `alg` does not match their hash
+        let algorithm = DigestAlgorithm::from_str(&ingredient.data.alg)
+            .ok()
+            .filter(|alg| hash::get_hash_length(alg.as_str()) == ingredient.data.hash.len())
+            .unwrap_or_else(|| hash::detect_hash_algorithm(&ingredient.data.hash).into());
+        match location.verify_with_algorithm(algorithm) {
+            Ok(true) => Ok(VerificationCheck::verified(
+                &ingredient.title,
+                "hash matches",
+            )),
+            Ok(false) => Err(Error::Validation(format!(
+                "Hash verification failed for component: {}. The file may have been modified.",
+                ingredient.title
+            ))),
+            Err(e) => Err(Error::Validation(format!(
+                "Error verifying component {}: {}. The file may be missing or inaccessible.",
+                ingredient.title, e
+            ))),
+        }
+    } else if ingredient.data.url.starts_with(STDIN_URL_SCHEME) {
+        Ok(VerificationCheck::warning(
+            &ingredient.title,
+            "was read from stdin when the manifest was created and cannot be re-hashed",
+        ))
+    } else if remote::is_remote_url(&ingredient.data.url) {
+        if config.offline {
+            return Ok(VerificationCheck::warning(
+                &ingredient.title,
+                "remote content was not fetched in offline mode",
+            ));
+        }
+
+        let algorithm = DigestAlgorithm::from_str(&ingredient.data.alg)?;
+        let calculated_hash =
+            remote::hash_remote(&ingredient.data.url, algorithm, &FetchLimits::default()).map_err(
+                |e| {
+                    Error::Validation(format!(
+                        "Error verifying component {}: {e}",
                         ingredient.title
-                    );
-                }
-            }
+                    ))
+                },
+            )?;
+        if calculated_hash != ingredient.data.hash {
+            return Err(Error::Validation(format!(
+                "Hash mismatch for ingredient: {}. The remote content at {} may have been modified.",
+                ingredient.title, ingredient.data.url
+            )));
         }
+        Ok(VerificationCheck::verified(
+            &ingredient.title,
+            "hash matches the remote content",
+        ))
+    } else {
+        Ok(VerificationCheck::warning(
+            &ingredient.title,
+            "uses an unsupported URL scheme and could not be verified directly",
+        ))
     }
+}
 
-    // Step 3: Verify cross-references if present
-    if !manifest.cross_references.is_empty() {
-        println!("Verifying cross-references...");
+/// Checks that each ingredient's recorded format and data type are what
+/// creation would record for it: detected from the file extension (the
+/// inner one for decompressed hashes) and, for local files, the content.
+///
+/// A mismatch, e.g. an `.onnx` file recorded as `application/json`, is a
+/// warning, or an error with `config.strict`. Ingredients of manifests of
+/// unknown type are not checked, nor types that cannot be derived from the
+/// file name.
+pub(crate) fn verify_ingredient_formats(
+    manifest: &Manifest,
+    config: &VerificationConfig,
+) -> Result<Vec<VerificationCheck>> {
+    let asset_kind = match determine_manifest_type(manifest) {
+        ManifestType::Model => AssetKind::Model,
+        ManifestType::Dataset => AssetKind::Dataset,
+        ManifestType::Software => AssetKind::Software,
+        ManifestType::Evaluation => AssetKind::Evaluation,
+        ManifestType::Unknown => return Ok(Vec::new()),
+    };
+    let decompressed = decompressed_ingredients(manifest);
 
-        for cross_ref in &manifest.cross_references {
-            let linked_manifest = storage.retrieve_manifest(&cross_ref.manifest_url)?;
-            let manifest_json = serde_json::to_string(&linked_manifest)
-                .map_err(|e| Error::Serialization(e.to_string()))?;
-            let calculated_hash = hex::encode(Sha256::digest(manifest_json.as_bytes()));
+    let mut checks = Vec::new();
+    for ingredient in &manifest.ingredients {
+        let url = &ingredient.data.url;
+        // Detection mirrors creation: stdin inputs and decompressed hashes
+        // are typed by name only, readable local files by content as well
+        let (path, sniff) = if let Some(name) = url.strip_prefix(STDIN_URL_SCHEME) {
+            (PathBuf::from(name), false)
+        } else if decompressed.contains_key(url) {
+            (
+                decompressed_path(Path::new(url.trim_start_matches("file://"))),
+                false,
+            )
+        } else if remote::is_remote_url(url) {
+            (PathBuf::from(url), false)
+        } else {
+            (
+                PathBuf::from(url.trim_start_matches("file://")),
+                ingredient_file_path(ingredient, config).is_ok(),
+            )
+        };
 
-            if calculated_hash != cross_ref.manifest_hash {
-                return Err(Error::Validation(format!(
-                    "Cross-reference verification failed for linked manifest: {}. Hash mismatch: stored={}, calculated={}",
-                    cross_ref.manifest_url, cross_ref.manifest_hash, calculated_hash
-                )));
-            }
-            println!(
-                "✓ Verified cross-reference to manifest: {}",
-                cross_ref.manifest_url
-            );
+        // Files may have been created with or without content sniffing
+        let mut expected = vec![(
+            detect_format(&path, false)?,
+            detect_asset_type(&path, &asset_kind, false).ok(),
+        )];
+        if sniff {
+            expected.push((
+                detect_format(&path, true)?,
+                detect_asset_type(&path, &asset_kind, true).ok(),
+            ));
+        }
+
+        let format_matches = expected
+            .iter()
+            .any(|(format, _)| *format == ingredient.format);
+        let type_matches = expected.iter().all(|(_, asset_type)| asset_type.is_none())
+            || expected.iter().any(|(_, asset_type)| {
+                asset_type
+                    .as_ref()
+                    .is_some_and(|asset_type| ingredient.data.data_types.contains(asset_type))
+            });
+        if format_matches && type_matches {
+            checks.push(VerificationCheck::verified(
+                &ingredient.title,
+                "format and type match the file",
+            ));
+            continue;
         }
+
+        let (expected_format, expected_type) = &expected[expected.len() - 1];
+        let recorded_types: Vec<String> = ingredient
+            .data
+            .data_types
+            .iter()
+            .map(asset_type_name)
+            .collect();
+        let message = format!(
+            "recorded as {} ({}), but {} indicates {}{}",
+            ingredient.format,
+            recorded_types.join(", "),
+            path.display(),
+            expected_format,
+            expected_type
+                .as_ref()
+                .map(|asset_type| format!(" ({})", asset_type_name(asset_type)))
+                .unwrap_or_default()
+        );
+        if config.strict {
+            return Err(Error::Validation(format!(
+                "Ingredient {} is {message}",
+                ingredient.title
+            )));
+        }
+        checks.push(VerificationCheck::warning(&ingredient.title, message));
     }
+    Ok(checks)
+}
 
-    // Step 4: Verify asset-specific requirements
-    verify_asset_specific_requirements(&manifest)?;
+/// The name an asset type is recorded under, e.g. `c2pa.types.model.onnx`
+fn asset_type_name(asset_type: &AssetType) -> String {
+    serde_json::to_value(asset_type)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{asset_type:?}"))
+}
 
-    println!("✓ Manifest verification successful");
-    Ok(())
+/// Returns true for `file://` URLs and for the bare local paths that some
+/// older manifests record as ingredient URLs
+fn is_local_file_url(url: &str) -> bool {
+    url.starts_with("file://") || !(url.contains("://") || url.starts_with(STDIN_URL_SCHEME))
+}
+
+/// Local path of a `file://` ingredient, checked against the safe-path
+/// policy: symlinks are only followed if allowed and into an allowed root
+fn ingredient_file_path(ingredient: &Ingredient, config: &VerificationConfig) -> Result<PathBuf> {
+    let path = Path::new(ingredient.data.url.trim_start_matches("file://"));
+    safe_file_path_with_roots(path, config.follow_symlinks, &config.symlink_roots).map_err(|e| {
+        Error::Validation(format!(
+            "Error verifying component {}: {e}",
+            ingredient.title
+        ))
+    })
 }
 
 // Verify asset-specific requirements based on the manifest content
-fn verify_asset_specific_requirements(manifest: &Manifest) -> Result<()> {
+fn verify_asset_specific_requirements(manifest: &Manifest) -> Result<Vec<VerificationCheck>> {
     // Determines the asset type from the manifest contents
     let is_dataset = is_dataset_manifest(manifest);
     let is_model = is_model_manifest(manifest);
@@ -461,6 +1549,8 @@ fn verify_asset_specific_requirements(manifest: &Manifest) -> Result<()> {
         ));
     }
 
+    let mut checks = Vec::new();
+
     // Check for dataset, model, software, or evaluation assertion
     if let Some(claim) = &manifest.claim_v2 {
         if is_dataset {
@@ -477,14 +1567,14 @@ fn verify_asset_specific_requirements(manifest: &Manifest) -> Result<()> {
             };
 
             if !has_dataset_assertion && !has_dataset_assertion_in_claim {
-                println!(
-                    "WARNING: Dataset manifest doesn't contain a Dataset creative work assertion"
-                );
-
                 return Err(Error::Validation(
                     "Dataset manifest must contain a Dataset creative work assertion".to_string(),
                 ));
             }
+            checks.push(VerificationCheck::verified(
+                "Dataset",
+                "creative work assertion present",
+            ));
         }
 
         if is_model {
@@ -501,12 +1591,14 @@ fn verify_asset_specific_requirements(manifest: &Manifest) -> Result<()> {
             };
 
             if !has_model_assertion && !has_model_assertion_in_claim {
-                println!("WARNING: Model manifest doesn't contain a Model creative work assertion");
-
                 return Err(Error::Validation(
                     "Model manifest must contain a Model creative work assertion".to_string(),
                 ));
             }
+            checks.push(VerificationCheck::verified(
+                "Model",
+                "creative work assertion present",
+            ));
         }
 
         if is_software {
@@ -529,14 +1621,14 @@ fn verify_asset_specific_requirements(manifest: &Manifest) -> Result<()> {
             });
 
             if !has_software_assertion && !has_software_parameters {
-                println!(
-                    "WARNING: Software manifest doesn't contain a Software creative work assertion or software_type parameter"
-                );
-
                 return Err(Error::Validation(
                     "Software manifest must contain a Software creative work assertion or software_type parameter".to_string(),
                 ));
             }
+            checks.push(VerificationCheck::verified(
+                "Software",
+                "creative work assertion or software_type parameter present",
+            ));
         }
 
         if is_evaluation {
@@ -545,19 +1637,19 @@ fn verify_asset_specific_requirements(manifest: &Manifest) -> Result<()> {
             });
 
             if !has_evaluation_assertion {
-                println!(
-                    "WARNING: Evaluation manifest doesn't contain an EvaluationResult creative work assertion"
-                );
-
                 return Err(Error::Validation(
                     "Evaluation manifest must contain an EvaluationResult creative work assertion"
                         .to_string(),
                 ));
             }
+            checks.push(VerificationCheck::verified(
+                "EvaluationResult",
+                "creative work assertion present",
+            ));
         }
     }
 
-    Ok(())
+    Ok(checks)
 }
 
 // Helper function to determine if a manifest is for a dataset
@@ -681,6 +1773,348 @@ fn is_evaluation_manifest(manifest: &Manifest) -> bool {
     }
 }
 
+/// Create an ingredient for a file, detecting its asset type from the asset kind
+/// unless the configuration overrides it
+fn create_ingredient_for_kind(
+    path: &Path,
+    ingredient_name: &str,
+    asset_kind: &AssetKind,
+    config: &ManifestCreationConfig,
+    show_progress: bool,
+) -> Result<Ingredient> {
+    let (asset_type, format) =
+        ingredient_type_and_format(path, ingredient_name, asset_kind, config)?;
+
+    create_ingredient(
+        path,
+        ingredient_name,
+        asset_type,
+        format,
+        config.hash_alg,
+        HashOptions {
+            mode: if config.quick_hash {
+                HashMode::Sampled
+            } else if config.hash_decompressed {
+                HashMode::Decompressed
+            } else {
+                HashMode::Full
+            },
+            show_progress,
+            follow_symlinks: config.follow_symlinks,
+            symlink_roots: &config.symlink_roots,
+            chunk_size: config.hash_chunk_size,
+        },
+    )
+}
+
+/// The signer of a new manifest's claim: `config.signer` if set, else the
+/// key at `config.key_path`
+fn claim_signer(config: &ManifestCreationConfig) -> Result<Option<Arc<dyn Signer>>> {
+    if let Some(signer) = &config.signer {
+        return Ok(Some(Arc::clone(signer)));
+    }
+    config
+        .key_path
+        .as_deref()
+        .map(|key_file| -> Result<Arc<dyn Signer>> {
+            Ok(Arc::new(LocalSigner::load(key_file, config.min_key_bits)?))
+        })
+        .transpose()
+}
+
+/// Name part of a `name/version` claim generator, recorded as the claim's
+/// generator info
+fn claim_generator_name(claim_generator: &str) -> &str {
+    claim_generator
+        .split_once('/')
+        .map_or(claim_generator, |(name, _)| name)
+}
+
+/// Returns true if the input path is `-`, meaning stdin
+pub(crate) fn is_stdin_path(path: &Path) -> bool {
+    path.as_os_str() == STDIN_PATH
+}
+
+/// Retrieve the target of a cross-reference, reporting a missing target as a
+/// dangling cross-reference rather than a storage failure
+pub(crate) fn retrieve_cross_reference_target(
+    storage: &(impl StorageBackend + ?Sized),
+    id: &str,
+) -> Result<Manifest> {
+    storage
+        .retrieve_manifest(id)
+        .map_err(|e| match storage.manifest_exists(id) {
+            Ok(false) => Error::Validation(format!("cross-reference target {id} not found")),
+            _ => e,
+        })
+}
+
+/// Hashes everything a manifest is built from except IDs and timestamps:
+/// the name, ingredient data, assertions and linked manifests
+fn deterministic_seed(
+    config: &ManifestCreationConfig,
+    ingredients: &[Ingredient],
+    assertions: &[Assertion],
+) -> Result<String> {
+    let ingredients: Vec<_> = ingredients
+        .iter()
+        .map(|ingredient| {
+            (
+                &ingredient.title,
+                &ingredient.format,
+                &ingredient.relationship,
+                &ingredient.data,
+            )
+        })
+        .collect();
+    let inputs = serde_json::to_vec(&(
+        &config.name,
+        ingredients,
+        assertions,
+        &config.linked_manifests,
+    ))
+    .map_err(|e| Error::Serialization(e.to_string()))?;
+    Ok(hex::encode(Sha256::digest(inputs)))
+}
+
+/// Name-based UUID for one kind of ID (`manifest`, `claim`, ...) of a seed
+fn deterministic_id(kind: &str, seed: &str) -> Uuid {
+    Uuid::new_v5(
+        &Uuid::NAMESPACE_URL,
+        format!("atlas-cli:{kind}:{seed}").as_bytes(),
+    )
+}
+
+/// Validates tags and returns them sorted with duplicates removed.
+///
+/// Tags are free-form (e.g. `env=prod`) but may not be empty or contain
+/// commas, which separate tags in list queries.
+fn normalize_tags(tags: &[String]) -> Result<Vec<String>> {
+    let mut normalized = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.is_empty() || tag.contains(',') {
+            return Err(Error::Validation(format!(
+                "Invalid tag '{tag}': tags must be non-empty and cannot contain commas"
+            )));
+        }
+        normalized.push(tag.to_string());
+    }
+    normalized.sort();
+    normalized.dedup();
+    Ok(normalized)
+}
+
+/// Determine the ingredient asset type and format based on asset kind
+fn ingredient_type_and_format(
+    path: &Path,
+    title: &str,
+    asset_kind: &AssetKind,
+    config: &ManifestCreationConfig,
+) -> Result<(AssetType, String)> {
+    // Stdin cannot be sniffed without consuming it, so its type comes from
+    // the title's extension
+    let decompressed;
+    let (path, sniff) = if is_stdin_path(path) {
+        (Path::new(title), false)
+    } else if config.hash_decompressed && Compression::from_path(path).is_some() {
+        // The content's type is detected from the inner extension, e.g. .pt
+        // for model.pt.zst; the compressed bytes cannot be sniffed
+        decompressed = decompressed_path(path);
+        (decompressed.as_path(), false)
+    } else {
+        (path, !config.no_sniff)
+    };
+    let format = detect_format(path, sniff)?;
+    let asset_type = match &config.asset_type {
+        Some(asset_type) => asset_type.clone(),
+        None => detect_asset_type(path, asset_kind, sniff)?,
+    };
+    Ok((asset_type, format))
+}
+
+/// Media type of an ingredient file: from its extension, or from its content
+/// if `sniff` is set and the extension does not identify it
+fn detect_format(path: &Path, sniff: bool) -> Result<String> {
+    let mut format = determine_format(path)?;
+    if sniff
+        && format == "application/octet-stream"
+        && let Some(content) = sniff_content(path)
+    {
+        format = content.media_type().to_string();
+    }
+    Ok(format)
+}
+
+/// Asset type of an ingredient file of the given kind
+fn detect_asset_type(path: &Path, asset_kind: &AssetKind, sniff: bool) -> Result<AssetType> {
+    match asset_kind {
+        AssetKind::Model => detect_model_type(path, sniff),
+        AssetKind::Dataset => detect_dataset_type(path, sniff),
+        AssetKind::Software => determine_software_type(path),
+        AssetKind::Evaluation => Ok(AssetType::Dataset), // Use Dataset type for evaluation results
+    }
+}
+
+/// Validates a manifest creation request and prints what would be created,
+/// without hashing file contents or touching storage
+fn report_dry_run(
+    config: &ManifestCreationConfig,
+    asset_kind: &AssetKind,
+    inputs: &[(PathBuf, String)],
+    directory_count: usize,
+) -> Result<()> {
+    // Check the output format up front since nothing will be serialized
+    if !matches!(
+        config.output_format.to_lowercase().as_str(),
+        "json" | "cbor" | "yaml"
+    ) {
+        return Err(Error::Validation(format!(
+            "Invalid output format '{}'. Valid options are: json, cbor, yaml",
+            config.output_format
+        )));
+    }
+
+    if let Some(key_file) = &config.key_path
+        && !key_file.is_file()
+    {
+        return Err(SigningError::KeyNotFound(format!(
+            "Signing key not found: {}",
+            key_file.display()
+        ))
+        .into());
+    }
+    if let Some(key_file) = &config.key_path {
+        signing::load_private_key_with_min_bits(key_file, config.min_key_bits)?;
+    }
+
+    let mut ingredients = Vec::with_capacity(inputs.len());
+    for (path, title) in inputs {
+        if !path.is_file() && !is_stdin_path(path) {
+            return Err(Error::Validation(format!(
+                "Input file not found: {}",
+                path.display()
+            )));
+        }
+        let (asset_type, format) = ingredient_type_and_format(path, title, asset_kind, config)?;
+        ingredients.push((title, path, asset_type, format));
+    }
+
+    println!("Dry run: no files were hashed and nothing was signed or stored");
+    println!("Manifest: {} ({:?})", config.name, asset_kind);
+    println!("Ingredients: {}", ingredients.len());
+    for (title, path, asset_type, format) in &ingredients {
+        println!("  {title}: {} ({asset_type:?}, {format})", path.display());
+    }
+    if directory_count > 0 {
+        println!("Directories: {directory_count}");
+    }
+
+    match (&config.signer, &config.key_path) {
+        (Some(signer), _) => println!(
+            "Signing: yes, with {} ({})",
+            signer.description(),
+            config.hash_alg
+        ),
+        (None, Some(key_file)) => println!(
+            "Signing: yes, with {} ({})",
+            key_file.display(),
+            config.hash_alg
+        ),
+        (None, None) => println!("Signing: no"),
+    }
+    if let Some(tsa_url) = &config.tsa_url {
+        println!("Timestamp: {tsa_url}");
+    }
+    if let Some(creative_type) = &config.creative_type_override {
+        println!("Creative type: {creative_type}");
+    }
+    if let Some(digital_source_type) = &config.digital_source_type_override {
+        println!("Digital source type: {digital_source_type}");
+    }
+    if config.with_cc {
+        println!("CC attestation: yes");
+    }
+    if config.record_environment {
+        println!(
+            "Environment: recorded{}",
+            if config.redact_hostname {
+                " (hostname redacted)"
+            } else {
+                ""
+            }
+        );
+    }
+    if let Some(linked) = &config.linked_manifests {
+        println!("Linked manifests: {}", linked.join(", "));
+    }
+
+    let storage = match (&config.output_path, config.storage) {
+        (Some(path), Some(_)) if !config.print => {
+            format!("would be stored and written to {}", path.display())
+        }
+        (Some(path), _) => format!("would be written to {}", path.display()),
+        (None, Some(_)) if !config.print => "would be stored".to_string(),
+        (None, _) => "would be printed".to_string(),
+    };
+    println!("Output: manifest {storage} as {}", config.output_format);
+
+    Ok(())
+}
+
+/// Returns true if a file or directory name matches an ignore pattern.
+///
+/// Patterns are either exact names (`.git`) or suffix globs (`*.tmp`).
+fn matches_ignore_pattern(name: &str, pattern: &str) -> bool {
+    match pattern.strip_prefix('*') {
+        Some(suffix) => name.ends_with(suffix),
+        None => name == pattern,
+    }
+}
+
+/// Recursively collects the regular files in a directory in a stable order,
+/// skipping entries that match the default or configured ignore patterns.
+/// Symlinked files are only included with `follow_symlinks`.
+fn collect_directory_files(
+    dir: &Path,
+    ignore_patterns: &[String],
+    follow_symlinks: bool,
+) -> Result<Vec<PathBuf>> {
+    let is_ignored = |name: &str| {
+        DEFAULT_IGNORE_PATTERNS
+            .iter()
+            .any(|p| matches_ignore_pattern(name, p))
+            || ignore_patterns
+                .iter()
+                .any(|p| matches_ignore_pattern(name, p))
+    };
+
+    let mut files = Vec::new();
+    let walker = walkdir::WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !is_ignored(&entry.file_name().to_string_lossy())
+        });
+
+    for entry in walker {
+        let entry = entry.map_err(|e| Error::Io(e.into()))?;
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        } else if entry.file_type().is_symlink() && follow_symlinks && entry.path().is_file() {
+            // Symlinked files are kept; their targets are checked when hashed
+            files.push(entry.into_path());
+        } else if entry.file_type().is_symlink() {
+            log::warn!(
+                "Skipping symlink in directory input: {}",
+                entry.path().display()
+            );
+        }
+    }
+
+    Ok(files)
+}
+
 /// Create an ingredient from a path
 pub fn create_ingredient_from_path(
     path: &Path,
@@ -693,7 +2127,7 @@ pub fn create_ingredient_from_path(
         name,
         asset_type,
         format,
-        &HashAlgorithm::Sha256,
+        DigestAlgorithm::Sha256,
     )
 }
 
@@ -702,12 +2136,156 @@ pub fn create_ingredient_from_path_with_algorithm(
     name: &str,
     asset_type: AssetType,
     format: String,
-    algorithm: &HashAlgorithm,
+    algorithm: impl Into<DigestAlgorithm>,
+) -> Result<Ingredient> {
+    create_ingredient(
+        path,
+        name,
+        asset_type,
+        format,
+        algorithm.into(),
+        HashOptions::default(),
+    )
+}
+
+/// Like [`create_ingredient_from_path_with_algorithm`], but with
+/// `hash_decompressed` a `.gz` or `.zst` file is hashed after decompressing
+/// it, so the hash does not depend on how it was compressed. The ingredient
+/// URL still points at the compressed file.
+///
+/// With `follow_symlinks`, a symlinked file is hashed if its target resolves
+/// into the current working directory; otherwise symlinks are rejected.
+pub fn create_ingredient_from_path_with_options(
+    path: &Path,
+    name: &str,
+    asset_type: AssetType,
+    format: String,
+    algorithm: impl Into<DigestAlgorithm>,
+    hash_decompressed: bool,
+    follow_symlinks: bool,
+) -> Result<Ingredient> {
+    create_ingredient(
+        path,
+        name,
+        asset_type,
+        format,
+        algorithm.into(),
+        HashOptions {
+            mode: if hash_decompressed {
+                HashMode::Decompressed
+            } else {
+                HashMode::Full
+            },
+            follow_symlinks,
+            ..Default::default()
+        },
+    )
+}
+
+/// Like [`create_ingredient_from_path`], but only hashes sampled regions of
+/// the file (see [`hash::sample`]) for a quick check of huge files. The
+/// ingredient records the `sample-sha256` algorithm, and verification never
+/// reports it as fully verified.
+pub fn create_ingredient_from_path_sampled(
+    path: &Path,
+    name: &str,
+    asset_type: AssetType,
+    format: String,
+) -> Result<Ingredient> {
+    create_ingredient(
+        path,
+        name,
+        asset_type,
+        format,
+        DigestAlgorithm::Sha256,
+        HashOptions {
+            mode: HashMode::Sampled,
+            ..Default::default()
+        },
+    )
+}
+
+/// How an ingredient file's content is hashed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum HashMode {
+    /// Every byte of the file as stored
+    #[default]
+    Full,
+    /// The decompressed content of a `.gz` or `.zst` file
+    Decompressed,
+    /// Sampled regions of the file only
+    Sampled,
+}
+
+/// Settings for hashing an ingredient file
+#[derive(Debug, Clone, Copy)]
+struct HashOptions<'a> {
+    mode: HashMode,
+    /// Show a bytes-processed progress bar while hashing
+    show_progress: bool,
+    /// Hash a symlinked file if its target is in an allowed root
+    follow_symlinks: bool,
+    /// Directories symlinks may point into; empty means the current directory
+    symlink_roots: &'a [PathBuf],
+    /// Bytes read at a time while hashing
+    chunk_size: usize,
+}
+
+impl Default for HashOptions<'_> {
+    fn default() -> Self {
+        Self {
+            mode: HashMode::default(),
+            show_progress: false,
+            follow_symlinks: false,
+            symlink_roots: &[],
+            chunk_size: hash::DEFAULT_CHUNK_SIZE,
+        }
+    }
+}
+
+fn create_ingredient(
+    path: &Path,
+    name: &str,
+    asset_type: AssetType,
+    format: String,
+    algorithm: DigestAlgorithm,
+    options: HashOptions<'_>,
 ) -> Result<Ingredient> {
+    let compression =
+        Compression::from_path(path).filter(|_| options.mode == HashMode::Decompressed);
+    let mut alg = algorithm.as_str();
+    let (url, hash) = if is_stdin_path(path) {
+        let hash = hash::calculate_reader_hash_with_chunk_size(
+            std::io::stdin().lock(),
+            algorithm,
+            options.chunk_size,
+        )?;
+        (format!("{STDIN_URL_SCHEME}{name}"), hash)
+    } else {
+        // The URL keeps the path as given, so a symlink is resolved and
+        // checked again on verification
+        let url = format!("file://{}", path.to_string_lossy());
+        let path =
+            &safe_file_path_with_roots(path, options.follow_symlinks, options.symlink_roots)?;
+        let hash = if options.mode == HashMode::Sampled {
+            alg = SAMPLE_ALG;
+            calculate_sampled_file_hash(path)?
+        } else if let Some(compression) = compression {
+            calculate_decompressed_file_hash(path, compression, algorithm)?
+        } else {
+            calculate_file_hash_with_progress(
+                path,
+                algorithm,
+                options.chunk_size,
+                options.show_progress,
+            )?
+        };
+        (url, hash)
+    };
     let ingredient_data = IngredientData {
-        url: format!("file://{}", path.to_string_lossy()),
-        alg: algorithm.as_str().to_string(),
-        hash: hash::calculate_file_hash_with_algorithm(path, algorithm)?,
+        url,
+        alg: alg.to_string(),
+        hash,
         data_types: vec![asset_type],
         linked_ingredient_url: None,
         linked_ingredient_hash: None,
@@ -716,7 +2294,7 @@ pub fn create_ingredient_from_path_with_algorithm(
     Ok(Ingredient {
         title: name.to_string(),
         format,
-        relationship: "componentOf".to_string(),
+        relationship: DEFAULT_RELATIONSHIP.to_string(),
         document_id: format!("uuid:{}", Uuid::new_v4()),
         instance_id: format!("uuid:{}", Uuid::new_v4()),
         data: ingredient_data,
@@ -736,7 +2314,7 @@ fn get_cc_attestation_assertion() -> Result<CustomAssertion> {
     };
 
     // detect the underlying CC platform
-    let platform = match get_platform_name() {
+    let platform = match cc_attestation::get_platform_name() {
         Ok(p) => p,
         Err(e) => {
             return Err(Error::CCAttestationError(format!(
@@ -752,3 +2330,159 @@ fn get_cc_attestation_assertion() -> Result<CustomAssertion> {
 
     Ok(cc_assertion)
 }
+
+/// Locates CC attestation assertions in the claim and checks them.
+///
+/// CC assertions are `CustomAssertion`s labelled with a CC platform name from
+/// [`cc_attestation::ATTESTATION_PLATFORMS`] whose data is the serialized
+/// attestation report; other custom assertions, including ones labelled with
+/// a plain OS name, are not looked at. Every report must be well-formed JSON.
+/// When the recorded platform matches the current CC platform, the recorded
+/// launch measurement is re-validated through the attestation
+/// provider; otherwise the check is skipped with a warning, or fails if
+/// `strict` is set.
+pub(crate) fn verify_cc_attestation(
+    manifest: &Manifest,
+    strict: bool,
+) -> Result<Vec<VerificationCheck>> {
+    let mut checks = Vec::new();
+    let Some(claim) = &manifest.claim_v2 else {
+        return Ok(checks);
+    };
+
+    for assertion in &claim.created_assertions {
+        let Assertion::CustomAssertion(custom) = assertion else {
+            continue;
+        };
+        if !cc_attestation::is_attestation_label(&custom.label) {
+            continue;
+        }
+        let serde_json::Value::String(report) = &custom.data else {
+            continue;
+        };
+
+        let report: serde_json::Value = serde_json::from_str(report).map_err(|e| {
+            Error::Validation(format!(
+                "Malformed CC attestation report for platform {}: {e}",
+                custom.label
+            ))
+        })?;
+        if !report.is_object() {
+            return Err(Error::Validation(format!(
+                "Malformed CC attestation report for platform {}: expected a JSON object",
+                custom.label
+            )));
+        }
+
+        let current_platform = cc_attestation::get_platform_name()?;
+        let recorded_measurement = cc_attestation::report_launch_measurement(&report);
+
+        match recorded_measurement {
+            Some(expected)
+                if custom.label == current_platform
+                    && cc_attestation::is_cc_platform(&current_platform) =>
+            {
+                let measurement = cc_attestation::get_launch_measurement()?;
+                cc_attestation::check_measurement(&expected, &measurement)?;
+                checks.push(VerificationCheck::verified(
+                    &custom.label,
+                    "launch measurement re-validated",
+                ));
+            }
+            _ if strict => {
+                return Err(Error::Validation(format!(
+                    "CC attestation from platform {} cannot be re-validated on platform {}",
+                    custom.label, current_platform
+                )));
+            }
+            _ => {
+                checks.push(VerificationCheck::warning(
+                    &custom.label,
+                    format!(
+                        "attestation is well-formed but could not be re-validated on platform {current_platform}"
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(checks)
+}
+
+/// Verify RFC 3161 timestamp tokens recorded for the claim signature
+pub(crate) fn verify_timestamps(
+    manifest: &Manifest,
+    config: &VerificationConfig,
+) -> Result<Vec<VerificationCheck>> {
+    match &manifest.claim_v2 {
+        Some(claim) => verify_claim_timestamps(claim, config),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn verify_claim_timestamps(
+    claim: &ClaimV2,
+    config: &VerificationConfig,
+) -> Result<Vec<VerificationCheck>> {
+    let mut checks = Vec::new();
+    for assertion in &claim.created_assertions {
+        let Assertion::CustomAssertion(custom) = assertion else {
+            continue;
+        };
+        if custom.label != timestamp::TIMESTAMP_ASSERTION_LABEL {
+            continue;
+        }
+
+        let signature = claim.signature.as_deref().ok_or_else(|| {
+            Error::Validation("Timestamp present but the claim is not signed".to_string())
+        })?;
+        let signature = STANDARD
+            .decode(signature)
+            .map_err(|e| Error::Validation(format!("Invalid claim signature encoding: {e}")))?;
+
+        let token = custom
+            .data
+            .get("token")
+            .and_then(|t| t.as_str())
+            .ok_or_else(|| Error::Validation("Timestamp assertion has no token".to_string()))?;
+        let token = STANDARD
+            .decode(token)
+            .map_err(|e| Error::Validation(format!("Invalid timestamp token encoding: {e}")))?;
+
+        let trust_store = match &config.tsa_ca {
+            Some(path) => Some(load_trust_store(path)?),
+            None => None,
+        };
+
+        let info = timestamp::verify_timestamp_token(&token, &signature, trust_store.as_ref())?;
+        let subject = info.gen_time.to_string();
+        if trust_store.is_none() {
+            checks.push(VerificationCheck::warning(
+                subject,
+                "timestamp matches the signature, but the TSA certificate chain was not validated; use --tsa-ca to supply trusted certificates",
+            ));
+        } else {
+            checks.push(VerificationCheck::verified(
+                subject,
+                "timestamp matches the signature",
+            ));
+        }
+    }
+
+    Ok(checks)
+}
+
+fn load_trust_store(path: &Path) -> Result<openssl::x509::store::X509Store> {
+    let pem = std::fs::read(path)?;
+    let certs = openssl::x509::X509::stack_from_pem(&pem)
+        .map_err(|e| Error::Validation(format!("Failed to load TSA certificates: {e}")))?;
+
+    let mut builder = openssl::x509::store::X509StoreBuilder::new()
+        .map_err(|e| Error::Validation(e.to_string()))?;
+    for cert in certs {
+        builder
+            .add_cert(cert)
+            .map_err(|e| Error::Validation(e.to_string()))?;
+    }
+    Ok(builder.build())
+}
@@ -1,26 +1,111 @@
+use crate::hash::DigestAlgorithm;
+use crate::signing::signer::Signer;
 use crate::storage::traits::StorageBackend;
-use atlas_c2pa_lib::cose::HashAlgorithm;
+use atlas_c2pa_lib::asset_type::AssetType;
 use std::path::PathBuf;
+use std::sync::Arc;
+use time::OffsetDateTime;
 
 pub struct ManifestCreationConfig {
     pub paths: Vec<PathBuf>,
     pub ingredient_names: Vec<String>,
+    // C2PA relationship of each ingredient (componentOf, parentOf, inputTo),
+    // parallel to ingredient_names; empty means componentOf for all
+    pub ingredient_relationships: Vec<String>,
+    // URL recorded for each ingredient instead of the `file://` URL of its
+    // path, e.g. where the artifact is published; parallel to
+    // ingredient_names, empty means local URLs for all. A directory input's
+    // URL is the base of its files' URLs
+    pub ingredient_urls: Vec<String>,
     pub name: String,
     pub author_org: Option<String>,
     pub author_name: Option<String>,
     pub description: Option<String>,
     pub linked_manifests: Option<Vec<String>>,
+    // Manifests this one derives from (e.g. the base of a fine-tuned model),
+    // recorded as its lineage and cross-referenced
+    pub base_manifests: Vec<String>,
+    // Dataset manifests a model was trained on, recorded with their hashes
+    pub trained_on: Vec<String>,
+    // Model card to reference: a local file, hashed as a `documentOf`
+    // ingredient, or a URL linked with the optional expected hash
+    pub model_card: Option<String>,
+    pub model_card_hash: Option<String>,
     pub storage: Option<&'static dyn StorageBackend>,
     pub print: bool,
     pub output_format: String,
     pub key_path: Option<PathBuf>,
-    pub hash_alg: HashAlgorithm,
+    pub hash_alg: DigestAlgorithm,
     pub with_cc: bool,
     // Software-specific fields
     pub software_type: Option<String>,
     pub version: Option<String>,
     // Generic custom_fields for extensions
     pub custom_fields: Option<serde_json::Value>,
+    // Forces the ingredient asset type instead of detecting it from the extension
+    pub asset_type: Option<AssetType>,
+    // Walk directory inputs and skip entries matching the ignore patterns
+    pub recursive: bool,
+    pub ignore_patterns: Vec<String>,
+    // Show progress bars while hashing inputs
+    pub show_progress: bool,
+    // RFC 3161 Time Stamp Authority used to timestamp the signature
+    pub tsa_url: Option<String>,
+    // Validate inputs and report what would be created without hashing,
+    // signing or storing anything
+    pub dry_run: bool,
+    // User-defined tags such as `env=prod`, used to filter manifest listings
+    pub tags: Vec<String>,
+    // Smallest RSA signing key accepted, in bits
+    pub min_key_bits: u32,
+    // Replace the per-kind defaults in the CreativeWork and Action assertions,
+    // e.g. to follow a newer IPTC vocabulary
+    pub creative_type_override: Option<String>,
+    pub digital_source_type_override: Option<String>,
+    // Store manifests without validating them against the bundled JSON Schema
+    pub skip_schema_check: bool,
+    // Detect ingredient asset types from the extension only, without
+    // inspecting the file's leading bytes
+    pub no_sniff: bool,
+    // Write the serialized manifest to this file instead of stdout
+    pub output_path: Option<PathBuf>,
+    // Print CBOR output as hex instead of raw bytes
+    pub hex_output: bool,
+    // Derive manifest, claim and ingredient IDs from the inputs instead of
+    // random UUIDs, so the same inputs produce the same manifest
+    pub deterministic: bool,
+    // Creation time recorded in the manifest; defaults to now
+    pub created_at: Option<OffsetDateTime>,
+    // Hash the decompressed content of gzip and zstd inputs instead of the
+    // compressed bytes
+    pub hash_decompressed: bool,
+    // Hash only sampled regions of each input file (quick-check mode); the
+    // ingredients record `sample-sha256` so they are never taken for full hashes
+    pub quick_hash: bool,
+    // Hash symlinked inputs whose targets resolve into an allowed root
+    // instead of rejecting them
+    pub follow_symlinks: bool,
+    // Directories symlinked inputs may point into; empty allows only the
+    // current working directory
+    pub symlink_roots: Vec<PathBuf>,
+    // Bytes read at a time while hashing input files and streams
+    pub hash_chunk_size: usize,
+    // Audit log the stored manifest is recorded in, if any
+    pub audit_log: Option<PathBuf>,
+    // Generator recorded in the manifest, e.g. `my-tool/1.2.0`; defaults to
+    // this crate's name and version
+    pub claim_generator: Option<String>,
+    // Period in which the manifest may be relied on; verification rejects it
+    // outside this window
+    pub valid_from: Option<OffsetDateTime>,
+    pub valid_until: Option<OffsetDateTime>,
+    // Record the OS, architecture, hostname and CLI version in an
+    // `atlas.environment` assertion; `redact_hostname` leaves the hostname out
+    pub record_environment: bool,
+    pub redact_hostname: bool,
+    // Signs the claim instead of a key loaded from `key_path`, e.g. a key
+    // held in a cloud KMS
+    pub signer: Option<Arc<dyn Signer>>,
 }
 
 impl ManifestCreationConfig {
@@ -28,20 +113,90 @@ impl ManifestCreationConfig {
         Self {
             paths: self.paths.clone(),
             ingredient_names: self.ingredient_names.clone(),
+            ingredient_relationships: self.ingredient_relationships.clone(),
+            ingredient_urls: self.ingredient_urls.clone(),
             name: self.name.clone(),
             author_org: self.author_org.clone(),
             author_name: self.author_name.clone(),
             description: self.description.clone(),
             linked_manifests: self.linked_manifests.clone(),
+            base_manifests: self.base_manifests.clone(),
+            trained_on: self.trained_on.clone(),
+            model_card: self.model_card.clone(),
+            model_card_hash: self.model_card_hash.clone(),
             storage: self.storage,
             print: self.print,
             output_format: self.output_format.clone(),
             key_path: self.key_path.clone(),
-            hash_alg: self.hash_alg.clone(),
+            hash_alg: self.hash_alg,
             with_cc: self.with_cc,
             software_type: self.software_type.clone(),
             version: self.version.clone(),
             custom_fields: self.custom_fields.clone(),
+            asset_type: self.asset_type.clone(),
+            recursive: self.recursive,
+            ignore_patterns: self.ignore_patterns.clone(),
+            show_progress: self.show_progress,
+            tsa_url: self.tsa_url.clone(),
+            dry_run: self.dry_run,
+            tags: self.tags.clone(),
+            min_key_bits: self.min_key_bits,
+            creative_type_override: self.creative_type_override.clone(),
+            digital_source_type_override: self.digital_source_type_override.clone(),
+            skip_schema_check: self.skip_schema_check,
+            no_sniff: self.no_sniff,
+            output_path: self.output_path.clone(),
+            hex_output: self.hex_output,
+            deterministic: self.deterministic,
+            created_at: self.created_at,
+            hash_decompressed: self.hash_decompressed,
+            quick_hash: self.quick_hash,
+            follow_symlinks: self.follow_symlinks,
+            claim_generator: self.claim_generator.clone(),
+            valid_from: self.valid_from,
+            valid_until: self.valid_until,
+            record_environment: self.record_environment,
+            redact_hostname: self.redact_hostname,
+            signer: self.signer.clone(),
+            symlink_roots: self.symlink_roots.clone(),
+            hash_chunk_size: self.hash_chunk_size,
+            audit_log: self.audit_log.clone(),
         }
     }
 }
+
+/// Options controlling how manifests are verified
+#[derive(Debug, Clone, Default)]
+pub struct VerificationConfig {
+    /// Fail verification when a CC attestation assertion cannot be
+    /// re-validated on the current platform, instead of only warning
+    pub strict_cc: bool,
+    /// PEM file of trusted TSA certificates; without it, timestamp tokens are
+    /// checked against their embedded certificate only
+    pub tsa_ca: Option<PathBuf>,
+    /// Warn instead of failing when a cross-reference target is missing, so
+    /// partial bundles can still be inspected
+    pub allow_dangling: bool,
+    /// Skip `http(s)://` and `s3://` ingredients with a warning instead of
+    /// downloading and hashing them
+    pub offline: bool,
+    /// File of trusted signer public keys or fingerprints; when set, every
+    /// signature must come from a listed key
+    pub trust_list: Option<PathBuf>,
+    /// Warn instead of failing when the manifest's validity window has ended
+    pub allow_expired: bool,
+    /// JSON file of policy rules checked after the built-in checks
+    pub policy: Option<PathBuf>,
+    /// Fail when an ingredient's recorded format or data type does not
+    /// match what its file extension and content give, instead of warning
+    pub strict: bool,
+    /// Hash symlinked ingredient files whose targets resolve into an allowed
+    /// root instead of failing
+    pub follow_symlinks: bool,
+    /// Directories symlinked ingredient files may point into; empty allows
+    /// only the current working directory
+    pub symlink_roots: Vec<PathBuf>,
+    /// Check every ingredient before failing, so the error lists all
+    /// ingredients that failed instead of only the first
+    pub continue_on_error: bool,
+}
@@ -0,0 +1,310 @@
+use crate::error::{Error, Result};
+use crate::storage::traits::StorageBackend;
+use atlas_c2pa_lib::ingredient::Ingredient;
+use atlas_c2pa_lib::manifest::Manifest;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// A change to a single named field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Summary of an ingredient that was added or removed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IngredientSummary {
+    pub title: String,
+    pub hash: String,
+    pub url: String,
+}
+
+/// Changes to an ingredient present in both manifests, matched by title
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IngredientChange {
+    pub title: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Structured differences between two manifests
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ManifestDiff {
+    pub fields: Vec<FieldChange>,
+    pub ingredients_added: Vec<IngredientSummary>,
+    pub ingredients_removed: Vec<IngredientSummary>,
+    pub ingredients_changed: Vec<IngredientChange>,
+    pub assertions_added: Vec<Value>,
+    pub assertions_removed: Vec<Value>,
+    pub cross_references_added: Vec<String>,
+    pub cross_references_removed: Vec<String>,
+    pub cross_references_changed: Vec<FieldChange>,
+}
+
+impl ManifestDiff {
+    /// Returns true if the manifests are equivalent
+    pub fn is_empty(&self) -> bool {
+        *self == ManifestDiff::default()
+    }
+
+    /// Render the diff as human-readable text
+    pub fn to_text(&self) -> String {
+        if self.is_empty() {
+            return "No differences found".to_string();
+        }
+
+        let mut out = String::new();
+        for change in &self.fields {
+            let _ = writeln!(out, "~ {}: {} -> {}", change.field, change.old, change.new);
+        }
+        for ingredient in &self.ingredients_added {
+            let _ = writeln!(
+                out,
+                "+ ingredient {} ({})",
+                ingredient.title, ingredient.hash
+            );
+        }
+        for ingredient in &self.ingredients_removed {
+            let _ = writeln!(
+                out,
+                "- ingredient {} ({})",
+                ingredient.title, ingredient.hash
+            );
+        }
+        for ingredient in &self.ingredients_changed {
+            for change in &ingredient.changes {
+                let _ = writeln!(
+                    out,
+                    "~ ingredient {} {}: {} -> {}",
+                    ingredient.title, change.field, change.old, change.new
+                );
+            }
+        }
+        for assertion in &self.assertions_added {
+            let _ = writeln!(out, "+ assertion {assertion}");
+        }
+        for assertion in &self.assertions_removed {
+            let _ = writeln!(out, "- assertion {assertion}");
+        }
+        for url in &self.cross_references_added {
+            let _ = writeln!(out, "+ cross-reference {url}");
+        }
+        for url in &self.cross_references_removed {
+            let _ = writeln!(out, "- cross-reference {url}");
+        }
+        for change in &self.cross_references_changed {
+            let _ = writeln!(
+                out,
+                "~ cross-reference {}: {} -> {}",
+                change.field, change.old, change.new
+            );
+        }
+
+        out.trim_end().to_string()
+    }
+}
+
+/// Compare two manifests, ignoring volatile fields (`instance_id`,
+/// `created_at` and the claim signature)
+pub fn diff_manifests(a: &Manifest, b: &Manifest) -> ManifestDiff {
+    diff_manifests_with_options(a, b, false)
+}
+
+/// Compare two manifests, optionally including volatile fields
+pub fn diff_manifests_with_options(
+    a: &Manifest,
+    b: &Manifest,
+    include_volatile: bool,
+) -> ManifestDiff {
+    let mut diff = ManifestDiff::default();
+
+    compare_field(&mut diff.fields, "title", &a.title, &b.title);
+    compare_field(
+        &mut diff.fields,
+        "claim_generator",
+        &a.claim_generator,
+        &b.claim_generator,
+    );
+    compare_field(
+        &mut diff.fields,
+        "is_active",
+        &a.is_active.to_string(),
+        &b.is_active.to_string(),
+    );
+
+    if include_volatile {
+        compare_field(
+            &mut diff.fields,
+            "instance_id",
+            &a.instance_id,
+            &b.instance_id,
+        );
+        compare_field(
+            &mut diff.fields,
+            "created_at",
+            &a.created_at.0.to_string(),
+            &b.created_at.0.to_string(),
+        );
+        compare_field(
+            &mut diff.fields,
+            "claim.signature",
+            a.claim.signature.as_deref().unwrap_or(""),
+            b.claim.signature.as_deref().unwrap_or(""),
+        );
+    }
+
+    diff_ingredients(&mut diff, &a.ingredients, &b.ingredients, include_volatile);
+
+    let assertions_a = assertion_values(a);
+    let assertions_b = assertion_values(b);
+    diff.assertions_added = assertions_b
+        .iter()
+        .filter(|v| !assertions_a.contains(v))
+        .cloned()
+        .collect();
+    diff.assertions_removed = assertions_a
+        .iter()
+        .filter(|v| !assertions_b.contains(v))
+        .cloned()
+        .collect();
+
+    let refs_a: BTreeMap<_, _> = a
+        .cross_references
+        .iter()
+        .map(|r| (r.manifest_url.clone(), r.manifest_hash.clone()))
+        .collect();
+    let refs_b: BTreeMap<_, _> = b
+        .cross_references
+        .iter()
+        .map(|r| (r.manifest_url.clone(), r.manifest_hash.clone()))
+        .collect();
+    for (url, hash) in &refs_b {
+        match refs_a.get(url) {
+            None => diff.cross_references_added.push(url.clone()),
+            Some(old) => compare_field(&mut diff.cross_references_changed, url, old, hash),
+        }
+    }
+    diff.cross_references_removed = refs_a
+        .keys()
+        .filter(|url| !refs_b.contains_key(*url))
+        .cloned()
+        .collect();
+
+    diff
+}
+
+/// Retrieve two manifests from storage and print their differences
+pub fn diff_stored_manifests(
+    id_a: &str,
+    id_b: &str,
+    storage: &dyn StorageBackend,
+    include_volatile: bool,
+    format: &str,
+) -> Result<ManifestDiff> {
+    let a = storage.retrieve_manifest(id_a)?;
+    let b = storage.retrieve_manifest(id_b)?;
+    let diff = diff_manifests_with_options(&a, &b, include_volatile);
+
+    match format.to_lowercase().as_str() {
+        "text" => println!("{}", diff.to_text()),
+        "json" => println!(
+            "{}",
+            serde_json::to_string_pretty(&diff).map_err(|e| Error::Serialization(e.to_string()))?
+        ),
+        _ => {
+            return Err(Error::Validation(format!(
+                "Invalid output format '{format}'. Valid options are: text, json"
+            )));
+        }
+    }
+
+    Ok(diff)
+}
+
+fn compare_field(changes: &mut Vec<FieldChange>, field: &str, old: &str, new: &str) {
+    if old != new {
+        changes.push(FieldChange {
+            field: field.to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+        });
+    }
+}
+
+fn diff_ingredients(
+    diff: &mut ManifestDiff,
+    a: &[Ingredient],
+    b: &[Ingredient],
+    include_volatile: bool,
+) {
+    let by_title_a: BTreeMap<_, _> = a.iter().map(|i| (i.title.as_str(), i)).collect();
+    let by_title_b: BTreeMap<_, _> = b.iter().map(|i| (i.title.as_str(), i)).collect();
+
+    for (title, new) in &by_title_b {
+        let Some(old) = by_title_a.get(title) else {
+            diff.ingredients_added.push(summarize(new));
+            continue;
+        };
+
+        let mut changes = Vec::new();
+        compare_field(&mut changes, "hash", &old.data.hash, &new.data.hash);
+        compare_field(&mut changes, "alg", &old.data.alg, &new.data.alg);
+        compare_field(&mut changes, "url", &old.data.url, &new.data.url);
+        compare_field(&mut changes, "format", &old.format, &new.format);
+        compare_field(
+            &mut changes,
+            "relationship",
+            &old.relationship,
+            &new.relationship,
+        );
+        if include_volatile {
+            compare_field(
+                &mut changes,
+                "instance_id",
+                &old.instance_id,
+                &new.instance_id,
+            );
+            compare_field(
+                &mut changes,
+                "document_id",
+                &old.document_id,
+                &new.document_id,
+            );
+        }
+
+        if !changes.is_empty() {
+            diff.ingredients_changed.push(IngredientChange {
+                title: title.to_string(),
+                changes,
+            });
+        }
+    }
+
+    diff.ingredients_removed = by_title_a
+        .iter()
+        .filter(|(title, _)| !by_title_b.contains_key(*title))
+        .map(|(_, ingredient)| summarize(ingredient))
+        .collect();
+}
+
+fn summarize(ingredient: &Ingredient) -> IngredientSummary {
+    IngredientSummary {
+        title: ingredient.title.clone(),
+        hash: ingredient.data.hash.clone(),
+        url: ingredient.data.url.clone(),
+    }
+}
+
+fn assertion_values(manifest: &Manifest) -> Vec<Value> {
+    manifest
+        .claim_v2
+        .as_ref()
+        .unwrap_or(&manifest.claim)
+        .created_assertions
+        .iter()
+        .filter_map(|assertion| serde_json::to_value(assertion).ok())
+        .collect()
+}
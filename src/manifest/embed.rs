@@ -0,0 +1,733 @@
+//! # Embedded Manifests
+//!
+//! Embeds manifests into the assets they describe instead of storing them
+//! separately. The manifest is wrapped in a JUMBF manifest store box (ISO/IEC
+//! 19566-5) labelled `c2pa`, holding one manifest box with the manifest as a
+//! JSON content box, and written into the asset's container:
+//!
+//! - PNG: a `caBX` chunk placed after `IHDR`
+//! - JPEG: one or more APP11 segments placed after any APP0/APP1 segments
+//!
+//! Other formats cannot carry an embedded manifest; the same manifest store
+//! can instead be written to a `.c2pa` sidecar file next to the asset.
+//!
+//! Embedding only inserts the manifest store, so removing it again restores
+//! the original asset bytes. Verification uses this to check that the asset
+//! still matches the ingredient hash recorded before embedding.
+
+use crate::error::{Error, Result};
+use crate::hash;
+use crate::manifest::common::verify_loaded_manifest;
+use crate::manifest::config::VerificationConfig;
+use crate::manifest::report::{VerificationCheck, VerificationReport};
+use crate::manifest::version;
+use crate::storage::filesystem::FilesystemStorage;
+use crate::storage::traits::StorageBackend;
+use atlas_c2pa_lib::manifest::Manifest;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File extension of manifest sidecar files
+pub const SIDECAR_EXTENSION: &str = "c2pa";
+
+const STORE_LABEL: &str = "c2pa";
+const CONTENT_LABEL: &str = "atlas.manifest";
+
+// JUMBF description box type UUIDs: a four character code followed by the
+// ISO suffix 0011-0010-8000-00AA00389B71
+const STORE_UUID: [u8; 16] = jumbf_uuid(*b"c2pa");
+const MANIFEST_UUID: [u8; 16] = jumbf_uuid(*b"c2ma");
+const JSON_UUID: [u8; 16] = jumbf_uuid(*b"json");
+
+// Description box toggles: requestable, with a label
+const JUMD_TOGGLES: u8 = 0x03;
+
+const SUPERBOX_TYPE: [u8; 4] = *b"jumb";
+
+const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const PNG_CHUNK_TYPE: &[u8; 4] = b"caBX";
+
+const JPEG_SOI: u8 = 0xd8;
+const JPEG_EOI: u8 = 0xd9;
+const JPEG_SOS: u8 = 0xda;
+const JPEG_APP0: u8 = 0xe0;
+const JPEG_APP1: u8 = 0xe1;
+const JPEG_APP11: u8 = 0xeb;
+// Segment length field, common identifier, box instance and sequence number
+const JPEG_APP11_HEADER_LEN: usize = 2 + 2 + 2 + 4;
+const JPEG_MAX_SEGMENT_LEN: usize = 0xffff;
+const JUMBF_BOX_HEADER_LEN: usize = 8;
+
+// A JUMBF box type and its payload
+type JumbfBox<'a> = ([u8; 4], &'a [u8]);
+
+const fn jumbf_uuid(code: [u8; 4]) -> [u8; 16] {
+    [
+        code[0], code[1], code[2], code[3], 0x00, 0x11, 0x00, 0x10, 0x80, 0x00, 0x00, 0xaa, 0x00,
+        0x38, 0x9b, 0x71,
+    ]
+}
+
+/// Asset container formats that can carry an embedded manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedFormat {
+    Png,
+    Jpeg,
+}
+
+impl EmbedFormat {
+    /// Detect the container format from the leading bytes of an asset
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(PNG_SIGNATURE) {
+            Some(EmbedFormat::Png)
+        } else if bytes.starts_with(&[0xff, JPEG_SOI]) {
+            Some(EmbedFormat::Jpeg)
+        } else {
+            None
+        }
+    }
+
+    fn strip(self, bytes: &[u8]) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+        match self {
+            EmbedFormat::Png => png_strip(bytes),
+            EmbedFormat::Jpeg => jpeg_strip(bytes),
+        }
+    }
+
+    fn insert(self, bytes: &[u8], store: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            EmbedFormat::Png => png_insert(bytes, store),
+            EmbedFormat::Jpeg => jpeg_insert(bytes, store),
+        }
+    }
+}
+
+/// Where an extracted manifest was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestSource {
+    Embedded(EmbedFormat),
+    Sidecar(PathBuf),
+}
+
+/// Returns the sidecar path for an asset, e.g. `model.onnx` -> `model.c2pa`
+pub fn sidecar_path(asset: &Path) -> PathBuf {
+    asset.with_extension(SIDECAR_EXTENSION)
+}
+
+/// Embed a manifest into an asset, writing the result to `output`
+///
+/// `output` may be the asset itself. Any manifest already embedded in the
+/// asset is replaced.
+///
+/// # Returns
+///
+/// * `Ok(EmbedFormat)` - The container format the manifest was embedded in
+/// * `Err(Error)` - If the asset format does not support embedding
+pub fn embed_manifest(asset: &Path, manifest: &Manifest, output: &Path) -> Result<EmbedFormat> {
+    let bytes = fs::read(asset)?;
+    let format = EmbedFormat::detect(&bytes).ok_or_else(|| {
+        Error::Validation(format!(
+            "Embedding manifests is not supported for {} (supported formats: PNG, JPEG); \
+             use --sidecar to write a .{SIDECAR_EXTENSION} sidecar file instead",
+            asset.display()
+        ))
+    })?;
+
+    let (original, _) = format.strip(&bytes)?;
+    let embedded = format.insert(&original, &build_manifest_store(manifest)?)?;
+    FilesystemStorage::write_atomic(output, &embedded)?;
+
+    Ok(format)
+}
+
+/// Write a manifest to the `.c2pa` sidecar file of an asset
+pub fn write_sidecar(asset: &Path, manifest: &Manifest) -> Result<PathBuf> {
+    if !asset.is_file() {
+        return Err(Error::Validation(format!(
+            "Asset not found: {}",
+            asset.display()
+        )));
+    }
+
+    let path = sidecar_path(asset);
+    FilesystemStorage::write_atomic(&path, &build_manifest_store(manifest)?)?;
+    Ok(path)
+}
+
+/// Extract the manifest embedded in an asset, or from its sidecar file
+pub fn extract_manifest(asset: &Path) -> Result<(Manifest, ManifestSource)> {
+    let bytes = fs::read(asset)?;
+    extract_with_original(asset, &bytes).map(|(manifest, source, _)| (manifest, source))
+}
+
+/// Verify the manifest embedded in an asset (or its sidecar)
+///
+/// The asset, with the manifest store removed, must match the hash of one of
+/// the manifest's ingredients. The manifest is then verified as usual, with
+/// cross-references resolved against `storage`.
+pub fn verify_embedded_manifest(
+    asset: &Path,
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+) -> Result<VerificationReport> {
+    let bytes = fs::read(asset)?;
+    let (manifest, source, original) = extract_with_original(asset, &bytes)?;
+
+    let mut bound_hash = None;
+    for ingredient in &manifest.ingredients {
+        // A sampled hash cannot bind the asset's full content
+        if hash::sample::is_sampled(&ingredient.data.alg) {
+            continue;
+        }
+        let algorithm: hash::DigestAlgorithm = ingredient.data.alg.parse()?;
+        let asset_hash = hash::calculate_hash_with_algorithm(&original, algorithm);
+        if asset_hash == ingredient.data.hash {
+            bound_hash = Some(asset_hash);
+            break;
+        }
+    }
+
+    let bound_hash = bound_hash.ok_or_else(|| {
+        Error::Validation(format!(
+            "Asset {} does not match any ingredient of its manifest; it may have been modified",
+            asset.display()
+        ))
+    })?;
+
+    let mut report = verify_loaded_manifest(
+        &manifest,
+        &manifest.instance_id,
+        Some(storage),
+        config,
+        Some(&bound_hash),
+    )?;
+    let origin = match &source {
+        ManifestSource::Embedded(format) => format!("embedded manifest ({format:?})"),
+        ManifestSource::Sidecar(path) => format!("sidecar {}", path.display()),
+    };
+    report.asset_checks.push(VerificationCheck::verified(
+        asset.display().to_string(),
+        format!("asset content matches the {origin}"),
+    ));
+    Ok(report)
+}
+
+// Returns the manifest, where it came from, and the asset bytes without the
+// embedded manifest store
+fn extract_with_original(
+    asset: &Path,
+    bytes: &[u8],
+) -> Result<(Manifest, ManifestSource, Vec<u8>)> {
+    if let Some(format) = EmbedFormat::detect(bytes)
+        && let (original, Some(store)) = format.strip(bytes)?
+    {
+        let manifest = parse_manifest_store(&store)?;
+        return Ok((manifest, ManifestSource::Embedded(format), original));
+    }
+
+    let sidecar = sidecar_path(asset);
+    if sidecar.is_file() {
+        let manifest = parse_manifest_store(&fs::read(&sidecar)?)?;
+        return Ok((manifest, ManifestSource::Sidecar(sidecar), bytes.to_vec()));
+    }
+
+    Err(Error::Validation(format!(
+        "No manifest found in {} or its sidecar {}",
+        asset.display(),
+        sidecar.display()
+    )))
+}
+
+fn jumbf_box(box_type: &[u8; 4], payload: &[u8]) -> Result<Vec<u8>> {
+    let len = u32::try_from(JUMBF_BOX_HEADER_LEN + payload.len())
+        .map_err(|_| Error::Validation("Manifest is too large to embed".to_string()))?;
+
+    let mut out = Vec::with_capacity(len as usize);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(box_type);
+    out.extend_from_slice(payload);
+    Ok(out)
+}
+
+// A JUMBF superbox: a description box followed by the content boxes
+fn jumbf_superbox(uuid: &[u8; 16], label: &str, contents: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let mut description = uuid.to_vec();
+    description.push(JUMD_TOGGLES);
+    description.extend_from_slice(label.as_bytes());
+    description.push(0);
+
+    let mut payload = jumbf_box(b"jumd", &description)?;
+    for content in contents {
+        payload.extend_from_slice(content);
+    }
+    jumbf_box(&SUPERBOX_TYPE, &payload)
+}
+
+fn build_manifest_store(manifest: &Manifest) -> Result<Vec<u8>> {
+    let json = serde_json::to_vec(manifest).map_err(|e| {
+        Error::Serialization(e.to_string()).with_manifest_context(
+            "embedding manifest",
+            &manifest.title,
+            &manifest.instance_id,
+        )
+    })?;
+    let content = jumbf_superbox(&JSON_UUID, CONTENT_LABEL, &[jumbf_box(b"json", &json)?])?;
+    let manifest_box = jumbf_superbox(&MANIFEST_UUID, &manifest.instance_id, &[content])?;
+    jumbf_superbox(&STORE_UUID, STORE_LABEL, &[manifest_box])
+}
+
+// Split a sequence of JUMBF boxes into (type, payload) pairs
+fn parse_boxes(mut data: &[u8]) -> Result<Vec<JumbfBox<'_>>> {
+    let invalid = || Error::Validation("Invalid JUMBF box in manifest store".to_string());
+
+    let mut boxes = Vec::new();
+    while !data.is_empty() {
+        if data.len() < JUMBF_BOX_HEADER_LEN {
+            return Err(invalid());
+        }
+        let len = u32::from_be_bytes(data[0..4].try_into().unwrap()) as u64;
+        let box_type: [u8; 4] = data[4..8].try_into().unwrap();
+
+        let (header_len, len) = match len {
+            0 => (JUMBF_BOX_HEADER_LEN, data.len() as u64),
+            1 => {
+                let extended = data.get(8..16).ok_or_else(invalid)?;
+                (16, u64::from_be_bytes(extended.try_into().unwrap()))
+            }
+            len => (JUMBF_BOX_HEADER_LEN, len),
+        };
+        if len < header_len as u64 || len > data.len() as u64 {
+            return Err(invalid());
+        }
+
+        let len = len as usize;
+        boxes.push((box_type, &data[header_len..len]));
+        data = &data[len..];
+    }
+    Ok(boxes)
+}
+
+// Split a JUMBF superbox payload into its description UUID and content boxes
+fn parse_superbox(payload: &[u8]) -> Result<([u8; 16], Vec<JumbfBox<'_>>)> {
+    let mut boxes = parse_boxes(payload)?;
+    if boxes.is_empty() || &boxes[0].0 != b"jumd" || boxes[0].1.len() < 17 {
+        return Err(Error::Validation(
+            "JUMBF superbox is missing its description box".to_string(),
+        ));
+    }
+    let (_, description) = boxes.remove(0);
+    Ok((description[..16].try_into().unwrap(), boxes))
+}
+
+// Find the content of the first child superbox with the given type UUID
+fn find_superbox<'a>(boxes: &[JumbfBox<'a>], uuid: &[u8; 16]) -> Option<&'a [u8]> {
+    boxes
+        .iter()
+        .filter(|(box_type, _)| *box_type == SUPERBOX_TYPE)
+        .map(|(_, payload)| *payload)
+        .rfind(|payload| matches!(parse_superbox(payload), Ok((found, _)) if &found == uuid))
+}
+
+fn is_manifest_store(store: &[u8]) -> bool {
+    match parse_boxes(store).as_deref() {
+        Ok([(SUPERBOX_TYPE, payload)]) => {
+            matches!(parse_superbox(payload), Ok((uuid, _)) if uuid == STORE_UUID)
+        }
+        _ => false,
+    }
+}
+
+fn parse_manifest_store(store: &[u8]) -> Result<Manifest> {
+    let missing = |what: &str| Error::Validation(format!("Manifest store has no {what}"));
+
+    let boxes = parse_boxes(store)?;
+    let store_box = match boxes.as_slice() {
+        [(SUPERBOX_TYPE, payload)] => *payload,
+        _ => return Err(missing("store box")),
+    };
+    let (uuid, manifests) = parse_superbox(store_box)?;
+    if uuid != STORE_UUID {
+        return Err(missing("C2PA store box"));
+    }
+
+    // The last manifest in the store is the active one
+    let manifest_box =
+        find_superbox(&manifests, &MANIFEST_UUID).ok_or_else(|| missing("manifest"))?;
+    let (_, contents) = parse_superbox(manifest_box)?;
+    let content_box =
+        find_superbox(&contents, &JSON_UUID).ok_or_else(|| missing("JSON content"))?;
+    let (_, json) = parse_superbox(content_box)?;
+    let json = json
+        .iter()
+        .find(|(box_type, _)| box_type == b"json")
+        .map(|(_, payload)| *payload)
+        .ok_or_else(|| missing("JSON content"))?;
+
+    serde_json::from_slice(json)
+        .map_err(Error::from)
+        .and_then(version::migrate_manifest)
+        .map_err(|e| Error::Validation(format!("Invalid embedded manifest: {e}")))
+}
+
+fn png_chunks(bytes: &[u8]) -> Result<Vec<(&[u8; 4], std::ops::Range<usize>)>> {
+    let invalid = || Error::Validation("Invalid PNG file".to_string());
+
+    let mut chunks = Vec::new();
+    let mut offset = PNG_SIGNATURE.len();
+    while offset < bytes.len() {
+        let header = bytes.get(offset..offset + 8).ok_or_else(invalid)?;
+        let len = u32::from_be_bytes(header[0..4].try_into().unwrap()) as usize;
+        let chunk_type: &[u8; 4] = header[4..8].try_into().unwrap();
+        // Length, type, data and CRC
+        let end = offset
+            .checked_add(12 + len)
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(invalid)?;
+
+        chunks.push((chunk_type, offset..end));
+        offset = end;
+        if chunk_type == b"IEND" {
+            break;
+        }
+    }
+
+    if chunks.first().map(|(chunk_type, _)| *chunk_type) != Some(b"IHDR") {
+        return Err(invalid());
+    }
+    Ok(chunks)
+}
+
+fn png_strip(bytes: &[u8]) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let mut original = PNG_SIGNATURE.to_vec();
+    let mut store = None;
+    let mut end = PNG_SIGNATURE.len();
+    for (chunk_type, range) in png_chunks(bytes)? {
+        end = range.end;
+        let data = &bytes[range.start + 8..range.end - 4];
+        if chunk_type == PNG_CHUNK_TYPE && is_manifest_store(data) {
+            store = Some(data.to_vec());
+        } else {
+            original.extend_from_slice(&bytes[range]);
+        }
+    }
+    // Keep anything trailing the last chunk
+    original.extend_from_slice(&bytes[end..]);
+    Ok((original, store))
+}
+
+fn png_insert(bytes: &[u8], store: &[u8]) -> Result<Vec<u8>> {
+    let len = u32::try_from(store.len())
+        .map_err(|_| Error::Validation("Manifest is too large to embed".to_string()))?;
+    let ihdr_end = png_chunks(bytes)?[0].1.end;
+
+    let mut chunk = Vec::with_capacity(store.len() + 12);
+    chunk.extend_from_slice(&len.to_be_bytes());
+    chunk.extend_from_slice(PNG_CHUNK_TYPE);
+    chunk.extend_from_slice(store);
+    chunk.extend_from_slice(&crc32(&chunk[4..]).to_be_bytes());
+
+    let mut out = Vec::with_capacity(bytes.len() + chunk.len());
+    out.extend_from_slice(&bytes[..ihdr_end]);
+    out.extend_from_slice(&chunk);
+    out.extend_from_slice(&bytes[ihdr_end..]);
+    Ok(out)
+}
+
+// CRC-32 (ISO 3309) as used by PNG chunks
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+struct JpegSegment {
+    marker: u8,
+    range: std::ops::Range<usize>,
+}
+
+impl JpegSegment {
+    // The segment payload after the marker and length
+    fn payload<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
+        &bytes[self.range.start + 4..self.range.end]
+    }
+
+    // Box instance and sequence number of a JUMBF APP11 segment
+    fn jumbf_packet(&self, bytes: &[u8]) -> Option<(u16, u32)> {
+        let payload = self.payload(bytes);
+        if self.marker != JPEG_APP11 || payload.len() < 8 || &payload[0..2] != b"JP" {
+            return None;
+        }
+        Some((
+            u16::from_be_bytes(payload[2..4].try_into().unwrap()),
+            u32::from_be_bytes(payload[4..8].try_into().unwrap()),
+        ))
+    }
+}
+
+// Parse the marker segments before the start of scan; the returned offset
+// is where the scan (and the rest of the file) begins
+fn jpeg_segments(bytes: &[u8]) -> Result<(Vec<JpegSegment>, usize)> {
+    let invalid = || Error::Validation("Invalid JPEG file".to_string());
+
+    let mut segments = Vec::new();
+    let mut offset = 2;
+    loop {
+        let header = bytes.get(offset..offset + 2).ok_or_else(invalid)?;
+        if header[0] != 0xff {
+            return Err(invalid());
+        }
+        let marker = header[1];
+        // Skip fill bytes before a marker
+        if marker == 0xff {
+            offset += 1;
+            continue;
+        }
+        if marker == JPEG_SOS || marker == JPEG_EOI {
+            return Ok((segments, offset));
+        }
+
+        let len = bytes.get(offset + 2..offset + 4).ok_or_else(invalid)?;
+        let len = u16::from_be_bytes(len.try_into().unwrap()) as usize;
+        let end = offset + 2 + len;
+        if len < 2 || end > bytes.len() {
+            return Err(invalid());
+        }
+
+        segments.push(JpegSegment {
+            marker,
+            range: offset..end,
+        });
+        offset = end;
+    }
+}
+
+fn jpeg_strip(bytes: &[u8]) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let (segments, scan_start) = jpeg_segments(bytes)?;
+
+    // Group JUMBF packets by box instance, in sequence order
+    let mut instances: BTreeMap<u16, Vec<(u32, &[u8])>> = BTreeMap::new();
+    for segment in &segments {
+        if let Some((instance, sequence)) = segment.jumbf_packet(bytes) {
+            let data = &segment.payload(bytes)[8..];
+            instances
+                .entry(instance)
+                .or_default()
+                .push((sequence, data));
+        }
+    }
+
+    let mut store_instance = None;
+    let mut store = None;
+    for (instance, mut packets) in instances {
+        packets.sort_by_key(|(sequence, _)| *sequence);
+
+        // Continuation packets repeat the box header
+        let mut data = Vec::new();
+        for (i, (_, packet)) in packets.iter().enumerate() {
+            let skip = if i == 0 { 0 } else { JUMBF_BOX_HEADER_LEN };
+            data.extend_from_slice(packet.get(skip..).unwrap_or_default());
+        }
+
+        if is_manifest_store(&data) {
+            store_instance = Some(instance);
+            store = Some(data);
+        }
+    }
+
+    let mut original = bytes[..2].to_vec();
+    for segment in &segments {
+        let is_store = store_instance.is_some()
+            && segment.jumbf_packet(bytes).map(|(instance, _)| instance) == store_instance;
+        if !is_store {
+            original.extend_from_slice(&bytes[segment.range.clone()]);
+        }
+    }
+    original.extend_from_slice(&bytes[scan_start..]);
+    Ok((original, store))
+}
+
+fn jpeg_insert(bytes: &[u8], store: &[u8]) -> Result<Vec<u8>> {
+    let (segments, _) = jpeg_segments(bytes)?;
+
+    // Use a box instance number not taken by other JUMBF boxes
+    let instance = segments
+        .iter()
+        .filter_map(|segment| segment.jumbf_packet(bytes))
+        .map(|(instance, _)| instance)
+        .max()
+        .map_or(Ok(1), |max| {
+            max.checked_add(1)
+                .ok_or_else(|| Error::Validation("Too many JUMBF boxes in JPEG".to_string()))
+        })?;
+
+    // Insert after the JFIF/Exif headers, which readers expect first
+    let insert_at = segments
+        .iter()
+        .take_while(|segment| matches!(segment.marker, JPEG_APP0 | JPEG_APP1))
+        .last()
+        .map_or(2, |segment| segment.range.end);
+
+    // Split the store into packets; every packet after the first repeats
+    // the box header
+    let max_data = JPEG_MAX_SEGMENT_LEN - JPEG_APP11_HEADER_LEN;
+    let header = &store[..JUMBF_BOX_HEADER_LEN];
+    let (first, mut rest) = store.split_at(store.len().min(max_data));
+    let mut packets = vec![first];
+    while !rest.is_empty() {
+        let (packet, remaining) = rest.split_at(rest.len().min(max_data - header.len()));
+        packets.push(packet);
+        rest = remaining;
+    }
+
+    let mut app11 = Vec::new();
+    for (i, packet) in packets.into_iter().enumerate() {
+        let prefix = if i == 0 { &[][..] } else { header };
+        let len = JPEG_APP11_HEADER_LEN + prefix.len() + packet.len();
+        app11.extend_from_slice(&[0xff, JPEG_APP11]);
+        app11.extend_from_slice(&(len as u16).to_be_bytes());
+        app11.extend_from_slice(b"JP");
+        app11.extend_from_slice(&instance.to_be_bytes());
+        app11.extend_from_slice(&(i as u32 + 1).to_be_bytes());
+        app11.extend_from_slice(prefix);
+        app11.extend_from_slice(packet);
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() + app11.len());
+    out.extend_from_slice(&bytes[..insert_at]);
+    out.extend_from_slice(&app11);
+    out.extend_from_slice(&bytes[insert_at..]);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+    use atlas_c2pa_lib::claim::ClaimV2;
+    use atlas_c2pa_lib::datetime_wrapper::OffsetDateTimeWrapper;
+    use tempfile::tempdir;
+    use time::OffsetDateTime;
+
+    fn test_manifest(padding: usize) -> Manifest {
+        let claim = ClaimV2 {
+            instance_id: "urn:c2pa:claim".to_string(),
+            ingredients: vec![],
+            created_assertions: vec![Assertion::CustomAssertion(CustomAssertion {
+                label: "test.padding".to_string(),
+                data: serde_json::json!("x".repeat(padding)),
+            })],
+            claim_generator_info: "c2pa-cli".to_string(),
+            signature: None,
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::UNIX_EPOCH),
+        };
+        Manifest {
+            claim_generator: "c2pa-cli/0.1.0".to_string(),
+            title: "Embedded".to_string(),
+            instance_id: "urn:c2pa:embedded".to_string(),
+            ingredients: vec![],
+            claim: claim.clone(),
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::UNIX_EPOCH),
+            cross_references: vec![],
+            claim_v2: Some(claim),
+            is_active: true,
+        }
+    }
+
+    fn png_chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(chunk_type);
+        chunk.extend_from_slice(data);
+        chunk.extend_from_slice(&crc32(&chunk[4..]).to_be_bytes());
+        chunk
+    }
+
+    fn test_png() -> Vec<u8> {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(png_chunk(b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]));
+        png.extend(png_chunk(b"IDAT", b"pixels"));
+        png.extend(png_chunk(b"IEND", b""));
+        png
+    }
+
+    fn test_jpeg() -> Vec<u8> {
+        let mut jpeg = vec![0xff, JPEG_SOI];
+        jpeg.extend([0xff, JPEG_APP0, 0x00, 0x07]);
+        jpeg.extend(b"JFIF\0");
+        jpeg.extend([0xff, 0xdb, 0x00, 0x04, 0x01, 0x02]);
+        jpeg.extend([0xff, JPEG_SOS, 0x00, 0x03, 0x00]);
+        jpeg.extend(b"scan data");
+        jpeg.extend([0xff, JPEG_EOI]);
+        jpeg
+    }
+
+    fn roundtrip(asset: &[u8], manifest: &Manifest, format: EmbedFormat) -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("asset");
+        fs::write(&path, asset)?;
+
+        assert_eq!(embed_manifest(&path, manifest, &path)?, format);
+        let embedded = fs::read(&path)?;
+        assert_ne!(embedded, asset);
+
+        let (extracted, source) = extract_manifest(&path)?;
+        assert_eq!(source, ManifestSource::Embedded(format));
+        assert_eq!(
+            serde_json::to_value(&extracted).unwrap(),
+            serde_json::to_value(manifest).unwrap()
+        );
+
+        // Removing the manifest store restores the original bytes
+        assert_eq!(format.strip(&embedded)?.0, asset);
+
+        // Embedding again replaces the existing manifest
+        embed_manifest(&path, manifest, &path)?;
+        assert_eq!(fs::read(&path)?, embedded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_embed_png_roundtrip() -> Result<()> {
+        roundtrip(&test_png(), &test_manifest(16), EmbedFormat::Png)
+    }
+
+    #[test]
+    fn test_embed_jpeg_roundtrip() -> Result<()> {
+        roundtrip(&test_jpeg(), &test_manifest(16), EmbedFormat::Jpeg)?;
+
+        // Manifests larger than one APP11 segment are split across several
+        roundtrip(&test_jpeg(), &test_manifest(150_000), EmbedFormat::Jpeg)
+    }
+
+    #[test]
+    fn test_embed_unsupported_format_and_sidecar() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("model.onnx");
+        fs::write(&path, b"onnx weights")?;
+        let manifest = test_manifest(16);
+
+        let result = embed_manifest(&path, &manifest, &path);
+        assert!(matches!(result, Err(Error::Validation(msg)) if msg.contains("--sidecar")));
+        assert!(extract_manifest(&path).is_err());
+
+        let sidecar = write_sidecar(&path, &manifest)?;
+        assert_eq!(sidecar, dir.path().join("model.c2pa"));
+        assert_eq!(fs::read(&path)?, b"onnx weights");
+
+        let (extracted, source) = extract_manifest(&path)?;
+        assert_eq!(source, ManifestSource::Sidecar(sidecar));
+        assert_eq!(extracted.instance_id, manifest.instance_id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+//! # Creation Environment
+//!
+//! For reproducibility, a manifest can record the environment it was created
+//! in (`create --record-environment`): the platform reported by
+//! [`get_platform_name`], the operating system, CPU architecture, hostname
+//! and the version of this CLI, in an `atlas.environment` assertion of the
+//! claim. This complements the CC attestation assertion on machines without
+//! confidential computing support.
+//!
+//! Recording is opt-in because the hostname can be sensitive;
+//! `--redact-hostname` leaves it out.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::environment::Environment;
+//!
+//! let environment = Environment::capture(true).unwrap();
+//! assert_eq!(environment.os, std::env::consts::OS);
+//! assert!(environment.hostname.is_none());
+//! ```
+
+use crate::cc_attestation::get_platform_name;
+use crate::error::{Error, Result};
+use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+use atlas_c2pa_lib::manifest::Manifest;
+use serde::{Deserialize, Serialize};
+
+/// Label of the assertion recording the creation environment
+pub const ENVIRONMENT_LABEL: &str = "atlas.environment";
+
+/// The environment a manifest was created in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    /// Platform name, e.g. `linux`, or `tdx-linux` in a TDX guest
+    pub platform: String,
+    pub os: String,
+    pub arch: String,
+    /// Left out when redacted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    pub cli_version: String,
+}
+
+impl Environment {
+    /// Describes the current environment, without the hostname if
+    /// `redact_hostname` is set
+    pub fn capture(redact_hostname: bool) -> Result<Self> {
+        Ok(Self {
+            platform: get_platform_name()?,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            hostname: (!redact_hostname)
+                .then(|| gethostname::gethostname().to_string_lossy().into_owned()),
+            cli_version: env!("CARGO_PKG_VERSION").to_string(),
+        })
+    }
+
+    /// The assertion recording this environment
+    pub fn to_assertion(&self) -> Result<Assertion> {
+        Ok(Assertion::CustomAssertion(CustomAssertion {
+            label: ENVIRONMENT_LABEL.to_string(),
+            data: serde_json::to_value(self).map_err(|e| Error::Serialization(e.to_string()))?,
+        }))
+    }
+
+    /// The environment recorded in a manifest's claim, if any
+    pub fn from_manifest(manifest: &Manifest) -> Result<Option<Self>> {
+        let assertions = match &manifest.claim_v2 {
+            Some(claim) => &claim.created_assertions,
+            None => &manifest.claim.created_assertions,
+        };
+        assertions
+            .iter()
+            .find_map(|assertion| match assertion {
+                Assertion::CustomAssertion(custom) if custom.label == ENVIRONMENT_LABEL => {
+                    Some(&custom.data)
+                }
+                _ => None,
+            })
+            .map(|data| {
+                serde_json::from_value(data.clone()).map_err(|e| {
+                    Error::Validation(format!("Invalid {ENVIRONMENT_LABEL} assertion: {e}"))
+                })
+            })
+            .transpose()
+    }
+}
@@ -1,10 +1,11 @@
 use crate::error::{Error, Result};
-use crate::manifest::common::{self, AssetKind, list_manifests, verify_manifest};
-use crate::manifest::config::ManifestCreationConfig;
+use crate::manifest::common::{self, AssetKind, list_manifests, verify_manifest_with_config};
+use crate::manifest::config::{ManifestCreationConfig, VerificationConfig};
+use crate::manifest::report::{VerificationCheck, VerificationReport};
 use crate::manifest::{determine_manifest_type, manifest_type_to_str};
-use crate::storage::traits::StorageBackend;
+use crate::storage::traits::{ManifestFilter, StorageBackend};
 use atlas_c2pa_lib::assertion::Assertion;
-use std::collections::HashMap;
+use serde_json::{Map, Value};
 
 /// Create a new evaluation result manifest using the standard configuration
 pub fn create_manifest(
@@ -13,18 +14,7 @@ pub fn create_manifest(
     dataset_id: String,
     metrics: Vec<String>,
 ) -> Result<()> {
-    // Parse metrics into a map
-    let mut metrics_map = HashMap::new();
-    for metric in metrics {
-        let parts: Vec<&str> = metric.split('=').collect();
-        if parts.len() == 2 {
-            metrics_map.insert(parts[0].to_string(), parts[1].to_string());
-        } else {
-            return Err(Error::Validation(format!(
-                "Invalid metric format: {metric}. Expected format: key=value"
-            )));
-        }
-    }
+    let metrics_map = parse_metrics(&metrics)?;
 
     // Add evaluation-specific custom_fields to the config
     let eval_params = serde_json::json!({
@@ -61,15 +51,75 @@ pub fn create_manifest(
     common::create_manifest(config, AssetKind::Evaluation)
 }
 
+/// Parses `--metrics` values into a JSON object.
+///
+/// Each entry is either `key=value` or `@file.json`. Values that are valid JSON
+/// numbers, booleans, arrays or objects keep their type and anything else is
+/// stored as a string. A `@file.json` entry must contain a JSON object whose
+/// entries are merged in; later entries override earlier ones.
+pub fn parse_metrics(metrics: &[String]) -> Result<Map<String, Value>> {
+    let mut metrics_map = Map::new();
+    for metric in metrics {
+        if let Some(path) = metric.strip_prefix('@') {
+            let contents = std::fs::read_to_string(path)?;
+            match serde_json::from_str(&contents) {
+                Ok(Value::Object(entries)) => metrics_map.extend(entries),
+                Ok(_) => {
+                    return Err(Error::Validation(format!(
+                        "Metrics file {path} must contain a JSON object"
+                    )));
+                }
+                Err(e) => {
+                    return Err(Error::Validation(format!(
+                        "Invalid metrics file {path}: {e}"
+                    )));
+                }
+            }
+            continue;
+        }
+
+        match metric.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                let value = match serde_json::from_str(value) {
+                    Ok(Value::Null) | Err(_) => Value::String(value.to_string()),
+                    Ok(parsed) => parsed,
+                };
+                metrics_map.insert(key.to_string(), value);
+            }
+            _ => {
+                return Err(Error::Validation(format!(
+                    "Invalid metric format: {metric}. Expected format: key=value or @file.json"
+                )));
+            }
+        }
+    }
+    Ok(metrics_map)
+}
+
 /// List evaluation manifests from storage
-pub fn list_evaluation_manifests(storage: &dyn StorageBackend) -> Result<()> {
-    list_manifests(storage, Some(AssetKind::Evaluation))
+pub fn list_evaluation_manifests(
+    storage: &dyn StorageBackend,
+    filter: &ManifestFilter,
+) -> Result<()> {
+    list_manifests(storage, Some(AssetKind::Evaluation), filter)
 }
 
 /// Verify an evaluation manifest
-pub fn verify_evaluation_manifest(id: &str, storage: &dyn StorageBackend) -> Result<()> {
+pub fn verify_evaluation_manifest(
+    id: &str,
+    storage: &dyn StorageBackend,
+) -> Result<VerificationReport> {
+    verify_evaluation_manifest_with_config(id, storage, &VerificationConfig::default())
+}
+
+/// Verify an evaluation manifest using the given verification options
+pub fn verify_evaluation_manifest_with_config(
+    id: &str,
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+) -> Result<VerificationReport> {
     // Use the common verification function first
-    verify_manifest(id, storage)?;
+    let mut report = verify_manifest_with_config(id, storage, config)?;
 
     // Additional verification specific to evaluation manifests
     let manifest = storage.retrieve_manifest(id)?;
@@ -114,8 +164,11 @@ pub fn verify_evaluation_manifest(id: &str, storage: &dyn StorageBackend) -> Res
         ));
     }
 
-    println!("✓ Evaluation manifest verification successful");
-    Ok(())
+    report.asset_checks.push(VerificationCheck::verified(
+        "EvaluationResult",
+        "references a model and a dataset",
+    ));
+    Ok(report)
 }
 
 /// Check if a manifest is an evaluation result manifest
@@ -128,3 +181,65 @@ fn is_evaluation_manifest(manifest: &atlas_c2pa_lib::manifest::Manifest) -> bool
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_metrics_keeps_types() -> Result<()> {
+        let dir = tempdir()?;
+        let file = dir.path().join("metrics.json");
+        std::fs::write(
+            &file,
+            r#"{"per_class_f1": [0.91, 0.87], "confusion": {"tp": 90, "fp": 4}, "accuracy": 0.95}"#,
+        )?;
+
+        let metrics = parse_metrics(&[
+            "accuracy=0.93".to_string(),
+            "epochs=10".to_string(),
+            "converged=true".to_string(),
+            "split=test".to_string(),
+            "query=a=b".to_string(),
+            format!("@{}", file.display()),
+        ])?;
+
+        assert_eq!(
+            Value::Object(metrics),
+            json!({
+                "accuracy": 0.95,
+                "epochs": 10,
+                "converged": true,
+                "split": "test",
+                "query": "a=b",
+                "per_class_f1": [0.91, 0.87],
+                "confusion": {"tp": 90, "fp": 4}
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_metrics_rejects_invalid_entries() -> Result<()> {
+        let dir = tempdir()?;
+        let file = dir.path().join("metrics.json");
+        std::fs::write(&file, "[0.9]")?;
+
+        for metric in [
+            "accuracy".to_string(),
+            "=0.9".to_string(),
+            format!("@{}", file.display()),
+        ] {
+            assert!(
+                matches!(
+                    parse_metrics(std::slice::from_ref(&metric)),
+                    Err(Error::Validation(_))
+                ),
+                "{metric} should be rejected"
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! # Model Lineage
+//!
+//! A derived model (e.g. a fine-tune) records the manifests it derives from
+//! in an `atlas.lineage` assertion of its claim, and cross-references each of
+//! them so verification checks their hashes. [`build_lineage`] follows these
+//! links through a storage backend to answer "what was this model derived
+//! from?", all the way back to the original base model.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::lineage::{LineageNode, LineageStatus, format_lineage};
+//!
+//! let tree = LineageNode {
+//!     id: "urn:c2pa:tuned".to_string(),
+//!     title: "Tuned".to_string(),
+//!     status: LineageStatus::Found,
+//!     parents: vec![LineageNode {
+//!         id: "urn:c2pa:base".to_string(),
+//!         title: "Base".to_string(),
+//!         status: LineageStatus::Found,
+//!         parents: vec![],
+//!     }],
+//! };
+//! assert_eq!(
+//!     format_lineage(&tree),
+//!     "urn:c2pa:tuned (Tuned)\n└── urn:c2pa:base (Base)\n"
+//! );
+//! ```
+
+use crate::error::{Error, Result};
+use crate::storage::traits::StorageBackend;
+use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+use atlas_c2pa_lib::manifest::Manifest;
+use serde::Serialize;
+use serde_json::json;
+
+/// Label of the assertion listing the manifests a manifest derives from
+pub const LINEAGE_LABEL: &str = "atlas.lineage";
+
+/// Relationship recorded for each base manifest
+pub const DERIVED_FROM_RELATIONSHIP: &str = "parentOf";
+
+/// The lineage assertion recording the given base manifests
+pub fn lineage_assertion(base_ids: &[String]) -> Assertion {
+    let parents: Vec<_> = base_ids
+        .iter()
+        .map(|id| json!({ "manifest_id": id, "relationship": DERIVED_FROM_RELATIONSHIP }))
+        .collect();
+    Assertion::CustomAssertion(CustomAssertion {
+        label: LINEAGE_LABEL.to_string(),
+        data: json!({ "parents": parents }),
+    })
+}
+
+/// IDs of the manifests a manifest records as its bases
+pub fn parent_ids(manifest: &Manifest) -> Vec<String> {
+    let assertions = match &manifest.claim_v2 {
+        Some(claim) => &claim.created_assertions,
+        None => &manifest.claim.created_assertions,
+    };
+    assertions
+        .iter()
+        .filter_map(|assertion| match assertion {
+            Assertion::CustomAssertion(custom) if custom.label == LINEAGE_LABEL => {
+                custom.data.get("parents")?.as_array()
+            }
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|parent| parent.get("manifest_id")?.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Whether an ancestor could be followed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineageStatus {
+    Found,
+    /// The manifest is not in the storage backend
+    Missing,
+    /// The manifest is one of its own descendants; its parents are not repeated
+    Cycle,
+}
+
+/// A manifest and the manifests it derives from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LineageNode {
+    pub id: String,
+    /// Manifest title; empty if the manifest is missing
+    pub title: String,
+    pub status: LineageStatus,
+    pub parents: Vec<LineageNode>,
+}
+
+/// Follows lineage assertions from a manifest back to its earliest bases.
+///
+/// Missing ancestors and cycles are recorded in the tree rather than failing,
+/// so the rest of the ancestry is still shown; only a missing root is an
+/// error.
+pub fn build_lineage(id: &str, storage: &(impl StorageBackend + ?Sized)) -> Result<LineageNode> {
+    let root = storage.retrieve_manifest(id).map_err(|e| {
+        Error::Manifest(format!(
+            "Failed to retrieve manifest {id} for its lineage: {e}"
+        ))
+    })?;
+    let mut path = vec![id.to_string()];
+    Ok(lineage_node(id, &root, storage, &mut path))
+}
+
+fn lineage_node(
+    id: &str,
+    manifest: &Manifest,
+    storage: &(impl StorageBackend + ?Sized),
+    path: &mut Vec<String>,
+) -> LineageNode {
+    let mut parents = Vec::new();
+    for parent_id in parent_ids(manifest) {
+        if path.contains(&parent_id) {
+            parents.push(LineageNode {
+                title: String::new(),
+                status: LineageStatus::Cycle,
+                parents: Vec::new(),
+                id: parent_id,
+            });
+            continue;
+        }
+        match storage.retrieve_manifest(&parent_id) {
+            Ok(parent) => {
+                path.push(parent_id.clone());
+                parents.push(lineage_node(&parent_id, &parent, storage, path));
+                path.pop();
+            }
+            Err(e) => {
+                log::warn!("Could not retrieve base manifest {parent_id}: {e}");
+                parents.push(LineageNode {
+                    title: String::new(),
+                    status: LineageStatus::Missing,
+                    parents: Vec::new(),
+                    id: parent_id,
+                });
+            }
+        }
+    }
+
+    LineageNode {
+        id: id.to_string(),
+        title: manifest.title.clone(),
+        status: LineageStatus::Found,
+        parents,
+    }
+}
+
+/// Renders a lineage tree with one manifest per line, bases indented below
+/// the manifests derived from them
+pub fn format_lineage(node: &LineageNode) -> String {
+    let mut out = format!("{}\n", node_label(node));
+    format_parents(&node.parents, "", &mut out);
+    out
+}
+
+fn format_parents(parents: &[LineageNode], prefix: &str, out: &mut String) {
+    for (index, parent) in parents.iter().enumerate() {
+        let last = index + 1 == parents.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        out.push_str(&format!("{prefix}{branch}{}\n", node_label(parent)));
+        format_parents(&parent.parents, &format!("{prefix}{indent}"), out);
+    }
+}
+
+fn node_label(node: &LineageNode) -> String {
+    match node.status {
+        LineageStatus::Found => format!("{} ({})", node.id, node.title),
+        LineageStatus::Missing => format!("{} [missing]", node.id),
+        LineageStatus::Cycle => format!("{} [cycle]", node.id),
+    }
+}
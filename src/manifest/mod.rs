@@ -1,33 +1,63 @@
 use crate::error::{Error, Result};
+use crate::manifest::canonical::CrossReferenceHashScheme;
+use crate::signing::trust::TrustList;
 use crate::storage::traits::StorageBackend;
-use atlas_c2pa_lib::cose::HashAlgorithm;
+use crate::utils::{safe_create_file, safe_open_file};
 use atlas_c2pa_lib::cross_reference::CrossReference;
+use atlas_c2pa_lib::manifest::Manifest;
 use serde::{Deserialize, Serialize};
-use sha2::Digest;
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::Write;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
 use uuid::Uuid;
+pub mod batch;
+pub mod canonical;
+pub mod checksums;
 pub mod common;
 pub mod config;
 pub mod dataset;
+pub mod diff;
+pub mod embed;
+pub mod environment;
 pub mod evaluation;
+pub mod lineage;
 pub mod model;
+pub mod model_card;
+pub mod policy;
+pub mod refresh;
+pub mod report;
+pub mod schema;
+pub mod signatures;
 pub mod software;
+pub mod training;
 pub mod utils;
+pub mod validity;
+pub mod version;
+pub mod watch;
 pub use dataset::create_manifest as create_dataset_manifest;
 pub use dataset::list_dataset_manifests as list_dataset_manifest;
-pub use dataset::verify_dataset_manifest;
+pub use dataset::{verify_dataset_manifest, verify_dataset_manifest_with_config};
 
 pub use model::create_manifest as create_model_manifest;
 pub use model::list_model_manifests as list_model_manifest;
-pub use model::verify_model_manifest;
+pub use model::{verify_model_manifest, verify_model_manifest_with_config};
 
 pub use software::create_manifest as create_software_manifest;
 pub use software::list_software_manifests;
-pub use software::verify_software_manifest;
+pub use software::{verify_software_manifest, verify_software_manifest_with_config};
 
 pub use evaluation::create_manifest as create_evaluation_manifest;
+pub use evaluation::{verify_evaluation_manifest, verify_evaluation_manifest_with_config};
+
+pub use common::{
+    AssetKind, VerifyAllSummary, build_manifest, verify_all, verify_all_with_config,
+    verify_manifest, verify_manifest_value, verify_manifest_value_with_config,
+    verify_manifest_with_config,
+};
+pub use config::{ManifestCreationConfig, VerificationConfig};
+pub use embed::verify_embedded_manifest;
+pub use report::{CheckStatus, VerificationCheck, VerificationReport};
 
 pub use utils::{
     determine_manifest_type, manifest_type_to_str, manifest_type_to_string, parse_manifest_type,
@@ -94,23 +124,8 @@ pub fn link_manifests(
         }
     };
 
-    // Detect the hash algorithm used in the source manifest
-    let algorithm = if let Some(first_ingredient) = source_manifest.ingredients.first() {
-        match first_ingredient.data.alg.as_str() {
-            "sha256" => HashAlgorithm::Sha256,
-            "sha384" => HashAlgorithm::Sha384,
-            "sha512" => HashAlgorithm::Sha512,
-            _ => HashAlgorithm::Sha256, // Default
-        }
-    } else {
-        // If no ingredients, check if source manifest has any cross-references
-        if let Some(first_cross_ref) = source_manifest.cross_references.first() {
-            // Detect algorithm from existing cross-reference hash length
-            crate::hash::detect_hash_algorithm(&first_cross_ref.manifest_hash) // This already returns HashAlgorithm
-        } else {
-            HashAlgorithm::Sha256 // Default if no ingredients or cross-references
-        }
-    };
+    // Hash the target the same way create_manifest and verification do
+    let target_hash = canonical::cross_reference_hash(&target_manifest, target_id)?;
 
     // Check if a cross-reference to this target already exists
     let duplicate_ref = source_manifest
@@ -119,52 +134,45 @@ pub fn link_manifests(
         .find(|cr| cr.manifest_url == target_id);
 
     if let Some(existing_ref) = duplicate_ref {
-        println!("Warning: A cross-reference to {target_id} already exists");
-
-        // Check if hash matches (if it doesn't, this could indicate a conflict)
-        let target_json = serde_json::to_string(&target_manifest)
-            .map_err(|e| Error::Serialization(e.to_string()))?;
-        let target_hash =
-            crate::hash::calculate_hash_with_algorithm(target_json.as_bytes(), &algorithm);
-
-        if existing_ref.manifest_hash != target_hash {
-            // Handle conflict by creating a versioned reference
-            println!("Manifest hash conflict detected, creating versioned reference");
-            return create_versioned_link(
-                source_manifest,
-                target_manifest,
-                source_id,
-                target_id,
-                storage,
-                &algorithm,
-            );
-        } else {
-            println!("Existing cross-reference is identical, no changes needed");
-            return Ok(());
+        if canonical::match_cross_reference_hash(
+            &target_manifest,
+            target_id,
+            &existing_ref.manifest_hash,
+        )?
+        .is_some()
+        {
+            return Err(Error::Validation(format!(
+                "Manifest {source_id} already links to {target_id}"
+            )));
         }
-    }
 
-    // Create a hash of the target manifest using the detected algorithm
-    let target_json =
-        serde_json::to_string(&target_manifest).map_err(|e| Error::Serialization(e.to_string()))?;
-    let target_hash =
-        crate::hash::calculate_hash_with_algorithm(target_json.as_bytes(), &algorithm);
-
-    // Convert IDs to proper C2PA URNs if they're not already
-    let target_urn = ensure_c2pa_urn(target_id);
+        // The target changed since it was linked; keep both versions
+        log::info!("Manifest hash conflict detected, creating versioned reference");
+        return create_versioned_link(
+            source_manifest,
+            target_manifest,
+            source_id,
+            target_id,
+            target_hash,
+            storage,
+        );
+    }
 
     // Create a cross-reference from source to target
-    let cross_reference = CrossReference::new(target_urn, target_hash);
+    let cross_reference = CrossReference {
+        manifest_url: target_id.to_string(),
+        manifest_hash: target_hash,
+        media_type: Some("application/json".to_string()),
+    };
 
     // Add the cross-reference to the source manifest
     source_manifest.cross_references.push(cross_reference);
 
     // Update the source manifest in storage
-    let updated_id = storage.store_manifest(&source_manifest)?;
+    let updated_id = storage.update_manifest(&source_manifest)?;
 
     println!("Successfully linked manifest {source_id} to {target_id}");
     println!("Updated manifest ID: {updated_id}");
-    println!("Using hash algorithm: {}", algorithm.as_str());
 
     Ok(())
 }
@@ -175,8 +183,8 @@ fn create_versioned_link(
     target_manifest: atlas_c2pa_lib::manifest::Manifest,
     source_id: &str,
     target_id: &str,
+    target_hash: String,
     storage: &(impl StorageBackend + ?Sized),
-    algorithm: &HashAlgorithm,
 ) -> Result<()> {
     // Generate a versioned ID following C2PA spec section 8.2
     // Format: original_urn:claim_generator:version_reason
@@ -201,14 +209,12 @@ fn create_versioned_link(
             .starts_with(&format!("urn:c2pa:{uuid_part}:"))
         {
             let parts: Vec<&str> = cr.manifest_url.split(':').collect();
-            if parts.len() >= 5 {
-                if let Some(version_reason) = parts.get(4) {
-                    if let Some(version_str) = version_reason.split('_').next() {
-                        if let Ok(version) = version_str.parse::<i32>() {
-                            max_version = max_version.max(version);
-                        }
-                    }
-                }
+            if parts.len() >= 5
+                && let Some(version_reason) = parts.get(4)
+                && let Some(version_str) = version_reason.split('_').next()
+                && let Ok(version) = version_str.parse::<i32>()
+            {
+                max_version = max_version.max(version);
             }
         }
     }
@@ -223,11 +229,6 @@ fn create_versioned_link(
         1
     );
 
-    // Create a hash of the target manifest using the specified algorithm
-    let target_json =
-        serde_json::to_string(&target_manifest).map_err(|e| Error::Serialization(e.to_string()))?;
-    let target_hash = crate::hash::calculate_hash_with_algorithm(target_json.as_bytes(), algorithm);
-
     // Create a cross-reference with the versioned ID
     let cross_reference = CrossReference::new(versioned_id.clone(), target_hash);
 
@@ -235,23 +236,48 @@ fn create_versioned_link(
     source_manifest.cross_references.push(cross_reference);
 
     // Update the source manifest in storage
-    let updated_id = storage.store_manifest(&source_manifest)?;
+    let updated_id = storage.update_manifest(&source_manifest)?;
 
     println!(
         "Successfully linked manifest {source_id} to {target_id} (versioned as {versioned_id})"
     );
     println!("Updated manifest ID: {updated_id}");
-    println!("Using hash algorithm: {}", algorithm.as_str());
 
     Ok(())
 }
 
 pub fn show_manifest(id: &str, storage: &(impl StorageBackend + ?Sized)) -> Result<()> {
+    show_manifest_with_format(id, storage, "text")
+}
+
+/// Show a manifest in the given format (`text`, `json`, `cbor` or `yaml`)
+pub fn show_manifest_with_format(
+    id: &str,
+    storage: &(impl StorageBackend + ?Sized),
+    format: &str,
+) -> Result<()> {
     let manifest = storage.retrieve_manifest(id)?;
 
+    match format.to_lowercase().as_str() {
+        "text" => print_manifest_details(&manifest),
+        "json" | "cbor" | "yaml" => {
+            println!("{}", common::serialize_manifest(&manifest, format)?);
+            Ok(())
+        }
+        _ => Err(Error::Validation(format!(
+            "Invalid output format '{format}'. Valid options are: text, json, cbor, yaml"
+        ))),
+    }
+}
+
+fn print_manifest_details(manifest: &atlas_c2pa_lib::manifest::Manifest) -> Result<()> {
     println!("============ Manifest Details ============");
     println!("ID: {}", manifest.instance_id);
     println!("Title: {}", manifest.title);
+    println!(
+        "Type: {}",
+        manifest_type_to_str(&determine_manifest_type(manifest))
+    );
     println!("Created: {}", manifest.created_at.0);
     println!("Claim Generator: {}", manifest.claim_generator);
     println!("Active: {}", manifest.is_active);
@@ -303,7 +329,34 @@ pub fn show_manifest(id: &str, storage: &(impl StorageBackend + ?Sized)) -> Resu
                     }
                 }
             }
-            _ => println!("  Unknown assertion type"),
+            atlas_c2pa_lib::assertion::Assertion::CustomAssertion(custom) => {
+                println!("  Type: Custom");
+                println!("  Label: {}", custom.label);
+                println!(
+                    "  Data: {}",
+                    serde_json::to_string_pretty(&custom.data)
+                        .unwrap_or_else(|_| format!("{:?}", custom.data))
+                );
+            }
+            atlas_c2pa_lib::assertion::Assertion::Ingredient(ingredient) => {
+                println!("  Type: Ingredient");
+                println!("  Title: {}", ingredient.title);
+                println!("  Relationship: {}", ingredient.relationship);
+            }
+            atlas_c2pa_lib::assertion::Assertion::Hash(hash) => {
+                println!("  Type: Hash");
+                println!("  Algorithm: {}", hash.algorithm);
+                println!("  Hash: {}", hex::encode(&hash.hash_value));
+            }
+            atlas_c2pa_lib::assertion::Assertion::Metadata(metadata) => {
+                println!("  Type: Metadata");
+                println!("  Metadata Type: {}", metadata.metadata_type);
+            }
+            atlas_c2pa_lib::assertion::Assertion::DoNotTrain(do_not_train) => {
+                println!("  Type: DoNotTrain");
+                println!("  Reason: {}", do_not_train.reason);
+                println!("  Enforced: {}", do_not_train.enforced);
+            }
         }
     }
 
@@ -388,7 +441,7 @@ pub mod linking {
         }
 
         // Store updated model manifest
-        storage.store_manifest(&model_manifest)?;
+        storage.update_manifest(&model_manifest)?;
 
         Ok(model_manifest)
     }
@@ -416,100 +469,88 @@ pub mod linking {
     }
 }
 
+/// Validates every cross-reference of a stored manifest: the format of its
+/// recorded hash, that the target can be retrieved and hashes to it, and
+/// the target's structure.
+///
+/// Returns one check per cross-reference, a warning for a legacy
+/// non-canonical hash. Failures are collected and returned together as a
+/// [`Error::Validation`] listing each of them.
 pub fn validate_linked_manifests(
     manifest_id: &str,
     storage: &(impl StorageBackend + ?Sized),
-) -> Result<()> {
+) -> Result<Vec<VerificationCheck>> {
     let manifest = storage.retrieve_manifest(manifest_id)?;
 
-    println!("Validating cross-references for manifest: {manifest_id}");
-
-    if manifest.cross_references.is_empty() {
-        println!("No cross-references found in manifest");
-        return Ok(());
-    }
-
-    println!("Found {} cross-references", manifest.cross_references.len());
+    log::info!(
+        "Validating {} cross-references for manifest: {manifest_id}",
+        manifest.cross_references.len()
+    );
 
+    let mut checks = Vec::new();
     let mut validation_errors = Vec::new();
 
-    for (index, cross_ref) in manifest.cross_references.iter().enumerate() {
-        println!(
-            "\nValidating cross-reference #{}: {}",
-            index + 1,
-            cross_ref.manifest_url
-        );
-
-        // Validate the hash format first
-        if let Err(hash_err) = validate_hash_format(&cross_ref.manifest_hash) {
-            let error = format!("Invalid hash format: {hash_err}");
-            validation_errors.push(error.clone());
-            println!("  ❌ {error}");
-            continue;
-        }
-
-        // Try to retrieve the referenced manifest
-        match storage.retrieve_manifest(&cross_ref.manifest_url) {
-            Ok(referenced_manifest) => {
-                // Calculate hash of the referenced manifest
-                let ref_json = match serde_json::to_string(&referenced_manifest) {
-                    Ok(json) => json,
-                    Err(e) => {
-                        let error = format!("Failed to serialize referenced manifest: {e}");
-                        validation_errors.push(error.clone());
-                        println!("  ❌ {error}");
-                        continue;
-                    }
-                };
-
-                let calculated_hash = hex::encode(sha2::Sha256::digest(ref_json.as_bytes()));
-
-                // Compare calculated hash with stored hash
-                if calculated_hash == cross_ref.manifest_hash {
-                    println!("  ✓ Hash verification successful");
-                } else {
-                    let error = format!(
-                        "Hash mismatch for manifest {}: stored={}, calculated={}",
-                        cross_ref.manifest_url, cross_ref.manifest_hash, calculated_hash
-                    );
-                    validation_errors.push(error.clone());
-                    println!("  ❌ {error}");
-                }
-
-                // Check manifest structure
-                match atlas_c2pa_lib::manifest::validate_manifest(&referenced_manifest) {
-                    Ok(_) => println!("  ✓ Manifest structure validation successful"),
-                    Err(e) => {
-                        let error = format!("Manifest structure validation failed: {e}");
-                        validation_errors.push(error.clone());
-                        println!("  ❌ {error}");
-                    }
-                }
+    for cross_ref in &manifest.cross_references {
+        let url = &cross_ref.manifest_url;
+        match validate_cross_reference(storage, cross_ref) {
+            Ok(check) => {
+                log::info!("Cross-reference to {url} validated: {}", check.message);
+                checks.push(check);
             }
-            Err(e) => {
-                let error = format!("Failed to retrieve referenced manifest: {e}");
-                validation_errors.push(error.clone());
-                println!("  ❌ {error}");
+            Err(error) => {
+                log::warn!("Cross-reference to {url} failed validation: {error}");
+                validation_errors.push(format!("{url}: {error}"));
             }
         }
     }
 
-    // Summarize validation results
-    if validation_errors.is_empty() {
-        println!("\nAll cross-references validated successfully");
-        Ok(())
-    } else {
-        println!(
-            "\nValidation failed with {} errors:",
-            validation_errors.len()
-        );
-        for (i, error) in validation_errors.iter().enumerate() {
-            println!("  {}. {}", i + 1, error);
-        }
-        Err(Error::Validation(
-            "Cross-reference validation failed".to_string(),
-        ))
+    if !validation_errors.is_empty() {
+        return Err(Error::Validation(format!(
+            "{} of {} cross-references failed validation:\n  - {}",
+            validation_errors.len(),
+            manifest.cross_references.len(),
+            validation_errors.join("\n  - ")
+        )));
     }
+    Ok(checks)
+}
+
+/// Validates one cross-reference, returning the failure as a message
+fn validate_cross_reference(
+    storage: &(impl StorageBackend + ?Sized),
+    cross_ref: &CrossReference,
+) -> std::result::Result<VerificationCheck, String> {
+    let url = &cross_ref.manifest_url;
+    validate_hash_format(&cross_ref.manifest_hash)
+        .map_err(|e| format!("Invalid hash format: {e}"))?;
+
+    let referenced_manifest = common::retrieve_cross_reference_target(storage, url)
+        .map_err(|e| format!("Failed to retrieve referenced manifest: {e}"))?;
+
+    let scheme =
+        canonical::match_cross_reference_hash(&referenced_manifest, url, &cross_ref.manifest_hash)
+            .map_err(|e| e.to_string())?;
+    let check = match scheme {
+        Some(CrossReferenceHashScheme::Canonical) => {
+            VerificationCheck::verified(url, "hash matches")
+        }
+        Some(CrossReferenceHashScheme::Legacy) => VerificationCheck::warning(
+            url,
+            "hash matches the legacy non-canonical JSON of the linked manifest; link it again to record a canonical hash",
+        ),
+        None => {
+            let calculated = canonical::cross_reference_hash(&referenced_manifest, url)
+                .map_err(|e| e.to_string())?;
+            return Err(format!(
+                "Hash mismatch: stored={}, calculated={calculated}",
+                cross_ref.manifest_hash
+            ));
+        }
+    };
+
+    common::validate_manifest_structure(&referenced_manifest)
+        .map_err(|e| format!("Manifest structure validation failed: {e}"))?;
+    Ok(check)
 }
 
 /// Validate hash format according to C2PA spec (64-character hex string for SHA-256)
@@ -548,16 +589,23 @@ pub fn verify_manifest_link(
     match cross_ref {
         Some(reference) => {
             // Target reference found, verify hash
-            let target_manifest = storage.retrieve_manifest(target_id)?;
-            let target_json = serde_json::to_string(&target_manifest)
-                .map_err(|e| Error::Serialization(e.to_string()))?;
-            let calculated_hash = hex::encode(sha2::Sha256::digest(target_json.as_bytes()));
-
-            if calculated_hash == reference.manifest_hash {
-                println!("Manifest link verified: {source_id} -> {target_id}");
-                println!("Hash verification successful");
+            let target_manifest = common::retrieve_cross_reference_target(storage, target_id)?;
+            let scheme = canonical::match_cross_reference_hash(
+                &target_manifest,
+                target_id,
+                &reference.manifest_hash,
+            )?;
+            if let Some(scheme) = scheme {
+                if scheme == CrossReferenceHashScheme::Legacy {
+                    log::warn!(
+                        "Link from {source_id} to {target_id} uses a legacy non-canonical hash; link it again to record a canonical hash"
+                    );
+                }
+                log::info!("Manifest link verified: {source_id} -> {target_id}");
+                log::info!("Hash verification successful");
                 Ok(true)
             } else {
+                let calculated_hash = canonical::cross_reference_hash(&target_manifest, target_id)?;
                 println!("Hash mismatch for linked manifest: {target_id}");
                 println!("  Stored hash:     {}", reference.manifest_hash);
                 println!("  Calculated hash: {calculated_hash}");
@@ -836,6 +884,156 @@ pub fn export_provenance(
 
     Ok(())
 }
+
+/// Index file written alongside the manifests of an exported bundle
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleIndex {
+    pub root_id: String,
+    /// Maps each manifest ID to its file name within the bundle directory
+    pub manifests: BTreeMap<String, String>,
+}
+
+/// Name of the index file written by `export_bundle`
+pub const BUNDLE_INDEX_FILE: &str = "index.json";
+
+/// Export a manifest together with every manifest it transitively links to.
+///
+/// Starting from the root manifest, `cross_references` are followed through
+/// the storage backend and each manifest is written as JSON into `out_dir`,
+/// along with an `index.json` mapping manifest IDs to file names. Manifests
+/// that were already exported are skipped, so reference cycles terminate.
+/// IDs that sanitize to the same file name get a numeric suffix.
+pub fn export_bundle(
+    id: &str,
+    storage: &(impl StorageBackend + ?Sized),
+    out_dir: &Path,
+) -> Result<BundleIndex> {
+    fs::create_dir_all(out_dir)?;
+
+    let mut index = BundleIndex {
+        root_id: id.to_string(),
+        manifests: BTreeMap::new(),
+    };
+    let mut visited = HashSet::new();
+    let mut used_names = HashSet::from([BUNDLE_INDEX_FILE.to_string()]);
+    let mut queue = VecDeque::from([id.to_string()]);
+
+    while let Some(current_id) = queue.pop_front() {
+        if !visited.insert(current_id.clone()) {
+            continue;
+        }
+
+        let manifest = storage.retrieve_manifest(&current_id).map_err(|e| {
+            Error::Manifest(format!("Failed to retrieve manifest {current_id}: {e}"))
+        })?;
+
+        let file_name = bundle_file_name(&current_id, &mut used_names);
+        let manifest_json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| Error::Serialization(format!("Failed to serialize to JSON: {e}")))?;
+        let mut file = safe_create_file(&out_dir.join(&file_name), false)?;
+        file.write_all(manifest_json.as_bytes())?;
+
+        index.manifests.insert(current_id, file_name);
+
+        for cross_ref in &manifest.cross_references {
+            if !visited.contains(&cross_ref.manifest_url) {
+                queue.push_back(cross_ref.manifest_url.clone());
+            }
+        }
+    }
+
+    let index_json = serde_json::to_string_pretty(&index)
+        .map_err(|e| Error::Serialization(format!("Failed to serialize to JSON: {e}")))?;
+    let mut index_file = safe_create_file(&out_dir.join(BUNDLE_INDEX_FILE), false)?;
+    index_file.write_all(index_json.as_bytes())?;
+
+    println!(
+        "Exported {} manifest(s) to bundle: {}",
+        index.manifests.len(),
+        out_dir.display()
+    );
+
+    Ok(index)
+}
+
+/// Read a manifest from a JSON or CBOR file. Without a `format`, files with a
+/// `.cbor` extension are read as CBOR and everything else as JSON.
+pub fn read_manifest_file(path: &Path, format: Option<&str>) -> Result<Manifest> {
+    let format = match format {
+        Some(format) => format.to_lowercase(),
+        None => match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("cbor") => "cbor".to_string(),
+            _ => "json".to_string(),
+        },
+    };
+
+    let mut bytes = Vec::new();
+    safe_open_file(path, false)?.read_to_end(&mut bytes)?;
+
+    let invalid =
+        |e: String| Error::Validation(format!("Invalid manifest file {}: {e}", path.display()));
+    match format.as_str() {
+        "json" => serde_json::from_slice(&bytes)
+            .map_err(Error::from)
+            .and_then(version::migrate_manifest)
+            .map_err(|e| invalid(e.to_string())),
+        "cbor" => serde_cbor::from_slice(&bytes)
+            .map_err(|e| Error::Serialization(e.to_string()))
+            .and_then(version::migrate_manifest)
+            .map_err(|e| invalid(e.to_string())),
+        _ => Err(Error::Validation(format!(
+            "Invalid input format '{format}'. Valid options are: json, cbor"
+        ))),
+    }
+}
+
+/// Import a manifest file produced elsewhere into a storage backend.
+///
+/// The manifest is validated before it is stored. With `verify_signatures`,
+/// its countersignatures are checked too, and with a `trust_list` every
+/// signer must be listed. Returns the ID assigned by the backend.
+pub fn import_manifest(
+    path: &Path,
+    format: Option<&str>,
+    storage: &(impl StorageBackend + ?Sized),
+    verify_signatures: bool,
+    trust_list: Option<&Path>,
+) -> Result<String> {
+    let manifest = read_manifest_file(path, format)?;
+    common::validate_manifest_structure(&manifest)?;
+
+    if verify_signatures || trust_list.is_some() {
+        signatures::verify_signatures(&manifest)?;
+    }
+    if let Some(trust_list) = trust_list {
+        signatures::verify_trusted_signers(&manifest, &TrustList::load(trust_list)?)?;
+    }
+
+    storage.store_manifest(&manifest)
+}
+
+/// Derive a filesystem-safe file name from a manifest ID that is not among
+/// `used_names` (compared case-insensitively), and add it to them
+fn bundle_file_name(id: &str, used_names: &mut HashSet<String>) -> String {
+    let name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut file_name = format!("{name}.json");
+    let mut suffix = 1;
+    while !used_names.insert(file_name.to_lowercase()) {
+        suffix += 1;
+        file_name = format!("{name}-{suffix}.json");
+    }
+    file_name
+}
+
 /// Recursively build the provenance graph
 fn build_provenance_graph(
     id: &str,
@@ -1,20 +1,30 @@
 use crate::error::Result;
-use crate::manifest::common::{AssetKind, list_manifests, verify_manifest};
-use crate::manifest::config::ManifestCreationConfig;
-use crate::storage::traits::StorageBackend;
+use crate::manifest::common::{AssetKind, list_manifests, verify_manifest_with_config};
+use crate::manifest::config::{ManifestCreationConfig, VerificationConfig};
+use crate::manifest::report::VerificationReport;
+use crate::storage::traits::{ManifestFilter, StorageBackend};
 
 pub fn create_manifest(config: ManifestCreationConfig) -> Result<()> {
     crate::manifest::common::create_manifest(config, AssetKind::Model)
 }
 
 /// List model manifests
-pub fn list_model_manifests(storage: &dyn StorageBackend) -> Result<()> {
+pub fn list_model_manifests(storage: &dyn StorageBackend, filter: &ManifestFilter) -> Result<()> {
     // Call the unified implementation with AssetKind::Model
-    list_manifests(storage, Some(AssetKind::Model))
+    list_manifests(storage, Some(AssetKind::Model), filter)
 }
 
 /// Verify a model manifest
-pub fn verify_model_manifest(id: &str, storage: &dyn StorageBackend) -> Result<()> {
+pub fn verify_model_manifest(id: &str, storage: &dyn StorageBackend) -> Result<VerificationReport> {
+    verify_model_manifest_with_config(id, storage, &VerificationConfig::default())
+}
+
+/// Verify a model manifest using the given verification options
+pub fn verify_model_manifest_with_config(
+    id: &str,
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+) -> Result<VerificationReport> {
     // Call the unified implementation
-    verify_manifest(id, storage)
+    verify_manifest_with_config(id, storage, config)
 }
@@ -0,0 +1,201 @@
+//! # Model Cards
+//!
+//! A model manifest can reference the model card or other documentation of
+//! the model (`model create --model-card <path-or-url>`). A local file is
+//! hashed into an ingredient with the [`MODEL_CARD_RELATIONSHIP`]
+//! relationship, like the model files themselves. A URL is recorded as a
+//! link in an `atlas.model_card` assertion, much like a cross-reference,
+//! optionally with the hash its content is expected to have
+//! (`--model-card-hash`).
+//!
+//! Documentation is often revised after a model is released, so a model card
+//! that is missing or no longer matches its hash is only a warning when the
+//! manifest is verified, or an error with `--strict`.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::model_card::ModelCardLink;
+//!
+//! let hash = "ab".repeat(32);
+//! let link = ModelCardLink::new("https://example.com/card.md", Some(&hash)).unwrap();
+//! assert_eq!(link.alg.as_deref(), Some("sha256"));
+//! ```
+
+use crate::error::{Error, Result};
+use crate::hash::DigestAlgorithm;
+use crate::hash::remote::{self, FetchLimits};
+use crate::manifest::config::VerificationConfig;
+use crate::manifest::report::VerificationCheck;
+use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+use atlas_c2pa_lib::ingredient::Ingredient;
+use atlas_c2pa_lib::manifest::Manifest;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Label of the assertion linking to a model card by URL
+pub const MODEL_CARD_LABEL: &str = "atlas.model_card";
+
+/// Relationship recorded for a model card ingredient
+pub const MODEL_CARD_RELATIONSHIP: &str = "documentOf";
+
+/// Title of the ingredient or check for a model card
+pub const MODEL_CARD_TITLE: &str = "Model card";
+
+/// A model card referenced by URL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCardLink {
+    pub url: String,
+    /// Algorithm of `hash`, derived from its length
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alg: Option<String>,
+    /// Expected hash of the content at `url`, hex-encoded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+impl ModelCardLink {
+    /// Links to `url`, expecting its content to hash to `expected_hash` (a
+    /// SHA-256, SHA-384 or SHA-512 hex digest) if given
+    pub fn new(url: &str, expected_hash: Option<&str>) -> Result<Self> {
+        if !remote::is_remote_url(url) {
+            return Err(Error::Validation(format!(
+                "Model card URL must be http(s):// or s3://: {url}"
+            )));
+        }
+        let (alg, hash) = match expected_hash {
+            Some(hash) => {
+                let hash = hash.trim().to_ascii_lowercase();
+                let alg = match hash.len() {
+                    64 => "sha256",
+                    96 => "sha384",
+                    128 => "sha512",
+                    _ => "",
+                };
+                if alg.is_empty() || hex::decode(&hash).is_err() {
+                    return Err(Error::Validation(format!(
+                        "Invalid model card hash '{hash}': expected a SHA-256, SHA-384 or SHA-512 hex digest"
+                    )));
+                }
+                (Some(alg.to_string()), Some(hash))
+            }
+            None => (None, None),
+        };
+        Ok(Self {
+            url: url.to_string(),
+            alg,
+            hash,
+        })
+    }
+
+    /// The assertion recording this link
+    pub fn to_assertion(&self) -> Result<Assertion> {
+        Ok(Assertion::CustomAssertion(CustomAssertion {
+            label: MODEL_CARD_LABEL.to_string(),
+            data: serde_json::to_value(self).map_err(|e| Error::Serialization(e.to_string()))?,
+        }))
+    }
+
+    /// The model card link recorded in a manifest's claim, if any
+    pub fn from_manifest(manifest: &Manifest) -> Result<Option<Self>> {
+        let assertions = match &manifest.claim_v2 {
+            Some(claim) => &claim.created_assertions,
+            None => &manifest.claim.created_assertions,
+        };
+        assertions
+            .iter()
+            .find_map(|assertion| match assertion {
+                Assertion::CustomAssertion(custom) if custom.label == MODEL_CARD_LABEL => {
+                    Some(&custom.data)
+                }
+                _ => None,
+            })
+            .map(|data| {
+                serde_json::from_value(data.clone()).map_err(|e| {
+                    Error::Validation(format!("Invalid {MODEL_CARD_LABEL} assertion: {e}"))
+                })
+            })
+            .transpose()
+    }
+
+    /// Fetches the linked content and compares it with the expected hash.
+    ///
+    /// Content that cannot be fetched or does not match is a warning, or an
+    /// error with `config.strict`. Without an expected hash, or in offline
+    /// mode, the content is not fetched.
+    pub fn verify(&self, config: &VerificationConfig) -> Result<VerificationCheck> {
+        let (Some(alg), Some(hash)) = (&self.alg, &self.hash) else {
+            return Ok(VerificationCheck::warning(
+                MODEL_CARD_TITLE,
+                format!("{} is linked without a hash and was not checked", self.url),
+            ));
+        };
+        if config.offline {
+            return Ok(VerificationCheck::warning(
+                MODEL_CARD_TITLE,
+                format!("{} was not fetched in offline mode", self.url),
+            ));
+        }
+
+        let algorithm = DigestAlgorithm::from_str(alg)?;
+        match remote::hash_remote(&self.url, algorithm, &FetchLimits::default()) {
+            Ok(calculated) if calculated == *hash => Ok(VerificationCheck::verified(
+                MODEL_CARD_TITLE,
+                format!("hash matches the content at {}", self.url),
+            )),
+            Ok(_) => lenient_failure(
+                format!("content at {} does not match its recorded hash", self.url),
+                config,
+            ),
+            Err(e) => lenient_failure(format!("{} could not be fetched: {e}", self.url), config),
+        }
+    }
+}
+
+/// Whether an ingredient is a model card
+pub fn is_model_card(ingredient: &Ingredient) -> bool {
+    ingredient.relationship == MODEL_CARD_RELATIONSHIP
+}
+
+/// A model card problem: a warning, or an error with `config.strict`
+pub(crate) fn lenient_failure(
+    message: String,
+    config: &VerificationConfig,
+) -> Result<VerificationCheck> {
+    if config.strict {
+        return Err(Error::Validation(format!("Model card {message}")));
+    }
+    Ok(VerificationCheck::warning(MODEL_CARD_TITLE, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_card_link_validates_url_and_hash() {
+        let link = ModelCardLink::new("https://example.com/card.md", None).unwrap();
+        assert!(link.hash.is_none());
+        let hash = "AB".repeat(48);
+        let link = ModelCardLink::new("s3://bucket/card.md", Some(&hash)).unwrap();
+        assert_eq!(link.alg.as_deref(), Some("sha384"));
+        assert_eq!(link.hash, Some(hash.to_lowercase()));
+
+        assert!(ModelCardLink::new("card.md", None).is_err());
+        assert!(ModelCardLink::new("https://example.com/card.md", Some("abc")).is_err());
+        let not_hex = "zz".repeat(32);
+        assert!(ModelCardLink::new("https://example.com/card.md", Some(&not_hex)).is_err());
+    }
+
+    #[test]
+    fn test_model_card_link_without_hash_is_not_fetched() {
+        let link = ModelCardLink::new("https://invalid.example/card.md", None).unwrap();
+        let check = link
+            .verify(&VerificationConfig {
+                strict: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(check.subject, MODEL_CARD_TITLE);
+    }
+}
@@ -0,0 +1,280 @@
+//! # Verification Policies
+//!
+//! Organizations often have conventions beyond what the built-in checks
+//! enforce, e.g. "every model manifest names an author organization". A
+//! policy file states such rules as JSONPath expressions evaluated against
+//! the manifest's JSON; `verify --policy policy.json` runs them after the
+//! built-in checks.
+//!
+//! The policy is a JSON array of rules:
+//!
+//! ```json
+//! [
+//!   {
+//!     "path": "$.claim.created_assertions[*].CreativeWork.author[*]['@type']",
+//!     "equals": "Organization",
+//!     "applies_to": ["model"],
+//!     "message": "Model manifests must name an author organization"
+//!   },
+//!   {
+//!     "path": "$..CustomAssertion.data.version",
+//!     "applies_to": ["software"],
+//!     "message": "Software manifests must declare a version"
+//!   }
+//! ]
+//! ```
+//!
+//! A rule passes if its path selects a value that is not null or empty, or,
+//! with `equals`, a value equal to it. Rules without `applies_to` apply to
+//! every manifest. Every rule is evaluated and all violations are reported
+//! together.
+//!
+//! Paths support the JSONPath subset `$`, `.name`, `['name']`, `[n]`, `[*]`,
+//! `.*` and `..name` (recursive descent).
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::policy::select;
+//! use serde_json::json;
+//!
+//! let manifest = json!({"ingredients": [{"title": "weights"}, {"title": "config"}]});
+//! let titles = select(&manifest, "$.ingredients[*].title").unwrap();
+//! assert_eq!(titles, [&json!("weights"), &json!("config")]);
+//! ```
+
+use crate::error::{Error, Result};
+use crate::manifest::report::VerificationCheck;
+use crate::manifest::utils::determine_manifest_type;
+use crate::storage::traits::ManifestType;
+use atlas_c2pa_lib::manifest::Manifest;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// One requirement of a policy
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRule {
+    /// JSONPath expression evaluated against the manifest's JSON
+    pub path: String,
+    /// Value the path must select; without it any non-empty value passes
+    pub equals: Option<Value>,
+    /// Manifest types the rule applies to; empty means every type
+    #[serde(default)]
+    pub applies_to: Vec<String>,
+    /// Describes the requirement; reported when the rule is violated
+    pub message: String,
+}
+
+/// Rules a manifest must satisfy to pass verification
+#[derive(Debug, Clone)]
+pub struct Policy {
+    pub rules: Vec<PolicyRule>,
+}
+
+impl Policy {
+    /// Reads a policy file, rejecting rules with invalid paths or types
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let rules: Vec<PolicyRule> = serde_json::from_str(&content).map_err(|e| {
+            Error::Validation(format!(
+                "Invalid policy {}: {e}. Expected a JSON array of rules",
+                path.display()
+            ))
+        })?;
+        for rule in &rules {
+            parse_path(&rule.path)?;
+            for manifest_type in &rule.applies_to {
+                manifest_type.parse::<ManifestType>()?;
+            }
+        }
+        Ok(Self { rules })
+    }
+}
+
+/// Evaluates every applicable rule against a manifest.
+///
+/// Returns a check per satisfied rule, or a validation error listing every
+/// violated rule.
+pub fn check_policy(manifest: &Manifest, policy: &Policy) -> Result<Vec<VerificationCheck>> {
+    let json = serde_json::to_value(manifest).map_err(|e| Error::Serialization(e.to_string()))?;
+    let manifest_type = determine_manifest_type(manifest);
+
+    let mut checks = Vec::new();
+    let mut violations = Vec::new();
+    for rule in &policy.rules {
+        let applies = rule.applies_to.is_empty()
+            || rule.applies_to.iter().any(|applies_to| {
+                applies_to.parse::<ManifestType>().ok() == Some(manifest_type.clone())
+            });
+        if !applies {
+            continue;
+        }
+
+        let selected = select(&json, &rule.path)?;
+        let satisfied = match &rule.equals {
+            Some(expected) => selected.contains(&expected),
+            None => selected.iter().any(|value| !is_empty(value)),
+        };
+        if satisfied {
+            checks.push(VerificationCheck::verified(&rule.path, &rule.message));
+        } else {
+            violations.push(rule.message.clone());
+        }
+    }
+
+    if !violations.is_empty() {
+        return Err(Error::Validation(format!(
+            "Manifest {} violates the policy: {}",
+            manifest.instance_id,
+            violations.join("; ")
+        )));
+    }
+    Ok(checks)
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    }
+}
+
+/// One step of a JSONPath expression
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(String),
+    Index(usize),
+    Wildcard,
+    Descendant(String),
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let invalid = |reason: &str| Error::Validation(format!("Invalid JSONPath '{path}': {reason}"));
+    let mut rest = path
+        .strip_prefix('$')
+        .ok_or_else(|| invalid("must start with '$'"))?;
+
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 || &after[..end] == "*" {
+                return Err(invalid("'..' must be followed by a field name"));
+            }
+            segments.push(Segment::Descendant(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            segments.push(match &after[..end] {
+                "" => return Err(invalid("empty field name")),
+                "*" => Segment::Wildcard,
+                name => Segment::Child(name.to_string()),
+            });
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(|| invalid("unclosed '['"))?;
+            let selector = &after[..end];
+            segments.push(if selector == "*" {
+                Segment::Wildcard
+            } else if let Some(name) = selector
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| selector.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+            {
+                Segment::Child(name.to_string())
+            } else {
+                Segment::Index(
+                    selector
+                        .parse()
+                        .map_err(|_| invalid(&format!("unsupported selector [{selector}]")))?,
+                )
+            });
+            rest = &after[end + 1..];
+        } else {
+            return Err(invalid(&format!("unexpected '{rest}'")));
+        }
+    }
+    Ok(segments)
+}
+
+/// Values selected by a JSONPath expression, in document order
+pub fn select<'a>(value: &'a Value, path: &str) -> Result<Vec<&'a Value>> {
+    let mut current = vec![value];
+    for segment in parse_path(path)? {
+        let mut next = Vec::new();
+        for value in current {
+            match &segment {
+                Segment::Child(name) => next.extend(value.get(name)),
+                Segment::Index(index) => next.extend(value.get(index)),
+                Segment::Wildcard => match value {
+                    Value::Array(items) => next.extend(items),
+                    Value::Object(fields) => next.extend(fields.values()),
+                    _ => {}
+                },
+                Segment::Descendant(name) => collect_descendants(value, name, &mut next),
+            }
+        }
+        current = next;
+    }
+    Ok(current)
+}
+
+fn collect_descendants<'a>(value: &'a Value, name: &str, out: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(fields) => {
+            out.extend(fields.get(name));
+            for field in fields.values() {
+                collect_descendants(field, name, out);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_descendants(item, name, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_select_paths() -> Result<()> {
+        let value = json!({
+            "claim": {
+                "created_assertions": [
+                    {"CreativeWork": {"author": [{"@type": "Organization", "name": "Example"}]}},
+                    {"CustomAssertion": {"label": "atlas.software", "data": {"version": "1.2"}}}
+                ]
+            }
+        });
+
+        assert_eq!(
+            select(
+                &value,
+                "$.claim.created_assertions[0].CreativeWork.author[*]['@type']"
+            )?,
+            [&json!("Organization")]
+        );
+        assert_eq!(select(&value, "$..version")?, [&json!("1.2")]);
+        assert_eq!(select(&value, "$.claim.*")?.len(), 1);
+        assert!(select(&value, "$.claim.missing")?.is_empty());
+
+        for invalid in ["claim", "$.", "$[0", "$[first]", "$..*"] {
+            assert!(
+                select(&value, invalid).is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+//! # Ingredient Hash Refresh
+//!
+//! Re-baselines a manifest after its `file://` ingredients were legitimately
+//! updated: each file is re-hashed with the algorithm recorded for it (after
+//! decompressing it, if it was hashed that way) and the changed hashes are
+//! written back to the ingredients, both claims and the directory hashes
+//! assertion.
+//!
+//! Changing the hashes changes the signed claim bytes, so every existing
+//! signature becomes invalid. A signed manifest can only be refreshed with a
+//! key to re-sign it, and countersignatures and timestamps are removed since
+//! they vouch for the old contents.
+
+use crate::error::{Error, Result};
+use crate::hash::decompress::calculate_decompressed_file_hash;
+use crate::hash::sample::{calculate_sampled_file_hash, is_sampled};
+use crate::hash::{self, DigestAlgorithm};
+use crate::manifest::common::DIRECTORY_HASHES_LABEL;
+use crate::manifest::signatures::{self, COUNTERSIGNATURES_LABEL};
+use crate::manifest::utils::decompressed_ingredients;
+use crate::signing;
+use crate::signing::timestamp::TIMESTAMP_ASSERTION_LABEL;
+use atlas_c2pa_lib::assertion::Assertion;
+use atlas_c2pa_lib::claim::ClaimV2;
+use atlas_c2pa_lib::manifest::Manifest;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use openssl::pkey::{PKey, Private};
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// An ingredient whose file no longer matches its recorded hash
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashChange {
+    pub title: String,
+    pub old_hash: String,
+    pub new_hash: String,
+}
+
+/// Outcome of refreshing a manifest's ingredient hashes
+#[derive(Debug, Default)]
+pub struct RefreshSummary {
+    pub changes: Vec<HashChange>,
+    /// Titles of ingredients that are not local files and were left as is
+    pub skipped: Vec<String>,
+    /// Number of countersignatures removed because they covered the old hashes
+    pub removed_countersignatures: usize,
+}
+
+/// Re-hashes the manifest's `file://` ingredients and returns the changes
+/// without modifying anything
+pub fn find_hash_changes(manifest: &Manifest) -> Result<RefreshSummary> {
+    let mut summary = RefreshSummary::default();
+    let decompressed = decompressed_ingredients(manifest);
+    for ingredient in &manifest.ingredients {
+        let Some(path) = ingredient.data.url.strip_prefix("file://") else {
+            summary.skipped.push(ingredient.title.clone());
+            continue;
+        };
+        let new_hash = if is_sampled(&ingredient.data.alg) {
+            calculate_sampled_file_hash(Path::new(path))
+        } else {
+            let algorithm = DigestAlgorithm::from_str(&ingredient.data.alg)?;
+            match decompressed.get(&ingredient.data.url) {
+                Some(compression) => {
+                    calculate_decompressed_file_hash(Path::new(path), *compression, algorithm)
+                }
+                None => hash::calculate_file_hash_with_algorithm(Path::new(path), algorithm),
+            }
+        }
+        .map_err(|e| {
+            Error::Validation(format!("Cannot re-hash {} ({path}): {e}", ingredient.title))
+        })?;
+        if new_hash != ingredient.data.hash {
+            summary.changes.push(HashChange {
+                title: ingredient.title.clone(),
+                old_hash: ingredient.data.hash.clone(),
+                new_hash,
+            });
+        }
+    }
+    Ok(summary)
+}
+
+/// Writes the current hashes of changed `file://` ingredients into the
+/// manifest, re-signing the claim with `signing_key` if given.
+///
+/// Fails without modifying the manifest if it is signed and no key is given.
+pub fn refresh_ingredient_hashes(
+    manifest: &mut Manifest,
+    signing_key: Option<(&PKey<Private>, DigestAlgorithm)>,
+) -> Result<RefreshSummary> {
+    let mut summary = find_hash_changes(manifest)?;
+    if summary.changes.is_empty() {
+        return Ok(summary);
+    }
+
+    let signed = manifest
+        .claim_v2
+        .as_ref()
+        .unwrap_or(&manifest.claim)
+        .signature
+        .is_some();
+    if signed && signing_key.is_none() {
+        return Err(Error::Validation(format!(
+            "Manifest {} is signed; a key is needed to re-sign it after refreshing its hashes",
+            manifest.instance_id
+        )));
+    }
+    summary.removed_countersignatures = signatures::countersignatures(manifest)?.len();
+
+    let new_hashes: HashMap<&str, &str> = summary
+        .changes
+        .iter()
+        .map(|change| (change.title.as_str(), change.new_hash.as_str()))
+        .collect();
+    let update = |ingredients: &mut [atlas_c2pa_lib::ingredient::Ingredient]| {
+        for ingredient in ingredients {
+            if let Some(new_hash) = new_hashes.get(ingredient.title.as_str()) {
+                ingredient.data.hash = new_hash.to_string();
+            }
+        }
+    };
+    update(&mut manifest.ingredients);
+
+    let mut claim = manifest
+        .claim_v2
+        .clone()
+        .unwrap_or_else(|| manifest.claim.clone());
+    update(&mut claim.ingredients);
+    refresh_directory_hashes(&mut claim)?;
+
+    // Everything vouching for the old claim bytes no longer applies
+    claim.created_assertions.retain(|assertion| {
+        !matches!(
+            assertion,
+            Assertion::CustomAssertion(custom)
+                if custom.label == COUNTERSIGNATURES_LABEL || custom.label == TIMESTAMP_ASSERTION_LABEL
+        )
+    });
+    claim.signature = None;
+    if let Some((key, algorithm)) = signing_key {
+        let claim_bytes = signatures::signed_claim_bytes(&claim)?;
+        let signature = signing::sign_data_with_algorithm(&claim_bytes, key, algorithm)?;
+        claim.signature = Some(STANDARD.encode(&signature));
+    }
+
+    if manifest.claim_v2.is_some() {
+        manifest.claim_v2 = Some(claim.clone());
+    }
+    manifest.claim = claim;
+
+    Ok(summary)
+}
+
+/// Recomputes each directory's combined hash and Merkle root from the
+/// hashes of the ingredients under it
+fn refresh_directory_hashes(claim: &mut ClaimV2) -> Result<()> {
+    let ingredients = claim.ingredients.clone();
+    for assertion in &mut claim.created_assertions {
+        let Assertion::CustomAssertion(custom) = assertion else {
+            continue;
+        };
+        if custom.label != DIRECTORY_HASHES_LABEL {
+            continue;
+        }
+        let Some(directories) = custom.data.as_array_mut() else {
+            continue;
+        };
+        for directory in directories {
+            let Some(title) = directory["title"].as_str() else {
+                continue;
+            };
+            let prefix = format!("{title}/");
+            let file_hashes: Vec<String> = ingredients
+                .iter()
+                .filter(|ingredient| ingredient.title.starts_with(&prefix))
+                .map(|ingredient| ingredient.data.hash.clone())
+                .collect();
+            if file_hashes.is_empty() {
+                continue;
+            }
+            let hash_refs: Vec<&str> = file_hashes.iter().map(String::as_str).collect();
+            directory["hash"] = hash::combine_hashes(&hash_refs)?.into();
+            directory["merkle_root"] = hash::merkle::build_merkle_root(&file_hashes)?.into();
+        }
+    }
+    Ok(())
+}
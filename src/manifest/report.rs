@@ -0,0 +1,142 @@
+//! # Verification Reports
+//!
+//! Verification returns a [`VerificationReport`] describing every check that
+//! was performed instead of printing progress, so callers can render the
+//! results themselves (the CLI prints them, other tools may emit JSON).
+//!
+//! A check that fails aborts verification with an error; the report therefore
+//! only holds checks that passed or passed with a warning. With
+//! `continue_on_error`, every ingredient is checked first and the error lists
+//! all ingredients that failed.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::report::{CheckStatus, VerificationCheck, VerificationReport};
+//!
+//! let mut report = VerificationReport::new("urn:c2pa:example");
+//! report.ingredients.push(VerificationCheck::verified("weights", "hash matches"));
+//! report.cross_references.push(VerificationCheck::warning("urn:c2pa:gone", "target missing"));
+//!
+//! assert_eq!(report.status(), CheckStatus::Warning);
+//! assert_eq!(report.warnings().count(), 1);
+//! ```
+
+use serde::Serialize;
+
+/// Outcome of a single verification check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Verified,
+    /// The check could not be completed but was not treated as a failure
+    Warning,
+}
+
+/// One check performed during verification
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerificationCheck {
+    /// What was checked, e.g. an ingredient title or a manifest ID
+    pub subject: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl VerificationCheck {
+    /// A check that passed
+    pub fn verified(subject: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            status: CheckStatus::Verified,
+            message: message.into(),
+        }
+    }
+
+    /// A check that could not be completed and was downgraded to a warning
+    pub fn warning(subject: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            status: CheckStatus::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+/// Results of verifying one manifest
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerificationReport {
+    pub manifest_id: String,
+    /// Hash checks of the manifest's ingredients
+    pub ingredients: Vec<VerificationCheck>,
+    /// Hash checks of the manifests this one references
+    pub cross_references: Vec<VerificationCheck>,
+    /// CC attestation checks, one per recorded platform
+    pub cc_attestations: Vec<VerificationCheck>,
+    /// RFC 3161 signature timestamp checks
+    pub timestamps: Vec<VerificationCheck>,
+    /// Countersignature checks, one per signer
+    pub countersignatures: Vec<VerificationCheck>,
+    /// Trust list checks, one per signature, when a trust list was given
+    pub trusted_signers: Vec<VerificationCheck>,
+    /// Transparency log inclusion checks, when the manifest was read from Rekor
+    pub transparency_log: Vec<VerificationCheck>,
+    /// Validity window check, when the manifest records one
+    pub validity: Vec<VerificationCheck>,
+    /// Checks specific to the asset kind (model, dataset, embedding asset, ...)
+    pub asset_checks: Vec<VerificationCheck>,
+    /// Policy rule checks, one per applicable rule, when a policy was given
+    pub policy: Vec<VerificationCheck>,
+    /// Hash checks of the dataset manifests a model was trained on
+    pub training_data: Vec<VerificationCheck>,
+}
+
+impl VerificationReport {
+    /// Creates an empty report for the given manifest
+    pub fn new(manifest_id: impl Into<String>) -> Self {
+        Self {
+            manifest_id: manifest_id.into(),
+            ingredients: Vec::new(),
+            cross_references: Vec::new(),
+            cc_attestations: Vec::new(),
+            timestamps: Vec::new(),
+            countersignatures: Vec::new(),
+            trusted_signers: Vec::new(),
+            transparency_log: Vec::new(),
+            validity: Vec::new(),
+            asset_checks: Vec::new(),
+            policy: Vec::new(),
+            training_data: Vec::new(),
+        }
+    }
+
+    /// Iterates over every check in the order they were performed
+    pub fn checks(&self) -> impl Iterator<Item = &VerificationCheck> {
+        self.ingredients
+            .iter()
+            .chain(&self.cross_references)
+            .chain(&self.cc_attestations)
+            .chain(&self.timestamps)
+            .chain(&self.countersignatures)
+            .chain(&self.trusted_signers)
+            .chain(&self.transparency_log)
+            .chain(&self.validity)
+            .chain(&self.asset_checks)
+            .chain(&self.policy)
+            .chain(&self.training_data)
+    }
+
+    /// Iterates over the checks that ended in a warning
+    pub fn warnings(&self) -> impl Iterator<Item = &VerificationCheck> {
+        self.checks()
+            .filter(|check| check.status == CheckStatus::Warning)
+    }
+
+    /// Overall status: `Warning` if any check ended in a warning
+    pub fn status(&self) -> CheckStatus {
+        if self.warnings().next().is_some() {
+            CheckStatus::Warning
+        } else {
+            CheckStatus::Verified
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! # Manifest JSON Schema
+//!
+//! Validates serialized manifests against a bundled JSON Schema before they
+//! are stored, so malformed manifests are rejected client-side with the path
+//! of every offending field instead of being accepted by a storage backend
+//! and failing later on retrieval or verification.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::schema::validate_against_schema;
+//! use serde_json::json;
+//!
+//! let result = validate_against_schema(&json!({"title": ""}));
+//! assert!(result.unwrap_err().to_string().contains("/title"));
+//! ```
+
+use crate::error::{Error, Result};
+use atlas_c2pa_lib::manifest::Manifest;
+use jsonschema::Validator;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/// The bundled schema for manifests as serialized by this crate
+pub const MANIFEST_SCHEMA: &str = include_str!("manifest.schema.json");
+
+fn validator() -> Result<&'static Validator> {
+    static VALIDATOR: OnceLock<std::result::Result<Validator, String>> = OnceLock::new();
+    VALIDATOR
+        .get_or_init(|| {
+            let schema: Value = serde_json::from_str(MANIFEST_SCHEMA).map_err(|e| e.to_string())?;
+            jsonschema::validator_for(&schema).map_err(|e| e.to_string())
+        })
+        .as_ref()
+        .map_err(|e| Error::Validation(format!("Invalid bundled manifest schema: {e}")))
+}
+
+/// Validates a serialized manifest against the bundled schema, reporting
+/// every violation with its JSON pointer
+pub fn validate_against_schema(manifest_json: &Value) -> Result<()> {
+    let errors: Vec<String> = validator()?
+        .iter_errors(manifest_json)
+        .map(|error| {
+            let path = error.instance_path.to_string();
+            let path = if path.is_empty() { "/" } else { &path };
+            format!("{path}: {error}")
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Validation(format!(
+            "Manifest does not match the schema:\n  {}",
+            errors.join("\n  ")
+        )))
+    }
+}
+
+/// Serializes a manifest and validates it against the bundled schema
+pub fn validate_manifest_schema(manifest: &Manifest) -> Result<()> {
+    let value = serde_json::to_value(manifest).map_err(|e| {
+        Error::Serialization(e.to_string()).with_manifest_context(
+            "validating schema of manifest",
+            &manifest.title,
+            &manifest.instance_id,
+        )
+    })?;
+    validate_against_schema(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn valid_manifest() -> Value {
+        let claim = json!({
+            "instance_id": "urn:c2pa:claim",
+            "created_assertions": [
+                {"CreativeWork": {"@context": "http://schema.org/", "@type": "Model", "author": []}},
+                {"CustomAssertion": {"label": "atlas.tags", "data": ["env=prod"]}}
+            ],
+            "ingredients": [],
+            "signature": null,
+            "claim_generator_info": "c2pa-cli",
+            "created_at": "2025-01-01T00:00:00Z"
+        });
+        json!({
+            "claim_generator": "c2pa-cli/0.1.0",
+            "title": "Model",
+            "instance_id": "urn:c2pa:manifest",
+            "ingredients": [{
+                "title": "Weights",
+                "relationship": "componentOf",
+                "dc:format": "application/onnx",
+                "document_id": "uuid:doc",
+                "instance_id": "uuid:instance",
+                "data": {
+                    "url": "file:///model.onnx",
+                    "alg": "sha384",
+                    "hash": "ab".repeat(48),
+                    "data_types": ["c2pa.types.model.onnx"],
+                    "linked_ingredient_url": null,
+                    "linked_ingredient_hash": null
+                },
+                "linked_ingredient": null
+            }],
+            "claim": claim.clone(),
+            "created_at": "2025-01-01T00:00:00.5+01:00",
+            "cross_references": [],
+            "c2pa.claim.v2": claim,
+            "is_active": true
+        })
+    }
+
+    #[test]
+    fn test_valid_manifest_passes() -> Result<()> {
+        validate_against_schema(&valid_manifest())
+    }
+
+    #[test]
+    fn test_schema_errors_report_field_paths() {
+        let mut manifest = valid_manifest();
+        manifest["ingredients"][0]["data"]["hash"] = json!("not-a-hash");
+        manifest["c2pa.claim.v2"]["created_assertions"][0] = json!({"Unknown": {}});
+        manifest["cross_references"] = json!([{"manifest_url": "urn:c2pa:x"}]);
+
+        let message = match validate_against_schema(&manifest) {
+            Err(Error::Validation(message)) => message,
+            other => panic!("expected a schema validation error, got {other:?}"),
+        };
+        assert!(message.contains("/ingredients/0/data/hash"), "{message}");
+        assert!(
+            message.contains("/c2pa.claim.v2/created_assertions/0"),
+            "{message}"
+        );
+        assert!(message.contains("/cross_references/0"), "{message}");
+    }
+}
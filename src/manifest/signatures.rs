@@ -0,0 +1,368 @@
+//! # Countersignatures
+//!
+//! Lets several parties sign the same manifest, for example a data scientist
+//! and a release manager in a review workflow. Each countersignature records
+//! the signer's identity, public key, hash algorithm and signature, and all of
+//! them are kept in a single `atlas.countersignatures` assertion.
+//!
+//! Every signature, including the original claim signature, covers the same
+//! stable claim bytes: the canonical CBOR of the claim without its signature
+//! and without the assertions that are added after signing (timestamps and
+//! countersignatures). Adding a countersignature therefore never invalidates
+//! the existing ones.
+//!
+//! With a [`TrustList`], verification also requires every signer to be
+//! trusted. Countersignatures are matched by the fingerprint of their recorded
+//! key; the claim signature does not record its key, so it must verify with
+//! one of the PEM keys in the trust list.
+
+use crate::error::{Error, Result, SigningError};
+use crate::hash::DigestAlgorithm;
+use crate::manifest::canonical;
+use crate::manifest::report::VerificationCheck;
+use crate::signing;
+use crate::signing::timestamp::TIMESTAMP_ASSERTION_LABEL;
+use crate::signing::trust::{TrustList, public_key_fingerprint};
+use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+use atlas_c2pa_lib::claim::ClaimV2;
+use atlas_c2pa_lib::manifest::Manifest;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use openssl::pkey::{PKey, Private};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Label of the assertion holding a manifest's countersignatures
+pub const COUNTERSIGNATURES_LABEL: &str = "atlas.countersignatures";
+
+/// One signer's signature over the stable claim bytes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Countersignature {
+    pub signer_identity: String,
+    /// PEM-encoded public key of the signer
+    pub public_key: String,
+    /// Hash algorithm used with the key (`sha384`, `sha3-256`, ...)
+    pub alg: String,
+    /// Base64-encoded signature
+    pub signature: String,
+}
+
+/// Canonical CBOR of the claim as covered by every signature
+pub fn signed_claim_bytes(claim: &ClaimV2) -> Result<Vec<u8>> {
+    let mut claim = claim.clone();
+    claim.signature = None;
+    claim.created_assertions.retain(|assertion| {
+        !matches!(
+            assertion,
+            Assertion::CustomAssertion(custom)
+                if custom.label == TIMESTAMP_ASSERTION_LABEL
+                    || custom.label == COUNTERSIGNATURES_LABEL
+        )
+    });
+    canonical::to_canonical_cbor(&claim)
+}
+
+/// Returns the countersignatures recorded in a manifest, oldest first
+pub fn countersignatures(manifest: &Manifest) -> Result<Vec<Countersignature>> {
+    match &manifest.claim_v2 {
+        Some(claim) => claim_countersignatures(claim),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Returns the countersignatures recorded in a claim, oldest first
+pub fn claim_countersignatures(claim: &ClaimV2) -> Result<Vec<Countersignature>> {
+    let mut signatures = Vec::new();
+    for assertion in &claim.created_assertions {
+        if let Assertion::CustomAssertion(custom) = assertion
+            && custom.label == COUNTERSIGNATURES_LABEL
+        {
+            let entries: Vec<Countersignature> = serde_json::from_value(custom.data.clone())
+                .map_err(|e| {
+                    Error::Validation(format!("Invalid countersignature assertion: {e}"))
+                })?;
+            signatures.extend(entries);
+        }
+    }
+    Ok(signatures)
+}
+
+/// Adds a countersignature by `identity` using SHA-384
+pub fn add_signature(manifest: &mut Manifest, key: &PKey<Private>, identity: &str) -> Result<()> {
+    add_signature_with_algorithm(manifest, key, identity, DigestAlgorithm::Sha384)
+}
+
+/// Adds a countersignature by `identity` using the given hash algorithm
+pub fn add_signature_with_algorithm(
+    manifest: &mut Manifest,
+    key: &PKey<Private>,
+    identity: &str,
+    algorithm: impl Into<DigestAlgorithm>,
+) -> Result<()> {
+    let algorithm = algorithm.into();
+    if identity.trim().is_empty() {
+        return Err(Error::Validation(
+            "Signer identity must not be empty".to_string(),
+        ));
+    }
+
+    let mut signatures = countersignatures(manifest)?;
+    if signatures.iter().any(|s| s.signer_identity == identity) {
+        return Err(Error::Validation(format!(
+            "Manifest is already signed by {identity}"
+        )));
+    }
+
+    let claim = manifest
+        .claim_v2
+        .as_mut()
+        .ok_or_else(|| Error::Validation("Manifest has no claim to sign".to_string()))?;
+
+    let signature = signing::sign_data_with_algorithm(&signed_claim_bytes(claim)?, key, algorithm)?;
+    let public_key = key
+        .public_key_to_pem()
+        .map_err(|e| Error::Signing(format!("Failed to encode public key: {e}")))?;
+
+    signatures.push(Countersignature {
+        signer_identity: identity.to_string(),
+        public_key: String::from_utf8_lossy(&public_key).into_owned(),
+        alg: algorithm.as_str().to_string(),
+        signature: STANDARD.encode(&signature),
+    });
+    let data =
+        serde_json::to_value(&signatures).map_err(|e| Error::Serialization(e.to_string()))?;
+
+    claim.created_assertions.retain(|assertion| {
+        !matches!(assertion, Assertion::CustomAssertion(custom) if custom.label == COUNTERSIGNATURES_LABEL)
+    });
+    claim
+        .created_assertions
+        .push(Assertion::CustomAssertion(CustomAssertion {
+            label: COUNTERSIGNATURES_LABEL.to_string(),
+            data,
+        }));
+
+    // Keep the legacy claim field in step with claim_v2
+    manifest.claim = claim.clone();
+
+    Ok(())
+}
+
+/// Verifies every countersignature against the stable claim bytes and
+/// returns how many were checked
+pub fn verify_signatures(manifest: &Manifest) -> Result<usize> {
+    match &manifest.claim_v2 {
+        Some(claim) => verify_claim_signatures(claim),
+        None => Ok(0),
+    }
+}
+
+/// Like [`verify_signatures`], for a claim on its own
+pub fn verify_claim_signatures(claim: &ClaimV2) -> Result<usize> {
+    let signatures = claim_countersignatures(claim)?;
+    if signatures.is_empty() {
+        return Ok(0);
+    }
+
+    let claim_bytes = signed_claim_bytes(claim)?;
+    for countersignature in &signatures {
+        let identity = &countersignature.signer_identity;
+        let public_key = PKey::public_key_from_pem(countersignature.public_key.as_bytes())
+            .map_err(|e| Error::Validation(format!("Invalid public key for {identity}: {e}")))?;
+        let algorithm = DigestAlgorithm::from_str(&countersignature.alg)
+            .map_err(|e| Error::Validation(format!("Invalid algorithm for {identity}: {e}")))?;
+        let signature = STANDARD.decode(&countersignature.signature).map_err(|e| {
+            Error::Validation(format!("Invalid signature encoding for {identity}: {e}"))
+        })?;
+
+        if !signing::verify_signature_with_algorithm(
+            &claim_bytes,
+            &signature,
+            &public_key,
+            algorithm,
+        )
+        .unwrap_or(false)
+        {
+            return Err(SigningError::VerificationFailed(format!(
+                "Countersignature by {identity} does not match the claim"
+            ))
+            .into());
+        }
+    }
+
+    Ok(signatures.len())
+}
+
+/// Checks that every signature on the manifest was made by a trusted key.
+/// Signatures must already have been verified with [`verify_signatures`].
+pub fn verify_trusted_signers(
+    manifest: &Manifest,
+    trust_list: &TrustList,
+) -> Result<Vec<VerificationCheck>> {
+    let claim = manifest.claim_v2.as_ref().unwrap_or(&manifest.claim);
+    verify_trusted_claim_signers(claim, trust_list)
+}
+
+/// Like [`verify_trusted_signers`], for a claim on its own
+pub fn verify_trusted_claim_signers(
+    claim: &ClaimV2,
+    trust_list: &TrustList,
+) -> Result<Vec<VerificationCheck>> {
+    let not_trusted =
+        |detail: String| Error::Validation(format!("signer not in trust list: {detail}"));
+    let mut checks = Vec::new();
+
+    if let Some(signature) = &claim.signature {
+        let signature = STANDARD
+            .decode(signature)
+            .map_err(|e| Error::Validation(format!("Invalid claim signature encoding: {e}")))?;
+        let claim_bytes = signed_claim_bytes(claim)?;
+        // The claim does not record its hash algorithm, so try each one
+        let trusted_key = trust_list.keys().iter().find(|key| {
+            DigestAlgorithm::ALL.into_iter().any(|algorithm| {
+                signing::verify_signature_with_algorithm(&claim_bytes, &signature, key, algorithm)
+                    .unwrap_or(false)
+            })
+        });
+        let Some(key) = trusted_key else {
+            return Err(not_trusted(
+                "the claim signature does not verify with any trusted public key".to_string(),
+            ));
+        };
+        checks.push(VerificationCheck::verified(
+            "claim signature",
+            format!(
+                "signed by trusted key sha256:{}",
+                public_key_fingerprint(key)?
+            ),
+        ));
+    }
+
+    for countersignature in claim_countersignatures(claim)? {
+        let identity = &countersignature.signer_identity;
+        let public_key = PKey::public_key_from_pem(countersignature.public_key.as_bytes())
+            .map_err(|e| Error::Validation(format!("Invalid public key for {identity}: {e}")))?;
+        let fingerprint = public_key_fingerprint(&public_key)?;
+        if !trust_list.contains(&public_key)? {
+            return Err(not_trusted(format!("{identity} (sha256:{fingerprint})")));
+        }
+        checks.push(VerificationCheck::verified(
+            identity,
+            format!("key sha256:{fingerprint} is trusted"),
+        ));
+    }
+
+    if checks.is_empty() {
+        return Err(not_trusted("the manifest is not signed".to_string()));
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atlas_c2pa_lib::datetime_wrapper::OffsetDateTimeWrapper;
+    use openssl::rsa::Rsa;
+    use time::OffsetDateTime;
+
+    fn test_key() -> PKey<Private> {
+        PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap()
+    }
+
+    fn test_manifest() -> Manifest {
+        let claim = ClaimV2 {
+            instance_id: "urn:c2pa:claim".to_string(),
+            ingredients: vec![],
+            created_assertions: vec![],
+            claim_generator_info: "c2pa-cli".to_string(),
+            signature: None,
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::UNIX_EPOCH),
+        };
+        Manifest {
+            claim_generator: "c2pa-cli/0.1.0".to_string(),
+            title: "Reviewed".to_string(),
+            instance_id: "urn:c2pa:reviewed".to_string(),
+            ingredients: vec![],
+            claim: claim.clone(),
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::UNIX_EPOCH),
+            cross_references: vec![],
+            claim_v2: Some(claim),
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn test_multiple_signers() -> Result<()> {
+        let mut manifest = test_manifest();
+
+        // The original single signature covers the same stable bytes
+        let author_key = test_key();
+        let claim = manifest.claim_v2.as_mut().unwrap();
+        let signature = signing::sign_data(&signed_claim_bytes(claim)?, &author_key)?;
+        claim.signature = Some(STANDARD.encode(&signature));
+        let original_bytes = signed_claim_bytes(claim)?;
+
+        add_signature(&mut manifest, &test_key(), "data-scientist@example.com")?;
+        add_signature_with_algorithm(
+            &mut manifest,
+            &test_key(),
+            "release-manager@example.com",
+            DigestAlgorithm::Sha256,
+        )?;
+        add_signature_with_algorithm(
+            &mut manifest,
+            &test_key(),
+            "auditor@example.com",
+            DigestAlgorithm::Sha3_256,
+        )?;
+
+        assert_eq!(verify_signatures(&manifest)?, 3);
+        let claim = manifest.claim_v2.as_ref().unwrap();
+        assert_eq!(signed_claim_bytes(claim)?, original_bytes);
+        assert_eq!(
+            serde_json::to_value(&manifest.claim).unwrap(),
+            serde_json::to_value(claim).unwrap()
+        );
+
+        let identities: Vec<_> = countersignatures(&manifest)?
+            .into_iter()
+            .map(|s| s.signer_identity)
+            .collect();
+        assert_eq!(
+            identities,
+            [
+                "data-scientist@example.com",
+                "release-manager@example.com",
+                "auditor@example.com"
+            ]
+        );
+
+        // The same signer cannot sign twice
+        assert!(add_signature(&mut manifest, &test_key(), "data-scientist@example.com").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_countersignature_detects_tampering() -> Result<()> {
+        let mut manifest = test_manifest();
+        add_signature(&mut manifest, &test_key(), "reviewer")?;
+
+        manifest.claim_v2.as_mut().unwrap().claim_generator_info = "tampered".to_string();
+
+        match verify_signatures(&manifest) {
+            Err(Error::SigningFailure(SigningError::VerificationFailed(msg))) => {
+                assert!(msg.contains("reviewer"), "{msg}")
+            }
+            other => panic!("expected a countersignature failure, got {other:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsigned_manifest_has_no_countersignatures() -> Result<()> {
+        assert_eq!(verify_signatures(&test_manifest())?, 0);
+        Ok(())
+    }
+}
@@ -1,13 +1,26 @@
 use crate::error::Result;
-use crate::manifest::common::{AssetKind, list_manifests, verify_manifest};
-use crate::manifest::config::ManifestCreationConfig;
-use crate::storage::traits::StorageBackend;
+use crate::manifest::common::{AssetKind, list_manifests, verify_manifest_with_config};
+use crate::manifest::config::{ManifestCreationConfig, VerificationConfig};
+use crate::manifest::report::VerificationReport;
+use crate::storage::traits::{ManifestFilter, StorageBackend};
 
 pub fn create_manifest(
-    mut config: ManifestCreationConfig,
+    config: ManifestCreationConfig,
     software_type: String,
     version: Option<String>,
 ) -> Result<()> {
+    let config = with_software_fields(config, software_type, version);
+
+    // Call the common implementation with AssetKind::Software
+    crate::manifest::common::create_manifest(config, AssetKind::Software)
+}
+
+/// Records the software type and version in the config and its description
+pub(crate) fn with_software_fields(
+    mut config: ManifestCreationConfig,
+    software_type: String,
+    version: Option<String>,
+) -> ManifestCreationConfig {
     config.software_type = Some(software_type.clone());
     config.version = version.clone();
 
@@ -21,19 +34,32 @@ pub fn create_manifest(
 
     // Update the description in the config
     config.description = enhanced_description;
-
-    // Call the common implementation with AssetKind::Software
-    crate::manifest::common::create_manifest(config, AssetKind::Software)
+    config
 }
 
 /// List software manifests
-pub fn list_software_manifests(storage: &dyn StorageBackend) -> Result<()> {
+pub fn list_software_manifests(
+    storage: &dyn StorageBackend,
+    filter: &ManifestFilter,
+) -> Result<()> {
     // Call the unified implementation with AssetKind::Software
-    list_manifests(storage, Some(AssetKind::Software))
+    list_manifests(storage, Some(AssetKind::Software), filter)
 }
 
 /// Verify a software manifest
-pub fn verify_software_manifest(id: &str, storage: &dyn StorageBackend) -> Result<()> {
+pub fn verify_software_manifest(
+    id: &str,
+    storage: &dyn StorageBackend,
+) -> Result<VerificationReport> {
+    verify_software_manifest_with_config(id, storage, &VerificationConfig::default())
+}
+
+/// Verify a software manifest using the given verification options
+pub fn verify_software_manifest_with_config(
+    id: &str,
+    storage: &dyn StorageBackend,
+    config: &VerificationConfig,
+) -> Result<VerificationReport> {
     // Call the unified implementation
-    verify_manifest(id, storage)
+    verify_manifest_with_config(id, storage, config)
 }
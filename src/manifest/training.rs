@@ -0,0 +1,139 @@
+//! # Training Provenance
+//!
+//! A model manifest can declare the dataset manifests the model was trained
+//! on (`model create --trained-on <id>`). Each dataset is recorded in an
+//! `atlas.training` assertion of the claim with the SHA-256 hash of its
+//! canonical JSON, the same hash cross-references use, so the claim's
+//! signature covers exactly which dataset manifests were used.
+//!
+//! Verification retrieves every recorded dataset manifest and fails if one
+//! is missing (a warning with `--allow-dangling`) or its hash no longer
+//! matches.
+
+use crate::error::{Error, Result};
+use crate::manifest::canonical;
+use crate::manifest::common::retrieve_cross_reference_target;
+use crate::manifest::report::VerificationCheck;
+use crate::manifest::utils::determine_manifest_type;
+use crate::storage::traits::{ManifestType, StorageBackend};
+use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+use atlas_c2pa_lib::manifest::Manifest;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Label of the assertion listing the datasets a model was trained on
+pub const TRAINING_LABEL: &str = "atlas.training";
+
+/// A dataset manifest a model was trained on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingDataset {
+    pub manifest_id: String,
+    /// SHA-256 of the dataset manifest's canonical JSON when the model
+    /// manifest was created
+    pub manifest_hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TrainingData {
+    datasets: Vec<TrainingDataset>,
+}
+
+/// Builds the training assertion for the given dataset manifests, hashing
+/// each as currently stored.
+///
+/// Every ID must name a dataset manifest in `storage`.
+pub fn training_assertion(
+    dataset_ids: &[String],
+    storage: &(impl StorageBackend + ?Sized),
+) -> Result<Assertion> {
+    let mut datasets = Vec::with_capacity(dataset_ids.len());
+    for id in dataset_ids {
+        let dataset = retrieve_cross_reference_target(storage, id)?;
+        if determine_manifest_type(&dataset) != ManifestType::Dataset {
+            return Err(Error::Validation(format!(
+                "Training data {id} is a {} manifest, not a dataset manifest",
+                determine_manifest_type(&dataset)
+            )));
+        }
+        datasets.push(TrainingDataset {
+            manifest_id: id.clone(),
+            manifest_hash: manifest_hash(&dataset, id)?,
+        });
+    }
+
+    Ok(Assertion::CustomAssertion(CustomAssertion {
+        label: TRAINING_LABEL.to_string(),
+        data: serde_json::to_value(TrainingData { datasets })
+            .map_err(|e| Error::Serialization(e.to_string()))?,
+    }))
+}
+
+/// The datasets a manifest records as its training data
+pub fn training_datasets(manifest: &Manifest) -> Result<Vec<TrainingDataset>> {
+    let assertions = match &manifest.claim_v2 {
+        Some(claim) => &claim.created_assertions,
+        None => &manifest.claim.created_assertions,
+    };
+    let mut datasets = Vec::new();
+    for assertion in assertions {
+        if let Assertion::CustomAssertion(custom) = assertion
+            && custom.label == TRAINING_LABEL
+        {
+            let data: TrainingData = serde_json::from_value(custom.data.clone()).map_err(|e| {
+                Error::Validation(format!("Invalid {TRAINING_LABEL} assertion: {e}"))
+            })?;
+            datasets.extend(data.datasets);
+        }
+    }
+    Ok(datasets)
+}
+
+/// Checks that every recorded training dataset is still stored unchanged.
+///
+/// With `allow_dangling`, a missing dataset manifest gives a warning instead
+/// of an error; a changed one always fails.
+pub fn verify_training_datasets(
+    manifest: &Manifest,
+    storage: &(impl StorageBackend + ?Sized),
+    allow_dangling: bool,
+) -> Result<Vec<VerificationCheck>> {
+    let mut checks = Vec::new();
+    for dataset in training_datasets(manifest)? {
+        let id = &dataset.manifest_id;
+        let stored = match retrieve_cross_reference_target(storage, id) {
+            Ok(stored) => stored,
+            Err(Error::Validation(_)) if allow_dangling => {
+                checks.push(VerificationCheck::warning(
+                    id,
+                    "training dataset manifest not found",
+                ));
+                continue;
+            }
+            Err(Error::Validation(_)) => {
+                return Err(Error::Validation(format!(
+                    "Training dataset manifest {id} not found"
+                )));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let calculated_hash = manifest_hash(&stored, id)?;
+        if calculated_hash != dataset.manifest_hash {
+            return Err(Error::Validation(format!(
+                "Training dataset manifest {id} has changed. Hash mismatch: recorded={}, calculated={calculated_hash}",
+                dataset.manifest_hash
+            )));
+        }
+        checks.push(VerificationCheck::verified(
+            id,
+            "training dataset hash matches",
+        ));
+    }
+    Ok(checks)
+}
+
+fn manifest_hash(manifest: &Manifest, id: &str) -> Result<String> {
+    let json = canonical::to_canonical_json(manifest)
+        .map_err(|e| e.with_manifest_context("hashing training dataset", &manifest.title, id))?;
+    Ok(hex::encode(Sha256::digest(json.as_bytes())))
+}
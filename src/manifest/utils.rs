@@ -1,88 +1,109 @@
 use crate::error::{Error, Result};
+use crate::hash::decompress::Compression;
 use crate::storage::traits::ManifestType;
 use atlas_c2pa_lib::assertion::Assertion;
 use atlas_c2pa_lib::asset_type::AssetType;
 use atlas_c2pa_lib::manifest::Manifest;
+use std::collections::HashMap;
+use std::io::Read;
 use std::path::Path;
+use time::format_description::well_known::Rfc3339;
+
+/// What a file extension says about an asset: its asset type when it is a
+/// model or a dataset, and its media type
+#[derive(Debug, Clone)]
+pub struct AssetInfo {
+    pub extension: &'static str,
+    pub model_type: AssetType,
+    pub dataset_type: AssetType,
+    pub format: &'static str,
+}
 
-pub fn determine_model_type(path: &Path) -> Result<AssetType> {
-    match path.extension().and_then(|ext| ext.to_str()) {
-        // TensorFlow models
-        Some("pb") | Some("savedmodel") | Some("tf") => Ok(AssetType::ModelTensorFlow),
-
-        // PyTorch models
-        Some("pt") | Some("pth") | Some("pytorch") => Ok(AssetType::ModelPytorch),
-
-        // ONNX models
-        Some("onnx") => Ok(AssetType::ModelOnnx),
-
-        // OpenVINO models
-        Some("bin") | Some("xml") => Ok(AssetType::ModelOpenVino),
-
-        // Keras models
-        Some("h5") | Some("keras") | Some("hdf5") => Ok(AssetType::ModelKeras),
-
-        // JAX models
-        Some("jax") => Ok(AssetType::ModelJax),
-
-        // ML.NET models
-        Some("mlnet") | Some("zip") => Ok(AssetType::ModelMlNet),
-
-        // MXNet models
-        Some("params") | Some("json") | Some("mxnet") => Ok(AssetType::ModelMxNet),
-
-        // Format types
-        Some("npy") | Some("npz") => Ok(AssetType::FormatNumpy),
-        Some("protobuf") | Some("proto") => Ok(AssetType::FormatProtobuf),
-        Some("pkl") | Some("pickle") => Ok(AssetType::FormatPickle),
+const fn asset(
+    extension: &'static str,
+    model_type: AssetType,
+    dataset_type: AssetType,
+    format: &'static str,
+) -> AssetInfo {
+    AssetInfo {
+        extension,
+        model_type,
+        dataset_type,
+        format,
+    }
+}
 
-        // Default generic model when extension doesn't match
-        Some(_) => Ok(AssetType::Model),
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Known asset file extensions. Extensions not listed are generic models
+/// and datasets of type `application/octet-stream`.
+#[rustfmt::skip]
+const ASSET_MAPPINGS: &[AssetInfo] = {
+    use AssetType::*;
+    &[
+        // TensorFlow
+        asset("pb", ModelTensorFlow, DatasetTensorFlow, "application/x-protobuf"),
+        asset("savedmodel", ModelTensorFlow, Dataset, "application/x-tensorflow"),
+        asset("tf", ModelTensorFlow, DatasetTensorFlow, "application/x-tensorflow"),
+        asset("tfrecord", Model, DatasetTensorFlow, OCTET_STREAM),
+        asset("tfrec", Model, DatasetTensorFlow, OCTET_STREAM),
+        // PyTorch
+        asset("pt", ModelPytorch, DatasetPytorch, "application/x-pytorch"),
+        asset("pth", ModelPytorch, DatasetPytorch, "application/x-pytorch"),
+        asset("pytorch", ModelPytorch, DatasetPytorch, "application/x-pytorch"),
+        // ONNX
+        asset("onnx", ModelOnnx, DatasetOnnx, "application/onnx"),
+        // OpenVINO
+        asset("bin", ModelOpenVino, DatasetOpenVino, "application/x-openvino"),
+        asset("xml", ModelOpenVino, DatasetOpenVino, "application/x-openvino"),
+        // Keras
+        asset("h5", ModelKeras, DatasetKeras, "application/x-hdf5"),
+        asset("keras", ModelKeras, DatasetKeras, "application/x-hdf5"),
+        asset("hdf5", ModelKeras, DatasetKeras, "application/x-hdf5"),
+        // JAX
+        asset("jax", ModelJax, DatasetJax, "application/x-jax"),
+        // ML.NET
+        asset("mlnet", ModelMlNet, DatasetMlNet, "application/x-mlnet"),
+        asset("zip", ModelMlNet, DatasetMlNet, "application/zip"),
+        // MXNet
+        asset("params", ModelMxNet, DatasetMxNet, "application/x-mxnet"),
+        asset("mxnet", ModelMxNet, DatasetMxNet, "application/x-mxnet"),
+        asset("json", ModelMxNet, Dataset, "application/json"),
+        asset("rec", Model, DatasetMxNet, OCTET_STREAM),
+        asset("idx", Model, DatasetMxNet, OCTET_STREAM),
+        asset("lst", Model, DatasetMxNet, OCTET_STREAM),
+        // Framework-neutral formats
+        asset("npy", FormatNumpy, Dataset, "application/x-numpy"),
+        asset("npz", FormatNumpy, Dataset, "application/x-numpy"),
+        asset("protobuf", FormatProtobuf, Dataset, "application/x-protobuf"),
+        asset("proto", FormatProtobuf, DatasetTensorFlow, "application/x-protobuf"),
+        asset("pkl", FormatPickle, Dataset, "application/x-pickle"),
+        asset("pickle", FormatPickle, Dataset, "application/x-pickle"),
+    ]
+};
+
+/// Looks up what the file's extension says about it; `None` for files
+/// without an extension or with one that is not in the table
+pub fn get_asset_info(path: &Path) -> Option<&'static AssetInfo> {
+    let extension = path.extension()?.to_str()?;
+    ASSET_MAPPINGS
+        .iter()
+        .find(|info| info.extension == extension)
+}
 
-        // No extension
-        None => Err(Error::Validation(
+pub fn determine_model_type(path: &Path) -> Result<AssetType> {
+    if path.extension().and_then(|ext| ext.to_str()).is_none() {
+        return Err(Error::Validation(
             "Unsupported model format: file has no extension".to_string(),
-        )),
+        ));
     }
+    Ok(get_asset_info(path).map_or(AssetType::Model, |info| info.model_type.clone()))
 }
 
 pub fn determine_format(path: &Path) -> Result<String> {
-    match path.extension().and_then(|ext| ext.to_str()) {
-        // TensorFlow models
-        Some("pb") => Ok("application/x-protobuf".to_string()),
-        Some("savedmodel") | Some("tf") => Ok("application/x-tensorflow".to_string()),
-
-        // PyTorch models
-        Some("pt") | Some("pth") | Some("pytorch") => Ok("application/x-pytorch".to_string()),
-
-        // ONNX models
-        Some("onnx") => Ok("application/onnx".to_string()),
-
-        // OpenVINO models
-        Some("bin") | Some("xml") => Ok("application/x-openvino".to_string()),
-
-        // Keras models
-        Some("h5") | Some("keras") | Some("hdf5") => Ok("application/x-hdf5".to_string()),
-
-        // JAX models
-        Some("jax") => Ok("application/x-jax".to_string()),
-
-        // ML.NET models
-        Some("mlnet") => Ok("application/x-mlnet".to_string()),
-        Some("zip") => Ok("application/zip".to_string()),
-
-        // MXNet models
-        Some("params") | Some("mxnet") => Ok("application/x-mxnet".to_string()),
-        Some("json") => Ok("application/json".to_string()),
-
-        // Format types
-        Some("npy") | Some("npz") => Ok("application/x-numpy".to_string()),
-        Some("protobuf") | Some("proto") => Ok("application/x-protobuf".to_string()),
-        Some("pkl") | Some("pickle") => Ok("application/x-pickle".to_string()),
-
-        // Default
-        _ => Ok("application/octet-stream".to_string()),
-    }
+    Ok(get_asset_info(path)
+        .map_or(OCTET_STREAM, |info| info.format)
+        .to_string())
 }
 
 pub fn determine_software_type(path: &Path) -> Result<AssetType> {
@@ -125,66 +146,203 @@ pub fn determine_software_type(path: &Path) -> Result<AssetType> {
 }
 
 pub fn determine_dataset_type(path: &Path) -> Result<AssetType> {
-    match path.extension().and_then(|ext| ext.to_str()) {
-        // Common dataset formats
-        Some("csv") | Some("tsv") | Some("txt") => Ok(AssetType::Dataset),
-
-        // JSON-based datasets
-        Some("json") | Some("jsonl") => Ok(AssetType::Dataset),
-
-        // Parquet and other columnar formats
-        Some("parquet") | Some("orc") | Some("avro") => Ok(AssetType::Dataset),
-
-        // TensorFlow specific datasets
-        Some("tfrecord") | Some("tfrec") => Ok(AssetType::DatasetTensorFlow),
-        Some("pb") | Some("proto") | Some("tf") => Ok(AssetType::DatasetTensorFlow),
-
-        // PyTorch specific datasets
-        Some("pt") | Some("pth") | Some("pytorch") => Ok(AssetType::DatasetPytorch),
-
-        // ONNX specific datasets
-        Some("onnx") => Ok(AssetType::DatasetOnnx),
-
-        // OpenVINO specific datasets
-        Some("bin") | Some("xml") => Ok(AssetType::DatasetOpenVino),
-
-        // Keras specific datasets
-        Some("h5") | Some("hdf5") | Some("keras") => Ok(AssetType::DatasetKeras),
-
-        // JAX specific datasets
-        Some("jax") => Ok(AssetType::DatasetJax),
+    if path.extension().and_then(|ext| ext.to_str()).is_none() {
+        return Err(Error::Validation(
+            "Unsupported dataset format: file has no extension".to_string(),
+        ));
+    }
+    Ok(get_asset_info(path).map_or(AssetType::Dataset, |info| info.dataset_type.clone()))
+}
 
-        // ML.NET specific datasets
-        Some("mlnet") | Some("zip") => Ok(AssetType::DatasetMlNet),
+/// File formats recognised from their leading bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SniffedContent {
+    /// PyTorch checkpoint: a zip archive holding `data.pkl`, or the legacy
+    /// pickle format starting with PyTorch's magic number
+    PyTorch,
+    /// Zip archive of unknown contents (Keras 3, ML.NET, `.npz`, ...)
+    Zip,
+    Hdf5,
+    Onnx,
+    Pickle,
+    Numpy,
+    Parquet,
+}
 
-        // MXNet specific datasets
-        Some("rec") | Some("idx") | Some("params") | Some("lst") | Some("mxnet") => {
-            Ok(AssetType::DatasetMxNet)
+impl SniffedContent {
+    /// Media type recorded when the extension does not identify the format
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            SniffedContent::PyTorch => "application/x-pytorch",
+            SniffedContent::Zip => "application/zip",
+            SniffedContent::Hdf5 => "application/x-hdf5",
+            SniffedContent::Onnx => "application/onnx",
+            SniffedContent::Pickle => "application/x-pickle",
+            SniffedContent::Numpy => "application/x-numpy",
+            SniffedContent::Parquet => "application/vnd.apache.parquet",
         }
+    }
+}
 
-        // NumPy formats (could be any framework)
-        Some("npy") | Some("npz") => Ok(AssetType::Dataset),
+// Enough for a zip local file header and the name of its first entry
+const SNIFF_LEN: u64 = 512;
+
+/// Identifies a file's format from its first bytes.
+///
+/// Returns `None` when the content is not recognised or the file cannot be
+/// read, leaving detection to the extension.
+pub fn sniff_content(path: &Path) -> Option<SniffedContent> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut header = Vec::new();
+    file.take(SNIFF_LEN).read_to_end(&mut header).ok()?;
+    sniff_bytes(&header)
+}
 
-        // Pickle formats (could be any framework)
-        Some("pkl") | Some("pickle") => Ok(AssetType::Dataset),
+fn sniff_bytes(header: &[u8]) -> Option<SniffedContent> {
+    const PYTORCH_LEGACY_MAGIC: &[u8] = b"\x80\x02\x8a\x0a\x6c\xfc\x9c\x46\xf9\x20\x6a\xa8\x50\x19";
+    // Fields an ONNX ModelProto may start with after ir_version: producer
+    // name and version, domain, model version, doc string, graph, opsets
+    const ONNX_SECOND_FIELDS: [u8; 7] = [0x12, 0x1a, 0x22, 0x28, 0x32, 0x3a, 0x42];
+
+    if header.starts_with(b"PK\x03\x04") {
+        // PyTorch archives store the pickled object as `<name>/data.pkl`
+        let name_len = header
+            .get(26..28)
+            .map(|len| u16::from_le_bytes([len[0], len[1]]) as usize)?;
+        let name = header.get(30..30 + name_len).unwrap_or_default();
+        return Some(if name.ends_with(b"data.pkl") {
+            SniffedContent::PyTorch
+        } else {
+            SniffedContent::Zip
+        });
+    }
+    if header.starts_with(b"\x89HDF\r\n\x1a\n") {
+        return Some(SniffedContent::Hdf5);
+    }
+    if header.starts_with(b"\x93NUMPY") {
+        return Some(SniffedContent::Numpy);
+    }
+    if header.starts_with(b"PAR1") {
+        return Some(SniffedContent::Parquet);
+    }
+    if header.starts_with(PYTORCH_LEGACY_MAGIC) {
+        return Some(SniffedContent::PyTorch);
+    }
+    // Pickle protocols 2 and later open with PROTO <version>
+    if let [0x80, 2..=5, ..] = header {
+        return Some(SniffedContent::Pickle);
+    }
+    // ONNX models are protobufs beginning with the ir_version varint (field 1)
+    if let [0x08, 1..=20, next, ..] = header
+        && ONNX_SECOND_FIELDS.contains(next)
+    {
+        return Some(SniffedContent::Onnx);
+    }
+    None
+}
 
-        // Images and other media that might be datasets
-        Some("jpg") | Some("jpeg") | Some("png") | Some("bmp") | Some("tiff") => {
-            Ok(AssetType::Dataset)
+/// Determines a model's asset type, letting recognised content override the
+/// extension unless `sniff` is false
+pub fn detect_model_type(path: &Path, sniff: bool) -> Result<AssetType> {
+    let sniffed = sniff.then(|| sniff_content(path)).flatten();
+    match sniffed {
+        Some(SniffedContent::PyTorch) => Ok(AssetType::ModelPytorch),
+        Some(SniffedContent::Hdf5) => Ok(AssetType::ModelKeras),
+        Some(SniffedContent::Onnx) => Ok(AssetType::ModelOnnx),
+        Some(SniffedContent::Numpy) => Ok(AssetType::FormatNumpy),
+        Some(SniffedContent::Pickle) => Ok(AssetType::FormatPickle),
+        Some(SniffedContent::Zip) | Some(SniffedContent::Parquet) | None => {
+            determine_model_type(path)
         }
+    }
+}
 
-        // Default to generic dataset for any other extension
-        Some(_) => Ok(AssetType::Dataset),
-
-        // No extension
-        None => Err(Error::Validation(
-            "Unsupported dataset format: file has no extension".to_string(),
-        )),
+/// Determines a dataset's asset type, letting recognised content override
+/// the extension unless `sniff` is false
+pub fn detect_dataset_type(path: &Path, sniff: bool) -> Result<AssetType> {
+    let sniffed = sniff.then(|| sniff_content(path)).flatten();
+    match sniffed {
+        Some(SniffedContent::PyTorch) => Ok(AssetType::DatasetPytorch),
+        Some(SniffedContent::Hdf5) => Ok(AssetType::DatasetKeras),
+        Some(SniffedContent::Onnx) => Ok(AssetType::DatasetOnnx),
+        Some(SniffedContent::Numpy)
+        | Some(SniffedContent::Pickle)
+        | Some(SniffedContent::Parquet) => Ok(AssetType::Dataset),
+        Some(SniffedContent::Zip) | None => determine_dataset_type(path),
     }
 }
+/// Returns the user-defined tags recorded in a manifest's claim
+pub fn manifest_tags(manifest: &Manifest) -> Vec<String> {
+    manifest
+        .claim_v2
+        .iter()
+        .flat_map(|claim| claim.created_assertions.iter())
+        .chain(manifest.claim.created_assertions.iter())
+        .find_map(|assertion| match assertion {
+            Assertion::CustomAssertion(custom)
+                if custom.label == crate::manifest::common::TAGS_LABEL =>
+            {
+                serde_json::from_value(custom.data.clone()).ok()
+            }
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the URLs of ingredients whose hash covers their decompressed
+/// content, with the compression to undo before re-hashing
+pub fn decompressed_ingredients(manifest: &Manifest) -> HashMap<String, Compression> {
+    let entries: Vec<serde_json::Value> = manifest
+        .claim_v2
+        .iter()
+        .flat_map(|claim| claim.created_assertions.iter())
+        .chain(manifest.claim.created_assertions.iter())
+        .find_map(|assertion| match assertion {
+            Assertion::CustomAssertion(custom)
+                if custom.label == crate::manifest::common::DECOMPRESSED_HASHES_LABEL =>
+            {
+                serde_json::from_value(custom.data.clone()).ok()
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let url = entry["url"].as_str()?;
+            let compression = Compression::parse(entry["compression"].as_str()?).ok()?;
+            Some((url.to_string(), compression))
+        })
+        .collect()
+}
+
+/// Returns a manifest's creation time as RFC 3339, as listed by storage
+/// backends
+pub fn manifest_created_at(manifest: &Manifest) -> String {
+    manifest
+        .created_at
+        .0
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| manifest.created_at.0.to_string())
+}
+
 /// This function examines the ingredients and assertions in the manifest
-/// to determine whether it's a Dataset, Model, Software, or other type.
+/// to determine whether it's a Dataset, Model, Software, Evaluation, or other type.
 pub fn determine_manifest_type(manifest: &Manifest) -> ManifestType {
+    // Evaluation results list the evaluated model and dataset as ingredients,
+    // so they must be recognized before the ingredient-based checks
+    let has_evaluation_assertion = manifest
+        .claim_v2
+        .iter()
+        .chain(std::iter::once(&manifest.claim))
+        .flat_map(|claim| &claim.created_assertions)
+        .any(|assertion| {
+            matches!(assertion, Assertion::CreativeWork(creative_work) if creative_work.creative_type == "EvaluationResult")
+        });
+    if has_evaluation_assertion {
+        return ManifestType::Evaluation;
+    }
+
     // Check for Dataset assertion
     let has_dataset_assertion = (if let Some(claim) = &manifest.claim_v2 {
         claim.created_assertions.iter().any(|assertion| {
@@ -334,21 +492,59 @@ pub fn manifest_type_to_string(manifest_type: &ManifestType) -> String {
 
 /// Get the manifest type as a static string
 pub fn manifest_type_to_str(manifest_type: &ManifestType) -> &'static str {
-    match manifest_type {
-        ManifestType::Dataset => "Dataset",
-        ManifestType::Model => "Model",
-        ManifestType::Software => "Software",
-        ManifestType::Unknown => "Unknown",
-    }
+    manifest_type.as_str()
 }
 
-/// Convert a manifest type string to ManifestType enum
+/// Convert a manifest type string to ManifestType enum, mapping
+/// unrecognized strings to `ManifestType::Unknown`
 pub fn parse_manifest_type(type_str: &str) -> ManifestType {
-    match type_str.to_lowercase().as_str() {
-        "dataset" => ManifestType::Dataset,
-        "software" => ManifestType::Software,
-        _ => ManifestType::Model, // Default to Model for unknown types
-    }
+    type_str.parse().unwrap_or(ManifestType::Unknown)
+}
+
+/// Parse an asset type name, e.g. `model.onnx` or `c2pa.types.model.onnx`
+pub fn parse_asset_type(type_str: &str) -> Result<AssetType> {
+    let name = type_str.trim().to_lowercase();
+    let full_name = if name.starts_with("c2pa.types.") {
+        name
+    } else {
+        format!("c2pa.types.{name}")
+    };
+
+    serde_json::from_value(serde_json::Value::String(full_name)).map_err(|_| {
+        Error::Validation(format!(
+            "Invalid asset type '{type_str}'. Expected a C2PA asset type such as dataset, model.onnx or format.numpy"
+        ))
+    })
+}
+
+/// Ingredient relationships defined by C2PA
+pub const INGREDIENT_RELATIONSHIPS: &[&str] = &["componentOf", "parentOf", "inputTo"];
+
+/// Relationship recorded for ingredients that do not specify one
+pub const DEFAULT_RELATIONSHIP: &str = "componentOf";
+
+/// Parse an ingredient relationship, ignoring case
+///
+/// # Examples
+///
+/// ```
+/// use atlas_cli::manifest::utils::parse_relationship;
+///
+/// assert_eq!(parse_relationship("parentof").unwrap(), "parentOf");
+/// assert!(parse_relationship("derivedFrom").is_err());
+/// ```
+pub fn parse_relationship(relationship: &str) -> Result<String> {
+    let relationship = relationship.trim();
+    INGREDIENT_RELATIONSHIPS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(relationship))
+        .map(|known| known.to_string())
+        .ok_or_else(|| {
+            Error::Validation(format!(
+                "Invalid ingredient relationship '{relationship}'. Valid options are: {}",
+                INGREDIENT_RELATIONSHIPS.join(", ")
+            ))
+        })
 }
 
 #[cfg(test)]
@@ -489,6 +685,18 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_get_asset_info() {
+        let info = get_asset_info(&PathBuf::from("weights.h5")).unwrap();
+        assert_eq!(info.model_type, AssetType::ModelKeras);
+        assert_eq!(info.dataset_type, AssetType::DatasetKeras);
+        assert_eq!(info.format, "application/x-hdf5");
+
+        // Unknown and missing extensions fall back to the generic defaults
+        assert!(get_asset_info(&PathBuf::from("data.csv")).is_none());
+        assert!(get_asset_info(&PathBuf::from("Makefile")).is_none());
+    }
+
     #[test]
     fn test_determine_format() -> Result<()> {
         // Test TensorFlow formats
@@ -918,6 +1126,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_determine_evaluation_manifest_type() {
+        // Evaluation manifests carry Model and Dataset ingredients
+        let mut manifest = create_test_manifest();
+        manifest.ingredients[0].data.data_types = vec![AssetType::Dataset];
+
+        let evaluation_assertion = Assertion::CreativeWork(CreativeWorkAssertion {
+            context: "http://schema.org/".to_string(),
+            creative_type: "EvaluationResult".to_string(),
+            author: vec![],
+        });
+        if let Some(claim) = &mut manifest.claim_v2 {
+            claim.created_assertions = vec![evaluation_assertion];
+        }
+
+        assert_eq!(
+            determine_manifest_type(&manifest),
+            crate::storage::traits::ManifestType::Evaluation
+        );
+    }
+
     #[test]
     fn test_determine_manifest_type_with_action_parameters() {
         // Create a basic manifest
@@ -977,11 +1206,19 @@ mod tests {
         assert_eq!(manifest_type_to_string(&ManifestType::Dataset), "Dataset");
         assert_eq!(manifest_type_to_string(&ManifestType::Model), "Model");
         assert_eq!(manifest_type_to_string(&ManifestType::Software), "Software");
+        assert_eq!(
+            manifest_type_to_string(&ManifestType::Evaluation),
+            "Evaluation"
+        );
 
         // Test conversion to str
         assert_eq!(manifest_type_to_str(&ManifestType::Dataset), "Dataset");
         assert_eq!(manifest_type_to_str(&ManifestType::Model), "Model");
         assert_eq!(manifest_type_to_str(&ManifestType::Software), "Software");
+        assert_eq!(
+            manifest_type_to_str(&ManifestType::Evaluation),
+            "Evaluation"
+        );
 
         // Test parsing from string
         assert_eq!(parse_manifest_type("dataset"), ManifestType::Dataset);
@@ -992,10 +1229,34 @@ mod tests {
         assert_eq!(parse_manifest_type("Software"), ManifestType::Software);
         assert_eq!(parse_manifest_type("SOFTWARE"), ManifestType::Software);
 
-        // Test default to Model for unknown types
+        assert_eq!(parse_manifest_type("evaluation"), ManifestType::Evaluation);
+        assert_eq!(parse_manifest_type("Evaluation"), ManifestType::Evaluation);
+
         assert_eq!(parse_manifest_type("model"), ManifestType::Model);
-        assert_eq!(parse_manifest_type("unknown"), ManifestType::Model);
-        assert_eq!(parse_manifest_type(""), ManifestType::Model);
+
+        // Unrecognized types stay Unknown rather than defaulting to Model
+        assert_eq!(parse_manifest_type("unknown"), ManifestType::Unknown);
+        assert_eq!(parse_manifest_type(""), ManifestType::Unknown);
+
+        // Every type round-trips through its string and serialized forms
+        for manifest_type in [
+            ManifestType::Dataset,
+            ManifestType::Model,
+            ManifestType::Software,
+            ManifestType::Evaluation,
+            ManifestType::Unknown,
+        ] {
+            assert_eq!(
+                parse_manifest_type(&manifest_type_to_string(&manifest_type)),
+                manifest_type
+            );
+            let json = serde_json::to_string(&manifest_type).unwrap();
+            assert_eq!(json, format!("\"{manifest_type}\""));
+            assert_eq!(
+                serde_json::from_str::<ManifestType>(&json).unwrap(),
+                manifest_type
+            );
+        }
     }
 
     // Helper function to create a test manifest
@@ -1053,4 +1314,77 @@ mod tests {
             is_active: true,
         }
     }
+
+    #[test]
+    fn test_parse_asset_type() -> Result<()> {
+        assert_eq!(parse_asset_type("model.onnx")?, AssetType::ModelOnnx);
+        assert_eq!(parse_asset_type("Dataset")?, AssetType::Dataset);
+        assert_eq!(
+            parse_asset_type("c2pa.types.dataset.pytorch")?,
+            AssetType::DatasetPytorch
+        );
+        assert_eq!(parse_asset_type("format.numpy")?, AssetType::FormatNumpy);
+        assert!(parse_asset_type("model.unknown").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sniff_bytes() {
+        let mut torch_zip = b"PK\x03\x04".to_vec();
+        torch_zip.extend_from_slice(&[0; 22]);
+        torch_zip.extend_from_slice(&16u16.to_le_bytes());
+        torch_zip.extend_from_slice(&[0; 2]);
+        torch_zip.extend_from_slice(b"archive/data.pkl");
+        assert_eq!(sniff_bytes(&torch_zip), Some(SniffedContent::PyTorch));
+
+        let mut other_zip = torch_zip.clone();
+        other_zip.truncate(30);
+        other_zip.extend_from_slice(b"config.json.....");
+        assert_eq!(sniff_bytes(&other_zip), Some(SniffedContent::Zip));
+
+        assert_eq!(
+            sniff_bytes(b"\x89HDF\r\n\x1a\n\x00\x00"),
+            Some(SniffedContent::Hdf5)
+        );
+        assert_eq!(
+            sniff_bytes(b"\x08\x07\x12\x07pytorch"),
+            Some(SniffedContent::Onnx)
+        );
+        assert_eq!(
+            sniff_bytes(b"\x80\x04\x95\x10\x00"),
+            Some(SniffedContent::Pickle)
+        );
+        assert_eq!(
+            sniff_bytes(b"\x93NUMPY\x01\x00"),
+            Some(SniffedContent::Numpy)
+        );
+        assert_eq!(sniff_bytes(b"id,label\n1,cat\n"), None);
+        assert_eq!(sniff_bytes(b""), None);
+    }
+
+    #[test]
+    fn test_detect_model_type_sniffs_content() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        // An HDF5 file without an extension is detected from its signature
+        let keras = dir.path().join("checkpoint");
+        std::fs::write(&keras, b"\x89HDF\r\n\x1a\nrest of file")?;
+        assert_eq!(detect_model_type(&keras, true)?, AssetType::ModelKeras);
+        assert_eq!(detect_dataset_type(&keras, true)?, AssetType::DatasetKeras);
+        assert!(detect_model_type(&keras, false).is_err());
+
+        // Recognised content overrides a misleading extension
+        let onnx = dir.path().join("weights.bin");
+        std::fs::write(&onnx, b"\x08\x08\x12\x07pytorch")?;
+        assert_eq!(detect_model_type(&onnx, true)?, AssetType::ModelOnnx);
+        assert_eq!(detect_model_type(&onnx, false)?, AssetType::ModelOpenVino);
+
+        // Unrecognised content falls back to the extension
+        let text = dir.path().join("model.pt");
+        std::fs::write(&text, b"not a checkpoint")?;
+        assert_eq!(detect_model_type(&text, true)?, AssetType::ModelPytorch);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,200 @@
+//! # Validity Windows
+//!
+//! Models with a limited approved-use period record a validity window in an
+//! `atlas.validity` assertion of their claim. Both bounds are optional RFC
+//! 3339 timestamps: `valid_from` is inclusive and `valid_until` exclusive.
+//!
+//! Verification rejects a manifest outside its window. An expired manifest
+//! can be downgraded to a warning (`--allow-expired`), e.g. to audit models
+//! that have been retired; a manifest that is not valid yet always fails.
+
+use crate::error::{Error, Result};
+use crate::manifest::report::VerificationCheck;
+use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+use atlas_c2pa_lib::datetime_wrapper::OffsetDateTimeWrapper;
+use atlas_c2pa_lib::manifest::Manifest;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+/// Label of the assertion recording a manifest's validity window
+pub const VALIDITY_LABEL: &str = "atlas.validity";
+
+/// Period in which a manifest may be relied on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidityWindow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<OffsetDateTimeWrapper>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<OffsetDateTimeWrapper>,
+}
+
+impl ValidityWindow {
+    /// A window with the given bounds, or `None` if neither is set
+    pub fn new(
+        valid_from: Option<OffsetDateTime>,
+        valid_until: Option<OffsetDateTime>,
+    ) -> Result<Option<Self>> {
+        if let (Some(from), Some(until)) = (valid_from, valid_until)
+            && from >= until
+        {
+            return Err(Error::Validation(format!(
+                "The validity window is empty: valid_from {} is not before valid_until {}",
+                rfc3339(from),
+                rfc3339(until)
+            )));
+        }
+        Ok(
+            (valid_from.is_some() || valid_until.is_some()).then_some(Self {
+                valid_from: valid_from.map(OffsetDateTimeWrapper),
+                valid_until: valid_until.map(OffsetDateTimeWrapper),
+            }),
+        )
+    }
+
+    /// The assertion recording this window
+    pub fn to_assertion(&self) -> Result<Assertion> {
+        Ok(Assertion::CustomAssertion(CustomAssertion {
+            label: VALIDITY_LABEL.to_string(),
+            data: serde_json::to_value(self).map_err(|e| Error::Serialization(e.to_string()))?,
+        }))
+    }
+
+    /// The window recorded in a manifest's claim, if any
+    pub fn from_manifest(manifest: &Manifest) -> Result<Option<Self>> {
+        let assertions = match &manifest.claim_v2 {
+            Some(claim) => &claim.created_assertions,
+            None => &manifest.claim.created_assertions,
+        };
+        assertions
+            .iter()
+            .find_map(|assertion| match assertion {
+                Assertion::CustomAssertion(custom) if custom.label == VALIDITY_LABEL => {
+                    Some(&custom.data)
+                }
+                _ => None,
+            })
+            .map(|data| {
+                serde_json::from_value(data.clone()).map_err(|e| {
+                    Error::Validation(format!("Invalid {VALIDITY_LABEL} assertion: {e}"))
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Checks a manifest's validity window against `now`.
+///
+/// Returns `None` if the manifest has no window. With `allow_expired`, an
+/// expired manifest gives a warning instead of an error.
+pub fn check_validity(
+    manifest: &Manifest,
+    now: OffsetDateTime,
+    allow_expired: bool,
+) -> Result<Option<VerificationCheck>> {
+    let Some(window) = ValidityWindow::from_manifest(manifest)? else {
+        return Ok(None);
+    };
+
+    if let Some(OffsetDateTimeWrapper(from)) = window.valid_from
+        && now < from
+    {
+        return Err(Error::Validation(format!(
+            "manifest not valid yet: valid from {}",
+            rfc3339(from)
+        )));
+    }
+    if let Some(OffsetDateTimeWrapper(until)) = window.valid_until
+        && now >= until
+    {
+        if !allow_expired {
+            return Err(Error::Validation(format!(
+                "manifest expired: valid until {}",
+                rfc3339(until)
+            )));
+        }
+        return Ok(Some(VerificationCheck::warning(
+            &manifest.instance_id,
+            format!("manifest expired at {}", rfc3339(until)),
+        )));
+    }
+
+    let message = match window.valid_until {
+        Some(OffsetDateTimeWrapper(until)) => format!("valid until {}", rfc3339(until)),
+        None => "within its validity window".to_string(),
+    };
+    Ok(Some(VerificationCheck::verified(
+        &manifest.instance_id,
+        message,
+    )))
+}
+
+fn rfc3339(time: OffsetDateTime) -> String {
+    time.format(&Rfc3339).unwrap_or_else(|_| time.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::report::CheckStatus;
+    use atlas_c2pa_lib::claim::ClaimV2;
+    use time::Duration;
+
+    fn manifest_with_window(window: Option<ValidityWindow>) -> Manifest {
+        let claim = ClaimV2 {
+            instance_id: "urn:c2pa:claim".to_string(),
+            ingredients: vec![],
+            created_assertions: window
+                .map(|window| window.to_assertion().unwrap())
+                .into_iter()
+                .collect(),
+            claim_generator_info: "atlas-cli".to_string(),
+            signature: None,
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::UNIX_EPOCH),
+        };
+        Manifest {
+            claim_generator: "atlas-cli/0.1.0".to_string(),
+            title: "Approved Model".to_string(),
+            instance_id: "urn:c2pa:approved".to_string(),
+            ingredients: vec![],
+            claim: claim.clone(),
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::UNIX_EPOCH),
+            cross_references: vec![],
+            claim_v2: Some(claim),
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn test_check_validity_window() -> Result<()> {
+        let from = OffsetDateTime::UNIX_EPOCH + Duration::days(10);
+        let until = from + Duration::days(30);
+        let manifest = manifest_with_window(ValidityWindow::new(Some(from), Some(until))?);
+
+        let check = check_validity(&manifest, from, false)?.unwrap();
+        assert_eq!(check.status, CheckStatus::Verified);
+
+        // Not valid before the window, whether or not expiry is allowed
+        assert!(check_validity(&manifest, from - Duration::seconds(1), true).is_err());
+
+        // valid_until is exclusive; --allow-expired downgrades expiry to a warning
+        assert!(matches!(
+            check_validity(&manifest, until, false),
+            Err(Error::Validation(msg)) if msg.starts_with("manifest expired")
+        ));
+        let check = check_validity(&manifest, until, true)?.unwrap();
+        assert_eq!(check.status, CheckStatus::Warning);
+
+        // Manifests without a window are not checked
+        assert!(check_validity(&manifest_with_window(None), until, false)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_validity_window_rejected() {
+        let now = OffsetDateTime::UNIX_EPOCH;
+        assert!(ValidityWindow::new(Some(now), Some(now)).is_err());
+        assert!(matches!(ValidityWindow::new(None, None), Ok(None)));
+    }
+}
@@ -0,0 +1,212 @@
+//! # Manifest Schema Versions
+//!
+//! `Manifest` comes from `atlas-c2pa-lib` and has no version field, so each
+//! manifest created by this CLI records its schema version and the CLI
+//! version in an `atlas.manifest_version` assertion of its claim.
+//!
+//! Stored manifests are read through [`migrate_manifest`], which upgrades
+//! known older JSON shapes before deserializing them, so manifests written
+//! by earlier versions keep loading when the structure evolves. Manifests
+//! without the assertion are treated as version 0.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::version::migrate_manifest;
+//! use serde_json::json;
+//!
+//! let claim = json!({
+//!     "instance_id": "urn:c2pa:claim",
+//!     "created_assertions": [],
+//!     "ingredients": [],
+//!     "signature": null,
+//!     "claim_generator_info": "c2pa-cli",
+//!     "created_at": "2024-01-01T00:00:00Z",
+//! });
+//! // A version 0 manifest without cross_references or is_active
+//! let manifest = migrate_manifest(json!({
+//!     "claim_generator": "c2pa-cli/0.1.0",
+//!     "title": "Old Model",
+//!     "instance_id": "urn:c2pa:old",
+//!     "ingredients": [],
+//!     "claim": claim,
+//!     "created_at": "2024-01-01T00:00:00Z",
+//! }))
+//! .unwrap();
+//! assert!(manifest.is_active);
+//! assert!(manifest.cross_references.is_empty());
+//! ```
+
+use crate::error::{Error, Result};
+use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+use atlas_c2pa_lib::manifest::Manifest;
+use serde_json::{Value, json};
+
+/// Label of the assertion recording the schema and generator versions
+pub const MANIFEST_VERSION_LABEL: &str = "atlas.manifest_version";
+
+/// Schema version of the manifests this CLI creates
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// The version assertion added to every new manifest's claim
+pub fn version_assertion() -> Assertion {
+    Assertion::CustomAssertion(CustomAssertion {
+        label: MANIFEST_VERSION_LABEL.to_string(),
+        data: json!({
+            "schema_version": SCHEMA_VERSION,
+            "generator": env!("CARGO_PKG_NAME"),
+            "generator_version": env!("CARGO_PKG_VERSION"),
+        }),
+    })
+}
+
+/// Schema version recorded in a manifest's JSON, or 0 if it has none
+pub fn schema_version(value: &Value) -> u64 {
+    ["c2pa.claim.v2", "claim"]
+        .iter()
+        .filter_map(|claim| value.get(claim)?.get("created_assertions")?.as_array())
+        .flatten()
+        .filter_map(|assertion| assertion.get("CustomAssertion"))
+        .find(|custom| custom.get("label").and_then(Value::as_str) == Some(MANIFEST_VERSION_LABEL))
+        .and_then(|custom| custom.get("data")?.get("schema_version")?.as_u64())
+        .unwrap_or(0)
+}
+
+/// Upgrades a manifest's JSON from its recorded schema version to the
+/// current one and deserializes it
+pub fn migrate_manifest(mut value: Value) -> Result<Manifest> {
+    let version = schema_version(&value);
+    if version > SCHEMA_VERSION {
+        return Err(Error::Serialization(format!(
+            "Manifest schema version {version} is newer than the supported version {SCHEMA_VERSION}; upgrade atlas-cli to read it"
+        )));
+    }
+    if version < 1 {
+        migrate_v0(&mut value);
+    }
+
+    serde_json::from_value(value).map_err(|e| {
+        Error::Serialization(format!(
+            "Failed to parse manifest (schema version {version}): {e}"
+        ))
+    })
+}
+
+/// Version 0 manifests may lack `cross_references` and `is_active`, have
+/// only a `c2pa.claim.v2` claim, and record ingredient formats as `format`
+/// instead of `dc:format`
+fn migrate_v0(value: &mut Value) {
+    let Some(manifest) = value.as_object_mut() else {
+        return;
+    };
+
+    manifest
+        .entry("cross_references")
+        .or_insert_with(|| json!([]));
+    manifest.entry("is_active").or_insert(Value::Bool(true));
+    manifest.entry("ingredients").or_insert_with(|| json!([]));
+
+    if !manifest.contains_key("claim")
+        && let Some(claim) = manifest
+            .get("c2pa.claim.v2")
+            .filter(|claim| !claim.is_null())
+    {
+        manifest.insert("claim".to_string(), claim.clone());
+    }
+
+    let mut ingredient_lists: Vec<&mut Value> = Vec::new();
+    for (key, field) in manifest.iter_mut() {
+        match key.as_str() {
+            "ingredients" => ingredient_lists.push(field),
+            "claim" | "c2pa.claim.v2" => {
+                if let Some(ingredients) = field.get_mut("ingredients") {
+                    ingredient_lists.push(ingredients);
+                }
+            }
+            _ => {}
+        }
+    }
+    for ingredient in ingredient_lists
+        .into_iter()
+        .filter_map(Value::as_array_mut)
+        .flatten()
+        .filter_map(Value::as_object_mut)
+    {
+        if !ingredient.contains_key("dc:format")
+            && let Some(format) = ingredient.remove("format")
+        {
+            ingredient.insert("dc:format".to_string(), format);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim() -> Value {
+        json!({
+            "instance_id": "urn:c2pa:claim",
+            "created_assertions": [],
+            "ingredients": [{
+                "title": "weights",
+                "relationship": "componentOf",
+                "format": "application/onnx",
+                "document_id": "uuid:doc",
+                "instance_id": "uuid:instance",
+                "data": {
+                    "url": "file:///weights.onnx",
+                    "alg": "sha384",
+                    "hash": "00",
+                    "data_types": ["c2pa.types.model.onnx"],
+                },
+            }],
+            "signature": null,
+            "claim_generator_info": "c2pa-cli",
+            "created_at": "2024-01-01T00:00:00Z",
+        })
+    }
+
+    #[test]
+    fn test_migrate_v0_manifest() -> Result<()> {
+        let old = json!({
+            "claim_generator": "c2pa-cli/0.1.0",
+            "title": "Old Model",
+            "instance_id": "urn:c2pa:old",
+            "ingredients": claim()["ingredients"],
+            "c2pa.claim.v2": claim(),
+            "created_at": "2024-01-01T00:00:00Z",
+        });
+        // The old shape does not deserialize directly
+        assert!(serde_json::from_value::<Manifest>(old.clone()).is_err());
+
+        let manifest = migrate_manifest(old)?;
+        assert!(manifest.is_active);
+        assert_eq!(manifest.ingredients[0].format, "application/onnx");
+        assert_eq!(manifest.claim.ingredients[0].format, "application/onnx");
+        assert_eq!(
+            manifest.claim_v2.unwrap().ingredients[0].format,
+            "application/onnx"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_schema() {
+        let mut claim = claim();
+        claim["created_assertions"] = json!([{
+            "CustomAssertion": {
+                "label": MANIFEST_VERSION_LABEL,
+                "data": { "schema_version": SCHEMA_VERSION + 1 },
+            }
+        }]);
+        let newer = json!({ "claim": claim });
+
+        assert_eq!(schema_version(&newer), SCHEMA_VERSION + 1);
+        assert!(matches!(
+            migrate_manifest(newer),
+            Err(Error::Serialization(msg)) if msg.contains("newer")
+        ));
+    }
+}
@@ -0,0 +1,227 @@
+//! # Watching Ingredients
+//!
+//! For a local development loop, `manifest watch` re-verifies a manifest
+//! whenever one of its local file ingredients changes. [`FileWatcher`]
+//! detects changes by polling each file's size and modification time rather
+//! than through OS notifications, so it behaves the same on every platform
+//! and on network filesystems, and a file that is deleted and recreated
+//! (as many editors do when saving) is simply reported again once it is back.
+//!
+//! Changes are debounced: [`FileWatcher::wait`] returns only after the files
+//! have stopped changing for a while, so a burst of writes triggers a single
+//! verification.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::manifest::watch::{ChangeKind, FileWatcher};
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let path = dir.path().join("weights.bin");
+//! let mut watcher = FileWatcher::new(vec![path.clone()]);
+//!
+//! std::fs::write(&path, b"weights").unwrap();
+//! let changes = watcher.changes();
+//! assert_eq!(changes[0].kind, ChangeKind::Created);
+//! ```
+
+use crate::cancel;
+use crate::error::Result;
+use crate::manifest::common::STDIN_URL_SCHEME;
+use atlas_c2pa_lib::manifest::Manifest;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How a watched file changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Modified => "modified",
+            Self::Removed => "removed",
+        }
+    }
+}
+
+/// A change to one watched file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub kind: ChangeKind,
+}
+
+// What is compared between polls; `None` in the map is a missing file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileState {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Polls a set of files for creation, modification and removal
+#[derive(Debug)]
+pub struct FileWatcher {
+    paths: Vec<PathBuf>,
+    states: HashMap<PathBuf, Option<FileState>>,
+}
+
+impl FileWatcher {
+    /// Starts watching `paths` as they are now
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let states = paths
+            .iter()
+            .map(|path| (path.clone(), file_state(path)))
+            .collect();
+        Self { paths, states }
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// The files that changed since the last call (or since the watcher was
+    /// created)
+    pub fn changes(&mut self) -> Vec<FileChange> {
+        let mut changes = Vec::new();
+        for path in &self.paths {
+            let current = file_state(path);
+            let previous = self.states.insert(path.clone(), current).flatten();
+            if let Some(kind) = change_kind(previous, current) {
+                changes.push(FileChange {
+                    path: path.clone(),
+                    kind,
+                });
+            }
+        }
+        changes
+    }
+
+    /// Blocks until the files change and then stay unchanged for `debounce`,
+    /// checking every `poll_interval`, and returns how each file changed
+    /// overall.
+    ///
+    /// Returns [`crate::error::Error::Cancelled`] once the process-wide
+    /// cancellation token is cancelled.
+    pub fn wait(&mut self, poll_interval: Duration, debounce: Duration) -> Result<Vec<FileChange>> {
+        let before = self.states.clone();
+        let mut last_change: Option<Instant> = None;
+        loop {
+            cancel::check()?;
+            std::thread::sleep(poll_interval);
+            if !self.changes().is_empty() {
+                last_change = Some(Instant::now());
+                continue;
+            }
+            let Some(changed_at) = last_change else {
+                continue;
+            };
+            if changed_at.elapsed() < debounce {
+                continue;
+            }
+
+            // A file that was changed and then restored is not reported
+            let changes: Vec<FileChange> = self
+                .paths
+                .iter()
+                .filter_map(|path| {
+                    let kind = change_kind(before[path], self.states[path])?;
+                    Some(FileChange {
+                        path: path.clone(),
+                        kind,
+                    })
+                })
+                .collect();
+            if !changes.is_empty() {
+                return Ok(changes);
+            }
+            last_change = None;
+        }
+    }
+}
+
+/// The local files a manifest's ingredients were hashed from, without
+/// duplicates. Stdin and remote ingredients are left out.
+pub fn ingredient_paths(manifest: &Manifest) -> Vec<PathBuf> {
+    manifest
+        .ingredients
+        .iter()
+        .map(|ingredient| ingredient.data.url.as_str())
+        .filter(|url| {
+            url.starts_with("file://")
+                || !(url.contains("://") || url.starts_with(STDIN_URL_SCHEME))
+        })
+        .map(|url| PathBuf::from(url.trim_start_matches("file://")))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+fn file_state(path: &Path) -> Option<FileState> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some(FileState {
+        len: metadata.len(),
+        modified: metadata.modified().ok(),
+    })
+}
+
+fn change_kind(previous: Option<FileState>, current: Option<FileState>) -> Option<ChangeKind> {
+    match (previous, current) {
+        (None, Some(_)) => Some(ChangeKind::Created),
+        (Some(_), None) => Some(ChangeKind::Removed),
+        (Some(previous), Some(current)) if previous != current => Some(ChangeKind::Modified),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_file_watcher_reports_each_kind_of_change() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, b"a,b")?;
+        let mut watcher = FileWatcher::new(vec![path.clone()]);
+        assert!(watcher.changes().is_empty());
+
+        std::fs::write(&path, b"a,b,c")?;
+        assert_eq!(watcher.changes()[0].kind, ChangeKind::Modified);
+        assert!(watcher.changes().is_empty());
+
+        std::fs::remove_file(&path)?;
+        assert_eq!(watcher.changes()[0].kind, ChangeKind::Removed);
+        std::fs::write(&path, b"a,b")?;
+        assert_eq!(watcher.changes()[0].kind, ChangeKind::Created);
+
+        // A burst of writes is reported once, after it settles
+        let writer = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                for content in ["1", "12", "123"] {
+                    std::fs::write(&path, content).unwrap();
+                    std::thread::sleep(Duration::from_millis(20));
+                }
+            })
+        };
+        let changes = watcher.wait(Duration::from_millis(5), Duration::from_millis(100))?;
+        writer.join().unwrap();
+        assert_eq!(
+            changes,
+            [FileChange {
+                path,
+                kind: ChangeKind::Modified
+            }]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+//! Progress bars shown while hashing inputs and copying manifests.
+//!
+//! Whether to show them is up to the caller, e.g. the CLI's `--no-progress`
+//! and `--quiet` flags.
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+pub fn create_progress_bar(len: u64) -> ProgressBar {
+    let pb = ProgressBar::new(len);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
+            .expect("Invalid progress bar template")
+            .progress_chars("=>-"),
+    );
+    pb
+}
+
+/// Progress bar counting bytes processed, hidden when `show` is false
+pub fn create_bytes_progress_bar(len: u64, show: bool) -> ProgressBar {
+    if !show {
+        return ProgressBar::hidden();
+    }
+
+    let pb = ProgressBar::new(len);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {bytes:>10}/{total_bytes:10} {msg}")
+            .expect("Invalid progress bar template")
+            .progress_chars("=>-"),
+    );
+    pb
+}
+
+/// Progress bar counting completed files, hidden when `show` is false
+pub fn create_files_progress_bar(len: u64, show: bool) -> ProgressBar {
+    if !show {
+        return ProgressBar::hidden();
+    }
+
+    let pb = create_progress_bar(len);
+    pb.set_message("files");
+    pb
+}
@@ -0,0 +1,196 @@
+//! # Signing Key Resolution
+//!
+//! Commands that sign find the private key path from, in order:
+//!
+//! 1. the `--key` flag
+//! 2. the `ATLAS_CLI_KEY_PATH` environment variable
+//! 3. the OS keyring entry `signing-key-path` under the service named by
+//!    `ATLAS_CLI_KEYRING_SERVICE` (default `atlas-cli`), when built with the
+//!    `keyring` feature
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::signing::key_path::{KeySource, find_key_path};
+//! use std::path::Path;
+//!
+//! // An explicit path always wins
+//! let (path, source) = find_key_path(Some(Path::new("signing.pem")))
+//!     .unwrap()
+//!     .unwrap();
+//! assert_eq!(path, Path::new("signing.pem"));
+//! assert_eq!(source, KeySource::Flag);
+//! ```
+
+use crate::error::{Result, SigningError};
+use std::path::{Path, PathBuf};
+
+/// Environment variable holding the signing key path
+pub const KEY_PATH_ENV: &str = "ATLAS_CLI_KEY_PATH";
+/// Environment variable overriding the keyring service name
+pub const KEYRING_SERVICE_ENV: &str = "ATLAS_CLI_KEYRING_SERVICE";
+/// Keyring service used when `ATLAS_CLI_KEYRING_SERVICE` is not set
+pub const DEFAULT_KEYRING_SERVICE: &str = "atlas-cli";
+/// Keyring entry (user) holding the signing key path
+pub const KEYRING_ENTRY: &str = "signing-key-path";
+
+/// Where a signing key path was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySource {
+    Flag,
+    Environment,
+    Keyring,
+}
+
+/// Finds the signing key path, returning `None` when no source provides one
+/// so that signing stays optional
+pub fn find_key_path(explicit: Option<&Path>) -> Result<Option<(PathBuf, KeySource)>> {
+    Ok(lookup(
+        explicit,
+        std::env::var_os(KEY_PATH_ENV).map(PathBuf::from),
+        &keyring_service(),
+        read_keyring,
+    )
+    .found)
+}
+
+/// Like [`find_key_path`], but fails with every source tried when none of
+/// them provides a key
+pub fn resolve_key_path(explicit: Option<&Path>) -> Result<PathBuf> {
+    lookup(
+        explicit,
+        std::env::var_os(KEY_PATH_ENV).map(PathBuf::from),
+        &keyring_service(),
+        read_keyring,
+    )
+    .require()
+}
+
+fn keyring_service() -> String {
+    std::env::var(KEYRING_SERVICE_ENV).unwrap_or_else(|_| DEFAULT_KEYRING_SERVICE.to_string())
+}
+
+// The key path if one was found, and why each earlier source was skipped
+struct Lookup {
+    found: Option<(PathBuf, KeySource)>,
+    tried: Vec<String>,
+}
+
+impl Lookup {
+    fn require(self) -> Result<PathBuf> {
+        match self.found {
+            Some((path, _)) => Ok(path),
+            None => Err(SigningError::KeyNotFound(format!(
+                "No signing key found. Tried: {}",
+                self.tried.join("; ")
+            ))
+            .into()),
+        }
+    }
+}
+
+fn lookup(
+    explicit: Option<&Path>,
+    env_path: Option<PathBuf>,
+    service: &str,
+    keyring: impl FnOnce(&str) -> std::result::Result<Option<String>, String>,
+) -> Lookup {
+    let mut tried = Vec::new();
+    let found = |path, source| Lookup {
+        found: Some((path, source)),
+        tried: Vec::new(),
+    };
+
+    if let Some(path) = explicit {
+        return found(path.to_path_buf(), KeySource::Flag);
+    }
+    tried.push("--key flag (not given)".to_string());
+
+    match env_path {
+        Some(path) if !path.as_os_str().is_empty() => {
+            return found(path, KeySource::Environment);
+        }
+        _ => tried.push(format!("{KEY_PATH_ENV} (not set)")),
+    }
+
+    let location = format!("keyring service '{service}' entry '{KEYRING_ENTRY}'");
+    match keyring(service) {
+        Ok(Some(path)) if !path.trim().is_empty() => {
+            return found(PathBuf::from(path.trim()), KeySource::Keyring);
+        }
+        Ok(_) => tried.push(format!("{location} (no entry)")),
+        Err(reason) => tried.push(format!("{location} ({reason})")),
+    }
+
+    Lookup { found: None, tried }
+}
+
+#[cfg(feature = "keyring")]
+fn read_keyring(service: &str) -> std::result::Result<Option<String>, String> {
+    let entry = keyring::Entry::new(service, KEYRING_ENTRY).map_err(|e| e.to_string())?;
+    match entry.get_password() {
+        Ok(path) => Ok(Some(path)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+fn read_keyring(_service: &str) -> std::result::Result<Option<String>, String> {
+    Err("unavailable: built without the keyring feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    fn no_keyring(_: &str) -> std::result::Result<Option<String>, String> {
+        Ok(None)
+    }
+
+    #[test]
+    fn test_key_path_precedence() {
+        let flag = Path::new("flag.pem");
+        let env = Some(PathBuf::from("env.pem"));
+        let keyring = |_: &str| Ok(Some("keyring.pem".to_string()));
+
+        let found = lookup(Some(flag), env.clone(), "atlas-cli", keyring).found;
+        assert_eq!(found, Some((flag.to_path_buf(), KeySource::Flag)));
+
+        let found = lookup(None, env, "atlas-cli", keyring).found;
+        assert_eq!(
+            found,
+            Some((PathBuf::from("env.pem"), KeySource::Environment))
+        );
+
+        let found = lookup(None, None, "atlas-cli", |service| {
+            assert_eq!(service, "atlas-cli");
+            Ok(Some("keyring.pem\n".to_string()))
+        })
+        .found;
+        assert_eq!(
+            found,
+            Some((PathBuf::from("keyring.pem"), KeySource::Keyring))
+        );
+
+        // An empty environment variable counts as unset
+        let found = lookup(None, Some(PathBuf::new()), "atlas-cli", no_keyring).found;
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn test_missing_key_lists_sources() {
+        match lookup(None, None, "ci-signing", |_| Err("locked".to_string())).require() {
+            Err(Error::SigningFailure(SigningError::KeyNotFound(msg))) => {
+                assert!(msg.contains("--key flag"), "{msg}");
+                assert!(msg.contains(KEY_PATH_ENV), "{msg}");
+                assert!(
+                    msg.contains("keyring service 'ci-signing' entry 'signing-key-path' (locked)"),
+                    "{msg}"
+                );
+            }
+            other => panic!("expected a signing error, got {other:?}"),
+        }
+    }
+}
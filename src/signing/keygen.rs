@@ -0,0 +1,238 @@
+//! # Signing Key Generation
+//!
+//! Generates key pairs of the types accepted by [`validate_private_key`]
+//! and writes them as PEM: the private key as PKCS#8, readable only by its
+//! owner on Unix, and the public key as SubjectPublicKeyInfo next to it.
+//! [`public_key`] derives the public key of an existing private key, e.g. to
+//! add it to a trust list.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::signing::keygen::{KeyType, generate_private_key, write_key_pair};
+//! use atlas_cli::signing::load_private_key;
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let key_path = dir.path().join("signing.pem");
+//!
+//! let key = generate_private_key(KeyType::EcP256).unwrap();
+//! let public_path = write_key_pair(&key, &key_path, false).unwrap();
+//!
+//! assert!(public_path.ends_with("signing.pub.pem"));
+//! assert!(load_private_key(&key_path).unwrap().public_eq(&key));
+//! ```
+
+use super::{DEFAULT_MIN_RSA_KEY_BITS, validate_private_key};
+use crate::error::{Error, Result};
+use crate::utils::safe_open_options;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private, Public};
+use openssl::rsa::Rsa;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Key types that can be generated for signing.
+///
+/// Ed25519 is not offered: the signer hashes the claim with the selected
+/// `--hash-alg` and signs that digest, which Ed25519 cannot do, so
+/// [`validate_private_key`] rejects Ed25519 keys. ECDSA P-256 is the
+/// small-key alternative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    /// 3072-bit RSA
+    Rsa3072,
+    /// ECDSA on the NIST P-256 curve
+    EcP256,
+}
+
+/// Generates a new private key of the given type
+pub fn generate_private_key(key_type: KeyType) -> Result<PKey<Private>> {
+    let key = match key_type {
+        KeyType::Rsa3072 => Rsa::generate(3072).and_then(PKey::from_rsa),
+        KeyType::EcP256 => EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)
+            .and_then(|group| EcKey::generate(&group))
+            .and_then(PKey::from_ec_key),
+    }
+    .map_err(|e| Error::Signing(format!("Failed to generate {key_type:?} key: {e}")))?;
+
+    validate_private_key(&key, DEFAULT_MIN_RSA_KEY_BITS)?;
+    Ok(key)
+}
+
+/// Path the public key is written to: `key.pem` becomes `key.pub.pem`
+pub fn public_key_path(private_key_path: &Path) -> PathBuf {
+    let stem = private_key_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    private_key_path.with_file_name(format!("{stem}.pub.pem"))
+}
+
+/// Writes the private key as PKCS#8 PEM and the public key alongside it,
+/// returning the public key's path. Existing files are only replaced when
+/// `overwrite` is set.
+pub fn write_key_pair(
+    key: &PKey<Private>,
+    private_key_path: &Path,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    let public_path = public_key_path(private_key_path);
+    if !overwrite {
+        // Check both files first so neither is written if one already exists
+        for path in [private_key_path, public_path.as_path()] {
+            if path.exists() {
+                return Err(Error::Validation(format!(
+                    "{} already exists; pass --force to overwrite it",
+                    path.display()
+                )));
+            }
+        }
+    }
+
+    let private_pem = Zeroizing::new(
+        key.private_key_to_pem_pkcs8()
+            .map_err(|e| Error::Signing(format!("Failed to encode private key: {e}")))?,
+    );
+    let public_pem = public_key_pem(&public_key(key)?)?;
+
+    write_key_file(private_key_path, &private_pem, 0o600)?;
+    write_key_file(&public_path, &public_pem, 0o644)?;
+
+    Ok(public_path)
+}
+
+/// Derives the public key of a private key
+pub fn public_key(key: &PKey<Private>) -> Result<PKey<Public>> {
+    key.public_key_to_der()
+        .and_then(|der| PKey::public_key_from_der(&der))
+        .map_err(|e| Error::Signing(format!("Failed to derive public key: {e}")))
+}
+
+/// Encodes a public key as SubjectPublicKeyInfo PEM
+pub fn public_key_pem(key: &PKey<Public>) -> Result<Vec<u8>> {
+    key.public_key_to_pem()
+        .map_err(|e| Error::Signing(format!("Failed to encode public key: {e}")))
+}
+
+/// Writes a public key as PEM. An existing file is only replaced when
+/// `overwrite` is set.
+pub fn write_public_key(key: &PKey<Public>, path: &Path, overwrite: bool) -> Result<()> {
+    if !overwrite && path.exists() {
+        return Err(Error::Validation(format!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        )));
+    }
+    write_key_file(path, &public_key_pem(key)?, 0o644)
+}
+
+fn write_key_file(path: &Path, contents: &[u8], mode: u32) -> Result<()> {
+    let options = safe_open_options(path, false)?
+        .write(true)
+        .create(true)
+        .truncate(true);
+    #[cfg(unix)]
+    let options = options.mode(mode);
+    #[cfg(not(unix))]
+    let _ = mode;
+    let mut file = options.open()?;
+
+    // The mode only applies to new files, so tighten overwritten ones too
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(mode))?;
+    }
+
+    file.write_all(contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::{load_private_key, sign_data, verify_signature_with_algorithm};
+    use atlas_c2pa_lib::cose::HashAlgorithm;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_generated_keys_can_sign() -> Result<()> {
+        let dir = tempdir()?;
+
+        for (key_type, name) in [(KeyType::Rsa3072, "rsa.pem"), (KeyType::EcP256, "ec.pem")] {
+            let key_path = dir.path().join(name);
+            let key = generate_private_key(key_type)?;
+            let public_path = write_key_pair(&key, &key_path, false)?;
+
+            let loaded = load_private_key(&key_path)?;
+            let public = PKey::public_key_from_pem(&std::fs::read(&public_path)?)
+                .map_err(|e| Error::Signing(e.to_string()))?;
+            let signature = sign_data(b"payload", &loaded)?;
+            assert!(verify_signature_with_algorithm(
+                b"payload",
+                &signature,
+                &public,
+                &HashAlgorithm::Sha384
+            )?);
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = std::fs::metadata(&key_path)?.permissions().mode();
+                assert_eq!(mode & 0o777, 0o600, "{name} should be owner-only");
+            }
+        }
+
+        let rsa = generate_private_key(KeyType::Rsa3072)?;
+        assert_eq!(rsa.bits(), 3072);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_public_key_matches_key_pair() -> Result<()> {
+        use crate::signing::trust::public_key_fingerprint;
+
+        let dir = tempdir()?;
+        let key_path = dir.path().join("signing.pem");
+        let key = generate_private_key(KeyType::EcP256)?;
+        let public_path = write_key_pair(&key, &key_path, false)?;
+
+        let derived = public_key(&load_private_key(&key_path)?)?;
+        assert_eq!(public_key_pem(&derived)?, std::fs::read(&public_path)?);
+
+        let out = dir.path().join("exported.pem");
+        write_public_key(&derived, &out, false)?;
+        assert!(write_public_key(&derived, &out, false).is_err());
+        let exported = PKey::public_key_from_pem(&std::fs::read(&out)?)
+            .map_err(|e| Error::Signing(e.to_string()))?;
+        assert_eq!(
+            public_key_fingerprint(&exported)?,
+            public_key_fingerprint(&derived)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_key_pair_refuses_to_overwrite() -> Result<()> {
+        let dir = tempdir()?;
+        let key_path = dir.path().join("signing.pem");
+        let first = generate_private_key(KeyType::EcP256)?;
+        write_key_pair(&first, &key_path, false)?;
+
+        let second = generate_private_key(KeyType::EcP256)?;
+        match write_key_pair(&second, &key_path, false) {
+            Err(Error::Validation(msg)) => assert!(msg.contains("--force"), "{msg}"),
+            other => panic!("expected an overwrite error, got {other:?}"),
+        }
+        assert!(load_private_key(&key_path)?.public_eq(&first));
+
+        write_key_pair(&second, &key_path, true)?;
+        assert!(load_private_key(&key_path)?.public_eq(&second));
+
+        Ok(())
+    }
+}
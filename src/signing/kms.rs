@@ -0,0 +1,1102 @@
+//! # KMS Signing
+//!
+//! Signs claims with a key held in a cloud key management service, so the
+//! private key never touches disk. The key is named by a URI:
+//!
+//! - `awskms://<key ID, alias or ARN>`: AWS KMS. Credentials come from
+//!   `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`;
+//!   the region from the key ARN, `AWS_REGION` or `AWS_DEFAULT_REGION`.
+//! - `gcpkms://projects/<p>/locations/<l>/keyRings/<r>/cryptoKeys/<k>/cryptoKeyVersions/<v>`:
+//!   Google Cloud KMS.
+//! - `azurekms://<vault host>/<key name>[/<version>]`: Azure Key Vault; the
+//!   latest version if none is given.
+//!
+//! Google Cloud and Azure requests are authorized with the OAuth access
+//! token in [`ACCESS_TOKEN_ENV`], e.g. from `gcloud auth
+//! print-access-token` or `az account get-access-token --resource
+//! https://vault.azure.net`. Library users can pass credentials directly
+//! with [`KmsSigner::with_aws_credentials`] and
+//! [`KmsSigner::with_access_token`].
+//!
+//! The claim is hashed locally and only its digest is sent to the KMS. The
+//! key must be an RSA key signing with PKCS#1 v1.5 padding or an EC key,
+//! and the digest algorithm must be SHA-256, SHA-384 or SHA-512 and match
+//! what the key allows.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::signing::kms::{KmsKey, KmsProvider};
+//!
+//! let key = KmsKey::parse("azurekms://example.vault.azure.net/signing-key").unwrap();
+//! assert_eq!(key.provider, KmsProvider::Azure);
+//! assert_eq!(key.to_string(), "azurekms://example.vault.azure.net/signing-key");
+//! ```
+
+use crate::error::{Error, Result};
+use crate::hash::DigestAlgorithm;
+use crate::signing::message_digest;
+use crate::signing::signer::Signer;
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use openssl::bn::BigNum;
+use openssl::ecdsa::EcdsaSig;
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use reqwest::blocking::{Client, RequestBuilder};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+/// Environment variable holding the OAuth access token for Google Cloud KMS
+/// and Azure Key Vault
+pub const ACCESS_TOKEN_ENV: &str = "ATLAS_KMS_ACCESS_TOKEN";
+
+const GCP_ENDPOINT: &str = "https://cloudkms.googleapis.com/v1";
+const AZURE_API_VERSION: &str = "7.4";
+
+/// Cloud KMS holding a signing key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KmsProvider {
+    Aws,
+    Gcp,
+    Azure,
+}
+
+impl KmsProvider {
+    fn scheme(&self) -> &'static str {
+        match self {
+            KmsProvider::Aws => "awskms",
+            KmsProvider::Gcp => "gcpkms",
+            KmsProvider::Azure => "azurekms",
+        }
+    }
+}
+
+impl fmt::Display for KmsProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            KmsProvider::Aws => "AWS KMS",
+            KmsProvider::Gcp => "Google Cloud KMS",
+            KmsProvider::Azure => "Azure Key Vault",
+        })
+    }
+}
+
+/// A KMS signing key, as named by a `awskms://`, `gcpkms://` or
+/// `azurekms://` URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KmsKey {
+    pub provider: KmsProvider,
+    /// The key as the provider names it: an AWS key ID, alias or ARN, a
+    /// Google Cloud key version resource name, or `<vault host>/<key
+    /// name>[/<version>]` for Azure
+    pub key_id: String,
+}
+
+impl KmsKey {
+    /// Parses a KMS key URI
+    pub fn parse(uri: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| Error::Validation(format!("Invalid KMS key URI '{uri}': {reason}"));
+        let (scheme, key_id) = uri
+            .split_once("://")
+            .ok_or_else(|| invalid("expected awskms://, gcpkms:// or azurekms://"))?;
+        let provider = match scheme {
+            "awskms" => KmsProvider::Aws,
+            "gcpkms" => KmsProvider::Gcp,
+            "azurekms" => KmsProvider::Azure,
+            _ => return Err(invalid("expected awskms://, gcpkms:// or azurekms://")),
+        };
+        // `awskms:///<key>` with an empty endpoint is accepted too
+        let key_id = key_id.trim_start_matches('/').trim_end_matches('/');
+        if key_id.is_empty() {
+            return Err(invalid("the key is missing"));
+        }
+
+        match provider {
+            KmsProvider::Aws => {}
+            KmsProvider::Gcp => {
+                let segments: Vec<&str> = key_id.split('/').collect();
+                let names = [
+                    "projects",
+                    "locations",
+                    "keyRings",
+                    "cryptoKeys",
+                    "cryptoKeyVersions",
+                ];
+                if segments.len() != 10
+                    || segments.iter().step_by(2).ne(names.iter())
+                    || segments.iter().skip(1).step_by(2).any(|s| s.is_empty())
+                {
+                    return Err(invalid(
+                        "expected projects/<p>/locations/<l>/keyRings/<r>/cryptoKeys/<k>/cryptoKeyVersions/<v>",
+                    ));
+                }
+            }
+            KmsProvider::Azure => {
+                let segments: Vec<&str> = key_id.split('/').collect();
+                if !(2..=3).contains(&segments.len()) || segments.iter().any(|s| s.is_empty()) {
+                    return Err(invalid("expected <vault host>/<key name>[/<version>]"));
+                }
+            }
+        }
+
+        Ok(Self {
+            provider,
+            key_id: key_id.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for KmsKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}", self.provider.scheme(), self.key_id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyKind {
+    Rsa,
+    Ec,
+}
+
+/// What the KMS reports about the key, fetched once per signer
+#[derive(Debug)]
+struct KeyInfo {
+    kind: KeyKind,
+    /// Resolved Azure key version
+    version: Option<String>,
+}
+
+/// AWS access key, for signing requests with Signature Version 4
+#[derive(Clone)]
+struct AwsCredentials {
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+}
+
+/// Signs with a key held in AWS KMS, Google Cloud KMS or Azure Key Vault
+pub struct KmsSigner {
+    key: KmsKey,
+    /// Overrides the provider's public endpoint
+    endpoint: Option<String>,
+    /// Override the credentials read from the environment
+    aws_credentials: Option<AwsCredentials>,
+    access_token: Option<String>,
+    client: Client,
+    info: OnceLock<KeyInfo>,
+}
+
+impl KmsSigner {
+    /// Signs with `key` through the provider's public endpoint
+    pub fn new(key: KmsKey) -> Result<Self> {
+        let endpoint = match key.provider {
+            KmsProvider::Aws => std::env::var("AWS_ENDPOINT_URL_KMS")
+                .or_else(|_| std::env::var("AWS_ENDPOINT_URL"))
+                .ok(),
+            KmsProvider::Gcp | KmsProvider::Azure => None,
+        };
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| Error::Signing(format!("Failed to create HTTP client: {e}")))?;
+        Ok(Self {
+            key,
+            endpoint,
+            aws_credentials: None,
+            access_token: None,
+            client,
+            info: OnceLock::new(),
+        })
+    }
+
+    /// Parses a KMS key URI and signs with that key
+    pub fn from_uri(uri: &str) -> Result<Self> {
+        Self::new(KmsKey::parse(uri)?)
+    }
+
+    /// Sends requests to `endpoint` instead, e.g. a KMS emulator or a
+    /// private endpoint
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Signs AWS requests with these credentials instead of those in
+    /// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`
+    pub fn with_aws_credentials(
+        mut self,
+        access_key: impl Into<String>,
+        secret_key: impl Into<String>,
+        session_token: Option<String>,
+    ) -> Self {
+        self.aws_credentials = Some(AwsCredentials {
+            access_key: access_key.into(),
+            secret_key: secret_key.into(),
+            session_token,
+        });
+        self
+    }
+
+    /// Authorizes Google Cloud and Azure requests with this OAuth access
+    /// token instead of the one in [`ACCESS_TOKEN_ENV`]
+    pub fn with_access_token(mut self, token: impl Into<String>) -> Self {
+        self.access_token = Some(token.into());
+        self
+    }
+
+    fn access_token(&self) -> Result<String> {
+        match &self.access_token {
+            Some(token) => Ok(token.clone()),
+            None => access_token(),
+        }
+    }
+
+    fn aws_credentials(&self) -> Result<AwsCredentials> {
+        if let Some(credentials) = &self.aws_credentials {
+            return Ok(credentials.clone());
+        }
+        let env = |name: &str| {
+            std::env::var(name).map_err(|_| {
+                Error::Signing(format!("{name} must be set to sign with {}", self.key))
+            })
+        };
+        Ok(AwsCredentials {
+            access_key: env("AWS_ACCESS_KEY_ID")?,
+            secret_key: env("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+
+    fn endpoint(&self) -> Result<String> {
+        if let Some(endpoint) = &self.endpoint {
+            return Ok(endpoint.clone());
+        }
+        Ok(match self.key.provider {
+            KmsProvider::Aws => format!("https://kms.{}.amazonaws.com", aws_region(&self.key)?),
+            KmsProvider::Gcp => GCP_ENDPOINT.to_string(),
+            KmsProvider::Azure => format!("https://{}", azure_parts(&self.key).0),
+        })
+    }
+
+    fn info(&self) -> Result<&KeyInfo> {
+        if let Some(info) = self.info.get() {
+            return Ok(info);
+        }
+        let info = match self.key.provider {
+            KmsProvider::Aws => self.aws_key_info()?,
+            KmsProvider::Gcp => self.gcp_key_info()?,
+            KmsProvider::Azure => self.azure_key_info()?,
+        };
+        Ok(self.info.get_or_init(|| info))
+    }
+
+    fn aws_key_info(&self) -> Result<KeyInfo> {
+        let response = self.aws_call("DescribeKey", json!({ "KeyId": self.key.key_id }))?;
+        let spec = response["KeyMetadata"]["KeySpec"]
+            .as_str()
+            .unwrap_or_default();
+        let kind = if spec.starts_with("RSA_") {
+            KeyKind::Rsa
+        } else if spec.starts_with("ECC_NIST_") {
+            KeyKind::Ec
+        } else {
+            return Err(self.unsupported_key(spec));
+        };
+        Ok(KeyInfo {
+            kind,
+            version: None,
+        })
+    }
+
+    fn gcp_key_info(&self) -> Result<KeyInfo> {
+        let request = self
+            .client
+            .get(format!("{}/{}", self.endpoint()?, self.key.key_id))
+            .bearer_auth(self.access_token()?);
+        let response = self.send(request, "key lookup")?;
+        let algorithm = response["algorithm"].as_str().unwrap_or_default();
+        // PSS and raw PKCS#1 signatures would not verify as claim signatures
+        let kind = if algorithm.starts_with("RSA_SIGN_PKCS1_") && !algorithm.contains("RAW") {
+            KeyKind::Rsa
+        } else if algorithm.starts_with("EC_SIGN_P") {
+            KeyKind::Ec
+        } else {
+            return Err(self.unsupported_key(algorithm));
+        };
+        Ok(KeyInfo {
+            kind,
+            version: None,
+        })
+    }
+
+    fn azure_key_info(&self) -> Result<KeyInfo> {
+        let (_, name, version) = azure_parts(&self.key);
+        let path = match version {
+            Some(version) => format!("keys/{name}/{version}"),
+            None => format!("keys/{name}"),
+        };
+        let request = self
+            .client
+            .get(format!(
+                "{}/{path}?api-version={AZURE_API_VERSION}",
+                self.endpoint()?
+            ))
+            .bearer_auth(self.access_token()?);
+        let response = self.send(request, "key lookup")?;
+        let key = &response["key"];
+        let kty = key["kty"].as_str().unwrap_or_default();
+        let kind = match kty {
+            "RSA" | "RSA-HSM" => KeyKind::Rsa,
+            "EC" | "EC-HSM" => KeyKind::Ec,
+            _ => return Err(self.unsupported_key(kty)),
+        };
+        // The key ID ends with the version, which signing requests need
+        let version = key["kid"]
+            .as_str()
+            .and_then(|kid| kid.rsplit('/').next())
+            .filter(|version| !version.is_empty())
+            .ok_or_else(|| {
+                Error::Signing(format!(
+                    "{} returned no key version for {}",
+                    self.key.provider, self.key
+                ))
+            })?;
+        Ok(KeyInfo {
+            kind,
+            version: Some(version.to_string()),
+        })
+    }
+
+    fn aws_sign(&self, digest: &[u8], alg: DigestAlgorithm, kind: KeyKind) -> Result<Vec<u8>> {
+        let bits = digest_bits(alg);
+        let algorithm = match kind {
+            KeyKind::Rsa => format!("RSASSA_PKCS1_V1_5_SHA_{bits}"),
+            KeyKind::Ec => format!("ECDSA_SHA_{bits}"),
+        };
+        let response = self.aws_call(
+            "Sign",
+            json!({
+                "KeyId": self.key.key_id,
+                "Message": STANDARD.encode(digest),
+                "MessageType": "DIGEST",
+                "SigningAlgorithm": algorithm,
+            }),
+        )?;
+        self.decode_signature(response["Signature"].as_str(), false)
+    }
+
+    fn gcp_sign(&self, digest: &[u8], alg: DigestAlgorithm) -> Result<Vec<u8>> {
+        let request = self
+            .client
+            .post(format!(
+                "{}/{}:asymmetricSign",
+                self.endpoint()?,
+                self.key.key_id
+            ))
+            .bearer_auth(self.access_token()?)
+            .json(&json!({ "digest": { alg.as_str(): STANDARD.encode(digest) } }));
+        let response = self.send(request, "signing")?;
+        self.decode_signature(response["signature"].as_str(), false)
+    }
+
+    fn azure_sign(&self, digest: &[u8], alg: DigestAlgorithm, info: &KeyInfo) -> Result<Vec<u8>> {
+        let (_, name, _) = azure_parts(&self.key);
+        let version = info.version.as_deref().unwrap_or_default();
+        let bits = digest_bits(alg);
+        let algorithm = match info.kind {
+            KeyKind::Rsa => format!("RS{bits}"),
+            KeyKind::Ec => format!("ES{bits}"),
+        };
+        let request = self
+            .client
+            .post(format!(
+                "{}/keys/{name}/{version}/sign?api-version={AZURE_API_VERSION}",
+                self.endpoint()?
+            ))
+            .bearer_auth(self.access_token()?)
+            .json(&json!({ "alg": algorithm, "value": URL_SAFE_NO_PAD.encode(digest) }));
+        let response = self.send(request, "signing")?;
+        let signature = self.decode_signature(response["value"].as_str(), true)?;
+        match info.kind {
+            KeyKind::Rsa => Ok(signature),
+            // Key Vault returns ECDSA signatures as raw r || s
+            KeyKind::Ec => ecdsa_raw_to_der(&signature),
+        }
+    }
+
+    fn aws_call(&self, action: &str, body: Value) -> Result<Value> {
+        let region = aws_region(&self.key)?;
+        let AwsCredentials {
+            access_key,
+            secret_key,
+            session_token,
+        } = self.aws_credentials()?;
+
+        let endpoint = self.endpoint()?;
+        let url = reqwest::Url::parse(&endpoint)
+            .map_err(|e| Error::Signing(format!("Invalid AWS KMS endpoint {endpoint}: {e}")))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => {
+                return Err(Error::Signing(format!(
+                    "Invalid AWS KMS endpoint {endpoint}: no host"
+                )));
+            }
+        };
+        let payload = serde_json::to_vec(&body)?;
+        let amz_date = amz_date(OffsetDateTime::now_utc());
+
+        // Sorted by name, as SigV4 requires
+        let mut headers = vec![
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("host", host),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = session_token {
+            headers.push(("x-amz-security-token", token));
+        }
+        headers.push(("x-amz-target", format!("TrentService.{action}")));
+
+        let authorization = sigv4_authorization(
+            &SigV4Credentials {
+                access_key: &access_key,
+                secret_key: &secret_key,
+                region: &region,
+                service: "kms",
+            },
+            &amz_date,
+            url.path(),
+            &headers,
+            &payload,
+        )?;
+
+        let mut request = self.client.post(url.clone());
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            request = request.header(*name, value);
+        }
+        let request = request.header("authorization", authorization).body(payload);
+        self.send(request, action)
+    }
+
+    fn send(&self, request: RequestBuilder, what: &str) -> Result<Value> {
+        let provider = self.key.provider;
+        let response = request.send().map_err(|e| {
+            Error::Signing(format!(
+                "Failed to contact {provider} for {}: {e}",
+                self.key
+            ))
+        })?;
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        if !status.is_success() {
+            return Err(Error::Signing(format!(
+                "{provider} {what} for {} failed with status {status}: {}",
+                self.key,
+                body.trim()
+            )));
+        }
+        serde_json::from_str(&body).map_err(|e| {
+            Error::Signing(format!(
+                "Invalid {provider} {what} response for {}: {e}",
+                self.key
+            ))
+        })
+    }
+
+    fn decode_signature(&self, value: Option<&str>, url_safe: bool) -> Result<Vec<u8>> {
+        let missing = || {
+            Error::Signing(format!(
+                "{} returned no signature for {}",
+                self.key.provider, self.key
+            ))
+        };
+        let value = value.ok_or_else(missing)?;
+        let decoded = if url_safe {
+            URL_SAFE_NO_PAD.decode(value.trim_end_matches('='))
+        } else {
+            STANDARD.decode(value)
+        };
+        decoded.map_err(|e| {
+            Error::Signing(format!(
+                "Invalid signature from {} for {}: {e}",
+                self.key.provider, self.key
+            ))
+        })
+    }
+
+    fn unsupported_key(&self, key_type: &str) -> Error {
+        Error::Signing(format!(
+            "{} is a {} key; KMS signing needs an RSA PKCS#1 v1.5 or EC signing key",
+            self.key,
+            if key_type.is_empty() {
+                "unknown"
+            } else {
+                key_type
+            }
+        ))
+    }
+}
+
+impl Signer for KmsSigner {
+    fn sign(&self, data: &[u8], alg: DigestAlgorithm) -> Result<Vec<u8>> {
+        if !matches!(
+            alg,
+            DigestAlgorithm::Sha256 | DigestAlgorithm::Sha384 | DigestAlgorithm::Sha512
+        ) {
+            return Err(Error::Signing(format!(
+                "KMS signing supports sha256, sha384 and sha512, not {}",
+                alg.as_str()
+            )));
+        }
+        let digest = openssl::hash::hash(message_digest(alg), data)
+            .map_err(|e| Error::Signing(format!("Failed to hash claim: {e}")))?;
+        crate::cancel::check()?;
+
+        let info = self.info()?;
+        match self.key.provider {
+            KmsProvider::Aws => self.aws_sign(&digest, alg, info.kind),
+            KmsProvider::Gcp => self.gcp_sign(&digest, alg),
+            KmsProvider::Azure => self.azure_sign(&digest, alg, info),
+        }
+    }
+
+    fn description(&self) -> String {
+        self.key.to_string()
+    }
+}
+
+fn access_token() -> Result<String> {
+    std::env::var(ACCESS_TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+        .map(|token| token.trim().to_string())
+        .ok_or_else(|| {
+            Error::Signing(format!(
+                "{ACCESS_TOKEN_ENV} must hold an OAuth access token to sign with Google Cloud KMS or Azure Key Vault"
+            ))
+        })
+}
+
+fn digest_bits(alg: DigestAlgorithm) -> u16 {
+    match alg {
+        DigestAlgorithm::Sha256 | DigestAlgorithm::Sha3_256 => 256,
+        DigestAlgorithm::Sha384 | DigestAlgorithm::Sha3_384 => 384,
+        DigestAlgorithm::Sha512 | DigestAlgorithm::Sha3_512 => 512,
+    }
+}
+
+/// The vault host, key name and optional version of an Azure key
+fn azure_parts(key: &KmsKey) -> (&str, &str, Option<&str>) {
+    let mut segments = key.key_id.split('/');
+    let vault = segments.next().unwrap_or_default();
+    let name = segments.next().unwrap_or_default();
+    (vault, name, segments.next())
+}
+
+/// The region of an AWS key: from its ARN, else the environment
+fn aws_region(key: &KmsKey) -> Result<String> {
+    if let Some(arn) = key.key_id.strip_prefix("arn:") {
+        // arn:<partition>:kms:<region>:<account>:<resource>
+        if let Some(region) = arn.split(':').nth(2).filter(|region| !region.is_empty()) {
+            return Ok(region.to_string());
+        }
+    }
+    std::env::var("AWS_REGION")
+        .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+        .map_err(|_| {
+            Error::Signing(format!(
+                "Cannot tell the region of {key}; use a key ARN or set AWS_REGION"
+            ))
+        })
+}
+
+/// Converts a raw `r || s` ECDSA signature to DER
+fn ecdsa_raw_to_der(raw: &[u8]) -> Result<Vec<u8>> {
+    if raw.is_empty() || !raw.len().is_multiple_of(2) {
+        return Err(Error::Signing(format!(
+            "Invalid raw ECDSA signature of {} bytes",
+            raw.len()
+        )));
+    }
+    let (r, s) = raw.split_at(raw.len() / 2);
+    BigNum::from_slice(r)
+        .and_then(|r| Ok((r, BigNum::from_slice(s)?)))
+        .and_then(|(r, s)| EcdsaSig::from_private_components(r, s))
+        .and_then(|signature| signature.to_der())
+        .map_err(|e| Error::Signing(format!("Invalid raw ECDSA signature: {e}")))
+}
+
+struct SigV4Credentials<'a> {
+    access_key: &'a str,
+    secret_key: &'a str,
+    region: &'a str,
+    service: &'a str,
+}
+
+/// `YYYYMMDD'T'HHMMSS'Z'`, the timestamp format of AWS Signature Version 4
+fn amz_date(now: OffsetDateTime) -> String {
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    )
+}
+
+/// The `Authorization` header of an AWS Signature Version 4 POST request.
+/// `headers` are the signed headers, lowercase and sorted by name.
+fn sigv4_authorization(
+    credentials: &SigV4Credentials,
+    amz_date: &str,
+    path: &str,
+    headers: &[(&str, String)],
+    payload: &[u8],
+) -> Result<String> {
+    let date = &amz_date[..8];
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect();
+    let canonical_request = format!(
+        "POST\n{path}\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex::encode(Sha256::digest(payload))
+    );
+    let scope = format!(
+        "{date}/{}/{}/aws4_request",
+        credentials.region, credentials.service
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = sigv4_signing_key(credentials, date)?;
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes())?);
+    Ok(format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key
+    ))
+}
+
+fn sigv4_signing_key(credentials: &SigV4Credentials, date: &str) -> Result<Vec<u8>> {
+    let key = hmac_sha256(
+        format!("AWS4{}", credentials.secret_key).as_bytes(),
+        date.as_bytes(),
+    )?;
+    let key = hmac_sha256(&key, credentials.region.as_bytes())?;
+    let key = hmac_sha256(&key, credentials.service.as_bytes())?;
+    hmac_sha256(&key, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let key = PKey::hmac(key).map_err(|e| Error::Signing(e.to_string()))?;
+    let mut signer = openssl::sign::Signer::new(MessageDigest::sha256(), &key)
+        .map_err(|e| Error::Signing(e.to_string()))?;
+    signer
+        .update(data)
+        .map_err(|e| Error::Signing(e.to_string()))?;
+    signer
+        .sign_to_vec()
+        .map_err(|e| Error::Signing(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::keygen::{KeyType, generate_private_key, public_key};
+    use crate::signing::{sign_data_with_algorithm, verify_signature_with_algorithm};
+    use mockito::Matcher;
+    use openssl::pkey::Private;
+    use std::sync::{Arc, Mutex};
+
+    /// ECDSA signature over a digest the KMS was asked to sign, as DER
+    fn sign_digest(key: &PKey<Private>, digest: &[u8]) -> Vec<u8> {
+        let ec_key = key.ec_key().unwrap();
+        EcdsaSig::sign(digest, &ec_key).unwrap().to_der().unwrap()
+    }
+
+    fn claim_digest() -> Vec<u8> {
+        Sha256::digest(b"claim").to_vec()
+    }
+
+    /// Signs `b"claim"` and checks the signature with `key`
+    fn assert_signs_claim(signer: &KmsSigner, key: &PKey<Private>) -> Result<()> {
+        let signature = signer.sign(b"claim", DigestAlgorithm::Sha256)?;
+        assert!(verify_signature_with_algorithm(
+            b"claim",
+            &signature,
+            &public_key(key)?,
+            DigestAlgorithm::Sha256
+        )?);
+        Ok(())
+    }
+
+    fn assert_signing_error(result: Result<Vec<u8>>, expected: &str) {
+        match result {
+            Err(Error::Signing(message)) => assert!(message.contains(expected), "{message}"),
+            other => panic!("expected a signing error containing {expected:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_kms_key_uris() -> Result<()> {
+        let aws = KmsKey::parse("awskms:///arn:aws:kms:eu-west-1:111122223333:key/1234abcd")?;
+        assert_eq!(aws.provider, KmsProvider::Aws);
+        assert_eq!(
+            aws.key_id,
+            "arn:aws:kms:eu-west-1:111122223333:key/1234abcd"
+        );
+        assert_eq!(aws_region(&aws)?, "eu-west-1");
+
+        let gcp =
+            "gcpkms://projects/p/locations/global/keyRings/r/cryptoKeys/k/cryptoKeyVersions/1";
+        assert_eq!(KmsKey::parse(gcp)?.to_string(), gcp);
+
+        let azure = KmsKey::parse("azurekms://vault.vault.azure.net/signing/abc123")?;
+        assert_eq!(
+            azure_parts(&azure),
+            ("vault.vault.azure.net", "signing", Some("abc123"))
+        );
+
+        for invalid in [
+            "file:///key.pem",
+            "awskms://",
+            "gcpkms://projects/p/locations/global/keyRings/r/cryptoKeys/k",
+            "azurekms://vault.vault.azure.net",
+            "azurekms://vault.vault.azure.net/a/b/c",
+        ] {
+            assert!(
+                KmsKey::parse(invalid).is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sigv4_signing_key() -> Result<()> {
+        // Example from the AWS Signature Version 4 documentation
+        let credentials = SigV4Credentials {
+            access_key: "AKIDEXAMPLE",
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            region: "us-east-1",
+            service: "iam",
+        };
+        assert_eq!(
+            hex::encode(sigv4_signing_key(&credentials, "20120215")?),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ecdsa_raw_to_der() -> Result<()> {
+        let key = generate_private_key(KeyType::EcP256)?;
+        let der = sign_data_with_algorithm(b"claim", &key, DigestAlgorithm::Sha256)?;
+
+        let signature = EcdsaSig::from_der(&der).map_err(|e| Error::Signing(e.to_string()))?;
+        let mut raw = signature
+            .r()
+            .to_vec_padded(32)
+            .map_err(|e| Error::Signing(e.to_string()))?;
+        raw.extend(
+            signature
+                .s()
+                .to_vec_padded(32)
+                .map_err(|e| Error::Signing(e.to_string()))?,
+        );
+
+        let converted = ecdsa_raw_to_der(&raw)?;
+        assert!(verify_signature_with_algorithm(
+            b"claim",
+            &converted,
+            &public_key(&key)?,
+            DigestAlgorithm::Sha256
+        )?);
+        assert!(ecdsa_raw_to_der(&raw[1..]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_aws_kms_sign_request() -> Result<()> {
+        let key = generate_private_key(KeyType::EcP256)?;
+        let key_arn = "arn:aws:kms:eu-west-1:111122223333:key/1234abcd";
+        let mut server = mockito::Server::new();
+        let _describe = server
+            .mock("POST", "/")
+            .match_header("x-amz-target", "TrentService.DescribeKey")
+            .with_body(json!({"KeyMetadata": {"KeySpec": "ECC_NIST_P256"}}).to_string())
+            .create();
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let captured = requests.clone();
+        let signing_key = key.clone();
+        let sign = server
+            .mock("POST", "/")
+            .match_header("x-amz-target", "TrentService.Sign")
+            .match_header("content-type", "application/x-amz-json-1.1")
+            .match_body(Matcher::Json(json!({
+                "KeyId": key_arn,
+                "Message": STANDARD.encode(claim_digest()),
+                "MessageType": "DIGEST",
+                "SigningAlgorithm": "ECDSA_SHA_256",
+            })))
+            .with_body_from_request(move |request| {
+                let header = |name: &str| request.header(name)[0].to_str().unwrap().to_string();
+                captured.lock().unwrap().push((
+                    header("authorization"),
+                    header("x-amz-date"),
+                    header("host"),
+                    request.body().unwrap().clone(),
+                ));
+                let signature = sign_digest(&signing_key, &claim_digest());
+                json!({ "Signature": STANDARD.encode(signature) })
+                    .to_string()
+                    .into_bytes()
+            })
+            .create();
+
+        let signer = KmsSigner::from_uri(&format!("awskms:///{key_arn}"))?
+            .with_endpoint(server.url())
+            .with_aws_credentials("AKIDEXAMPLE", "secret", None);
+        assert_signs_claim(&signer, &key)?;
+        sign.assert();
+
+        // The request is signed with SigV4 over its headers and body
+        let (authorization, amz_date, host, body) = requests.lock().unwrap().remove(0);
+        let expected = sigv4_authorization(
+            &SigV4Credentials {
+                access_key: "AKIDEXAMPLE",
+                secret_key: "secret",
+                region: "eu-west-1",
+                service: "kms",
+            },
+            &amz_date,
+            "/",
+            &[
+                ("content-type", "application/x-amz-json-1.1".to_string()),
+                ("host", host),
+                ("x-amz-date", amz_date.clone()),
+                ("x-amz-target", "TrentService.Sign".to_string()),
+            ],
+            &body,
+        )?;
+        assert_eq!(authorization, expected);
+        assert!(authorization.starts_with(&format!(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/{}/eu-west-1/kms/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date;x-amz-target, Signature=",
+            &amz_date[..8]
+        )));
+        Ok(())
+    }
+
+    #[test]
+    fn test_aws_kms_sign_errors() -> Result<()> {
+        let mut server = mockito::Server::new();
+        let _describe = server
+            .mock("POST", "/")
+            .match_header("x-amz-target", "TrentService.DescribeKey")
+            .with_body(json!({"KeyMetadata": {"KeySpec": "ECC_NIST_P256"}}).to_string())
+            .create();
+        let key_arn = |name: &str| format!("arn:aws:kms:eu-west-1:111122223333:key/{name}");
+        let _denied = server
+            .mock("POST", "/")
+            .match_header("x-amz-target", "TrentService.Sign")
+            .match_body(Matcher::PartialJson(json!({"KeyId": key_arn("denied")})))
+            .with_status(400)
+            .with_body(r#"{"__type":"AccessDeniedException"}"#)
+            .create();
+        let _malformed = server
+            .mock("POST", "/")
+            .match_header("x-amz-target", "TrentService.Sign")
+            .match_body(Matcher::PartialJson(json!({"KeyId": key_arn("malformed")})))
+            .with_body(json!({"Signature": "not base64!"}).to_string())
+            .create();
+
+        let signer = |name: &str| -> Result<KmsSigner> {
+            Ok(
+                KmsSigner::from_uri(&format!("awskms:///{}", key_arn(name)))?
+                    .with_endpoint(server.url())
+                    .with_aws_credentials("AKIDEXAMPLE", "secret", None),
+            )
+        };
+        assert_signing_error(
+            signer("denied")?.sign(b"claim", DigestAlgorithm::Sha256),
+            "AccessDeniedException",
+        );
+        assert_signing_error(
+            signer("malformed")?.sign(b"claim", DigestAlgorithm::Sha256),
+            "Invalid signature",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_gcp_kms_sign_request() -> Result<()> {
+        let key = generate_private_key(KeyType::EcP256)?;
+        let key_id = "projects/p/locations/global/keyRings/r/cryptoKeys/k/cryptoKeyVersions/1";
+        let mut server = mockito::Server::new();
+        let _lookup = server
+            .mock("GET", format!("/{key_id}").as_str())
+            .match_header("authorization", "Bearer token")
+            .with_body(json!({"algorithm": "EC_SIGN_P256_SHA256"}).to_string())
+            .create();
+        let signing_key = key.clone();
+        let sign = server
+            .mock("POST", format!("/{key_id}:asymmetricSign").as_str())
+            .match_header("authorization", "Bearer token")
+            .match_body(Matcher::Json(
+                json!({"digest": {"sha256": STANDARD.encode(claim_digest())}}),
+            ))
+            .with_body_from_request(move |_| {
+                let signature = sign_digest(&signing_key, &claim_digest());
+                json!({ "signature": STANDARD.encode(signature) })
+                    .to_string()
+                    .into_bytes()
+            })
+            .create();
+
+        let signer = KmsSigner::from_uri(&format!("gcpkms://{key_id}"))?
+            .with_endpoint(server.url())
+            .with_access_token("token");
+        assert_signs_claim(&signer, &key)?;
+        sign.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_gcp_kms_sign_errors() -> Result<()> {
+        let key_id = |name: &str| {
+            format!("projects/p/locations/global/keyRings/r/cryptoKeys/{name}/cryptoKeyVersions/1")
+        };
+        let mut server = mockito::Server::new();
+        let _lookup = server
+            .mock("GET", Matcher::Regex("^/projects/".to_string()))
+            .with_body(json!({"algorithm": "EC_SIGN_P256_SHA256"}).to_string())
+            .create();
+        let _denied = server
+            .mock(
+                "POST",
+                format!("/{}:asymmetricSign", key_id("denied")).as_str(),
+            )
+            .with_status(403)
+            .with_body(r#"{"error": {"status": "PERMISSION_DENIED"}}"#)
+            .create();
+        let _malformed = server
+            .mock(
+                "POST",
+                format!("/{}:asymmetricSign", key_id("malformed")).as_str(),
+            )
+            .with_body(json!({"name": key_id("malformed")}).to_string())
+            .create();
+
+        let signer = |name: &str| -> Result<KmsSigner> {
+            Ok(KmsSigner::from_uri(&format!("gcpkms://{}", key_id(name)))?
+                .with_endpoint(server.url())
+                .with_access_token("token"))
+        };
+        assert_signing_error(
+            signer("denied")?.sign(b"claim", DigestAlgorithm::Sha256),
+            "PERMISSION_DENIED",
+        );
+        assert_signing_error(
+            signer("malformed")?.sign(b"claim", DigestAlgorithm::Sha256),
+            "returned no signature",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_azure_key_vault_sign_request() -> Result<()> {
+        let key = generate_private_key(KeyType::EcP256)?;
+        let mut server = mockito::Server::new();
+        let api_version = Matcher::UrlEncoded("api-version".into(), AZURE_API_VERSION.into());
+        let _lookup = server
+            .mock("GET", "/keys/signing")
+            .match_query(api_version.clone())
+            .match_header("authorization", "Bearer token")
+            .with_body(
+                json!({"key": {"kty": "EC-HSM", "kid": "https://vault/keys/signing/v1"}})
+                    .to_string(),
+            )
+            .create();
+
+        // Key Vault returns the signature as raw r || s
+        let signing_key = key.clone();
+        let sign = server
+            .mock("POST", "/keys/signing/v1/sign")
+            .match_query(api_version)
+            .match_header("authorization", "Bearer token")
+            .match_body(Matcher::Json(
+                json!({"alg": "ES256", "value": URL_SAFE_NO_PAD.encode(claim_digest())}),
+            ))
+            .with_body_from_request(move |_| {
+                let der = sign_digest(&signing_key, &claim_digest());
+                let signature = EcdsaSig::from_der(&der).unwrap();
+                let mut raw = signature.r().to_vec_padded(32).unwrap();
+                raw.extend(signature.s().to_vec_padded(32).unwrap());
+                json!({ "value": URL_SAFE_NO_PAD.encode(raw) })
+                    .to_string()
+                    .into_bytes()
+            })
+            .create();
+
+        let signer = KmsSigner::from_uri("azurekms://vault.vault.azure.net/signing")?
+            .with_endpoint(server.url())
+            .with_access_token("token");
+        assert_signs_claim(&signer, &key)?;
+        sign.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_azure_key_vault_sign_errors() -> Result<()> {
+        let mut server = mockito::Server::new();
+        let _denied = server
+            .mock("POST", "/keys/signing/denied/sign")
+            .match_query(Matcher::Any)
+            .with_status(401)
+            .with_body(r#"{"error": {"code": "Unauthorized"}}"#)
+            .create();
+        let _malformed = server
+            .mock("POST", "/keys/signing/malformed/sign")
+            .match_query(Matcher::Any)
+            .with_body(json!({"value": URL_SAFE_NO_PAD.encode([1u8; 3])}).to_string())
+            .create();
+
+        for (version, expected) in [
+            ("denied", "Unauthorized"),
+            ("malformed", "Invalid raw ECDSA signature"),
+        ] {
+            let _lookup = server
+                .mock("GET", format!("/keys/signing/{version}").as_str())
+                .match_query(Matcher::Any)
+                .with_body(
+                    json!({"key": {"kty": "EC", "kid": format!("https://vault/keys/signing/{version}")}})
+                        .to_string(),
+                )
+                .create();
+            let signer = KmsSigner::from_uri(&format!(
+                "azurekms://vault.vault.azure.net/signing/{version}"
+            ))?
+            .with_endpoint(server.url())
+            .with_access_token("token");
+            assert_signing_error(signer.sign(b"claim", DigestAlgorithm::Sha256), expected);
+        }
+        Ok(())
+    }
+}
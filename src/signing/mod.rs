@@ -1,31 +1,162 @@
-use crate::error::{Error, Result};
-use atlas_c2pa_lib::cose::HashAlgorithm;
+use crate::error::{Error, Result, SigningError};
+use crate::hash::DigestAlgorithm;
 use openssl::hash::MessageDigest;
-use openssl::pkey::{PKey, Private, Public};
-use openssl::sign::Signer;
+use openssl::pkey::{Id, PKey, Private, Public};
 use std::fs::read;
 use std::path::Path;
-
+use zeroize::Zeroizing;
+
+pub mod key_path;
+pub mod keygen;
+pub mod kms;
+pub mod signer;
+pub mod timestamp;
+pub mod trust;
 pub mod utils;
 
+/// Smallest RSA modulus accepted for signing unless configured otherwise
+pub const DEFAULT_MIN_RSA_KEY_BITS: u32 = 2048;
+
+/// Loads a private key, auto-detecting PEM, DER, and unencrypted PKCS#8 DER
+///
+/// The key file's bytes are wiped from memory once parsed. The returned
+/// `PKey` is owned by OpenSSL, which clears the key material when it is freed.
 pub fn load_private_key(key_path: &Path) -> Result<PKey<Private>> {
-    let key_data = read(key_path)?;
-    PKey::private_key_from_pem(&key_data)
-        .map_err(|e| crate::error::Error::Signing(format!("Failed to load private key: {e}")))
+    load_private_key_with_min_bits(key_path, DEFAULT_MIN_RSA_KEY_BITS)
+}
+
+/// Loads a private key and rejects RSA keys smaller than `min_rsa_bits`
+pub fn load_private_key_with_min_bits(key_path: &Path, min_rsa_bits: u32) -> Result<PKey<Private>> {
+    // Wiped on drop so the encoded key doesn't linger in freed memory
+    let key_data = Zeroizing::new(read_key_file(key_path)?);
+    let key = parse_private_key(&key_data).map_err(|errors| {
+        if is_public_key(&key_data) {
+            Error::Signing(format!(
+                "{} contains a public key; signing requires the private key",
+                key_path.display()
+            ))
+        } else {
+            Error::Signing(format!(
+                "Failed to load private key from {}: tried {}",
+                key_path.display(),
+                errors.join("; ")
+            ))
+        }
+    })?;
+
+    validate_private_key(&key, min_rsa_bits).map_err(|e| match e {
+        Error::Signing(msg) => Error::Signing(format!("{}: {msg}", key_path.display())),
+        other => other,
+    })?;
+    Ok(key)
+}
+
+// Reads a key file, reporting a missing file as a missing key
+fn read_key_file(key_path: &Path) -> Result<Vec<u8>> {
+    read(key_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            SigningError::KeyNotFound(format!("Key file not found: {}", key_path.display())).into()
+        }
+        _ => Error::Io(e),
+    })
+}
+
+/// Checks that a private key is usable for signing: RSA keys must pass the
+/// consistency check and be at least `min_rsa_bits`, EC keys must lie on
+/// their curve, and other key types are rejected
+pub fn validate_private_key(key: &PKey<Private>, min_rsa_bits: u32) -> Result<()> {
+    match key.id() {
+        Id::RSA => {
+            let rsa = key
+                .rsa()
+                .map_err(|e| Error::Signing(format!("Invalid RSA private key: {e}")))?;
+            if !rsa.check_key().unwrap_or(false) {
+                return Err(Error::Signing(
+                    "RSA private key failed its consistency check; the key may be truncated or corrupt"
+                        .to_string(),
+                ));
+            }
+            if key.bits() < min_rsa_bits {
+                return Err(Error::Signing(format!(
+                    "RSA key is {} bits; at least {min_rsa_bits} bits are required",
+                    key.bits()
+                )));
+            }
+        }
+        Id::EC => {
+            key.ec_key()
+                .and_then(|ec| ec.check_key())
+                .map_err(|e| Error::Signing(format!("Invalid EC private key: {e}")))?;
+        }
+        other => {
+            return Err(Error::Signing(format!(
+                "Unsupported private key type {other:?}; use an RSA or EC key"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Loads a password-protected private key (encrypted PEM or PKCS#8 DER)
+///
+/// As with [`load_private_key`], the file's bytes are wiped once parsed and
+/// the returned `PKey` is managed by OpenSSL.
+pub fn load_private_key_with_passphrase(
+    key_path: &Path,
+    passphrase: &[u8],
+) -> Result<PKey<Private>> {
+    let key_data = Zeroizing::new(read_key_file(key_path)?);
+
+    let pem_err = match PKey::private_key_from_pem_passphrase(&key_data, passphrase) {
+        Ok(key) => {
+            validate_private_key(&key, DEFAULT_MIN_RSA_KEY_BITS)?;
+            return Ok(key);
+        }
+        Err(e) => e,
+    };
+    let pkcs8_err = match PKey::private_key_from_pkcs8_passphrase(&key_data, passphrase) {
+        Ok(key) => {
+            validate_private_key(&key, DEFAULT_MIN_RSA_KEY_BITS)?;
+            return Ok(key);
+        }
+        Err(e) => e,
+    };
+
+    Err(Error::Signing(format!(
+        "Failed to load encrypted private key from {}: tried PEM ({pem_err}); PKCS#8 DER ({pkcs8_err})",
+        key_path.display()
+    )))
+}
+
+fn is_public_key(key_data: &[u8]) -> bool {
+    PKey::public_key_from_pem(key_data).is_ok() || PKey::public_key_from_der(key_data).is_ok()
+}
+
+fn parse_private_key(key_data: &[u8]) -> std::result::Result<PKey<Private>, Vec<String>> {
+    let mut errors = Vec::new();
+
+    match PKey::private_key_from_pem(key_data) {
+        Ok(key) => return Ok(key),
+        Err(e) => errors.push(format!("PEM ({e})")),
+    }
+    match PKey::private_key_from_der(key_data) {
+        Ok(key) => return Ok(key),
+        Err(e) => errors.push(format!("DER ({e})")),
+    }
+    match PKey::private_key_from_pkcs8(key_data) {
+        Ok(key) => return Ok(key),
+        Err(e) => errors.push(format!("PKCS#8 DER ({e})")),
+    }
+
+    Err(errors)
 }
 
 pub fn sign_data_with_algorithm(
     data: &[u8],
     private_key: &PKey<Private>,
-    algorithm: &HashAlgorithm,
+    algorithm: impl Into<DigestAlgorithm>,
 ) -> Result<Vec<u8>> {
-    let message_digest = match algorithm {
-        HashAlgorithm::Sha256 => MessageDigest::sha256(),
-        HashAlgorithm::Sha384 => MessageDigest::sha384(),
-        HashAlgorithm::Sha512 => MessageDigest::sha512(),
-    };
-
-    let mut signer = Signer::new(message_digest, private_key)
+    let mut signer = openssl::sign::Signer::new(message_digest(algorithm.into()), private_key)
         .map_err(|e| crate::error::Error::Signing(format!("Failed to create signer: {e}")))?;
 
     signer
@@ -37,8 +168,19 @@ pub fn sign_data_with_algorithm(
         .map_err(|e| crate::error::Error::Signing(format!("Failed to sign data: {e}")))
 }
 
+pub(crate) fn message_digest(algorithm: DigestAlgorithm) -> MessageDigest {
+    match algorithm {
+        DigestAlgorithm::Sha256 => MessageDigest::sha256(),
+        DigestAlgorithm::Sha384 => MessageDigest::sha384(),
+        DigestAlgorithm::Sha512 => MessageDigest::sha512(),
+        DigestAlgorithm::Sha3_256 => MessageDigest::sha3_256(),
+        DigestAlgorithm::Sha3_384 => MessageDigest::sha3_384(),
+        DigestAlgorithm::Sha3_512 => MessageDigest::sha3_512(),
+    }
+}
+
 pub fn sign_data(data: &[u8], private_key: &PKey<Private>) -> Result<Vec<u8>> {
-    sign_data_with_algorithm(data, private_key, &HashAlgorithm::Sha384)
+    sign_data_with_algorithm(data, private_key, DigestAlgorithm::Sha384)
 }
 
 pub fn verify_signature(data: &[u8], signature: &[u8], public_key: &PKey<Public>) -> Result<bool> {
@@ -55,6 +197,24 @@ pub fn verify_signature(data: &[u8], signature: &[u8], public_key: &PKey<Public>
         .map_err(|e| Error::Signing(e.to_string()))
 }
 
+pub fn verify_signature_with_algorithm(
+    data: &[u8],
+    signature: &[u8],
+    public_key: &PKey<Public>,
+    algorithm: impl Into<DigestAlgorithm>,
+) -> Result<bool> {
+    let mut verifier = openssl::sign::Verifier::new(message_digest(algorithm.into()), public_key)
+        .map_err(|e| Error::Signing(e.to_string()))?;
+
+    verifier
+        .update(data)
+        .map_err(|e| Error::Signing(e.to_string()))?;
+
+    verifier
+        .verify(signature)
+        .map_err(|e| Error::Signing(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,15 +335,183 @@ mod tests {
         // Should return an error
         assert!(result.is_err(), "Loading non-existent key should fail");
 
-        // The error should be an IO error
+        // The error should report the missing key
         if let Err(e) = result {
             match e {
-                crate::error::Error::Io(_) => {} // Expected error type
+                crate::error::Error::SigningFailure(SigningError::KeyNotFound(_)) => {}
                 _ => panic!("Unexpected error type: {e:?}"),
             }
         }
     }
 
+    #[test]
+    fn test_load_private_key_der() -> Result<()> {
+        let (original_key, dir) = generate_temp_key()?;
+        let test_data = b"test data for signing";
+
+        // Traditional DER and PKCS#8 DER encodings should both load
+        let encodings = [
+            (
+                "test_key.der",
+                original_key
+                    .private_key_to_der()
+                    .map_err(|e| crate::error::Error::Signing(e.to_string()))?,
+            ),
+            (
+                "test_key.pk8",
+                original_key
+                    .private_key_to_pkcs8()
+                    .map_err(|e| crate::error::Error::Signing(e.to_string()))?,
+            ),
+        ];
+
+        for (name, der) in encodings {
+            let key_path = dir.path().join(name);
+            std::fs::write(&key_path, der)?;
+
+            let loaded_key = load_private_key(&key_path)?;
+            assert_eq!(
+                sign_data(test_data, &original_key)?,
+                sign_data(test_data, &loaded_key)?,
+                "Signatures from original and {name} keys should match"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_private_key_with_passphrase() -> Result<()> {
+        use openssl::symm::Cipher;
+
+        let (original_key, dir) = generate_temp_key()?;
+        let passphrase = b"correct horse battery staple";
+
+        let encodings = [
+            (
+                "encrypted.pem",
+                original_key
+                    .private_key_to_pem_pkcs8_passphrase(Cipher::aes_256_cbc(), passphrase)
+                    .map_err(|e| crate::error::Error::Signing(e.to_string()))?,
+            ),
+            (
+                "encrypted.pk8",
+                original_key
+                    .private_key_to_pkcs8_passphrase(Cipher::aes_256_cbc(), passphrase)
+                    .map_err(|e| crate::error::Error::Signing(e.to_string()))?,
+            ),
+        ];
+
+        for (name, data) in encodings {
+            let key_path = dir.path().join(name);
+            std::fs::write(&key_path, data)?;
+
+            let loaded_key = load_private_key_with_passphrase(&key_path, passphrase)?;
+            assert!(
+                original_key.public_eq(&loaded_key),
+                "{name} should load with the correct passphrase"
+            );
+
+            // A wrong passphrase must be rejected
+            assert!(load_private_key_with_passphrase(&key_path, b"wrong").is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_private_key_rejects_weak_rsa() -> Result<()> {
+        let dir = tempdir()?;
+        let key_path = dir.path().join("weak.pem");
+        let rsa = Rsa::generate(1024).map_err(|e| crate::error::Error::Signing(e.to_string()))?;
+        let key = PKey::from_rsa(rsa).map_err(|e| crate::error::Error::Signing(e.to_string()))?;
+        std::fs::write(
+            &key_path,
+            key.private_key_to_pem_pkcs8()
+                .map_err(|e| crate::error::Error::Signing(e.to_string()))?,
+        )?;
+
+        match load_private_key(&key_path) {
+            Err(crate::error::Error::Signing(msg)) => {
+                assert!(msg.contains("1024 bits"), "unexpected message: {msg}");
+                assert!(msg.contains("2048"), "unexpected message: {msg}");
+            }
+            other => panic!("Expected signing error, got {other:?}"),
+        }
+
+        // The minimum is configurable
+        let loaded = load_private_key_with_min_bits(&key_path, 1024)?;
+        assert!(key.public_eq(&loaded));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_private_key_rejects_public_key() -> Result<()> {
+        let (private_key, dir) = generate_temp_key()?;
+        let key_path = dir.path().join("public.pem");
+        std::fs::write(
+            &key_path,
+            private_key
+                .public_key_to_pem()
+                .map_err(|e| crate::error::Error::Signing(e.to_string()))?,
+        )?;
+
+        match load_private_key(&key_path) {
+            Err(crate::error::Error::Signing(msg)) => {
+                assert!(
+                    msg.contains("contains a public key"),
+                    "unexpected message: {msg}"
+                );
+            }
+            other => panic!("Expected signing error, got {other:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_private_key_types() -> Result<()> {
+        use openssl::ec::{EcGroup, EcKey};
+        use openssl::nid::Nid;
+
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)
+            .map_err(|e| crate::error::Error::Signing(e.to_string()))?;
+        let ec =
+            EcKey::generate(&group).map_err(|e| crate::error::Error::Signing(e.to_string()))?;
+        let ec_key =
+            PKey::from_ec_key(ec).map_err(|e| crate::error::Error::Signing(e.to_string()))?;
+        validate_private_key(&ec_key, DEFAULT_MIN_RSA_KEY_BITS)?;
+
+        // Ed25519 cannot be used with a separate digest, so it is rejected up front
+        let ed_key =
+            PKey::generate_ed25519().map_err(|e| crate::error::Error::Signing(e.to_string()))?;
+        assert!(matches!(
+            validate_private_key(&ed_key, DEFAULT_MIN_RSA_KEY_BITS),
+            Err(crate::error::Error::Signing(msg)) if msg.contains("Unsupported private key type")
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_private_key_unknown_format() -> Result<()> {
+        let dir = tempdir()?;
+        let key_path = dir.path().join("garbage.key");
+        std::fs::write(&key_path, b"this is not a key")?;
+
+        match load_private_key(&key_path) {
+            Err(crate::error::Error::Signing(msg)) => {
+                assert!(msg.contains("PEM"), "error should name PEM: {msg}");
+                assert!(msg.contains("DER"), "error should name DER: {msg}");
+                assert!(msg.contains("PKCS#8"), "error should name PKCS#8: {msg}");
+            }
+            other => panic!("Expected signing error, got {other:?}"),
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_sign_data_with_empty_data() -> Result<()> {
         // Generate a temporary key
@@ -0,0 +1,71 @@
+//! # Signers
+//!
+//! A [`Signer`] produces the claim signature of a new manifest. The key
+//! behind it can be a local key file ([`LocalSigner`]) or a key that never
+//! leaves a cloud KMS ([`crate::signing::kms::KmsSigner`]). Either way the
+//! signature is encoded the way verification expects: PKCS#1 v1.5 for RSA
+//! keys and DER-encoded ECDSA for EC keys.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::hash::DigestAlgorithm;
+//! use atlas_cli::signing::keygen::{KeyType, generate_private_key, public_key};
+//! use atlas_cli::signing::signer::{LocalSigner, Signer};
+//! use atlas_cli::signing::verify_signature_with_algorithm;
+//!
+//! let key = generate_private_key(KeyType::EcP256).unwrap();
+//! let public = public_key(&key).unwrap();
+//! let signer = LocalSigner::new(key, "generated key");
+//!
+//! let signature = signer.sign(b"claim", DigestAlgorithm::Sha256).unwrap();
+//! assert!(verify_signature_with_algorithm(b"claim", &signature, &public, DigestAlgorithm::Sha256).unwrap());
+//! ```
+
+use crate::error::Result;
+use crate::hash::DigestAlgorithm;
+use crate::signing::{load_private_key_with_min_bits, sign_data_with_algorithm};
+use openssl::pkey::{PKey, Private};
+use std::path::Path;
+
+/// Signs claims with a key that may live outside this process
+pub trait Signer: Send + Sync {
+    /// Signs `data`, hashed with `alg`
+    fn sign(&self, data: &[u8], alg: DigestAlgorithm) -> Result<Vec<u8>>;
+
+    /// Where the key lives, e.g. a key file path or a KMS key URI
+    fn description(&self) -> String;
+}
+
+/// Signs with a private key held in memory, usually loaded from a key file
+pub struct LocalSigner {
+    key: PKey<Private>,
+    description: String,
+}
+
+impl LocalSigner {
+    pub fn new(key: PKey<Private>, description: impl Into<String>) -> Self {
+        Self {
+            key,
+            description: description.into(),
+        }
+    }
+
+    /// Loads the key file, rejecting RSA keys smaller than `min_rsa_bits`
+    pub fn load(key_path: &Path, min_rsa_bits: u32) -> Result<Self> {
+        Ok(Self::new(
+            load_private_key_with_min_bits(key_path, min_rsa_bits)?,
+            key_path.display().to_string(),
+        ))
+    }
+}
+
+impl Signer for LocalSigner {
+    fn sign(&self, data: &[u8], alg: DigestAlgorithm) -> Result<Vec<u8>> {
+        sign_data_with_algorithm(data, &self.key, alg)
+    }
+
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+}
@@ -0,0 +1,459 @@
+//! RFC 3161 trusted timestamps for claim signatures.
+//!
+//! After a claim is signed, the SHA-256 digest of the signature is sent to a
+//! Time Stamp Authority (TSA). The returned token is stored in the claim as a
+//! `CustomAssertion` labelled [`TIMESTAMP_ASSERTION_LABEL`], added after
+//! signing, so it is not covered by the claim signature.
+
+use crate::error::{Error, Result};
+use openssl::cms::{CMSOptions, CmsContentInfo};
+use openssl::x509::store::X509Store;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// Label of the assertion holding the RFC 3161 timestamp token
+pub const TIMESTAMP_ASSERTION_LABEL: &str = "atlas.timestamp";
+
+/// DER encoding of the SHA-256 algorithm OID (2.16.840.1.101.3.4.2.1)
+const SHA256_OID: &[u8] = &[
+    0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+];
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+
+/// Details extracted from a verified timestamp token
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimestampInfo {
+    /// Time at which the TSA asserts the signature existed (GeneralizedTime)
+    pub gen_time: String,
+}
+
+fn der_length(len: usize) -> Vec<u8> {
+    if len < 0x80 {
+        return vec![len as u8];
+    }
+    let bytes: Vec<u8> = len
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|b| *b == 0)
+        .collect();
+    let mut out = vec![0x80 | bytes.len() as u8];
+    out.extend(bytes);
+    out
+}
+
+fn der_tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    out.extend(der_length(content.len()));
+    out.extend_from_slice(content);
+    out
+}
+
+/// Content of a DER INTEGER holding the non-negative `value`
+fn der_unsigned(value: u64) -> Vec<u8> {
+    let mut bytes: Vec<u8> = value
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|b| *b == 0)
+        .collect();
+    if bytes.first().is_none_or(|b| b & 0x80 != 0) {
+        bytes.insert(0, 0);
+    }
+    bytes
+}
+
+/// Reads one DER element, returning its tag, content and the remaining bytes
+fn der_read(data: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    let malformed = || Error::Validation("Malformed DER in timestamp data".to_string());
+
+    let (&tag, rest) = data.split_first().ok_or_else(malformed)?;
+    let (&first, rest) = rest.split_first().ok_or_else(malformed)?;
+
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > std::mem::size_of::<usize>() || rest.len() < count {
+            return Err(malformed());
+        }
+        let len = rest[..count]
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, &rest[count..])
+    };
+
+    if rest.len() < len {
+        return Err(malformed());
+    }
+    Ok((tag, &rest[..len], &rest[len..]))
+}
+
+fn der_expect(data: &[u8], expected: u8) -> Result<(&[u8], &[u8])> {
+    let (tag, content, rest) = der_read(data)?;
+    if tag != expected {
+        return Err(Error::Validation(format!(
+            "Unexpected DER tag 0x{tag:02x} in timestamp data, expected 0x{expected:02x}"
+        )));
+    }
+    Ok((content, rest))
+}
+
+/// Build a DER-encoded `TimeStampReq` for a SHA-256 message imprint
+pub fn build_timestamp_request(digest: &[u8], nonce: u64) -> Vec<u8> {
+    let algorithm = der_tlv(TAG_SEQUENCE, &[SHA256_OID, &[TAG_NULL, 0x00]].concat());
+    let imprint = der_tlv(
+        TAG_SEQUENCE,
+        &[algorithm, der_tlv(TAG_OCTET_STRING, digest)].concat(),
+    );
+
+    der_tlv(
+        TAG_SEQUENCE,
+        &[
+            der_tlv(TAG_INTEGER, &[1]),
+            imprint,
+            der_tlv(TAG_INTEGER, &der_unsigned(nonce)),
+            // Ask the TSA to include its certificate in the token
+            der_tlv(TAG_BOOLEAN, &[0xff]),
+        ]
+        .concat(),
+    )
+}
+
+/// Extract the timestamp token from a DER-encoded `TimeStampResp`
+pub fn parse_timestamp_response(response: &[u8]) -> Result<Vec<u8>> {
+    let (resp, _) = der_expect(response, TAG_SEQUENCE)?;
+    let (status_info, token) = der_expect(resp, TAG_SEQUENCE)?;
+    let (status, _) = der_expect(status_info, TAG_INTEGER)?;
+
+    // 0 = granted, 1 = grantedWithMods
+    if !matches!(status, [0] | [1]) {
+        return Err(Error::Signing(format!(
+            "Timestamp request rejected with status {status:?}"
+        )));
+    }
+    if token.is_empty() {
+        return Err(Error::Signing(
+            "Timestamp response does not contain a token".to_string(),
+        ));
+    }
+
+    let (_, _, rest) = der_read(token)?;
+    Ok(token[..token.len() - rest.len()].to_vec())
+}
+
+/// Request an RFC 3161 timestamp token for a signature from a TSA
+pub fn request_timestamp(tsa_url: &str, signature: &[u8]) -> Result<Vec<u8>> {
+    let digest = Sha256::digest(signature);
+    let nonce = u64::from_be_bytes(uuid::Uuid::new_v4().as_bytes()[..8].try_into().unwrap());
+    let request = build_timestamp_request(&digest, nonce);
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| Error::Signing(format!("Failed to create HTTP client: {e}")))?;
+
+    let response = client
+        .post(tsa_url)
+        .header("Content-Type", "application/timestamp-query")
+        .body(request)
+        .send()
+        .map_err(|e| Error::Signing(format!("Failed to contact TSA at {tsa_url}: {e}")))?;
+
+    if !response.status().is_success() {
+        return Err(Error::Signing(format!(
+            "TSA at {tsa_url} returned status {}",
+            response.status()
+        )));
+    }
+
+    let body = response
+        .bytes()
+        .map_err(|e| Error::Signing(format!("Failed to read TSA response: {e}")))?;
+    let token = parse_timestamp_response(&body)?;
+
+    // Only keep a token that answers this request: the TSA must have signed
+    // our nonce and the digest of this signature
+    let tst_info = token_tst_info(&token, None)
+        .map_err(|e| Error::Signing(format!("TSA at {tsa_url} returned an invalid token: {e}")))?;
+    if tst_info.nonce != Some(nonce) {
+        return Err(Error::Signing(format!(
+            "TSA at {tsa_url} returned a token for a different request (nonce mismatch)"
+        )));
+    }
+    if tst_info.hashed != digest.as_slice() {
+        return Err(Error::Signing(format!(
+            "TSA at {tsa_url} returned a token that does not match the claim signature"
+        )));
+    }
+    Ok(token)
+}
+
+/// Verify a timestamp token against the signature it was issued for.
+///
+/// The token's CMS signature is always checked. The TSA certificate chain is
+/// validated only when `trust_store` is provided.
+pub fn verify_timestamp_token(
+    token: &[u8],
+    signature: &[u8],
+    trust_store: Option<&X509Store>,
+) -> Result<TimestampInfo> {
+    let tst_info = token_tst_info(token, trust_store)?;
+    if tst_info.hashed != Sha256::digest(signature).as_slice() {
+        return Err(Error::Validation(
+            "Timestamp token does not match the claim signature".to_string(),
+        ));
+    }
+
+    Ok(TimestampInfo {
+        gen_time: tst_info.gen_time,
+    })
+}
+
+/// The fields of a `TSTInfo` that are checked
+struct TstInfo {
+    /// SHA-256 digest from the message imprint
+    hashed: Vec<u8>,
+    gen_time: String,
+    nonce: Option<u64>,
+}
+
+/// Checks the CMS signature of a timestamp token and parses its `TSTInfo`.
+///
+/// The TSA certificate chain is validated only when `trust_store` is provided.
+fn token_tst_info(token: &[u8], trust_store: Option<&X509Store>) -> Result<TstInfo> {
+    let mut cms = CmsContentInfo::from_der(token)
+        .map_err(|e| Error::Validation(format!("Invalid timestamp token: {e}")))?;
+
+    let flags = match trust_store {
+        Some(_) => CMSOptions::BINARY,
+        None => CMSOptions::BINARY | CMSOptions::NO_SIGNER_CERT_VERIFY,
+    };
+    let mut tst_info = Vec::new();
+    cms.verify(
+        None,
+        trust_store.map(|s| &**s),
+        None,
+        Some(&mut tst_info),
+        flags,
+    )
+    .map_err(|e| Error::Validation(format!("Timestamp token signature is invalid: {e}")))?;
+
+    // TSTInfo ::= SEQUENCE { version, policy, messageImprint, serialNumber,
+    //   genTime, accuracy OPTIONAL, ordering DEFAULT FALSE, nonce OPTIONAL, ... }
+    let (fields, _) = der_expect(&tst_info, TAG_SEQUENCE)?;
+    let (_, rest) = der_expect(fields, TAG_INTEGER)?;
+    let (_, rest) = der_expect(rest, TAG_OID)?;
+    let (imprint, rest) = der_expect(rest, TAG_SEQUENCE)?;
+    let (_, rest) = der_expect(rest, TAG_INTEGER)?;
+    let (gen_time, mut rest) = der_expect(rest, TAG_GENERALIZED_TIME)?;
+
+    let mut nonce = None;
+    while !rest.is_empty() {
+        let (tag, content, after) = der_read(rest)?;
+        match tag {
+            TAG_SEQUENCE | TAG_BOOLEAN => {}
+            TAG_INTEGER => {
+                nonce = Some(der_integer_u64(content)?);
+                break;
+            }
+            _ => break,
+        }
+        rest = after;
+    }
+
+    let (algorithm, hashed) = der_expect(imprint, TAG_SEQUENCE)?;
+    let (hashed, _) = der_expect(hashed, TAG_OCTET_STRING)?;
+    if !algorithm.starts_with(SHA256_OID) {
+        return Err(Error::Validation(
+            "Timestamp token uses an unsupported message imprint algorithm".to_string(),
+        ));
+    }
+
+    Ok(TstInfo {
+        hashed: hashed.to_vec(),
+        gen_time: String::from_utf8_lossy(gen_time).to_string(),
+        nonce,
+    })
+}
+
+/// Decodes a non-negative DER INTEGER that fits in a `u64`
+fn der_integer_u64(content: &[u8]) -> Result<u64> {
+    let bytes: Vec<u8> = content.iter().copied().skip_while(|b| *b == 0).collect();
+    if content.first().is_some_and(|b| b & 0x80 != 0) || bytes.len() > 8 {
+        return Err(Error::Validation(
+            "Timestamp nonce is out of range".to_string(),
+        ));
+    }
+    Ok(bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use openssl::asn1::Asn1Time;
+    use openssl::bn::BigNum;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::{PKey, Private};
+    use openssl::rsa::Rsa;
+    use openssl::x509::{X509, X509NameBuilder};
+
+    fn tsa_identity() -> (X509, PKey<Private>) {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "Test TSA").unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        let serial = BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap();
+        builder.set_serial_number(&serial).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        (builder.build(), key)
+    }
+
+    /// Builds a `TimeStampResp` the way a TSA would for the given request
+    pub(crate) fn fake_tsa_response(request: &[u8]) -> Vec<u8> {
+        fake_tsa_response_with_nonce(request, None)
+    }
+
+    /// Like [`fake_tsa_response`], but answers with `nonce` instead of the
+    /// request's nonce if given
+    fn fake_tsa_response_with_nonce(request: &[u8], nonce: Option<u64>) -> Vec<u8> {
+        let (req, _) = der_expect(request, TAG_SEQUENCE).unwrap();
+        let (_, rest) = der_expect(req, TAG_INTEGER).unwrap();
+        let (_, _, after) = der_read(rest).unwrap();
+        let imprint = rest[..rest.len() - after.len()].to_vec();
+        let (request_nonce, _) = der_expect(after, TAG_INTEGER).unwrap();
+        let nonce = nonce.map_or_else(|| request_nonce.to_vec(), der_unsigned);
+
+        let tst_info = der_tlv(
+            TAG_SEQUENCE,
+            &[
+                der_tlv(TAG_INTEGER, &[1]),
+                der_tlv(TAG_OID, &[0x2a, 0x03, 0x04]),
+                imprint,
+                der_tlv(TAG_INTEGER, &[0x2a]),
+                der_tlv(TAG_GENERALIZED_TIME, b"20260101000000Z"),
+                der_tlv(TAG_INTEGER, &nonce),
+            ]
+            .concat(),
+        );
+
+        let (cert, key) = tsa_identity();
+        let token = CmsContentInfo::sign(
+            Some(&cert),
+            Some(&key),
+            None,
+            Some(&tst_info),
+            CMSOptions::BINARY,
+        )
+        .unwrap()
+        .to_der()
+        .unwrap();
+
+        let status = der_tlv(TAG_SEQUENCE, &der_tlv(TAG_INTEGER, &[0]));
+        der_tlv(TAG_SEQUENCE, &[status, token].concat())
+    }
+
+    #[test]
+    fn test_timestamp_round_trip() -> Result<()> {
+        let signature = b"claim signature";
+        let request = build_timestamp_request(&Sha256::digest(signature), 42);
+
+        let token = parse_timestamp_response(&fake_tsa_response(&request))?;
+        let info = verify_timestamp_token(&token, signature, None)?;
+        assert_eq!(info.gen_time, "20260101000000Z");
+
+        // A token for a different signature is rejected
+        assert!(matches!(
+            verify_timestamp_token(&token, b"other signature", None),
+            Err(Error::Validation(_))
+        ));
+
+        Ok(())
+    }
+
+    fn request_nonce(request: &[u8]) -> u64 {
+        let (req, _) = der_expect(request, TAG_SEQUENCE).unwrap();
+        let (_, rest) = der_expect(req, TAG_INTEGER).unwrap();
+        let (_, rest) = der_expect(rest, TAG_SEQUENCE).unwrap();
+        der_integer_u64(der_expect(rest, TAG_INTEGER).unwrap().0).unwrap()
+    }
+
+    #[test]
+    fn test_request_timestamp_checks_nonce_and_imprint() -> Result<()> {
+        let signature = b"claim signature";
+        let mut server = mockito::Server::new();
+
+        let _granted = server
+            .mock("POST", "/granted")
+            .with_body_from_request(|request| fake_tsa_response(request.body().unwrap()))
+            .create();
+        let token = request_timestamp(&format!("{}/granted", server.url()), signature)?;
+        verify_timestamp_token(&token, signature, None)?;
+
+        // A replayed token answers a different nonce
+        let _replayed = server
+            .mock("POST", "/replayed")
+            .with_body_from_request(|request| {
+                let nonce = request_nonce(request.body().unwrap());
+                fake_tsa_response_with_nonce(request.body().unwrap(), Some(nonce ^ 1))
+            })
+            .create();
+        // A token for another signature carries a different message imprint
+        let _other = server
+            .mock("POST", "/other")
+            .with_body_from_request(|request| {
+                let nonce = request_nonce(request.body().unwrap());
+                fake_tsa_response(&build_timestamp_request(
+                    &Sha256::digest(b"other signature"),
+                    nonce,
+                ))
+            })
+            .create();
+        for path in ["replayed", "other"] {
+            assert!(matches!(
+                request_timestamp(&format!("{}/{path}", server.url()), signature),
+                Err(Error::Signing(_))
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejected_timestamp_response() {
+        let status = der_tlv(TAG_SEQUENCE, &der_tlv(TAG_INTEGER, &[2]));
+        let response = der_tlv(TAG_SEQUENCE, &status);
+        assert!(matches!(
+            parse_timestamp_response(&response),
+            Err(Error::Signing(_))
+        ));
+    }
+
+    #[test]
+    fn test_der_length_encoding() {
+        assert_eq!(der_length(5), vec![5]);
+        assert_eq!(der_length(200), vec![0x81, 200]);
+        assert_eq!(der_length(300), vec![0x82, 0x01, 0x2c]);
+
+        let long = der_tlv(TAG_OCTET_STRING, &[7u8; 300]);
+        let (tag, content, rest) = der_read(&long).unwrap();
+        assert_eq!((tag, content.len(), rest.len()), (TAG_OCTET_STRING, 300, 0));
+    }
+}
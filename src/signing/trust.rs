@@ -0,0 +1,168 @@
+//! # Signer Trust Lists
+//!
+//! A trust list names the public keys allowed to sign manifests. Each entry
+//! is either a PEM public key or the key's fingerprint: the hex SHA-256 of its
+//! DER-encoded SubjectPublicKeyInfo, optionally prefixed with `sha256:` and
+//! written with or without colons. Blank lines and lines starting with `#`
+//! are ignored.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::signing::trust::{TrustList, public_key_fingerprint};
+//! use openssl::pkey::PKey;
+//!
+//! let key = PKey::generate_ed25519().unwrap();
+//! let public = PKey::public_key_from_der(&key.public_key_to_der().unwrap()).unwrap();
+//! let fingerprint = public_key_fingerprint(&public).unwrap();
+//!
+//! let list = TrustList::parse(&format!("# release signer\nsha256:{fingerprint}\n")).unwrap();
+//! assert!(list.contains(&public).unwrap());
+//! ```
+
+use crate::error::{Error, Result};
+use openssl::pkey::{PKey, Public};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::Path;
+
+const PEM_BEGIN: &str = "-----BEGIN ";
+const PEM_END: &str = "-----END ";
+
+/// Hex SHA-256 of the DER-encoded public key
+pub fn public_key_fingerprint(key: &PKey<Public>) -> Result<String> {
+    let der = key
+        .public_key_to_der()
+        .map_err(|e| Error::Signing(format!("Failed to encode public key: {e}")))?;
+    Ok(hex::encode(Sha256::digest(der)))
+}
+
+/// Public keys allowed to sign manifests
+#[derive(Default)]
+pub struct TrustList {
+    fingerprints: HashSet<String>,
+    keys: Vec<PKey<Public>>,
+}
+
+impl TrustList {
+    /// Reads a trust list file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            Error::Validation(format!("Failed to read trust list {}: {e}", path.display()))
+        })?;
+        Self::parse(&contents).map_err(|e| match e {
+            Error::Validation(msg) => {
+                Error::Validation(format!("Invalid trust list {}: {msg}", path.display()))
+            }
+            other => other,
+        })
+    }
+
+    /// Parses trust list entries; fails if there are none
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut list = Self::default();
+        let mut lines = contents.lines().enumerate();
+
+        while let Some((index, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with(PEM_BEGIN) {
+                let mut pem = format!("{line}\n");
+                for (_, line) in lines.by_ref() {
+                    pem.push_str(line.trim());
+                    pem.push('\n');
+                    if line.trim().starts_with(PEM_END) {
+                        break;
+                    }
+                }
+                let key = PKey::public_key_from_pem(pem.as_bytes()).map_err(|e| {
+                    Error::Validation(format!("line {}: invalid PEM public key: {e}", index + 1))
+                })?;
+                list.fingerprints.insert(public_key_fingerprint(&key)?);
+                list.keys.push(key);
+                continue;
+            }
+
+            let fingerprint = line
+                .strip_prefix("sha256:")
+                .unwrap_or(line)
+                .replace(':', "")
+                .to_lowercase();
+            if fingerprint.len() != 64 || !fingerprint.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(Error::Validation(format!(
+                    "line {}: expected a SHA-256 fingerprint or PEM public key",
+                    index + 1
+                )));
+            }
+            list.fingerprints.insert(fingerprint);
+        }
+
+        if list.fingerprints.is_empty() {
+            return Err(Error::Validation("no trusted keys listed".to_string()));
+        }
+        Ok(list)
+    }
+
+    /// Returns true if the key's fingerprint is listed
+    pub fn contains(&self, key: &PKey<Public>) -> Result<bool> {
+        Ok(self.fingerprints.contains(&public_key_fingerprint(key)?))
+    }
+
+    /// Keys listed in full, which can check signatures that do not record
+    /// their signer's key
+    pub fn keys(&self) -> &[PKey<Public>] {
+        &self.keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::nid::Nid;
+
+    fn ec_public_key() -> PKey<Public> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        PKey::public_key_from_pem(&key.public_key_to_pem().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_trust_list() -> Result<()> {
+        let pem_key = ec_public_key();
+        let listed = ec_public_key();
+        let unlisted = ec_public_key();
+
+        let colon_fingerprint = public_key_fingerprint(&listed)?
+            .to_uppercase()
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| String::from_utf8_lossy(pair).into_owned())
+            .collect::<Vec<_>>()
+            .join(":");
+        let contents = format!(
+            "# team keys\n\n{}\n{colon_fingerprint}\n",
+            String::from_utf8_lossy(&pem_key.public_key_to_pem().unwrap())
+        );
+
+        let list = TrustList::parse(&contents)?;
+        assert!(list.contains(&pem_key)?);
+        assert!(list.contains(&listed)?);
+        assert!(!list.contains(&unlisted)?);
+        assert_eq!(list.keys().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_trust_list_rejects_invalid_entries() {
+        assert!(TrustList::parse("# nothing trusted\n").is_err());
+        match TrustList::parse("abc123\n") {
+            Err(Error::Validation(msg)) => assert!(msg.contains("line 1"), "{msg}"),
+            other => panic!("expected a validation error, got {:?}", other.err()),
+        }
+    }
+}
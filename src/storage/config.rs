@@ -1,3 +1,12 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default time allowed to connect to an HTTP storage service
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default time allowed for a whole HTTP storage request
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Clone)]
 pub enum StorageType {
     Rekor,
@@ -9,6 +18,16 @@ pub struct StorageConfig {
     pub rekor_url: String,
     pub enable_verification: bool,
     pub filesystem_path: Option<String>,
+    /// Time allowed to connect to the database storage service
+    pub connect_timeout: Duration,
+    /// Time allowed for a whole request to the database storage service
+    pub request_timeout: Duration,
+    /// Reject the deprecated storage type names `local` and `filesystem`
+    /// instead of warning about them
+    pub deny_deprecated: bool,
+    /// PEM public key of the Rekor log, used to check its signed tree heads;
+    /// the public Sigstore log's key is built in
+    pub rekor_public_key: Option<PathBuf>,
 }
 
 impl Default for StorageConfig {
@@ -17,6 +36,10 @@ impl Default for StorageConfig {
             rekor_url: "https://rekor.sigstore.dev".to_string(),
             enable_verification: true,
             filesystem_path: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            deny_deprecated: false,
+            rekor_public_key: None,
         }
     }
 }
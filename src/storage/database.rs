@@ -1,16 +1,29 @@
 use crate::error::{Error, Result};
-use crate::manifest::utils::{determine_manifest_type, manifest_type_to_string};
-use crate::storage::traits::{ManifestMetadata, ManifestType, StorageBackend};
+use crate::manifest::utils::{
+    determine_manifest_type, manifest_tags, manifest_type_to_string, parse_manifest_type,
+};
+use crate::manifest::version::migrate_manifest;
+use crate::storage::config::{DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT, StorageConfig};
+use crate::storage::protocol::{
+    ClaimResponse, DeleteResponse, InsertedId, ListQuery, ManifestEntry, StoreRequest,
+};
+use crate::storage::traits::{ManifestFilter, ManifestMetadata, ManifestType, StorageBackend};
+use atlas_c2pa_lib::claim::ClaimV2;
 use atlas_c2pa_lib::manifest::Manifest;
 use reqwest::blocking::Client;
-use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::OnceLock;
 use std::time::Duration;
+use time::UtcOffset;
+use time::format_description::well_known::Rfc3339;
 
 #[derive(Debug, Clone)]
 pub struct DatabaseStorage {
     base_url: String,
     client: Client,
+    check_ready: bool,
+    // Set once the service has reported ready, so it is probed only once
+    ready: OnceLock<()>,
 }
 
 impl DatabaseStorage {
@@ -33,48 +46,192 @@ impl DatabaseStorage {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct StoredManifest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    _id: Option<serde_json::Value>,
-    manifest_id: String,
-    manifest_type: String,
-    manifest: Value,
-    created_at: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct ManifestWrapper {
-    manifest_id: String,
-    manifest_type: String,
-    #[serde(flatten)]
-    manifest: Manifest,
-    created_at: String,
-}
-
 impl DatabaseStorage {
+    /// Connects to the service at `url` with the default timeouts (5 seconds
+    /// to connect, 30 seconds per request)
     pub fn new(url: String) -> Result<Self> {
+        Self::with_timeouts(url, DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    /// Connects to the service at `url` with the timeouts from `config`
+    pub fn new_with_config(url: String, config: &StorageConfig) -> Result<Self> {
+        Self::with_timeouts(url, config.connect_timeout, config.request_timeout)
+    }
+
+    fn with_timeouts(
+        url: String,
+        connect_timeout: Duration,
+        request_timeout: Duration,
+    ) -> Result<Self> {
         let client = Client::builder()
-            .timeout(Duration::from_secs(30))
+            .connect_timeout(connect_timeout)
+            .timeout(request_timeout)
             .build()
             .map_err(|e| Error::Storage(format!("Failed to create HTTP client: {e}")))?;
 
         Ok(Self {
             base_url: url.trim_end_matches('/').to_string(),
             client,
+            check_ready: false,
+            ready: OnceLock::new(),
         })
     }
 
+    /// Probe the service's `/ready` endpoint before the first operation and
+    /// fail with "storage service not ready" instead of a connection error
+    pub fn with_readiness_check(mut self) -> Self {
+        self.check_ready = true;
+        self
+    }
+
+    // Probe `/ready` if readiness checks are enabled and the service has not
+    // yet reported ready
+    fn ensure_ready(&self) -> Result<()> {
+        if !self.check_ready || self.ready.get().is_some() {
+            return Ok(());
+        }
+
+        let not_ready = |reason: String| {
+            Error::Storage(format!(
+                "storage service not ready at {}: {reason}",
+                self.base_url
+            ))
+        };
+        let response = self
+            .client
+            .get(format!("{}/ready", self.base_url))
+            .timeout(Duration::from_secs(5))
+            .send()
+            .map_err(|e| not_ready(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(not_ready(format!("status {status}: {body}")));
+        }
+
+        let _ = self.ready.set(());
+        Ok(())
+    }
+
     fn manifest_url(&self, id: Option<&str>) -> String {
         match id {
             Some(id) => format!("{}/manifests/{}", self.base_url, id),
             None => format!("{}/manifests", self.base_url),
         }
     }
+
+    // Post a manifest to the service, which rejects entries without a known
+    // manifest_type
+    fn post_manifest(&self, id: &str, request: &StoreRequest) -> Result<()> {
+        self.ensure_ready()?;
+        let response = self
+            .client
+            .post(self.manifest_url(Some(id)))
+            .json(request)
+            .send()
+            .map_err(|e| Error::Storage(format!("Failed to store manifest: {e}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(Error::Storage(format!(
+                "Failed to store manifest. Status: {status}: {body}"
+            )));
+        }
+
+        if let Ok(inserted) = response.json::<InsertedId>() {
+            log::debug!("Stored manifest {id} as database entry {}", inserted.oid);
+        }
+        Ok(())
+    }
+
+    // Send a bulk delete request and return the number of deleted manifests
+    // reported by the service
+    fn send_bulk_delete(&self, request: reqwest::blocking::RequestBuilder) -> Result<usize> {
+        self.ensure_ready()?;
+        let response = request
+            .send()
+            .map_err(|e| Error::Storage(format!("Failed to delete manifests: {e}")))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Storage(format!(
+                "Failed to delete manifests. Status: {}",
+                response.status()
+            )));
+        }
+
+        let result: DeleteResponse = response
+            .json()
+            .map_err(|e| Error::Storage(format!("Failed to parse delete response: {e}")))?;
+        Ok(result.deleted as usize)
+    }
+
+    // Fetch the manifest list, filtered by the service on tags and creation
+    // time
+    fn fetch_manifest_list(&self, filter: &ManifestFilter) -> Result<Vec<ManifestMetadata>> {
+        self.ensure_ready()?;
+        let mut query = ListQuery {
+            tag: (!filter.tags.is_empty()).then(|| filter.tags.join(",")),
+            ..Default::default()
+        };
+        // The service stores creation times as UTC RFC 3339 strings with a
+        // `+00:00` offset, so bounds are sent in the same form to compare
+        for (name, bound, field) in [
+            ("since", filter.since, &mut query.since),
+            ("until", filter.until, &mut query.until),
+        ] {
+            if let Some(bound) = bound {
+                *field = Some(
+                    bound
+                        .to_offset(UtcOffset::UTC)
+                        .format(&Rfc3339)
+                        .map_err(|e| Error::Validation(format!("Invalid --{name} bound: {e}")))?
+                        .replace('Z', "+00:00"),
+                );
+            }
+        }
+        let request = self.client.get(self.manifest_url(None)).query(&query);
+        let response = request
+            .send()
+            .map_err(|e| Error::Storage(format!("Failed to list manifests: {e}")))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Storage(format!(
+                "Failed to list manifests. Status: {}",
+                response.status()
+            )));
+        }
+
+        let entries: Vec<ManifestEntry> = response
+            .json()
+            .map_err(|e| Error::Storage(format!("Failed to parse manifests list: {e}")))?;
+
+        Ok(entries
+            .into_iter()
+            .map(|m| {
+                let title = m
+                    .c2pa_manifest()
+                    .and_then(|manifest| manifest.get("title"))
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("Unknown")
+                    .to_string();
+
+                ManifestMetadata {
+                    id: m.manifest_id,
+                    name: title,
+                    manifest_type: parse_manifest_type(&m.manifest_type),
+                    created_at: m.created_at,
+                    tags: m.tags,
+                }
+            })
+            .collect())
+    }
 }
 
 impl StorageBackend for DatabaseStorage {
     fn store_manifest(&self, manifest: &Manifest) -> Result<String> {
+        self.ensure_ready()?;
         // Check if this ID already exists
         let existing = self
             .client
@@ -106,25 +263,22 @@ impl StorageBackend for DatabaseStorage {
                 .send()
                 .map_err(|e| Error::Storage(format!("Failed to list manifests: {e}")))?;
 
-            let all_manifests: Vec<serde_json::Value> = all_manifests_response
+            let all_manifests: Vec<ManifestEntry> = all_manifests_response
                 .json()
                 .map_err(|e| Error::Storage(format!("Failed to parse manifests list: {e}")))?;
 
             // Find highest version for this ID
             let mut max_version = 0;
             for manifest_entry in all_manifests {
-                if let Some(id) = manifest_entry.get("manifest_id").and_then(|v| v.as_str()) {
-                    if id.starts_with(&format!("urn:c2pa:{uuid_part}:")) {
-                        let id_parts: Vec<&str> = id.split(':').collect();
-                        if id_parts.len() >= 5 {
-                            if let Some(version_reason) = id_parts.get(4) {
-                                if let Some(version_str) = version_reason.split('_').next() {
-                                    if let Ok(version) = version_str.parse::<i32>() {
-                                        max_version = max_version.max(version);
-                                    }
-                                }
-                            }
-                        }
+                let id = manifest_entry.manifest_id.as_str();
+                if id.starts_with(&format!("urn:c2pa:{uuid_part}:")) {
+                    let id_parts: Vec<&str> = id.split(':').collect();
+                    if id_parts.len() >= 5
+                        && let Some(version_reason) = id_parts.get(4)
+                        && let Some(version_str) = version_reason.split('_').next()
+                        && let Ok(version) = version_str.parse::<i32>()
+                    {
+                        max_version = max_version.max(version);
                     }
                 }
             }
@@ -146,46 +300,49 @@ impl StorageBackend for DatabaseStorage {
             // Store the manifest with the versioned ID
             let manifest_type = manifest_type_to_string(&determine_manifest_type(manifest));
 
-            let stored_manifest = StoredManifest {
-                _id: None,
+            let request = StoreRequest {
                 manifest_id: versioned_id.clone(),
                 manifest_type,
-                manifest: serde_json::to_value(&updated_manifest)
-                    .map_err(|e| Error::Serialization(e.to_string()))?,
+                manifest: serde_json::to_value(&updated_manifest).map_err(|e| {
+                    Error::Serialization(e.to_string()).with_manifest_context(
+                        "storing manifest",
+                        &manifest.title,
+                        &versioned_id,
+                    )
+                })?,
                 created_at: time::OffsetDateTime::now_utc().to_string(),
+                tags: manifest_tags(manifest),
             };
 
-            self.client
-                .post(self.manifest_url(Some(&versioned_id)))
-                .json(&stored_manifest)
-                .send()
-                .map_err(|e| Error::Storage(format!("Failed to store manifest: {e}")))?;
+            self.post_manifest(&versioned_id, &request)?;
 
             Ok(versioned_id)
         } else {
             // No existing manifest - store normally
             let manifest_type = manifest_type_to_string(&determine_manifest_type(manifest));
 
-            let stored_manifest = StoredManifest {
-                _id: None,
+            let request = StoreRequest {
                 manifest_id: manifest.instance_id.clone(),
                 manifest_type,
-                manifest: serde_json::to_value(manifest)
-                    .map_err(|e| Error::Serialization(e.to_string()))?,
+                manifest: serde_json::to_value(manifest).map_err(|e| {
+                    Error::Serialization(e.to_string()).with_manifest_context(
+                        "storing manifest",
+                        &manifest.title,
+                        &manifest.instance_id,
+                    )
+                })?,
                 created_at: time::OffsetDateTime::now_utc().to_string(),
+                tags: manifest_tags(manifest),
             };
 
-            self.client
-                .post(self.manifest_url(Some(&manifest.instance_id)))
-                .json(&stored_manifest)
-                .send()
-                .map_err(|e| Error::Storage(format!("Failed to store manifest: {e}")))?;
+            self.post_manifest(&manifest.instance_id, &request)?;
 
             Ok(manifest.instance_id.clone())
         }
     }
 
     fn retrieve_manifest(&self, id: &str) -> Result<Manifest> {
+        self.ensure_ready()?;
         // Parse the ID to find the base UUID part
         let parts: Vec<&str> = id.split(':').collect();
         let uuid_part = if parts.len() >= 3 && parts[0] == "urn" && parts[1] == "c2pa" {
@@ -203,17 +360,18 @@ impl StorageBackend for DatabaseStorage {
 
         if response.status().is_success() {
             // Found the manifest, parse it
-            let stored_manifest: StoredManifest = response
+            let entry: ManifestEntry = response
                 .json()
-                .map_err(|e| Error::Storage(format!("Failed to parse manifest: {e}")))?;
+                .map_err(|e| Error::Storage(format!("Failed to parse manifest {id}: {e}")))?;
 
             // Extract the inner manifest
-            let manifest_value = stored_manifest
-                .manifest
-                .get("manifest")
-                .ok_or_else(|| Error::Storage("Invalid manifest structure".to_string()))?;
+            let manifest_value = entry.c2pa_manifest().ok_or_else(|| {
+                Error::Storage(format!(
+                    "Invalid entry for manifest {id}: it holds no manifest"
+                ))
+            })?;
 
-            return serde_json::from_value(manifest_value.clone())
+            return migrate_manifest(manifest_value.clone())
                 .map_err(|e| Error::Storage(format!("Failed to parse manifest data: {e}")));
         }
 
@@ -232,12 +390,12 @@ impl StorageBackend for DatabaseStorage {
         }
 
         // Parse the manifest list
-        let manifests: Vec<StoredManifest> = list_response
+        let manifests: Vec<ManifestEntry> = list_response
             .json()
             .map_err(|e| Error::Storage(format!("Failed to parse manifests list: {e}")))?;
 
         // Find all versions of this manifest
-        let mut versions: Vec<StoredManifest> = manifests
+        let mut versions: Vec<ManifestEntry> = manifests
             .into_iter()
             .filter(|m| m.manifest_id.contains(&format!("urn:c2pa:{uuid_part}:")))
             .collect();
@@ -253,59 +411,59 @@ impl StorageBackend for DatabaseStorage {
         let latest = &versions[0];
 
         // Extract the inner manifest
-        let manifest_value = latest
-            .manifest
-            .get("manifest")
-            .ok_or_else(|| Error::Storage("Invalid manifest structure".to_string()))?;
+        let manifest_value = latest.c2pa_manifest().ok_or_else(|| {
+            Error::Storage(format!(
+                "Invalid entry for manifest {}: it holds no manifest",
+                latest.manifest_id
+            ))
+        })?;
 
-        serde_json::from_value(manifest_value.clone())
+        migrate_manifest(manifest_value.clone())
             .map_err(|e| Error::Storage(format!("Failed to parse manifest data: {e}")))
     }
 
     fn list_manifests(&self) -> Result<Vec<ManifestMetadata>> {
+        self.fetch_manifest_list(&ManifestFilter::default())
+    }
+
+    /// Fetches only the claim via `GET /manifests/{id}/claim`. Unlike
+    /// `retrieve_manifest`, `id` must name a stored version exactly.
+    fn retrieve_claim(&self, id: &str) -> Result<ClaimV2> {
+        self.ensure_ready()?;
         let response = self
             .client
-            .get(self.manifest_url(None))
+            .get(format!("{}/claim", self.manifest_url(Some(id))))
             .send()
-            .map_err(|e| Error::Storage(format!("Failed to list manifests: {e}")))?;
+            .map_err(|e| Error::Storage(format!("Failed to retrieve claim: {e}")))?;
 
         if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
             return Err(Error::Storage(format!(
-                "Failed to list manifests. Status: {}",
-                response.status()
+                "Failed to retrieve claim of manifest {id}. Status: {status}: {body}"
             )));
         }
 
-        let stored_manifests: Vec<StoredManifest> = response
+        let claim: ClaimResponse = response
             .json()
-            .map_err(|e| Error::Storage(format!("Failed to parse manifests list: {e}")))?;
+            .map_err(|e| Error::Storage(format!("Failed to parse claim of manifest {id}: {e}")))?;
+        serde_json::from_value(claim.claim)
+            .map_err(|e| Error::Storage(format!("Failed to parse claim of manifest {id}: {e}")))
+    }
 
-        Ok(stored_manifests
+    /// Filters on the storage service via `?tag=`, `?since=` and `?until=`.
+    /// The service compares creation times as strings, so the results are
+    /// filtered again here to apply the exact bounds.
+    fn list_manifests_filtered(&self, filter: &ManifestFilter) -> Result<Vec<ManifestMetadata>> {
+        Ok(self
+            .fetch_manifest_list(filter)?
             .into_iter()
-            .map(|m| {
-                let title = m
-                    .manifest
-                    .get("manifest")
-                    .and_then(|inner| inner.get("manifest"))
-                    .and_then(|manifest| manifest.get("title"))
-                    .and_then(|t| t.as_str())
-                    .unwrap_or("Unknown")
-                    .to_string();
-
-                ManifestMetadata {
-                    id: m.manifest_id,
-                    name: title,
-                    manifest_type: match m.manifest_type.as_str() {
-                        "dataset" => ManifestType::Dataset,
-                        _ => ManifestType::Model,
-                    },
-                    created_at: m.created_at,
-                }
-            })
+            .filter(|m| filter.matches(m))
             .collect())
     }
 
     fn delete_manifest(&self, id: &str) -> Result<()> {
+        self.ensure_ready()?;
         let response = self
             .client
             .delete(self.manifest_url(Some(id)))
@@ -322,6 +480,24 @@ impl StorageBackend for DatabaseStorage {
         Ok(())
     }
 
+    /// Deletes server-side via `DELETE /manifests?type=`
+    fn delete_by_type(&self, manifest_type: &ManifestType) -> Result<usize> {
+        let request = self
+            .client
+            .delete(self.manifest_url(None))
+            .query(&[("type", manifest_type.as_str())]);
+        self.send_bulk_delete(request)
+    }
+
+    /// Deletes server-side with a JSON array of IDs sent to `DELETE /manifests`
+    fn delete_many(&self, ids: &[String]) -> Result<usize> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let request = self.client.delete(self.manifest_url(None)).json(ids);
+        self.send_bulk_delete(request)
+    }
+
     // Added for test suite
     fn as_any(&self) -> &dyn std::any::Any {
         self
@@ -1,22 +1,39 @@
 use crate::error::{Error, Result};
-use crate::manifest::utils::determine_manifest_type;
+use crate::manifest::canonical::to_canonical_json;
+use crate::manifest::utils::{determine_manifest_type, manifest_created_at, manifest_tags};
+use crate::manifest::version::migrate_manifest;
 use crate::storage::traits::{ManifestMetadata, ManifestType, StorageBackend};
 use crate::utils::{safe_create_file, safe_open_file};
 use atlas_c2pa_lib::manifest::Manifest;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::fs::{self, create_dir_all};
+use std::fs::{self, File, OpenOptions, create_dir_all};
 use std::io::{Read, Write};
 use std::path::Path;
 use std::path::PathBuf;
 
+const INDEX_FILE: &str = "manifest_index.json";
+const LOCK_FILE: &str = ".atlas.lock";
+
 #[derive(Debug, Clone)]
 pub struct FilesystemStorage {
     base_path: PathBuf,
+    content_addressed: bool,
 }
 
 impl FilesystemStorage {
     pub fn new<P: AsRef<Path>>(url: P) -> Result<Self> {
+        Self::with_mode(url, false)
+    }
+
+    /// Create a storage that keys manifests by the SHA-256 of their canonical
+    /// JSON serialization, so identical manifests deduplicate and keys are
+    /// reproducible. Manifests can still be retrieved by `instance_id`.
+    pub fn new_content_addressed<P: AsRef<Path>>(url: P) -> Result<Self> {
+        Self::with_mode(url, true)
+    }
+
+    fn with_mode<P: AsRef<Path>>(url: P, content_addressed: bool) -> Result<Self> {
         // Parse the URL to extract the path
         let path_str = url.as_ref().to_string_lossy();
         let path = if path_str.starts_with("file://") {
@@ -31,11 +48,64 @@ impl FilesystemStorage {
             create_dir_all(&path)?;
         }
 
-        Ok(Self { base_path: path })
+        Ok(Self {
+            base_path: path,
+            content_addressed,
+        })
+    }
+
+    /// Returns the content-addressed key of a manifest: the hex SHA-256 of
+    /// its canonical JSON serialization
+    pub fn content_hash(manifest: &Manifest) -> Result<String> {
+        let canonical = to_canonical_json(manifest).map_err(|e| {
+            e.with_manifest_context("hashing manifest", &manifest.title, &manifest.instance_id)
+        })?;
+        Ok(hex::encode(Sha256::digest(canonical.as_bytes())))
+    }
+
+    // Helper to get the key earlier releases stored a content-addressed
+    // manifest under: the SHA-256 of its plain, non-canonical JSON
+    fn legacy_content_hash(manifest: &Manifest) -> Result<String> {
+        let json = serde_json::to_vec(manifest).map_err(|e| {
+            Error::Serialization(e.to_string()).with_manifest_context(
+                "hashing manifest",
+                &manifest.title,
+                &manifest.instance_id,
+            )
+        })?;
+        Ok(hex::encode(Sha256::digest(&json)))
+    }
+
+    // Helper to get the key a content-addressed manifest file is stored
+    // under, which is its legacy key for files written by earlier releases
+    fn stored_key(&self, manifest: &Manifest, path: &Path) -> Result<String> {
+        let key = Self::content_hash(manifest)?;
+        if self.manifest_path(&key) != path {
+            let legacy_key = Self::legacy_content_hash(manifest)?;
+            if self.manifest_path(&legacy_key) == path {
+                return Ok(legacy_key);
+            }
+        }
+        Ok(key)
+    }
+
+    // Helper to find the file for a manifest, by its storage key or by any
+    // ID recorded in the index
+    fn resolve_path(&self, id: &str) -> Result<Option<PathBuf>> {
+        let path = self.manifest_path(id);
+        if path.exists() {
+            return Ok(Some(path));
+        }
+
+        Ok(self
+            .read_index()?
+            .get(id)
+            .map(|filename| self.base_path.join(filename))
+            .filter(|path| path.exists()))
     }
 
     // Helper to get path for a manifest
-    fn manifest_path(&self, id: &str) -> PathBuf {
+    pub(crate) fn manifest_path(&self, id: &str) -> PathBuf {
         // Create a hash of the ID to use as filename
         let digest = Sha256::digest(id.as_bytes());
         let filename = hex::encode(digest);
@@ -43,13 +113,70 @@ impl FilesystemStorage {
         self.base_path.join(format!("{filename}.json"))
     }
 
+    // Helper to get the temporary path a file is written to before being
+    // renamed into place
+    pub(crate) fn temp_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        path.with_file_name(name)
+    }
+
+    // Helper to take an exclusive advisory lock on the storage directory.
+    // The lock is released when the returned file is dropped.
+    fn lock(&self) -> Result<File> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.base_path.join(LOCK_FILE))?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    // Helper to replace a file atomically, so readers never observe a
+    // partially written file
+    pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+        let temp_path = Self::temp_path(path);
+
+        let mut file = safe_create_file(&temp_path, false)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    // Helper to read the index file
+    fn read_index(&self) -> Result<HashMap<String, String>> {
+        let index_path = self.base_path.join(INDEX_FILE);
+        if !index_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let mut file = safe_open_file(&index_path, false)?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        Ok(serde_json::from_str(&content).unwrap_or_default())
+    }
+
+    // Helper to write the index file
+    fn write_index(&self, index: &HashMap<String, String>) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(index).map_err(|e| Error::Serialization(e.to_string()))?;
+        Self::write_atomic(&self.base_path.join(INDEX_FILE), json.as_bytes())
+    }
+
     // Helper to list all manifest files
     fn list_manifest_files(&self) -> Result<Vec<PathBuf>> {
         let entries = fs::read_dir(&self.base_path)?
             .filter_map(|entry| {
                 let entry = entry.ok()?;
                 let path = entry.path();
-                if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+                if path.is_file()
+                    && path.extension().is_some_and(|ext| ext == "json")
+                    && path.file_name().is_some_and(|name| name != INDEX_FILE)
+                {
                     Some(path)
                 } else {
                     None
@@ -60,69 +187,112 @@ impl FilesystemStorage {
         Ok(entries)
     }
 
-    // Helper to update index file for quick ID lookups
-    fn update_index(&self, id: &str, filename: &str) -> Result<()> {
-        let index_path = self.base_path.join("manifest_index.json");
-
-        // Read existing index or create new one
-        let mut index: HashMap<String, String> = if index_path.exists() {
-            let mut file = safe_open_file(&index_path, false)?;
-            let mut content = String::new();
-            file.read_to_string(&mut content)?;
-            serde_json::from_str(&content).unwrap_or_default()
+    // Helper to write a manifest under its storage key, refusing to replace
+    // a different manifest unless overwriting
+    fn write_manifest(&self, manifest: &Manifest, overwrite: bool) -> Result<String> {
+        let manifest_id = if self.content_addressed {
+            // Already stored by an earlier release under its legacy key
+            let legacy_key = Self::legacy_content_hash(manifest)?;
+            if !overwrite && self.manifest_path(&legacy_key).exists() {
+                return Ok(legacy_key);
+            }
+            Self::content_hash(manifest)?
         } else {
-            HashMap::new()
+            manifest.instance_id.clone()
         };
+        let path = self.manifest_path(&manifest_id);
 
-        // Update index
-        index.insert(id.to_string(), filename.to_string());
+        // Serialize to JSON
+        let json = serde_json::to_string_pretty(manifest).map_err(|e| {
+            Error::Serialization(e.to_string()).with_manifest_context(
+                "storing manifest",
+                &manifest.title,
+                &manifest.instance_id,
+            )
+        })?;
+
+        // Hold the storage lock so concurrent writers don't interleave,
+        // and write via a temporary file so a crash can't truncate the manifest
+        let _lock = self.lock()?;
+        if !overwrite && path.exists() {
+            if fs::read(&path)? != json.as_bytes() {
+                return Err(Error::Storage(format!(
+                    "manifest already exists: {manifest_id}"
+                )));
+            }
+        } else {
+            Self::write_atomic(&path, json.as_bytes())?;
+        }
+
+        // Update index for quick lookups
+        if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
+            self.update_index(&manifest_id, filename)?;
+            // Content-addressed manifests stay retrievable by instance ID
+            if self.content_addressed {
+                self.update_index(&manifest.instance_id, filename)?;
+            }
+        }
 
-        // Write back to file
-        let json = serde_json::to_string_pretty(&index)
-            .map_err(|e| Error::Serialization(e.to_string()))?;
-        let mut file = safe_create_file(&index_path, false)?;
-        file.write_all(json.as_bytes())?;
+        Ok(manifest_id)
+    }
 
-        Ok(())
+    // Helper to update index file for quick ID lookups
+    fn update_index(&self, id: &str, filename: &str) -> Result<()> {
+        let mut index = self.read_index()?;
+        index.insert(id.to_string(), filename.to_string());
+        self.write_index(&index)
     }
 }
 
 impl StorageBackend for FilesystemStorage {
+    /// Stores a new manifest. Storing a manifest whose ID is already taken
+    /// fails with a storage error, unless the stored file is identical, so
+    /// retrying a store is safe; use `update_manifest` to replace a manifest.
     fn store_manifest(&self, manifest: &Manifest) -> Result<String> {
-        let manifest_id = manifest.instance_id.clone();
-        let path = self.manifest_path(&manifest_id);
+        self.write_manifest(manifest, false)
+    }
 
-        // Serialize to JSON
-        let json = serde_json::to_string_pretty(manifest)
-            .map_err(|e| Error::Serialization(e.to_string()))?;
+    fn update_manifest(&self, manifest: &Manifest) -> Result<String> {
+        self.write_manifest(manifest, true)
+    }
 
-        // Write to file
-        let mut file = safe_create_file(&path, false)?;
-        file.write_all(json.as_bytes())?;
+    fn get_manifest_size(&self, id: &str) -> Result<Option<u64>> {
+        let Some(path) = self.resolve_path(id)? else {
+            return Err(Error::Storage(format!("Manifest not found: {id}")));
+        };
+        Ok(Some(fs::metadata(path)?.len()))
+    }
 
-        // Update index for quick lookups
-        if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-            self.update_index(&manifest_id, filename)?;
+    fn get_total_storage_size(&self) -> Result<Option<u64>> {
+        let mut total_size = 0;
+
+        for path in self.list_manifest_files()? {
+            total_size += fs::metadata(path)?.len();
         }
 
-        Ok(manifest_id)
+        // Include index file if it exists
+        let index_path = self.base_path.join(INDEX_FILE);
+        if index_path.exists() {
+            total_size += fs::metadata(index_path)?.len();
+        }
+
+        Ok(Some(total_size))
     }
 
     fn retrieve_manifest(&self, id: &str) -> Result<Manifest> {
-        let path = self.manifest_path(id);
-
-        if !path.exists() {
+        let Some(path) = self.resolve_path(id)? else {
             return Err(Error::Storage(format!("Manifest not found: {id}")));
-        }
+        };
 
         // Read file
         let mut file = safe_open_file(&path, false)?;
         let mut content = String::new();
         file.read_to_string(&mut content)?;
 
-        // Deserialize
-        serde_json::from_str(&content)
-            .map_err(|e| Error::Serialization(format!("Failed to parse manifest: {e}")))
+        // Deserialize, upgrading manifests written by older versions
+        let value = serde_json::from_str(&content)
+            .map_err(|e| Error::Serialization(format!("Failed to parse manifest: {e}")))?;
+        migrate_manifest(value)
     }
 
     fn list_manifests(&self) -> Result<Vec<ManifestMetadata>> {
@@ -133,21 +303,31 @@ impl StorageBackend for FilesystemStorage {
             let mut content = String::new();
             file.read_to_string(&mut content)?;
 
-            match serde_json::from_str::<Manifest>(&content) {
+            match serde_json::from_str(&content)
+                .map_err(Error::from)
+                .and_then(migrate_manifest)
+            {
                 Ok(manifest) => {
                     // Determine manifest type
                     let manifest_type = determine_manifest_type(&manifest);
 
+                    let id = if self.content_addressed {
+                        self.stored_key(&manifest, &path)?
+                    } else {
+                        manifest.instance_id.clone()
+                    };
+
                     manifests.push(ManifestMetadata {
-                        id: manifest.instance_id.clone(),
+                        id,
                         name: manifest.title.clone(),
                         manifest_type,
-                        created_at: manifest.created_at.0.to_string(),
+                        created_at: manifest_created_at(&manifest),
+                        tags: manifest_tags(&manifest),
                     });
                 }
                 Err(e) => {
                     // Log but don't fail on unparseable manifest
-                    eprintln!("Error parsing manifest at {path:?}: {e}");
+                    log::warn!("Error parsing manifest at {path:?}: {e}");
                 }
             }
         }
@@ -156,32 +336,23 @@ impl StorageBackend for FilesystemStorage {
     }
 
     fn delete_manifest(&self, id: &str) -> Result<()> {
-        let path = self.manifest_path(id);
-
-        if !path.exists() {
+        let Some(path) = self.resolve_path(id)? else {
             return Err(Error::Storage(format!("Manifest not found: {id}")));
-        }
+        };
 
+        let _lock = self.lock()?;
         fs::remove_file(&path)?;
 
-        // Update index
-        let index_path = self.base_path.join("manifest_index.json");
-        if index_path.exists() {
-            let mut file = safe_open_file(&index_path, false)?;
-            let mut content = String::new();
-            file.read_to_string(&mut content)?;
-
-            let mut index: HashMap<String, String> =
-                serde_json::from_str(&content).unwrap_or_default();
-
-            // Remove entry
-            index.remove(id);
-
-            // Write back to file
-            let json = serde_json::to_string_pretty(&index)
-                .map_err(|e| Error::Serialization(e.to_string()))?;
-            let mut file = safe_create_file(&path, false)?;
-            file.write_all(json.as_bytes())?;
+        // Update index, removing every ID that pointed at the deleted file
+        let mut index = self.read_index()?;
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        let before = index.len();
+        index.retain(|key, value| key != id && value != filename);
+        if index.len() != before {
+            self.write_index(&index)?;
         }
 
         Ok(())
@@ -189,6 +360,10 @@ impl StorageBackend for FilesystemStorage {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn manifest_exists(&self, id: &str) -> Result<bool> {
+        Ok(self.resolve_path(id)?.is_some())
+    }
 }
 
 // No conflict impl.
@@ -217,8 +392,13 @@ impl FilesystemStorage {
 
         for metadata in manifests {
             let manifest = self.retrieve_manifest(&metadata.id)?;
-            let json = serde_json::to_string_pretty(&manifest)
-                .map_err(|e| Error::Serialization(e.to_string()))?;
+            let json = serde_json::to_string_pretty(&manifest).map_err(|e| {
+                Error::Serialization(e.to_string()).with_manifest_context(
+                    "exporting manifest",
+                    &manifest.title,
+                    &metadata.id,
+                )
+            })?;
 
             let filename = format!("{}.json", metadata.id.replace(":", "_"));
             let export_file_path = export_path.join(filename);
@@ -257,13 +437,16 @@ impl FilesystemStorage {
             let mut content = String::new();
             file.read_to_string(&mut content)?;
 
-            match serde_json::from_str::<Manifest>(&content) {
-                Ok(manifest) => {
-                    self.store_manifest(&manifest)?;
+            match serde_json::from_str(&content)
+                .map_err(Error::from)
+                .and_then(migrate_manifest)
+                .and_then(|manifest| self.store_manifest(&manifest))
+            {
+                Ok(_) => {
                     imported_count += 1;
                 }
                 Err(e) => {
-                    eprintln!("Error importing manifest from {path:?}: {e}");
+                    log::warn!("Error importing manifest from {path:?}: {e}");
                 }
             }
         }
@@ -271,40 +454,9 @@ impl FilesystemStorage {
         Ok(imported_count)
     }
 
-    // Get manifest file size
-    pub fn get_manifest_size(&self, id: &str) -> Result<u64> {
-        let path = self.manifest_path(id);
-
-        if !path.exists() {
-            return Err(Error::Storage(format!("Manifest not found: {id}")));
-        }
-
-        let metadata = fs::metadata(path)?;
-        Ok(metadata.len())
-    }
-
-    // Get total storage size
-    pub fn get_total_storage_size(&self) -> Result<u64> {
-        let mut total_size = 0;
-
-        for path in self.list_manifest_files()? {
-            let metadata = fs::metadata(path)?;
-            total_size += metadata.len();
-        }
-
-        // Include index file if it exists
-        let index_path = self.base_path.join("manifest_index.json");
-        if index_path.exists() {
-            let metadata = fs::metadata(index_path)?;
-            total_size += metadata.len();
-        }
-
-        Ok(total_size)
-    }
-
     // Check if a manifest exists
     pub fn manifest_exists(&self, id: &str) -> bool {
-        self.manifest_path(id).exists()
+        matches!(self.resolve_path(id), Ok(Some(_)))
     }
 
     // Backup all manifests to a directory
@@ -322,9 +474,9 @@ impl FilesystemStorage {
         }
 
         // Copy index file if it exists
-        let index_path = self.base_path.join("manifest_index.json");
+        let index_path = self.base_path.join(INDEX_FILE);
         if index_path.exists() {
-            let dest_path = backup_path.join("manifest_index.json");
+            let dest_path = backup_path.join(INDEX_FILE);
             fs::copy(index_path, dest_path)?;
         }
 
@@ -0,0 +1,74 @@
+use crate::error::{Error, Result};
+use crate::manifest::utils::{determine_manifest_type, manifest_created_at, manifest_tags};
+use crate::storage::traits::{ManifestMetadata, StorageBackend};
+use atlas_c2pa_lib::manifest::Manifest;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Storage backend that keeps manifests in process memory.
+///
+/// Useful for embedding the manifest logic in other tools and for ephemeral
+/// pipelines; nothing is persisted once the storage is dropped.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    manifests: Mutex<HashMap<String, Manifest>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn manifests(&self) -> Result<MutexGuard<'_, HashMap<String, Manifest>>> {
+        self.manifests
+            .lock()
+            .map_err(|e| Error::Storage(format!("Memory storage lock poisoned: {e}")))
+    }
+}
+
+impl StorageBackend for MemoryStorage {
+    fn store_manifest(&self, manifest: &Manifest) -> Result<String> {
+        let id = manifest.instance_id.clone();
+        self.manifests()?.insert(id.clone(), manifest.clone());
+        Ok(id)
+    }
+
+    fn retrieve_manifest(&self, id: &str) -> Result<Manifest> {
+        self.manifests()?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Error::Storage(format!("Manifest not found: {id}")))
+    }
+
+    fn list_manifests(&self) -> Result<Vec<ManifestMetadata>> {
+        let mut manifests: Vec<ManifestMetadata> = self
+            .manifests()?
+            .iter()
+            .map(|(id, manifest)| ManifestMetadata {
+                id: id.clone(),
+                name: manifest.title.clone(),
+                manifest_type: determine_manifest_type(manifest),
+                created_at: manifest_created_at(manifest),
+                tags: manifest_tags(manifest),
+            })
+            .collect();
+
+        manifests.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(manifests)
+    }
+
+    fn delete_manifest(&self, id: &str) -> Result<()> {
+        self.manifests()?
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| Error::Storage(format!("Manifest not found: {id}")))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn manifest_exists(&self, id: &str) -> Result<bool> {
+        Ok(self.manifests()?.contains_key(id))
+    }
+}
@@ -0,0 +1,116 @@
+//! # Storage Migration
+//!
+//! Copies every manifest from one storage backend to another, for example
+//! from a development filesystem store to the production database. Manifests
+//! keep their IDs unless the destination assigns its own (content-addressed
+//! filesystem storage, Rekor).
+//!
+//! Migration stops at the first manifest that cannot be copied. Re-running
+//! with `skip_existing` resumes without rewriting manifests already copied.
+
+use crate::error::{Error, Result};
+use crate::progress;
+use crate::storage::traits::StorageBackend;
+use std::collections::HashSet;
+
+/// Result of copying manifests between backends
+#[derive(Debug, Default)]
+pub struct MigrationSummary {
+    /// Number of manifests stored in the destination
+    pub copied: usize,
+    /// Number of manifests skipped because the destination already had them
+    pub skipped: usize,
+    /// Source and destination IDs of manifests the destination stored under a new ID
+    pub renamed: Vec<(String, String)>,
+}
+
+/// Splits a `type:url` storage spec such as `local-fs:/var/lib/atlas` or
+/// `database:http://localhost:8080`. The `memory` type needs no URL.
+///
+/// # Examples
+///
+/// ```
+/// use atlas_cli::storage::migrate::parse_storage_spec;
+///
+/// let (storage_type, url) = parse_storage_spec("database:http://localhost:8080").unwrap();
+/// assert_eq!(storage_type, "database");
+/// assert_eq!(url, "http://localhost:8080");
+///
+/// assert!(parse_storage_spec("local-fs").is_err());
+/// ```
+pub fn parse_storage_spec(spec: &str) -> Result<(String, String)> {
+    match spec.split_once(':') {
+        Some((storage_type, url)) if !storage_type.is_empty() && !url.is_empty() => {
+            Ok((storage_type.to_string(), url.to_string()))
+        }
+        None if spec == "memory" => Ok((spec.to_string(), String::new())),
+        _ => Err(Error::Validation(format!(
+            "Invalid storage '{spec}'. Expected <type>:<url>, e.g. local-fs:/path/to/manifests"
+        ))),
+    }
+}
+
+/// Copies every manifest in `source` to `destination`. With `skip_existing`,
+/// manifests whose IDs are already listed in the destination are left alone.
+///
+/// A cancelled migration stops between manifests with [`Error::Cancelled`];
+/// the manifests copied so far are complete, so running it again with
+/// `skip_existing` resumes it.
+pub fn migrate_manifests(
+    source: &dyn StorageBackend,
+    destination: &dyn StorageBackend,
+    skip_existing: bool,
+    show_progress: bool,
+) -> Result<MigrationSummary> {
+    let manifests = source.list_manifests()?;
+    let existing: HashSet<String> = if skip_existing {
+        destination
+            .list_manifests()?
+            .into_iter()
+            .map(|metadata| metadata.id)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    let pb = if show_progress {
+        let pb = progress::create_progress_bar(manifests.len() as u64);
+        pb.set_message("manifests");
+        pb
+    } else {
+        indicatif::ProgressBar::hidden()
+    };
+
+    let mut summary = MigrationSummary::default();
+    for metadata in manifests {
+        if let Err(e) = crate::cancel::check() {
+            pb.finish_and_clear();
+            log::warn!(
+                "Migration cancelled after copying {} manifest(s); run it again with --skip-existing to copy the rest",
+                summary.copied
+            );
+            return Err(e);
+        }
+        let id = metadata.id;
+        if existing.contains(&id) {
+            summary.skipped += 1;
+            pb.inc(1);
+            continue;
+        }
+
+        let manifest = source
+            .retrieve_manifest(&id)
+            .map_err(|e| Error::Storage(format!("Failed to read manifest {id}: {e}")))?;
+        let stored_id = destination
+            .store_manifest(&manifest)
+            .map_err(|e| Error::Storage(format!("Failed to copy manifest {id}: {e}")))?;
+        if stored_id != id {
+            summary.renamed.push((id, stored_id));
+        }
+        summary.copied += 1;
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+
+    Ok(summary)
+}
@@ -1,11 +1,17 @@
 pub mod config;
 pub mod database;
 pub mod filesystem;
+pub mod memory;
+pub mod migrate;
+pub mod protocol;
 pub mod rekor;
+pub mod stats;
 pub mod traits;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use config::StorageConfig;
 pub use database::DatabaseStorage;
 pub use filesystem::FilesystemStorage;
+pub use memory::MemoryStorage;
 pub use rekor::RekorStorage;
 pub use traits::{ManifestMetadata, ManifestType, StorageBackend};
 
@@ -13,26 +19,111 @@ pub fn initialize_storage() -> Result<RekorStorage> {
     RekorStorage::new()
 }
 
-pub fn create_storage(storage_type: &str, url: String) -> Result<Box<dyn StorageBackend>> {
-    match storage_type {
-        "database" => Ok(Box::new(DatabaseStorage::new(url)?)),
-        "rekor" => Ok(Box::new(RekorStorage::new_with_url(url)?)),
-        "local-fs" => Ok(Box::new(FilesystemStorage::new(url)?)),
-        // Backwards compatibility with warnings
-        "local" => {
-            eprintln!(
-                "Warning: Storage type 'local' is deprecated and will be removed in a future version. Use 'database' instead."
-            );
-            Ok(Box::new(DatabaseStorage::new(url)?))
-        }
-        "filesystem" => {
-            eprintln!(
-                "Warning: Storage type 'filesystem' is deprecated and will be removed in a future version. Use 'local-fs' instead."
-            );
-            Ok(Box::new(FilesystemStorage::new(url)?))
-        }
-        _ => Err(crate::error::Error::Validation(
-            "Invalid storage type. Valid options are: database, rekor, local-fs".to_string(),
-        )),
+/// Storage type names accepted by [`create_storage`]
+pub const STORAGE_TYPES: &[&str] = &["database", "rekor", "local-fs", "memory"];
+
+/// Deprecated storage type names and the types they stand for
+const DEPRECATED_STORAGE_TYPES: &[(&str, &str)] =
+    &[("local", "database"), ("filesystem", "local-fs")];
+
+/// The storage type a name resolved to, and the deprecated alias used for
+/// it if any, so callers can surface the deprecation themselves
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageTypeResolution {
+    /// Current name of the storage type, e.g. `database`
+    pub storage_type: &'static str,
+    /// Deprecated name that was given instead, e.g. `local`
+    pub deprecated_alias: Option<&'static str>,
+}
+
+impl StorageTypeResolution {
+    /// Deprecation message to show when an alias was used
+    pub fn warning(&self) -> Option<String> {
+        self.deprecated_alias.map(|alias| {
+            format!(
+                "Storage type '{alias}' is deprecated and will be removed in a future version. Use '{}' instead.",
+                self.storage_type
+            )
+        })
+    }
+}
+
+/// Resolves a storage type name, accepting the deprecated aliases `local`
+/// and `filesystem` unless `deny_deprecated` is set.
+///
+/// # Examples
+///
+/// ```
+/// use atlas_cli::storage::resolve_storage_type;
+///
+/// let resolution = resolve_storage_type("filesystem", false).unwrap();
+/// assert_eq!(resolution.storage_type, "local-fs");
+/// assert_eq!(resolution.deprecated_alias, Some("filesystem"));
+///
+/// assert!(resolve_storage_type("filesystem", true).is_err());
+/// assert!(resolve_storage_type("local-fs", true).unwrap().warning().is_none());
+/// ```
+pub fn resolve_storage_type(
+    storage_type: &str,
+    deny_deprecated: bool,
+) -> Result<StorageTypeResolution> {
+    if let Some(current) = STORAGE_TYPES.iter().find(|name| **name == storage_type) {
+        return Ok(StorageTypeResolution {
+            storage_type: current,
+            deprecated_alias: None,
+        });
     }
+
+    let Some((alias, current)) = DEPRECATED_STORAGE_TYPES
+        .iter()
+        .find(|(alias, _)| *alias == storage_type)
+    else {
+        return Err(Error::Validation(format!(
+            "Invalid storage type. Valid options are: {}",
+            STORAGE_TYPES.join(", ")
+        )));
+    };
+    let resolution = StorageTypeResolution {
+        storage_type: current,
+        deprecated_alias: Some(alias),
+    };
+    if deny_deprecated {
+        return Err(Error::Validation(resolution.warning().unwrap_or_default()));
+    }
+    Ok(resolution)
+}
+
+/// Creates a storage backend, logging a warning if a deprecated storage type
+/// name was used. Deprecated names are rejected instead if
+/// `config.deny_deprecated` is set (`--deny-deprecated-storage`).
+pub fn create_storage(
+    storage_type: &str,
+    url: String,
+    config: &StorageConfig,
+) -> Result<Box<dyn StorageBackend>> {
+    let (storage, resolution) = create_storage_with_options(storage_type, url, config)?;
+    if let Some(warning) = resolution.warning() {
+        log::warn!("{warning}");
+    }
+    Ok(storage)
+}
+
+/// Creates a storage backend and reports how its type name was resolved,
+/// leaving it to the caller to surface a deprecated alias
+pub fn create_storage_with_options(
+    storage_type: &str,
+    url: String,
+    config: &StorageConfig,
+) -> Result<(Box<dyn StorageBackend>, StorageTypeResolution)> {
+    let resolution = resolve_storage_type(storage_type, config.deny_deprecated)?;
+    let storage: Box<dyn StorageBackend> = match resolution.storage_type {
+        "database" => {
+            Box::new(DatabaseStorage::new_with_config(url, config)?.with_readiness_check())
+        }
+        "rekor" => Box::new(RekorStorage::new_with_config(url, config)?),
+        "local-fs" => Box::new(FilesystemStorage::new(url)?),
+        // In-memory storage ignores the URL
+        _ => Box::new(MemoryStorage::new()),
+    };
+    Ok((storage, resolution))
 }
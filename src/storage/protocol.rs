@@ -0,0 +1,117 @@
+//! # Storage Service Protocol
+//!
+//! Request and response bodies of the storage service's HTTP API
+//! (`storage_service/`), used by both the service and
+//! [`DatabaseStorage`](super::database::DatabaseStorage), so a change to the
+//! wire format on either side fails to compile instead of failing to parse
+//! at runtime. The service includes this file with `#[path]`, so it may only
+//! depend on `serde` and `serde_json` and must build with edition 2021.
+//!
+//! | Route                        | Request                                | Response                          |
+//! |------------------------------|----------------------------------------|-----------------------------------|
+//! | `GET /health`                |                                        | [`StatusResponse`]                |
+//! | `GET /ready`                 |                                        | [`StatusResponse`]                |
+//! | `GET /manifests`             | [`ListQuery`]                          | `Vec<`[`ManifestEntry`]`>`        |
+//! | `DELETE /manifests`          | [`DeleteQuery`] or a JSON array of IDs | [`DeleteResponse`]                |
+//! | `POST /manifests/{id}`       | [`StoreRequest`]                       | `201` with [`InsertedId`]         |
+//! | `GET /manifests/{id}`        |                                        | [`ManifestEntry`]                 |
+//! | `GET /manifests/{id}/claim`  |                                        | [`ClaimResponse`]                 |
+//! | `DELETE /manifests/{id}`     |                                        | plain text                        |
+//! | `GET /manifests/{id}/verify` |                                        | the service's verification report |
+//!
+//! Errors are returned as a non-success status with a plain text body.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Manifest types accepted on store, matched case-insensitively
+pub const MANIFEST_TYPES: [&str; 5] = ["dataset", "model", "software", "evaluation", "unknown"];
+
+/// Body of `POST /manifests/{id}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoreRequest {
+    pub manifest_id: String,
+    /// One of [`MANIFEST_TYPES`], in any case
+    pub manifest_type: String,
+    /// The C2PA manifest as JSON
+    pub manifest: Value,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// A stored manifest, as returned by `GET /manifests` and
+/// `GET /manifests/{id}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub manifest_id: String,
+    /// `Dataset`, `Model`, `Software`, `Evaluation` or `Unknown`
+    pub manifest_type: String,
+    /// The [`StoreRequest`] the manifest was stored with
+    pub manifest: Value,
+    /// RFC 3339 time the service stored the manifest, in UTC
+    pub created_at: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl ManifestEntry {
+    /// The C2PA manifest inside the stored request
+    pub fn c2pa_manifest(&self) -> Option<&Value> {
+        self.manifest.get("manifest")
+    }
+}
+
+/// Response of `GET /manifests/{id}/claim`: only the claim of a stored
+/// manifest, for verifying its signatures without the rest of the document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClaimResponse {
+    pub manifest_id: String,
+    /// The manifest's `claim_v2`, or its `claim` if it has none
+    pub claim: Value,
+}
+
+/// Query of `GET /manifests`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListQuery {
+    /// Comma-separated tags; only manifests carrying all of them are listed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Inclusive lower bound on `created_at`, RFC 3339
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Exclusive upper bound on `created_at`, RFC 3339
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+}
+
+/// Query of `DELETE /manifests`; without a type, the body lists the IDs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeleteQuery {
+    /// Manifest type to delete, matched case-insensitively
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub manifest_type: Option<String>,
+}
+
+/// Response of `DELETE /manifests`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeleteResponse {
+    pub deleted: u64,
+}
+
+/// Response of `POST /manifests/{id}`: the database ID of the new entry, in
+/// MongoDB extended JSON
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InsertedId {
+    #[serde(rename = "$oid")]
+    pub oid: String,
+}
+
+/// Response of `GET /health` and `GET /ready`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusResponse {
+    /// `ok`, `ready` or `unavailable`
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
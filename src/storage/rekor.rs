@@ -1,41 +1,541 @@
 use crate::error::{Error, Result};
+use crate::manifest::utils::{determine_manifest_type, manifest_created_at, manifest_tags};
+use crate::manifest::version::migrate_manifest;
+use crate::storage::config::StorageConfig;
 use crate::storage::traits::{ManifestMetadata, StorageBackend};
 use atlas_c2pa_lib::manifest::Manifest;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use openssl::pkey::{PKey, Public};
 use reqwest;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+/// Predicate type of in-toto statements that embed an Atlas manifest
+pub const ATLAS_PREDICATE_TYPE: &str = "https://github.com/IntelLabs/atlas-cli/manifest/v1";
+
+/// URL of the public Sigstore Rekor instance
+pub const SIGSTORE_REKOR_URL: &str = "https://rekor.sigstore.dev";
+
+/// Public key of the log at [`SIGSTORE_REKOR_URL`], pinned so that its
+/// signed tree heads are not checked with a key served by the log itself
+pub const SIGSTORE_REKOR_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE2G2Y+2tabdTV5BcGiBIx0a9fAFwr
+kBbmLSGtks4L3qX6yYY0zufBnhC8Ur/iy55GhWP/9A/bY2LhC30M9+RYtw==
+-----END PUBLIC KEY-----
+";
+
+/// Default number of recent log entries searched by `list_manifests`
+pub const DEFAULT_LIST_LIMIT: u64 = 100;
+
+/// Maximum number of entries Rekor returns per batch retrieval
+const RETRIEVE_BATCH_SIZE: u64 = 10;
+
+/// Inclusion proof of a log entry, as returned in its `verification` field
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionProof {
+    /// Index of the entry in the log shard the proof is for
+    pub log_index: u64,
+    pub tree_size: u64,
+    /// Hex-encoded Merkle tree root
+    pub root_hash: String,
+    /// Hex-encoded sibling hashes from the leaf up to the root
+    pub hashes: Vec<String>,
+    /// Signed tree head, as a signed note
+    pub checkpoint: String,
+}
 
-#[allow(dead_code)]
 pub struct RekorStorage {
     client: reqwest::blocking::Client,
     base_url: String,
+    list_limit: u64,
+    /// Key that signs the log's tree heads; the public Sigstore log's key is
+    /// pinned by default
+    log_public_key: Option<PKey<Public>>,
 }
 
 impl RekorStorage {
     pub fn new() -> Result<Self> {
-        Self::new_with_url("https://rekor.sigstore.dev".to_string())
+        Self::new_with_url(SIGSTORE_REKOR_URL.to_string())
     }
 
     pub fn new_with_url(url: String) -> Result<Self> {
-        Ok(RekorStorage {
+        let base_url = url.trim_end_matches('/').to_string();
+        let storage = RekorStorage {
             client: reqwest::blocking::Client::new(),
-            base_url: url,
-        })
+            base_url,
+            list_limit: DEFAULT_LIST_LIMIT,
+            log_public_key: None,
+        };
+        if storage.base_url == SIGSTORE_REKOR_URL {
+            return storage.with_log_public_key(SIGSTORE_REKOR_PUBLIC_KEY.as_bytes());
+        }
+        Ok(storage)
+    }
+
+    /// Connects to the log at `url`, pinning the log public key from
+    /// `config.rekor_public_key` if one is given
+    pub fn new_with_config(url: String, config: &StorageConfig) -> Result<Self> {
+        let storage = Self::new_with_url(url)?;
+        match &config.rekor_public_key {
+            Some(path) => storage.with_log_public_key(&std::fs::read(path).map_err(|e| {
+                Error::Storage(format!(
+                    "Failed to read Rekor public key {}: {e}",
+                    path.display()
+                ))
+            })?),
+            None => Ok(storage),
+        }
+    }
+
+    /// Pin the PEM public key the log signs its tree heads with, used by
+    /// [`verify_inclusion`](Self::verify_inclusion)
+    pub fn with_log_public_key(mut self, pem: &[u8]) -> Result<Self> {
+        let key = PKey::public_key_from_pem(pem)
+            .map_err(|e| Error::Storage(format!("Invalid Rekor public key: {e}")))?;
+        self.log_public_key = Some(key);
+        Ok(self)
+    }
+
+    /// Set how many of the most recent log entries `list_manifests` searches
+    pub fn with_list_limit(mut self, limit: u64) -> Self {
+        self.list_limit = limit;
+        self
+    }
+
+    fn get_json(&self, url: &str) -> Result<Value> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .map_err(|e| Error::Storage(format!("Failed to query Rekor: {e}")))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Storage(format!(
+                "Rekor request to {url} failed with status {}",
+                response.status()
+            )));
+        }
+
+        response
+            .json()
+            .map_err(|e| Error::Storage(format!("Failed to parse Rekor response: {e}")))
     }
+
+    fn post_json(&self, url: &str, body: &Value) -> Result<Value> {
+        let response = self
+            .client
+            .post(url)
+            .json(body)
+            .send()
+            .map_err(|e| Error::Storage(format!("Failed to query Rekor: {e}")))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Storage(format!(
+                "Rekor request to {url} failed with status {}",
+                response.status()
+            )));
+        }
+
+        response
+            .json()
+            .map_err(|e| Error::Storage(format!("Failed to parse Rekor response: {e}")))
+    }
+
+    /// Fetch a log entry by UUID, or by log index if `id` is numeric
+    fn fetch_entry(&self, id: &str) -> Result<(String, Value)> {
+        let url = if id.chars().all(|c| c.is_ascii_digit()) {
+            format!("{}/api/v1/log/entries?logIndex={id}", self.base_url)
+        } else {
+            format!("{}/api/v1/log/entries/{id}", self.base_url)
+        };
+
+        single_entry(self.get_json(&url)?)
+            .ok_or_else(|| Error::Storage(format!("Rekor entry not found: {id}")))
+    }
+
+    /// Proves that the entry `id` (a UUID or log index) is in the log.
+    ///
+    /// Recomputes the Merkle tree root from the entry body and its inclusion
+    /// proof, and checks the root against the checkpoint signed with the
+    /// pinned log public key (see [`with_log_public_key`](Self::with_log_public_key)).
+    /// Fails without a pinned key, since a key fetched from the log could
+    /// be replaced along with the checkpoint. Returns the verified proof.
+    pub fn verify_inclusion(&self, id: &str) -> Result<InclusionProof> {
+        let (uuid, entry) = self.fetch_entry(id)?;
+        self.prove_inclusion(&uuid, &entry)
+    }
+
+    /// Retrieves the manifest in entry `id` and proves that the entry is in
+    /// the log, from a single fetch of the entry, so that the manifest is
+    /// read from the same entry the proof is for. The manifest's attestation
+    /// is checked against the hash recorded in the proven body.
+    pub fn retrieve_proven_manifest(&self, id: &str) -> Result<(Manifest, InclusionProof)> {
+        let (uuid, entry) = self.fetch_entry(id)?;
+        let proof = self.prove_inclusion(&uuid, &entry)?;
+        let manifest = require_manifest(&uuid, manifest_from_entry(&uuid, &entry)?)?;
+        Ok((manifest, proof))
+    }
+
+    fn prove_inclusion(&self, uuid: &str, entry: &Value) -> Result<InclusionProof> {
+        let public_key = self.log_public_key.as_ref().ok_or_else(|| {
+            Error::Validation(format!(
+                "No public key is pinned for the Rekor log at {}; pass it with --rekor-public-key",
+                self.base_url
+            ))
+        })?;
+        let proof: InclusionProof = entry
+            .get("verification")
+            .and_then(|v| v.get("inclusionProof"))
+            .cloned()
+            .ok_or_else(|| Error::Validation(format!("Rekor entry {uuid} has no inclusion proof")))
+            .and_then(|proof| {
+                serde_json::from_value(proof).map_err(|e| {
+                    Error::Validation(format!("Invalid inclusion proof for entry {uuid}: {e}"))
+                })
+            })?;
+
+        let body = entry
+            .get("body")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::Storage(format!("Rekor entry {uuid} has no body")))?;
+        let body = decode_base64(body, "entry body")?;
+
+        // Entry UUIDs end with the leaf hash, after an optional tree ID
+        let leaf = leaf_hash(&body);
+        if !uuid.ends_with(&hex::encode(leaf)) {
+            return Err(Error::Validation(format!(
+                "Rekor entry {uuid} does not match the hash of its body"
+            )));
+        }
+
+        let hashes = proof
+            .hashes
+            .iter()
+            .map(|hash| decode_node_hash(hash))
+            .collect::<Result<Vec<_>>>()?;
+        let root = root_from_inclusion_proof(proof.log_index, proof.tree_size, leaf, &hashes)?;
+        if root != decode_node_hash(&proof.root_hash)? {
+            return Err(Error::Validation(format!(
+                "Inclusion proof for Rekor entry {uuid} does not lead to the logged root hash"
+            )));
+        }
+
+        verify_checkpoint(&proof.checkpoint, public_key, proof.tree_size, &root)?;
+
+        Ok(proof)
+    }
+
+    fn tree_size(&self) -> Result<u64> {
+        let log_info = self.get_json(&format!("{}/api/v1/log", self.base_url))?;
+        log_info
+            .get("treeSize")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| Error::Storage("Rekor log info is missing treeSize".to_string()))
+    }
+}
+
+/// RFC 6962 hash of a log entry
+fn leaf_hash(body: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update([0x00])
+        .chain_update(body)
+        .finalize()
+        .into()
+}
+
+/// RFC 6962 hash of an interior node
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update([0x01])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+fn decode_node_hash(hash: &str) -> Result<[u8; 32]> {
+    hex::decode(hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| Error::Validation(format!("Invalid Merkle tree hash: {hash}")))
+}
+
+/// Computes the tree root from a leaf and its inclusion proof, following
+/// the verification algorithm of RFC 9162, section 2.1.3.2
+fn root_from_inclusion_proof(
+    index: u64,
+    tree_size: u64,
+    leaf: [u8; 32],
+    proof: &[[u8; 32]],
+) -> Result<[u8; 32]> {
+    let invalid = |reason: &str| Error::Validation(format!("Invalid inclusion proof: {reason}"));
+    if index >= tree_size {
+        return Err(invalid("leaf index is outside the tree"));
+    }
+
+    let (mut fn_, mut sn) = (index, tree_size - 1);
+    let mut root = leaf;
+    for sibling in proof {
+        if sn == 0 {
+            return Err(invalid("too many hashes"));
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            root = node_hash(sibling, &root);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            root = node_hash(&root, sibling);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+
+    if sn != 0 {
+        return Err(invalid("too few hashes"));
+    }
+    Ok(root)
+}
+
+/// Checks a signed tree head: a signed note whose text holds the log origin,
+/// the tree size and the base64 root hash, followed by `— <name> <signature>`
+/// lines. Each signature is a 4-byte key hint followed by the signature bytes.
+fn verify_checkpoint(
+    checkpoint: &str,
+    public_key: &PKey<Public>,
+    tree_size: u64,
+    root: &[u8; 32],
+) -> Result<()> {
+    let invalid = |reason: String| Error::Validation(format!("Invalid Rekor checkpoint: {reason}"));
+    let (text, signatures) = checkpoint
+        .split_once("\n\n")
+        .ok_or_else(|| invalid("missing signature".to_string()))?;
+    let text = format!("{text}\n");
+
+    let mut lines = text.lines().skip(1);
+    let size = lines.next().and_then(|line| line.parse::<u64>().ok());
+    if size != Some(tree_size) {
+        return Err(invalid(format!(
+            "signed tree size does not match the proof's tree size {tree_size}"
+        )));
+    }
+    let signed_root = lines.next().and_then(|line| STANDARD.decode(line).ok());
+    if signed_root.as_deref() != Some(&root[..]) {
+        return Err(invalid(
+            "signed root hash does not match the proof".to_string(),
+        ));
+    }
+
+    let signed = signatures
+        .lines()
+        .filter_map(|line| line.strip_prefix("\u{2014} "))
+        .filter_map(|line| line.rsplit_once(' '))
+        .filter_map(|(_, signature)| STANDARD.decode(signature).ok())
+        .filter(|signature| signature.len() > 4)
+        .any(|signature| {
+            crate::signing::verify_signature_with_algorithm(
+                text.as_bytes(),
+                &signature[4..],
+                public_key,
+                crate::hash::DigestAlgorithm::Sha256,
+            )
+            .unwrap_or(false)
+        });
+    if !signed {
+        return Err(invalid("no signature by the log's public key".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Rekor returns entries as `{ "<uuid>": { ...entry } }` objects
+fn single_entry(response: Value) -> Option<(String, Value)> {
+    match response {
+        Value::Object(map) => map.into_iter().next(),
+        Value::Array(items) => items.into_iter().find_map(single_entry),
+        _ => None,
+    }
+}
+
+fn decode_base64(encoded: &str, what: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(encoded)
+        .map_err(|e| Error::Storage(format!("Invalid base64 in Rekor {what}: {e}")))
+}
+
+fn parse_json(bytes: &[u8], what: &str) -> Result<Value> {
+    serde_json::from_slice(bytes)
+        .map_err(|e| Error::Storage(format!("Invalid JSON in Rekor {what}: {e}")))
+}
+
+fn decode_base64_json(encoded: &str, what: &str) -> Result<Value> {
+    parse_json(&decode_base64(encoded, what)?, what)
+}
+
+fn require_manifest(uuid: &str, manifest: Option<Manifest>) -> Result<Manifest> {
+    manifest.ok_or_else(|| {
+        Error::Storage(format!(
+            "Rekor entry {uuid} does not contain an Atlas manifest"
+        ))
+    })
+}
+
+/// SHA-256 digest recorded in an entry body as `{"algorithm", "value"}`
+fn recorded_sha256(hash: Option<&Value>) -> Option<&str> {
+    let hash = hash?;
+    (hash.get("algorithm").and_then(Value::as_str) == Some("sha256"))
+        .then(|| hash.get("value").and_then(Value::as_str))
+        .flatten()
+}
+
+/// Checks that an entry's attestation is the one its body records.
+///
+/// Only the body is covered by the log's inclusion proof, so the
+/// attestation must match the payload hash in the body (`spec.content.payloadHash`
+/// for in-toto entries, `spec.payloadHash` for DSSE entries), or, without
+/// one, its envelope hash (`spec.content.hash` or `spec.envelopeHash`).
+fn verify_attestation_hash(
+    uuid: &str,
+    body: &Value,
+    envelope: &[u8],
+    payload: &[u8],
+) -> Result<()> {
+    let spec = body.get("spec");
+    let content = spec.and_then(|spec| spec.get("content"));
+    let payload_hash = recorded_sha256(content.and_then(|c| c.get("payloadHash")))
+        .or_else(|| recorded_sha256(spec.and_then(|s| s.get("payloadHash"))));
+    let envelope_hash = recorded_sha256(content.and_then(|c| c.get("hash")))
+        .or_else(|| recorded_sha256(spec.and_then(|s| s.get("envelopeHash"))));
+
+    let (recorded, calculated) = match (payload_hash, envelope_hash) {
+        (Some(recorded), _) => (recorded, Sha256::digest(payload)),
+        (None, Some(recorded)) => (recorded, Sha256::digest(envelope)),
+        (None, None) => {
+            return Err(Error::Validation(format!(
+                "Rekor entry {uuid} does not record a hash of its attestation"
+            )));
+        }
+    };
+    if recorded.to_ascii_lowercase() != hex::encode(calculated) {
+        return Err(Error::Validation(format!(
+            "Attestation of Rekor entry {uuid} does not match the hash recorded in its body"
+        )));
+    }
+    Ok(())
+}
+
+/// Extract the Atlas manifest embedded in a Rekor log entry.
+///
+/// The manifest is the predicate of an in-toto statement carried in the
+/// entry's attestation, either directly or wrapped in a DSSE envelope, and
+/// the attestation must match the hash recorded in the entry body.
+/// Returns `Ok(None)` for entries that don't carry an Atlas manifest.
+fn manifest_from_entry(uuid: &str, entry: &Value) -> Result<Option<Manifest>> {
+    // The canonicalized body describes the entry kind
+    let body = entry
+        .get("body")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::Storage(format!("Rekor entry {uuid} has no body")))?;
+    let body = decode_base64_json(body, "entry body")?;
+    let kind = body
+        .get("kind")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+
+    let Some(attestation) = entry
+        .get("attestation")
+        .and_then(|a| a.get("data"))
+        .and_then(Value::as_str)
+    else {
+        log::debug!("Rekor entry {uuid} of kind {kind} has no attestation");
+        return Ok(None);
+    };
+
+    let envelope = decode_base64(attestation, "attestation")?;
+    let mut payload = envelope.clone();
+    let mut statement = parse_json(&envelope, "attestation")?;
+
+    // Unwrap DSSE envelopes
+    if statement.get("payloadType").is_some()
+        && let Some(encoded) = statement.get("payload").and_then(Value::as_str)
+    {
+        payload = decode_base64(encoded, "DSSE payload")?;
+        statement = parse_json(&payload, "DSSE payload")?;
+    }
+
+    if statement.get("predicateType").and_then(Value::as_str) != Some(ATLAS_PREDICATE_TYPE) {
+        return Ok(None);
+    }
+    verify_attestation_hash(uuid, &body, &envelope, &payload)?;
+
+    let predicate = statement
+        .get("predicate")
+        .cloned()
+        .ok_or_else(|| Error::Storage(format!("Rekor entry {uuid} has no predicate")))?;
+
+    migrate_manifest(predicate)
+        .map(Some)
+        .map_err(|e| Error::Storage(format!("Failed to parse manifest in entry {uuid}: {e}")))
 }
 
 impl StorageBackend for RekorStorage {
     fn store_manifest(&self, _manifest: &Manifest) -> Result<String> {
         // TODO: Implement actual storage
-        println!("Would store manifest at: {}", self.base_url);
+        log::warn!("Would store manifest at: {}", self.base_url);
         Ok("dummy-manifest-id".to_string()) // Return a dummy manifest ID for now
     }
 
-    fn retrieve_manifest(&self, _id: &str) -> Result<Manifest> {
-        todo!("Implement manifest retrieval")
+    fn retrieve_manifest(&self, id: &str) -> Result<Manifest> {
+        let (uuid, entry) = self.fetch_entry(id)?;
+        require_manifest(&uuid, manifest_from_entry(&uuid, &entry)?)
     }
 
+    /// Searches the most recent log entries (see [`RekorStorage::with_list_limit`])
+    /// for entries carrying Atlas manifests, since Rekor cannot be queried by
+    /// predicate type.
     fn list_manifests(&self) -> Result<Vec<ManifestMetadata>> {
-        todo!("Implement manifest listing")
+        let tree_size = self.tree_size()?;
+        let start = tree_size.saturating_sub(self.list_limit);
+
+        let mut manifests = Vec::new();
+        let mut index = start;
+        while index < tree_size {
+            let end = (index + RETRIEVE_BATCH_SIZE).min(tree_size);
+            let log_indexes: Vec<u64> = (index..end).collect();
+            let response = self.post_json(
+                &format!("{}/api/v1/log/entries/retrieve", self.base_url),
+                &json!({ "logIndexes": log_indexes }),
+            )?;
+
+            for item in response.as_array().into_iter().flatten() {
+                let Some((uuid, entry)) = single_entry(item.clone()) else {
+                    continue;
+                };
+
+                match manifest_from_entry(&uuid, &entry) {
+                    Ok(Some(manifest)) => manifests.push(ManifestMetadata {
+                        id: uuid,
+                        name: manifest.title.clone(),
+                        manifest_type: determine_manifest_type(&manifest),
+                        created_at: manifest_created_at(&manifest),
+                        tags: manifest_tags(&manifest),
+                    }),
+                    Ok(None) => {}
+                    // Log but don't fail on malformed entries
+                    Err(e) => log::warn!("Error parsing Rekor entry {uuid}: {e}"),
+                }
+            }
+
+            index = end;
+        }
+
+        Ok(manifests)
     }
+
     fn delete_manifest(&self, _id: &str) -> Result<()> {
         // Implement deletion for Rekor if supported, or return error
         Err(Error::Storage(
@@ -0,0 +1,110 @@
+//! # Storage Statistics
+//!
+//! Summarizes a storage backend's contents: how many manifests of each type
+//! it holds, how many bytes they take up and when the oldest and newest were
+//! created. Sizes are only reported by backends that can measure them
+//! (filesystem storage); other backends show counts only.
+//!
+//! ## Example
+//!
+//! ```
+//! use atlas_cli::storage::stats::{TypeCount, StorageStats, format_stats};
+//! use atlas_cli::storage::traits::ManifestType;
+//!
+//! let stats = StorageStats {
+//!     total_manifests: 3,
+//!     by_type: vec![
+//!         TypeCount { manifest_type: ManifestType::Dataset, count: 1 },
+//!         TypeCount { manifest_type: ManifestType::Model, count: 2 },
+//!     ],
+//!     total_bytes: None,
+//!     oldest: None,
+//!     newest: None,
+//! };
+//! assert!(format_stats(&stats).contains("Model: 2"));
+//! ```
+
+use crate::error::Result;
+use crate::storage::traits::{ManifestType, StorageBackend};
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+/// Number of stored manifests of one type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeCount {
+    pub manifest_type: ManifestType,
+    pub count: usize,
+}
+
+/// Overview of a storage backend's contents
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorageStats {
+    pub total_manifests: usize,
+    /// Counts of the types present, in `ManifestType` order
+    pub by_type: Vec<TypeCount>,
+    /// Bytes used by the backend; `None` if it cannot report sizes
+    pub total_bytes: Option<u64>,
+    /// Earliest `created_at` of the stored manifests, as RFC 3339
+    pub oldest: Option<String>,
+    /// Latest `created_at` of the stored manifests, as RFC 3339
+    pub newest: Option<String>,
+}
+
+/// Collects statistics for every manifest in a storage backend.
+///
+/// Manifests whose creation time cannot be parsed are counted but not
+/// considered for the oldest and newest times.
+pub fn collect_stats(storage: &(impl StorageBackend + ?Sized)) -> Result<StorageStats> {
+    let manifests = storage.list_manifests()?;
+
+    let by_type = [
+        ManifestType::Dataset,
+        ManifestType::Model,
+        ManifestType::Software,
+        ManifestType::Evaluation,
+        ManifestType::Unknown,
+    ]
+    .into_iter()
+    .map(|manifest_type| TypeCount {
+        count: manifests
+            .iter()
+            .filter(|metadata| metadata.manifest_type == manifest_type)
+            .count(),
+        manifest_type,
+    })
+    .filter(|type_count| type_count.count > 0)
+    .collect();
+
+    let created: Vec<OffsetDateTime> = manifests
+        .iter()
+        .filter_map(|metadata| OffsetDateTime::parse(&metadata.created_at, &Rfc3339).ok())
+        .collect();
+    let format_time = |time: &OffsetDateTime| time.format(&Rfc3339).ok();
+
+    Ok(StorageStats {
+        total_manifests: manifests.len(),
+        by_type,
+        total_bytes: storage.get_total_storage_size()?,
+        oldest: created.iter().min().and_then(format_time),
+        newest: created.iter().max().and_then(format_time),
+    })
+}
+
+/// Renders statistics as one `label: value` line each
+pub fn format_stats(stats: &StorageStats) -> String {
+    let mut out = format!("Total manifests: {}\n", stats.total_manifests);
+    for type_count in &stats.by_type {
+        out.push_str(&format!(
+            "  {}: {}\n",
+            type_count.manifest_type, type_count.count
+        ));
+    }
+    if let Some(total_bytes) = stats.total_bytes {
+        out.push_str(&format!("Total size: {total_bytes} bytes\n"));
+    }
+    if let (Some(oldest), Some(newest)) = (&stats.oldest, &stats.newest) {
+        out.push_str(&format!("Oldest: {oldest}\nNewest: {newest}\n"));
+    }
+    out
+}
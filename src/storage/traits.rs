@@ -1,10 +1,14 @@
 use crate::error::Error;
 use crate::error::Result;
+use atlas_c2pa_lib::claim::ClaimV2;
 use atlas_c2pa_lib::manifest::Manifest;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 
 /// Represents metadata about a stored manifest
 ///
@@ -18,6 +22,7 @@ use std::path::PathBuf;
 ///     name: "My Model".to_string(),
 ///     manifest_type: ManifestType::Model,
 ///     created_at: "2025-01-23T12:00:00Z".to_string(),
+///     tags: vec!["env=prod".to_string()],
 /// };
 ///
 /// assert_eq!(metadata.id, "model-123");
@@ -29,6 +34,88 @@ pub struct ManifestMetadata {
     pub name: String,
     pub manifest_type: ManifestType,
     pub created_at: String,
+    /// User-defined tags recorded in the manifest
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Criteria for listing manifests; the default matches every manifest
+///
+/// # Examples
+///
+/// ```
+/// use atlas_cli::storage::traits::ManifestFilter;
+///
+/// let filter = ManifestFilter::new(vec![], Some("2024-01-01"), Some("2024-02-01")).unwrap();
+/// assert!(filter.matches_created_at("2024-01-15T08:30:00Z"));
+/// assert!(!filter.matches_created_at("2024-02-01T00:00:00Z"));
+/// assert!(ManifestFilter::new(vec![], Some("January"), None).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ManifestFilter {
+    /// Only manifests carrying every one of these tags
+    pub tags: Vec<String>,
+    /// Only manifests created at or after this time
+    pub since: Option<OffsetDateTime>,
+    /// Only manifests created before this time
+    pub until: Option<OffsetDateTime>,
+}
+
+impl ManifestFilter {
+    /// Builds a filter from tags and optional `--since`/`--until` bounds,
+    /// each either an RFC 3339 timestamp or a `YYYY-MM-DD` date (midnight UTC)
+    pub fn new(tags: Vec<String>, since: Option<&str>, until: Option<&str>) -> Result<Self> {
+        let since = since.map(parse_time_bound).transpose()?;
+        let until = until.map(parse_time_bound).transpose()?;
+        if let (Some(since), Some(until)) = (since, until)
+            && since >= until
+        {
+            return Err(Error::Validation(
+                "--since must be earlier than --until".to_string(),
+            ));
+        }
+        Ok(Self { tags, since, until })
+    }
+
+    /// Returns true if the filter only selects by tag
+    fn is_time_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    /// Returns true if the manifest satisfies every criterion
+    pub fn matches(&self, metadata: &ManifestMetadata) -> bool {
+        self.tags.iter().all(|tag| metadata.tags.contains(tag))
+            && self.matches_created_at(&metadata.created_at)
+    }
+
+    /// Returns true if an RFC 3339 creation time lies within the bounds;
+    /// unparseable times only match a filter without bounds
+    pub fn matches_created_at(&self, created_at: &str) -> bool {
+        if self.is_time_unbounded() {
+            return true;
+        }
+        let Ok(created_at) = OffsetDateTime::parse(created_at, &Rfc3339) else {
+            return false;
+        };
+        self.since.is_none_or(|since| created_at >= since)
+            && self.until.is_none_or(|until| created_at < until)
+    }
+}
+
+/// Parses an RFC 3339 timestamp or a `YYYY-MM-DD` date (midnight UTC)
+pub(crate) fn parse_time_bound(value: &str) -> Result<OffsetDateTime> {
+    if let Ok(time) = OffsetDateTime::parse(value, &Rfc3339) {
+        return Ok(time);
+    }
+    let date_format = time::format_description::parse("[year]-[month]-[day]")
+        .map_err(|e| Error::Validation(e.to_string()))?;
+    time::Date::parse(value, &date_format)
+        .map(|date| date.midnight().assume_utc())
+        .map_err(|_| {
+            Error::Validation(format!(
+                "Invalid date '{value}': expected YYYY-MM-DD or an RFC 3339 timestamp"
+            ))
+        })
 }
 
 pub trait StorageBackend {
@@ -37,16 +124,146 @@ pub trait StorageBackend {
     fn list_manifests(&self) -> Result<Vec<ManifestMetadata>>;
     fn delete_manifest(&self, id: &str) -> Result<()>;
     fn as_any(&self) -> &dyn Any;
+
+    /// Stores a changed version of a manifest, replacing the stored one
+    ///
+    /// Backends whose `store_manifest` refuses to replace an existing
+    /// manifest override this for updates such as linking or countersigning.
+    /// The default implementation calls `store_manifest`.
+    fn update_manifest(&self, manifest: &Manifest) -> Result<String> {
+        self.store_manifest(manifest)
+    }
+
+    /// Size in bytes of a stored manifest, or `None` if the backend cannot
+    /// report sizes
+    fn get_manifest_size(&self, _id: &str) -> Result<Option<u64>> {
+        Ok(None)
+    }
+
+    /// Total bytes used by the backend, or `None` if it cannot report sizes
+    fn get_total_storage_size(&self) -> Result<Option<u64>> {
+        Ok(None)
+    }
+
+    /// Returns whether a manifest with this ID is stored
+    ///
+    /// Used to tell a missing manifest apart from other retrieval failures.
+    /// The default implementation searches `list_manifests`.
+    fn manifest_exists(&self, id: &str) -> Result<bool> {
+        Ok(self.list_manifests()?.iter().any(|m| m.id == id))
+    }
+
+    /// Lists the manifests that carry every one of the given tags
+    fn list_manifests_with_tags(&self, tags: &[String]) -> Result<Vec<ManifestMetadata>> {
+        self.list_manifests_filtered(&ManifestFilter {
+            tags: tags.to_vec(),
+            ..Default::default()
+        })
+    }
+
+    /// Deletes every manifest of the given type and returns how many were
+    /// removed
+    ///
+    /// The default implementation deletes the matching entries of
+    /// `list_manifests` one by one; backends that can delete server-side
+    /// should override it.
+    fn delete_by_type(&self, manifest_type: &ManifestType) -> Result<usize> {
+        let ids: Vec<String> = self
+            .list_manifests()?
+            .into_iter()
+            .filter(|m| &m.manifest_type == manifest_type)
+            .map(|m| m.id)
+            .collect();
+        self.delete_many(&ids)
+    }
+
+    /// Deletes the manifests with the given IDs and returns how many were
+    /// removed; IDs that are not stored are skipped
+    fn delete_many(&self, ids: &[String]) -> Result<usize> {
+        let mut deleted = 0;
+        for id in ids {
+            if self.manifest_exists(id)? {
+                self.delete_manifest(id)?;
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Retrieves only the claim of a manifest, which is all that signature
+    /// verification needs
+    ///
+    /// The default implementation retrieves the whole manifest; backends
+    /// that can fetch the claim alone should override it.
+    fn retrieve_claim(&self, id: &str) -> Result<ClaimV2> {
+        let manifest = self.retrieve_manifest(id)?;
+        Ok(manifest.claim_v2.unwrap_or(manifest.claim))
+    }
+
+    /// Lists the manifests matching a filter
+    ///
+    /// The default implementation filters `list_manifests`; backends that
+    /// can filter server-side should override it.
+    fn list_manifests_filtered(&self, filter: &ManifestFilter) -> Result<Vec<ManifestMetadata>> {
+        Ok(self
+            .list_manifests()?
+            .into_iter()
+            .filter(|m| filter.matches(m))
+            .collect())
+    }
 }
 
+/// The kind of asset a manifest describes, as recorded by storage backends
+///
+/// The same names are used for display, serialization and parsing, so every
+/// variant, including `Unknown`, round-trips through storage.
+///
+/// # Examples
+///
+/// ```
+/// use atlas_cli::storage::traits::ManifestType;
+///
+/// let parsed: ManifestType = "unknown".parse().unwrap();
+/// assert_eq!(parsed, ManifestType::Unknown);
+/// assert_eq!(parsed.to_string(), "Unknown");
+/// assert!("checkpoint".parse::<ManifestType>().is_err());
+/// ```
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub enum ManifestType {
     Dataset,
     Model,
     Software,
+    Evaluation,
     Unknown,
 }
 
+impl ManifestType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ManifestType::Dataset => "Dataset",
+            ManifestType::Model => "Model",
+            ManifestType::Software => "Software",
+            ManifestType::Evaluation => "Evaluation",
+            ManifestType::Unknown => "Unknown",
+        }
+    }
+}
+
+impl FromStr for ManifestType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "dataset" => Ok(ManifestType::Dataset),
+            "model" => Ok(ManifestType::Model),
+            "software" => Ok(ManifestType::Software),
+            "evaluation" => Ok(ManifestType::Evaluation),
+            "unknown" => Ok(ManifestType::Unknown),
+            _ => Err(Error::Validation(format!("Unknown manifest type '{s}'"))),
+        }
+    }
+}
+
 /// Represents the location and verification info for an artifact
 ///
 /// # Examples
@@ -85,16 +302,12 @@ pub struct ArtifactLocation {
 
 impl fmt::Display for ManifestType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ManifestType::Dataset => write!(f, "Dataset"),
-            ManifestType::Model => write!(f, "Model"),
-            ManifestType::Software => write!(f, "Software"),
-            ManifestType::Unknown => write!(f, "Unknown"),
-        }
+        f.write_str(self.as_str())
     }
 }
 
 impl ArtifactLocation {
+    /// Records a local file by its `file://` URL and current SHA-384 hash
     pub fn new(path: PathBuf) -> Result<Self> {
         let hash = crate::hash::calculate_file_hash(&path)?;
         let url = format!("file://{}", path.to_string_lossy());
@@ -106,10 +319,37 @@ impl ArtifactLocation {
         })
     }
 
+    /// Rehashes the file and returns whether it still matches the recorded
+    /// hash; fails if the location has no local file path
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atlas_cli::ArtifactLocation;
+    /// use std::io::Write;
+    ///
+    /// let mut file = tempfile::NamedTempFile::new().unwrap();
+    /// file.write_all(b"weights").unwrap();
+    ///
+    /// let location = ArtifactLocation::new(file.path().to_path_buf()).unwrap();
+    /// assert!(location.verify().unwrap());
+    ///
+    /// file.write_all(b" tampered").unwrap();
+    /// assert!(!location.verify().unwrap());
+    /// ```
     pub fn verify(&self) -> Result<bool> {
+        self.verify_with_algorithm(crate::hash::DigestAlgorithm::Sha384)
+    }
+
+    /// Like [`ArtifactLocation::verify`], for a hash recorded with `algorithm`
+    pub fn verify_with_algorithm(
+        &self,
+        algorithm: impl Into<crate::hash::DigestAlgorithm>,
+    ) -> Result<bool> {
         match &self.file_path {
             Some(path) => {
-                let current_hash = crate::hash::calculate_file_hash(path)?;
+                let current_hash =
+                    crate::hash::calculate_file_hash_with_algorithm(path, algorithm)?;
                 Ok(current_hash == self.hash)
             }
             None => Err(Error::Validation(
@@ -95,3 +95,237 @@ fn test_attestation_in_manifest() -> Result<()> {
 
     Ok(())
 }
+
+// Test that platform detection agrees with the TDX library on non-SEV hosts
+#[test]
+fn test_platform_name_detection() -> Result<()> {
+    let platform = cc_attestation::get_platform_name()?;
+    let tdx_platform = get_platform_name().map_err(|e| Error::CCAttestationError(e.to_string()))?;
+
+    #[cfg(feature = "with-sev")]
+    if crate::cc_attestation::sev::is_sev_snp_guest() {
+        assert_eq!(platform, crate::cc_attestation::sev::SEV_SNP_PLATFORM);
+        return Ok(());
+    }
+
+    assert_eq!(platform, tdx_platform);
+
+    Ok(())
+}
+
+// Test that the launch measurement is read from the SNP report layout
+#[cfg(feature = "with-sev")]
+#[test]
+fn test_sev_snp_measurement_from_report() -> Result<()> {
+    use crate::cc_attestation::sev::measurement_from_report;
+
+    let mut report = vec![0u8; 0x4A0];
+    for (i, byte) in report[0x90..0x90 + 48].iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+
+    let measurement =
+        measurement_from_report(&report).map_err(|e| Error::CCAttestationError(e.to_string()))?;
+    assert_eq!(measurement[0], 0);
+    assert_eq!(measurement[47], 47);
+
+    // A truncated report must be rejected
+    assert!(measurement_from_report(&report[..0x90]).is_err());
+
+    Ok(())
+}
+
+// Test that a drifted launch measurement is reported with a typed error
+#[test]
+fn test_verify_launch_endorsement_against_mismatch() -> Result<()> {
+    let measurement = cc_attestation::get_launch_measurement()?;
+
+    let mut expected = measurement;
+    expected[0] ^= 0xFF;
+
+    match cc_attestation::verify_launch_endorsement_against("gcp-tdx", &expected) {
+        Err(Error::LaunchMeasurementMismatch {
+            expected: e,
+            actual: a,
+        }) => {
+            assert_eq!(e, hex::encode(expected));
+            assert_eq!(a, hex::encode(measurement));
+        }
+        other => panic!("Expected measurement mismatch, got {other:?}"),
+    }
+
+    Ok(())
+}
+
+// Test that a matching measurement proceeds to host endorsement verification
+#[test]
+fn test_verify_launch_endorsement_against_match() -> Result<()> {
+    let measurement = cc_attestation::get_launch_measurement()?;
+
+    let result = cc_attestation::verify_launch_endorsement_against("unknown-host", &measurement);
+    assert!(matches!(result, Err(Error::CCAttestationError(_))));
+
+    Ok(())
+}
+
+fn create_manifest_with_cc_assertion(
+    label: &str,
+    report: &str,
+) -> atlas_c2pa_lib::manifest::Manifest {
+    use crate::tests::common::create_default_claim;
+    use atlas_c2pa_lib::assertion::{Assertion, CustomAssertion};
+    use atlas_c2pa_lib::datetime_wrapper::OffsetDateTimeWrapper;
+
+    let mut claim = create_default_claim();
+    claim
+        .created_assertions
+        .push(Assertion::CustomAssertion(CustomAssertion {
+            label: label.to_string(),
+            data: serde_json::Value::String(report.to_string()),
+        }));
+
+    atlas_c2pa_lib::manifest::Manifest {
+        claim_generator: "test".to_string(),
+        title: "CC Test Manifest".to_string(),
+        instance_id: format!("urn:c2pa:{}", uuid::Uuid::new_v4()),
+        ingredients: vec![],
+        claim: claim.clone(),
+        created_at: OffsetDateTimeWrapper(time::OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: Some(claim),
+        is_active: true,
+    }
+}
+
+// Test that CC assertions are checked during manifest verification
+#[test]
+fn test_verify_cc_attestation_assertion() -> Result<()> {
+    use crate::manifest::common::verify_cc_attestation;
+
+    let report = cc_attestation::get_report(false)?;
+    let platform = cc_attestation::get_platform_name()?;
+    let manifest = create_manifest_with_cc_assertion(&platform, &report);
+
+    if cc_attestation::is_cc_platform(&platform) {
+        // On real CC hardware the measurement is re-validated
+        verify_cc_attestation(&manifest, true)?;
+    } else {
+        // A mock report is labelled with the OS name, not a CC platform, so
+        // it is not taken for an attestation
+        assert!(verify_cc_attestation(&manifest, true)?.is_empty());
+    }
+
+    Ok(())
+}
+
+// Test that malformed CC attestation reports are rejected
+#[test]
+fn test_verify_cc_attestation_malformed_report() {
+    use crate::manifest::common::verify_cc_attestation;
+
+    let manifest = create_manifest_with_cc_assertion("tdx-linux", "not json");
+
+    assert!(matches!(
+        verify_cc_attestation(&manifest, false),
+        Err(Error::Validation(_))
+    ));
+}
+
+// Test that string-valued custom assertions that are not CC reports are ignored
+#[test]
+fn test_verify_cc_attestation_ignores_other_string_assertions() -> Result<()> {
+    use crate::manifest::common::verify_cc_attestation;
+
+    let manifest = create_manifest_with_cc_assertion("test.padding", "not json");
+
+    assert!(verify_cc_attestation(&manifest, true)?.is_empty());
+    Ok(())
+}
+
+// Test that a plain OS platform label is not treated as a CC attestation
+#[test]
+fn test_verify_cc_attestation_ignores_os_platform_label() -> Result<()> {
+    use crate::manifest::common::verify_cc_attestation;
+
+    let report = r#"{"platform":"linux","measurement":"00"}"#;
+    let manifest = create_manifest_with_cc_assertion("linux", report);
+
+    assert!(verify_cc_attestation(&manifest, true)?.is_empty());
+    Ok(())
+}
+
+// Test that CC platform names are attestation labels and OS names are not
+#[test]
+fn test_attestation_labels_cover_platform_names() -> Result<()> {
+    let platform = cc_attestation::get_platform_name()?;
+    assert_eq!(
+        cc_attestation::is_attestation_label(&platform),
+        cc_attestation::is_cc_platform(&platform)
+    );
+    assert!(cc_attestation::is_attestation_label("tdx-linux"));
+    assert!(cc_attestation::is_attestation_label("sev-snp-linux"));
+    for label in ["linux", "macos", "windows"] {
+        assert!(!cc_attestation::is_attestation_label(label), "{label}");
+    }
+    #[cfg(feature = "with-sev")]
+    assert!(cc_attestation::is_attestation_label(
+        cc_attestation::sev::SEV_SNP_PLATFORM
+    ));
+    Ok(())
+}
+
+// Test that recorded launch measurements are extracted from TDX and SEV-SNP reports
+#[test]
+fn test_report_launch_measurement() {
+    let tdx_report = serde_json::json!({ "td_info": { "mrtd": vec![7u8; 48] } });
+    assert_eq!(
+        cc_attestation::report_launch_measurement(&tdx_report),
+        Some([7u8; 48])
+    );
+
+    let sev_report = serde_json::json!({ "measurement": hex::encode([9u8; 48]) });
+    assert_eq!(
+        cc_attestation::report_launch_measurement(&sev_report),
+        Some([9u8; 48])
+    );
+
+    let mock_report = serde_json::json!({ "type": "mock_attestation" });
+    assert_eq!(
+        cc_attestation::report_launch_measurement(&mock_report),
+        None
+    );
+}
+
+// Test that a nonce given to the mock provider is bound into its reports
+#[test]
+fn test_mock_attestation_with_nonce() -> Result<()> {
+    let report = |nonce: &[u8]| -> Result<Value> {
+        let report = MockAttestationProvider::with_nonce("test-platform", nonce)
+            .get_attestation_report()
+            .map_err(|e| Error::CCAttestationError(e.to_string()))?;
+        Ok(serde_json::from_str(&report)?)
+    };
+
+    let first = report(b"nonce-1")?;
+    let second = report(b"nonce-2")?;
+    assert_eq!(first["nonce"], hex::encode(b"nonce-1"));
+    assert_ne!(first["nonce"], second["nonce"]);
+
+    // Reports without a nonce carry none
+    let plain = MockAttestationProvider::new("test-platform")
+        .get_attestation_report()
+        .map_err(|e| Error::CCAttestationError(e.to_string()))?;
+    assert!(
+        serde_json::from_str::<Value>(&plain)?
+            .get("nonce")
+            .is_none()
+    );
+
+    if !cc_attestation::is_cc_platform(&cc_attestation::get_platform_name()?) {
+        let report: Value =
+            serde_json::from_str(&cc_attestation::get_report_with_nonce(false, Some(b"abc"))?)?;
+        assert_eq!(report["nonce"], hex::encode(b"abc"));
+    }
+
+    Ok(())
+}
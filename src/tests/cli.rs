@@ -0,0 +1,1590 @@
+use crate::Config;
+use crate::cli::commands::{HashAlgorithmChoice, ModelCommands};
+use crate::cli::handlers::handle_model_command;
+use crate::error::{Error, Result};
+use crate::storage::filesystem::FilesystemStorage;
+use crate::storage::traits::StorageBackend;
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::rsa::Rsa;
+use openssl::sign::Verifier;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tempfile::tempdir;
+
+/// `model create` of `paths` into local-fs storage at `storage_url`, with
+/// every other option at its default
+fn model_create(
+    paths: Vec<PathBuf>,
+    name: impl Into<String>,
+    storage_url: impl AsRef<Path>,
+) -> ModelCommands {
+    ModelCommands::Create {
+        paths,
+        ingredient_names: vec!["Model".to_string()],
+        relationships: vec![],
+        ingredient_urls: vec![],
+        name: name.into(),
+        author_org: None,
+        author_name: None,
+        description: None,
+        linked_manifests: None,
+        trained_on: Vec::new(),
+        model_card: None,
+        model_card_hash: None,
+        key: None,
+        kms_key: None,
+        hash_alg: HashAlgorithmChoice::Sha384,
+        tsa_url: None,
+        min_key_bits: 2048,
+        record_environment: false,
+        redact_hostname: false,
+        creative_type: None,
+        digital_source_type: None,
+        dry_run: false,
+        no_schema_check: false,
+        no_sniff: false,
+        tags: vec![],
+        print: false,
+        format: "json".to_string(),
+        output: None,
+        hex: false,
+        deterministic: false,
+        hash_decompressed: false,
+        quick_hash: false,
+        created_at: None,
+        valid_from: None,
+        valid_until: None,
+        storage_type: Box::new("local-fs".to_string()),
+        storage_url: Box::new(storage_url.as_ref().to_string_lossy().to_string()),
+        with_tdx: false,
+        asset_type: None,
+        recursive: false,
+        ignore: vec![],
+    }
+}
+
+/// [`model_create`] with some options overridden, e.g.
+/// `model_create_with!(paths, "Model", &storage_dir, dry_run: true)`
+macro_rules! model_create_with {
+    ($paths:expr, $name:expr, $storage_url:expr, $($field:ident: $value:expr),+ $(,)?) => {{
+        let mut create = model_create($paths, $name, $storage_url);
+        $(
+            let value = $value;
+            if let ModelCommands::Create { $field, .. } = &mut create {
+                *$field = value;
+            }
+        )+
+        create
+    }};
+}
+
+// Test that hash algorithm names are validated
+#[test]
+fn test_hash_algorithm_choice_parsing() {
+    assert!(matches!(
+        HashAlgorithmChoice::from_str("sha256"),
+        Ok(HashAlgorithmChoice::Sha256)
+    ));
+    assert!(matches!(
+        HashAlgorithmChoice::from_str("SHA384"),
+        Ok(HashAlgorithmChoice::Sha384)
+    ));
+    assert!(matches!(
+        HashAlgorithmChoice::from_str("sha512"),
+        Ok(HashAlgorithmChoice::Sha512)
+    ));
+    assert!(matches!(
+        HashAlgorithmChoice::from_str("sha3-256"),
+        Ok(HashAlgorithmChoice::Sha3_256)
+    ));
+    assert!(matches!(
+        HashAlgorithmChoice::from_str("md5"),
+        Err(Error::Validation(_))
+    ));
+}
+
+// Test that --hash-alg selects the digest used to sign the claim
+#[test]
+fn test_model_create_with_each_hash_algorithm() -> Result<()> {
+    let dir = tempdir()?;
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"model data")?;
+
+    let rsa = Rsa::generate(2048).map_err(|e| Error::Signing(e.to_string()))?;
+    let private_key = PKey::from_rsa(rsa).map_err(|e| Error::Signing(e.to_string()))?;
+    let key_path = dir.path().join("key.pem");
+    std::fs::write(
+        &key_path,
+        private_key
+            .private_key_to_pem_pkcs8()
+            .map_err(|e| Error::Signing(e.to_string()))?,
+    )?;
+
+    let digests = [
+        ("sha256", MessageDigest::sha256()),
+        ("sha384", MessageDigest::sha384()),
+        ("sha512", MessageDigest::sha512()),
+        ("sha3-256", MessageDigest::sha3_256()),
+        ("sha3-384", MessageDigest::sha3_384()),
+        ("sha3-512", MessageDigest::sha3_512()),
+    ];
+
+    for (alg, _) in digests {
+        let storage_dir = dir.path().join(format!("storage-{alg}"));
+
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                format!("Model signed with {alg}"),
+                &storage_dir,
+                key: Some(key_path.clone()),
+                hash_alg: HashAlgorithmChoice::from_str(alg)?,
+            ),
+            &Config::default(),
+        )?;
+
+        let storage = FilesystemStorage::new(&storage_dir)?;
+        let manifests = storage.list_manifests()?;
+        assert_eq!(manifests.len(), 1);
+        let manifest = storage.retrieve_manifest(&manifests[0].id)?;
+
+        // Ingredients are hashed with the same algorithm and verify with it
+        assert_eq!(manifest.ingredients[0].data.alg, alg);
+        handle_model_command(
+            ModelCommands::Verify {
+                id: Some(manifests[0].id.clone()),
+                file: None,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_dir.to_string_lossy().to_string()),
+                strict_cc: false,
+                tsa_ca: None,
+                allow_dangling: false,
+                offline: false,
+                trust_list: None,
+                allow_expired: false,
+                policy: None,
+                strict: false,
+                continue_on_error: false,
+            },
+            &Config::default(),
+        )?;
+
+        let mut claim = manifest.claim_v2.expect("manifest should have a claim");
+        let signature = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            claim.signature.take().expect("claim should be signed"),
+        )
+        .map_err(|e| Error::Signing(e.to_string()))?;
+        let claim_cbor = crate::manifest::canonical::to_canonical_cbor(&claim)?;
+
+        // The signature only verifies with the digest selected on the command line
+        for (other_alg, other_digest) in digests {
+            let mut verifier = Verifier::new(other_digest, &private_key)
+                .map_err(|e| Error::Signing(e.to_string()))?;
+            verifier
+                .update(&claim_cbor)
+                .map_err(|e| Error::Signing(e.to_string()))?;
+            let valid = verifier.verify(&signature).unwrap_or(false);
+            assert_eq!(
+                valid,
+                other_alg == alg,
+                "{alg} signature checked with {other_alg}"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// Test that --asset-type overrides extension-based type detection
+#[test]
+fn test_model_create_with_asset_type_override() -> Result<()> {
+    use crate::manifest::utils::parse_asset_type;
+    use atlas_c2pa_lib::asset_type::AssetType;
+
+    let dir = tempdir()?;
+
+    // .json is detected as an MXNet model by default
+    let model_path = dir.path().join("model.json");
+    std::fs::write(&model_path, b"{}")?;
+
+    for (asset_type, expected) in [
+        (None, AssetType::ModelMxNet),
+        (Some("model.pytorch"), AssetType::ModelPytorch),
+    ] {
+        let storage_dir = dir
+            .path()
+            .join(format!("storage-{}", asset_type.unwrap_or("default")));
+
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                "Model",
+                &storage_dir,
+                asset_type: asset_type.map(parse_asset_type).transpose()?,
+            ),
+            &Config::default(),
+        )?;
+
+        let storage = FilesystemStorage::new(&storage_dir)?;
+        let manifests = storage.list_manifests()?;
+        let manifest = storage.retrieve_manifest(&manifests[0].id)?;
+        assert_eq!(manifest.ingredients[0].data.data_types, vec![expected]);
+    }
+
+    Ok(())
+}
+
+// Test that deprecated storage type names are resolved, or rejected with
+// --deny-deprecated-storage, by every subcommand
+#[test]
+fn test_model_create_deprecated_storage_type() -> Result<()> {
+    let dir = tempdir()?;
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"model data")?;
+    let storage_dir = dir.path().join("storage");
+
+    let create = || {
+        model_create_with!(
+            vec![model_path.clone()],
+            "Model",
+            &storage_dir,
+            storage_type: Box::new("filesystem".to_string()),
+        )
+    };
+
+    let deny = Config {
+        storage_config: crate::storage::config::StorageConfig {
+            deny_deprecated: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    match handle_model_command(create(), &deny) {
+        Err(Error::Validation(msg)) => assert!(msg.contains("local-fs"), "{msg}"),
+        other => panic!("expected a validation error, got {other:?}"),
+    }
+    assert!(!storage_dir.exists());
+
+    handle_model_command(create(), &Config::default())?;
+    assert_eq!(
+        FilesystemStorage::new(&storage_dir)?
+            .list_manifests()?
+            .len(),
+        1
+    );
+
+    Ok(())
+}
+
+// Test that --dry-run validates inputs without hashing or storing anything
+#[test]
+fn test_model_create_dry_run() -> Result<()> {
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    let create = |paths: Vec<PathBuf>| {
+        handle_model_command(
+            model_create_with!(
+                paths,
+                "Model",
+                &storage_dir,
+                dry_run: true,
+            ),
+            &Config::default(),
+        )
+    };
+
+    create(vec![model_path])?;
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    assert!(storage.list_manifests()?.is_empty());
+
+    // Missing inputs are reported before anything is hashed
+    let result = create(vec![dir.path().join("missing.onnx")]);
+    assert!(matches!(result, Err(Error::Validation(_))));
+
+    Ok(())
+}
+
+// Test that --output writes the manifest to a file, with CBOR as raw bytes
+#[test]
+fn test_model_create_output_file() -> Result<()> {
+    use atlas_c2pa_lib::manifest::Manifest;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    let create = |format: &str, output: PathBuf| {
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                "Written",
+                &storage_dir,
+                print: true,
+                format: format.to_string(),
+                output: Some(output),
+            ),
+            &Config::default(),
+        )
+    };
+
+    let json_path = dir.path().join("manifest.json");
+    create("json", json_path.clone())?;
+    let manifest: Manifest = serde_json::from_slice(&std::fs::read(&json_path)?)?;
+    assert_eq!(manifest.title, "Written");
+
+    let cbor_path = dir.path().join("manifest.cbor");
+    create("cbor", cbor_path.clone())?;
+    let manifest: Manifest = serde_cbor::from_slice(&std::fs::read(&cbor_path)?)
+        .map_err(|e| Error::Serialization(e.to_string()))?;
+    assert_eq!(manifest.title, "Written");
+
+    // --print still keeps the manifest out of storage
+    assert!(
+        FilesystemStorage::new(&storage_dir)?
+            .list_manifests()?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+// Test that --deterministic with --created-at reproduces the same manifest
+#[test]
+fn test_model_create_deterministic() -> Result<()> {
+    let dir = tempdir()?;
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    let create = |output: &str, deterministic: bool| -> Result<Vec<u8>> {
+        let output = dir.path().join(output);
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                "Reproducible",
+                dir.path().join("storage"),
+                print: true,
+                output: Some(output.clone()),
+                deterministic: deterministic,
+                created_at: Some("2024-01-01T00:00:00Z".to_string()),
+            ),
+            &Config::default(),
+        )?;
+        Ok(std::fs::read(output)?)
+    };
+
+    let first = create("first.json", true)?;
+    assert_eq!(first, create("second.json", true)?);
+    assert!(String::from_utf8_lossy(&first).contains("2024-01-01T00:00:00Z"));
+
+    // Without --deterministic the IDs are random
+    assert_ne!(create("third.json", false)?, create("fourth.json", false)?);
+
+    // Different inputs give different IDs
+    std::fs::write(&model_path, b"retrained weights")?;
+    let changed: serde_json::Value = serde_json::from_slice(&create("fifth.json", true)?)?;
+    let first: serde_json::Value = serde_json::from_slice(&first)?;
+    assert_ne!(changed["instance_id"], first["instance_id"]);
+
+    Ok(())
+}
+
+// Test that manifests written with --output can be imported into storage
+#[test]
+fn test_manifest_import() -> Result<()> {
+    use crate::cli::commands::ManifestCommands;
+    use crate::cli::handlers::handle_manifest_command;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+    let storage_url = storage_dir.to_string_lossy().to_string();
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    for (format, file_name) in [("json", "manifest.json"), ("cbor", "manifest.cbor")] {
+        let output = dir.path().join(file_name);
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                format!("Imported {format}"),
+                &storage_url,
+                print: true,
+                format: format.to_string(),
+                output: Some(output.clone()),
+            ),
+            &Config::default(),
+        )?;
+
+        handle_manifest_command(
+            ManifestCommands::Import {
+                file: output,
+                format: None,
+                verify_signatures: true,
+                trust_list: None,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+            },
+            &Config::default(),
+        )?;
+    }
+
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let mut names: Vec<String> = storage
+        .list_manifests()?
+        .into_iter()
+        .map(|metadata| metadata.name)
+        .collect();
+    names.sort();
+    assert_eq!(names, ["Imported cbor", "Imported json"]);
+
+    // Files that are not manifests are rejected before anything is stored
+    let invalid = dir.path().join("invalid.json");
+    std::fs::write(&invalid, b"{\"title\": \"not a manifest\"}")?;
+    let result = handle_manifest_command(
+        ManifestCommands::Import {
+            file: invalid,
+            format: None,
+            verify_signatures: false,
+            trust_list: None,
+            storage_type: Box::new("local-fs".to_string()),
+            storage_url: Box::new(storage_url.clone()),
+        },
+        &Config::default(),
+    );
+    assert!(matches!(result, Err(Error::Validation(_))));
+    assert_eq!(storage.list_manifests()?.len(), 2);
+
+    Ok(())
+}
+
+// Test that a deleted link target is reported as a dangling cross-reference
+#[test]
+fn test_model_verify_dangling_cross_reference() -> Result<()> {
+    use crate::manifest::config::VerificationConfig;
+    use crate::manifest::report::CheckStatus;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+    let storage_url = storage_dir.to_string_lossy().to_string();
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    let create = |name: &str, linked_manifests: Option<Vec<String>>| {
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                name,
+                &storage_url,
+                linked_manifests: linked_manifests,
+            ),
+            &Config::default(),
+        )
+    };
+    let verify = |id: &str, allow_dangling: bool| {
+        handle_model_command(
+            ModelCommands::Verify {
+                id: Some(id.to_string()),
+                file: None,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+                strict_cc: false,
+                tsa_ca: None,
+                allow_dangling,
+                offline: false,
+                trust_list: None,
+                allow_expired: false,
+                policy: None,
+                strict: false,
+                continue_on_error: false,
+            },
+            &Config::default(),
+        )
+    };
+
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    create("Base", None)?;
+    let base_id = storage.list_manifests()?[0].id.clone();
+    create("Derived", Some(vec![base_id.clone()]))?;
+    let derived_id = storage
+        .list_manifests()?
+        .into_iter()
+        .find(|m| m.name == "Derived")
+        .expect("derived manifest should be stored")
+        .id;
+
+    verify(&derived_id, false)?;
+    storage.delete_manifest(&base_id)?;
+
+    match verify(&derived_id, false) {
+        Err(Error::Validation(message)) => {
+            assert_eq!(
+                message,
+                format!("cross-reference target {base_id} not found")
+            )
+        }
+        other => panic!("expected a dangling cross-reference error, got {other:?}"),
+    }
+
+    // --allow-dangling downgrades the missing target to a warning
+    verify(&derived_id, true)?;
+
+    let config = VerificationConfig {
+        allow_dangling: true,
+        ..Default::default()
+    };
+    let report =
+        crate::manifest::common::verify_manifest_with_config(&derived_id, &storage, &config)?;
+    assert_eq!(report.status(), CheckStatus::Warning);
+    assert_eq!(report.cross_references.len(), 1);
+    assert_eq!(report.cross_references[0].subject, base_id);
+    assert_eq!(report.cross_references[0].status, CheckStatus::Warning);
+    assert!(
+        report
+            .ingredients
+            .iter()
+            .all(|c| c.status == CheckStatus::Verified)
+    );
+
+    Ok(())
+}
+
+// Test linking an existing manifest to another after creation
+#[test]
+fn test_manifest_link_after_creation() -> Result<()> {
+    use crate::cli::commands::ManifestCommands;
+    use crate::cli::handlers::handle_manifest_command;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+    let storage_url = storage_dir.to_string_lossy().to_string();
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    let create = |name: &str| {
+        handle_model_command(
+            model_create(vec![model_path.clone()], name, &storage_url),
+            &Config::default(),
+        )
+    };
+    let link = |source: &str, target: &str| {
+        handle_manifest_command(
+            ManifestCommands::Link {
+                source: source.to_string(),
+                target: target.to_string(),
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+            },
+            &Config::default(),
+        )
+    };
+
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    create("Model")?;
+    let model_id = storage.list_manifests()?[0].id.clone();
+    create("Evaluation")?;
+    let evaluation_id = storage
+        .list_manifests()?
+        .into_iter()
+        .find(|m| m.name == "Evaluation")
+        .expect("evaluation manifest should be stored")
+        .id;
+
+    link(&model_id, &evaluation_id)?;
+
+    let model = storage.retrieve_manifest(&model_id)?;
+    assert_eq!(model.cross_references.len(), 1);
+    assert_eq!(model.cross_references[0].manifest_url, evaluation_id);
+    let report = crate::manifest::common::verify_manifest(&model_id, &storage)?;
+    assert_eq!(report.cross_references.len(), 1);
+
+    // Linking the same target twice is refused
+    match link(&model_id, &evaluation_id) {
+        Err(Error::Validation(message)) => assert!(message.contains("already links"), "{message}"),
+        other => panic!("expected a duplicate link error, got {other:?}"),
+    }
+    assert_eq!(
+        storage.retrieve_manifest(&model_id)?.cross_references.len(),
+        1
+    );
+
+    // The target must exist
+    let missing = "urn:c2pa:00000000-0000-0000-0000-000000000000";
+    assert!(link(&model_id, missing).is_err());
+
+    Ok(())
+}
+
+// Test that --tag values are recorded in the manifest and filter listings
+#[test]
+fn test_model_create_with_tags() -> Result<()> {
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    let create = |name: &str, tags: &[&str]| {
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                name,
+                &storage_dir,
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ),
+            &Config::default(),
+        )
+    };
+
+    create("Prod", &["team=vision", "env=prod", "env=prod"])?;
+    create("Dev", &["env=dev", "team=vision"])?;
+    assert!(matches!(create("Bad", &["a,b"]), Err(Error::Validation(_))));
+
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let prod = storage.list_manifests_with_tags(&["env=prod".to_string()])?;
+    assert_eq!(prod.len(), 1);
+    assert_eq!(prod[0].name, "Prod");
+    assert_eq!(prod[0].tags, vec!["env=prod", "team=vision"]);
+
+    let vision = storage.list_manifests_with_tags(&["team=vision".to_string()])?;
+    assert_eq!(vision.len(), 2);
+
+    // Tagged manifests still verify
+    crate::manifest::common::verify_manifest(&prod[0].id, &storage)?;
+
+    Ok(())
+}
+
+// Test that manifests failing the JSON Schema are rejected unless --no-schema-check
+#[test]
+fn test_model_create_schema_check() -> Result<()> {
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    // An empty ingredient title is not allowed by the schema
+    let create = |no_schema_check: bool| {
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                "Model",
+                &storage_dir,
+                ingredient_names: vec![String::new()],
+                no_schema_check: no_schema_check,
+            ),
+            &Config::default(),
+        )
+    };
+
+    match create(false) {
+        Err(Error::Validation(message)) => {
+            assert!(message.contains("/ingredients/0/title"), "{message}")
+        }
+        other => panic!("expected a schema validation error, got {other:?}"),
+    }
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    assert!(storage.list_manifests()?.is_empty());
+
+    create(true)?;
+    assert_eq!(storage.list_manifests()?.len(), 1);
+
+    Ok(())
+}
+
+// Test that --creative-type and --digital-source-type replace the defaults
+#[test]
+fn test_model_create_with_type_overrides() -> Result<()> {
+    use atlas_c2pa_lib::assertion::Assertion;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    let create = |creative_type: Option<&str>, digital_source_type: Option<&str>| {
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                "Model",
+                &storage_dir,
+                creative_type: creative_type.map(str::to_string),
+                digital_source_type: digital_source_type.map(str::to_string),
+            ),
+            &Config::default(),
+        )
+    };
+
+    let source_type = "http://cv.iptc.org/newscodes/digitalsourcetype/trainedAlgorithmicMedia";
+    create(Some("MachineLearningModel"), Some(source_type))?;
+
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let manifest = storage.retrieve_manifest(&storage.list_manifests()?[0].id)?;
+    let assertions = &manifest.claim_v2.as_ref().unwrap().created_assertions;
+    assert!(assertions.iter().any(|assertion| matches!(
+        assertion,
+        Assertion::CreativeWork(creative) if creative.creative_type == "MachineLearningModel"
+    )));
+    assert!(assertions.iter().any(|assertion| matches!(
+        assertion,
+        Assertion::Action(action)
+            if action.actions[0].digital_source_type.as_deref() == Some(source_type)
+    )));
+
+    // Empty overrides are rejected
+    assert!(matches!(create(Some(" "), None), Err(Error::Validation(_))));
+    assert!(matches!(create(None, Some("")), Err(Error::Validation(_))));
+
+    Ok(())
+}
+
+// Test that --recursive manifests each file in a directory input
+#[test]
+fn test_model_create_recursive_directory() -> Result<()> {
+    use crate::hash;
+    use crate::manifest::common::DIRECTORY_HASHES_LABEL;
+    use atlas_c2pa_lib::assertion::Assertion;
+
+    let dir = tempdir()?;
+
+    let model_dir = dir.path().join("weights");
+    std::fs::create_dir_all(model_dir.join("sub"))?;
+    std::fs::create_dir_all(model_dir.join(".git"))?;
+    std::fs::write(model_dir.join("a.onnx"), b"layer a")?;
+    std::fs::write(model_dir.join("sub").join("b.onnx"), b"layer b")?;
+    std::fs::write(model_dir.join(".git").join("config"), b"ignored")?;
+    std::fs::write(model_dir.join("scratch.tmp"), b"ignored")?;
+
+    let create = |storage_dir: &std::path::Path, recursive: bool| {
+        handle_model_command(
+            model_create_with!(
+                vec![model_dir.clone()],
+                "Model",
+                &storage_dir,
+                recursive: recursive,
+                ignore: vec!["*.tmp".to_string()],
+            ),
+            &Config::default(),
+        )
+    };
+
+    // Directories are rejected without --recursive
+    assert!(matches!(
+        create(&dir.path().join("storage-flat"), false),
+        Err(Error::Validation(_))
+    ));
+
+    let storage_dir = dir.path().join("storage");
+    create(&storage_dir, true)?;
+
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let manifests = storage.list_manifests()?;
+    let manifest = storage.retrieve_manifest(&manifests[0].id)?;
+
+    let titles: Vec<_> = manifest
+        .ingredients
+        .iter()
+        .map(|i| i.title.as_str())
+        .collect();
+    assert_eq!(titles, vec!["Model/a.onnx", "Model/sub/b.onnx"]);
+
+    let hashes: Vec<_> = manifest
+        .ingredients
+        .iter()
+        .map(|i| i.data.hash.as_str())
+        .collect();
+    let expected = hash::combine_hashes(&hashes)?;
+
+    let directory_hashes = manifest
+        .claim_v2
+        .as_ref()
+        .unwrap()
+        .created_assertions
+        .iter()
+        .find_map(|assertion| match assertion {
+            Assertion::CustomAssertion(custom) if custom.label == DIRECTORY_HASHES_LABEL => {
+                Some(custom.data.clone())
+            }
+            _ => None,
+        })
+        .expect("directory hash assertion");
+    assert_eq!(directory_hashes[0]["file_count"], 2);
+    assert_eq!(directory_hashes[0]["hash"], expected);
+
+    // The Merkle root lets a single file be proven a member of the directory
+    let root = directory_hashes[0]["merkle_root"].as_str().unwrap();
+    let hashes: Vec<String> = hashes.iter().map(|h| h.to_string()).collect();
+    let proof = hash::merkle::build_merkle_proof(&hashes, 1)?;
+    assert!(hash::merkle::verify_merkle_proof(&hashes[1], &proof, root)?);
+
+    // Manifests with directory hash assertions still verify
+    crate::manifest::common::verify_manifest(&manifests[0].id, &storage)?;
+
+    Ok(())
+}
+
+// Test that glob patterns in --paths expand to sorted matches
+#[test]
+fn test_expand_path_patterns() -> Result<()> {
+    use crate::cli::handlers::expand_path_patterns;
+
+    let dir = tempdir()?;
+    for shard in [
+        "train-2.parquet",
+        "train-0.parquet",
+        "train-1.parquet",
+        "test.parquet",
+    ] {
+        std::fs::write(dir.path().join(shard), shard)?;
+    }
+
+    let (paths, names) = expand_path_patterns(
+        vec![
+            dir.path().join("train-*.parquet"),
+            dir.path().join("test.parquet"),
+        ],
+        vec!["Train".to_string(), "Test".to_string()],
+    )?;
+
+    assert_eq!(
+        paths,
+        vec![
+            dir.path().join("train-0.parquet"),
+            dir.path().join("train-1.parquet"),
+            dir.path().join("train-2.parquet"),
+            dir.path().join("test.parquet"),
+        ]
+    );
+    assert_eq!(
+        names,
+        vec![
+            "Train/train-0.parquet",
+            "Train/train-1.parquet",
+            "Train/train-2.parquet",
+            "Test"
+        ]
+    );
+
+    // A pattern that matches nothing is rejected
+    let result = expand_path_patterns(
+        vec![dir.path().join("valid-*.parquet")],
+        vec!["Valid".to_string()],
+    );
+    assert!(matches!(result, Err(Error::Validation(_))));
+
+    Ok(())
+}
+
+// Test that verify-all reports every failing manifest instead of stopping
+#[test]
+fn test_verify_all_collects_failures() -> Result<()> {
+    use crate::cli::commands::ManifestCommands;
+    use crate::cli::handlers::handle_manifest_command;
+    use crate::manifest::common::verify_all;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+
+    for name in ["good", "tampered"] {
+        let model_path = dir.path().join(format!("{name}.onnx"));
+        std::fs::write(&model_path, name)?;
+
+        handle_model_command(
+            model_create_with!(
+                vec![model_path],
+                name,
+                &storage_dir,
+                ingredient_names: vec![name.to_string()],
+            ),
+            &Config::default(),
+        )?;
+    }
+
+    // Modify one of the ingredients so its hash no longer matches
+    std::fs::write(dir.path().join("tampered.onnx"), b"modified")?;
+
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let summary = verify_all(&storage)?;
+    assert_eq!(summary.total, 2);
+    assert_eq!(summary.passed, 1);
+    assert_eq!(summary.failed.len(), 1);
+
+    let tampered = storage
+        .list_manifests()?
+        .into_iter()
+        .find(|m| m.name == "tampered")
+        .unwrap();
+    assert_eq!(summary.failed[0].0, tampered.id);
+
+    // The command fails so the process exits with a nonzero status
+    let result = handle_manifest_command(
+        ManifestCommands::VerifyAll {
+            storage_type: Box::new("local-fs".to_string()),
+            storage_url: Box::new(storage_dir.to_string_lossy().to_string()),
+            strict_cc: false,
+            tsa_ca: None,
+            allow_dangling: false,
+            offline: false,
+            trust_list: None,
+            allow_expired: false,
+            policy: None,
+            strict: false,
+            continue_on_error: false,
+            signature_only: false,
+        },
+        &Config::default(),
+    );
+    assert!(matches!(result, Err(Error::Validation(_))));
+
+    Ok(())
+}
+
+// Test that --tsa-url timestamps the signature and verification checks it
+#[test]
+fn test_model_create_with_timestamp_authority() -> Result<()> {
+    use crate::manifest::common::verify_timestamps;
+    use crate::manifest::config::VerificationConfig;
+    use crate::manifest::report::CheckStatus;
+    use crate::signing::timestamp::TIMESTAMP_ASSERTION_LABEL;
+    use crate::signing::timestamp::tests::fake_tsa_response;
+    use atlas_c2pa_lib::assertion::Assertion;
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"model data")?;
+
+    let rsa = Rsa::generate(2048).map_err(|e| Error::Signing(e.to_string()))?;
+    let private_key = PKey::from_rsa(rsa).map_err(|e| Error::Signing(e.to_string()))?;
+    let key_path = dir.path().join("key.pem");
+    std::fs::write(
+        &key_path,
+        private_key
+            .private_key_to_pem_pkcs8()
+            .map_err(|e| Error::Signing(e.to_string()))?,
+    )?;
+
+    let mut tsa = mockito::Server::new();
+    let _mock = tsa
+        .mock("POST", "/")
+        .match_header("content-type", "application/timestamp-query")
+        .with_body_from_request(|request| fake_tsa_response(request.body().unwrap()))
+        .create();
+
+    let storage_dir = dir.path().join("storage");
+    let create = |key: Option<std::path::PathBuf>| {
+        handle_model_command(
+            model_create_with!(
+                vec![model_path.clone()],
+                "Timestamped",
+                &storage_dir,
+                key: key,
+                tsa_url: Some(tsa.url()),
+            ),
+            &Config::default(),
+        )
+    };
+
+    // Timestamping requires a signature
+    assert!(matches!(create(None), Err(Error::Validation(_))));
+
+    create(Some(key_path))?;
+
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let id = storage.list_manifests()?[0].id.clone();
+    let mut manifest = storage.retrieve_manifest(&id)?;
+
+    let claim = manifest.claim_v2.as_ref().unwrap();
+    assert!(claim.created_assertions.iter().any(|assertion| matches!(
+        assertion,
+        Assertion::CustomAssertion(custom) if custom.label == TIMESTAMP_ASSERTION_LABEL
+    )));
+    verify_timestamps(&manifest, &VerificationConfig::default())?;
+    let report = crate::manifest::common::verify_manifest(&id, &storage)?;
+
+    // Without --tsa-ca the TSA chain is unchecked, which is reported as a warning
+    assert_eq!(report.timestamps.len(), 1);
+    assert_eq!(report.timestamps[0].status, CheckStatus::Warning);
+    assert_eq!(report.status(), CheckStatus::Warning);
+
+    // A token issued for a different signature does not verify
+    manifest.claim_v2.as_mut().unwrap().signature = Some("b3RoZXI=".to_string());
+    assert!(verify_timestamps(&manifest, &VerificationConfig::default()).is_err());
+
+    Ok(())
+}
+
+// Test embedding a stored manifest into a PNG and verifying it offline
+#[test]
+fn test_manifest_embed_and_verify_embedded() -> Result<()> {
+    use crate::cli::commands::ManifestCommands;
+    use crate::cli::handlers::handle_manifest_command;
+    use crate::manifest::embed::{EmbedFormat, ManifestSource, extract_manifest};
+    use crate::manifest::utils::parse_asset_type;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+    let storage_url = storage_dir.to_string_lossy().to_string();
+
+    // Minimal PNG: signature, IHDR and IEND
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png.extend_from_slice(&[
+        0, 0, 0, 13, b'I', b'H', b'D', b'R', 0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0, 0x3a, 0x7e,
+        0x9b, 0x55,
+    ]);
+    png.extend_from_slice(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]);
+    let asset_path = dir.path().join("model.png");
+    std::fs::write(&asset_path, &png)?;
+
+    handle_model_command(
+        model_create_with!(
+            vec![asset_path.clone()],
+            "Embedded",
+            &storage_url,
+            asset_type: Some(parse_asset_type("model.onnx")?),
+        ),
+        &Config::default(),
+    )?;
+    let id = FilesystemStorage::new(&storage_dir)?.list_manifests()?[0]
+        .id
+        .clone();
+
+    handle_manifest_command(
+        ManifestCommands::Embed {
+            asset: asset_path.clone(),
+            id: Some(id.clone()),
+            manifest: None,
+            output: None,
+            sidecar: false,
+            storage_type: Box::new("local-fs".to_string()),
+            storage_url: Box::new(storage_url.clone()),
+        },
+        &Config::default(),
+    )?;
+
+    let (manifest, source) = extract_manifest(&asset_path)?;
+    assert_eq!(manifest.instance_id, id);
+    assert_eq!(source, ManifestSource::Embedded(EmbedFormat::Png));
+
+    let verify = || {
+        handle_manifest_command(
+            ManifestCommands::VerifyEmbedded {
+                asset: asset_path.clone(),
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+                strict_cc: false,
+                tsa_ca: None,
+                allow_dangling: false,
+                offline: false,
+                trust_list: None,
+                allow_expired: false,
+                policy: None,
+                strict: false,
+                continue_on_error: false,
+            },
+            &Config::default(),
+        )
+    };
+    verify()?;
+
+    // Appending data after IEND changes the asset bytes outside the manifest
+    let mut tampered = std::fs::read(&asset_path)?;
+    tampered.extend_from_slice(b"tampered");
+    std::fs::write(&asset_path, tampered)?;
+    assert!(verify().is_err());
+
+    Ok(())
+}
+
+// Test that several signers can countersign a manifest and that verify checks them
+#[test]
+fn test_manifest_countersignatures() -> Result<()> {
+    use crate::cli::commands::ManifestCommands;
+    use crate::cli::handlers::handle_manifest_command;
+    use crate::manifest::signatures::{COUNTERSIGNATURES_LABEL, countersignatures};
+    use atlas_c2pa_lib::assertion::Assertion;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+    let storage_url = storage_dir.to_string_lossy().to_string();
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    let write_key = |name: &str| -> Result<PathBuf> {
+        let rsa = Rsa::generate(2048).map_err(|e| Error::Signing(e.to_string()))?;
+        let private_key = PKey::from_rsa(rsa).map_err(|e| Error::Signing(e.to_string()))?;
+        let key_path = dir.path().join(name);
+        std::fs::write(
+            &key_path,
+            private_key
+                .private_key_to_pem_pkcs8()
+                .map_err(|e| Error::Signing(e.to_string()))?,
+        )?;
+        Ok(key_path)
+    };
+
+    handle_model_command(
+        model_create_with!(
+            vec![model_path.clone()],
+            "Reviewed",
+            &storage_url,
+            key: Some(write_key("author.pem")?),
+        ),
+        &Config::default(),
+    )?;
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let id = storage.list_manifests()?[0].id.clone();
+    let original_signature = storage.retrieve_manifest(&id)?.claim_v2.unwrap().signature;
+
+    for identity in ["data-scientist", "release-manager"] {
+        handle_manifest_command(
+            ManifestCommands::Sign {
+                id: id.clone(),
+                key: Some(write_key(&format!("{identity}.pem"))?),
+                identity: identity.to_string(),
+                hash_alg: HashAlgorithmChoice::Sha256,
+                min_key_bits: 2048,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+            },
+            &Config::default(),
+        )?;
+    }
+
+    let manifest = storage.retrieve_manifest(&id)?;
+    assert_eq!(countersignatures(&manifest)?.len(), 2);
+    assert_eq!(
+        manifest.claim_v2.as_ref().unwrap().signature,
+        original_signature
+    );
+
+    let verify = || {
+        handle_model_command(
+            ModelCommands::Verify {
+                id: Some(id.clone()),
+                file: None,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+                strict_cc: false,
+                tsa_ca: None,
+                allow_dangling: false,
+                offline: false,
+                trust_list: None,
+                allow_expired: false,
+                policy: None,
+                strict: false,
+                continue_on_error: false,
+            },
+            &Config::default(),
+        )
+    };
+    verify()?;
+
+    // Swap the two signatures so each no longer matches its public key
+    let mut tampered = manifest.clone();
+    for assertion in &mut tampered.claim_v2.as_mut().unwrap().created_assertions {
+        if let Assertion::CustomAssertion(custom) = assertion
+            && custom.label == COUNTERSIGNATURES_LABEL
+        {
+            let entries = custom.data.as_array_mut().unwrap();
+            let first = entries[0]["signature"].clone();
+            entries[0]["signature"] = entries[1]["signature"].clone();
+            entries[1]["signature"] = first;
+        }
+    }
+    storage.update_manifest(&tampered)?;
+    assert!(verify().is_err());
+
+    Ok(())
+}
+
+// Test that --trust-list rejects signers whose keys are not listed
+#[test]
+fn test_model_verify_trust_list() -> Result<()> {
+    use crate::cli::commands::ManifestCommands;
+    use crate::cli::handlers::handle_manifest_command;
+    use crate::signing::trust::public_key_fingerprint;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+    let storage_url = storage_dir.to_string_lossy().to_string();
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    // Writes a private key and returns its path and PEM public key
+    let write_key = |name: &str| -> Result<(PathBuf, String)> {
+        let rsa = Rsa::generate(2048).map_err(|e| Error::Signing(e.to_string()))?;
+        let private_key = PKey::from_rsa(rsa).map_err(|e| Error::Signing(e.to_string()))?;
+        let key_path = dir.path().join(name);
+        std::fs::write(
+            &key_path,
+            private_key
+                .private_key_to_pem_pkcs8()
+                .map_err(|e| Error::Signing(e.to_string()))?,
+        )?;
+        let public_pem = private_key
+            .public_key_to_pem()
+            .map_err(|e| Error::Signing(e.to_string()))?;
+        Ok((key_path, String::from_utf8_lossy(&public_pem).into_owned()))
+    };
+    let fingerprint = |pem: &str| -> Result<String> {
+        let key =
+            PKey::public_key_from_pem(pem.as_bytes()).map_err(|e| Error::Signing(e.to_string()))?;
+        public_key_fingerprint(&key)
+    };
+
+    let (author_key, author_pem) = write_key("author.pem")?;
+    let (reviewer_key, reviewer_pem) = write_key("reviewer.pem")?;
+    let (_, other_pem) = write_key("other.pem")?;
+
+    handle_model_command(
+        model_create_with!(
+            vec![model_path.clone()],
+            "Trusted",
+            &storage_url,
+            key: Some(author_key),
+        ),
+        &Config::default(),
+    )?;
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let id = storage.list_manifests()?[0].id.clone();
+
+    handle_manifest_command(
+        ManifestCommands::Sign {
+            id: id.clone(),
+            key: Some(reviewer_key),
+            identity: "reviewer".to_string(),
+            hash_alg: HashAlgorithmChoice::Sha256,
+            min_key_bits: 2048,
+            storage_type: Box::new("local-fs".to_string()),
+            storage_url: Box::new(storage_url.clone()),
+        },
+        &Config::default(),
+    )?;
+
+    let verify = |contents: String| -> Result<()> {
+        let trust_list = dir.path().join("trusted.txt");
+        std::fs::write(&trust_list, contents)?;
+        handle_model_command(
+            ModelCommands::Verify {
+                id: Some(id.clone()),
+                file: None,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+                strict_cc: false,
+                tsa_ca: None,
+                allow_dangling: false,
+                offline: false,
+                trust_list: Some(trust_list),
+                allow_expired: false,
+                policy: None,
+                strict: false,
+                continue_on_error: false,
+            },
+            &Config::default(),
+        )
+    };
+
+    // The claim signer is listed by PEM key and the countersigner by fingerprint
+    verify(format!(
+        "# release keys\n{author_pem}\nsha256:{}\n",
+        fingerprint(&reviewer_pem)?
+    ))?;
+
+    // Listing the author only by fingerprint cannot check the claim signature
+    assert!(verify(format!("{}\n{reviewer_pem}\n", fingerprint(&author_pem)?)).is_err());
+
+    // An unlisted countersigner fails verification
+    match verify(format!("{author_pem}\n{other_pem}\n")) {
+        Err(Error::Validation(msg)) => {
+            assert!(msg.contains("signer not in trust list"), "{msg}")
+        }
+        other => panic!("expected an untrusted signer error, got {:?}", other.err()),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_manifest_refresh() -> Result<()> {
+    use crate::cli::commands::ManifestCommands;
+    use crate::cli::handlers::handle_manifest_command;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+    let storage_url = storage_dir.to_string_lossy().to_string();
+
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+
+    let rsa = Rsa::generate(2048).map_err(|e| Error::Signing(e.to_string()))?;
+    let private_key = PKey::from_rsa(rsa).map_err(|e| Error::Signing(e.to_string()))?;
+    let key_path = dir.path().join("key.pem");
+    std::fs::write(
+        &key_path,
+        private_key
+            .private_key_to_pem_pkcs8()
+            .map_err(|e| Error::Signing(e.to_string()))?,
+    )?;
+
+    handle_model_command(
+        model_create_with!(
+            vec![model_path.clone()],
+            "Retrained",
+            &storage_url,
+            key: Some(key_path.clone()),
+        ),
+        &Config::default(),
+    )?;
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let id = storage.list_manifests()?[0].id.clone();
+
+    let verify = || {
+        handle_model_command(
+            ModelCommands::Verify {
+                id: Some(id.clone()),
+                file: None,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+                strict_cc: false,
+                tsa_ca: None,
+                allow_dangling: false,
+                offline: false,
+                trust_list: None,
+                allow_expired: false,
+                policy: None,
+                strict: false,
+                continue_on_error: false,
+            },
+            &Config::default(),
+        )
+    };
+    let refresh = |key: Option<PathBuf>, confirm: bool| {
+        handle_manifest_command(
+            ManifestCommands::Refresh {
+                id: id.clone(),
+                key,
+                hash_alg: HashAlgorithmChoice::Sha384,
+                min_key_bits: 2048,
+                confirm,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+            },
+            &Config::default(),
+        )
+    };
+
+    std::fs::write(&model_path, b"retrained weights")?;
+    assert!(verify().is_err());
+
+    // Without --confirm the changes are only shown
+    assert!(refresh(Some(key_path.clone()), false).is_err());
+    assert!(verify().is_err());
+
+    refresh(Some(key_path), true)?;
+    verify()?;
+
+    // Nothing left to refresh
+    refresh(None, false)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_model_create_hash_decompressed() -> Result<()> {
+    use crate::hash::calculate_hash_with_algorithm;
+    use atlas_c2pa_lib::asset_type::AssetType;
+    use atlas_c2pa_lib::cose::HashAlgorithm;
+
+    let dir = tempdir()?;
+    let storage_dir = dir.path().join("storage");
+    let storage_url = storage_dir.to_string_lossy().to_string();
+
+    let weights = b"model weights".repeat(100);
+    let model_path = dir.path().join("model.pt.zst");
+    std::fs::write(&model_path, zstd::encode_all(&weights[..], 3)?)?;
+
+    handle_model_command(
+        model_create_with!(
+            vec![model_path.clone()],
+            "Compressed",
+            &storage_url,
+            hash_decompressed: true,
+        ),
+        &Config::default(),
+    )?;
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let id = storage.list_manifests()?[0].id.clone();
+
+    // The compressed file's URL is recorded with the content's hash and type
+    let manifest = storage.retrieve_manifest(&id)?;
+    let ingredient = &manifest.ingredients[0];
+    assert_eq!(
+        ingredient.data.url,
+        format!("file://{}", model_path.to_string_lossy())
+    );
+    assert_eq!(
+        ingredient.data.hash,
+        calculate_hash_with_algorithm(&weights, &HashAlgorithm::Sha384)
+    );
+    assert_eq!(ingredient.data.data_types, vec![AssetType::ModelPytorch]);
+
+    // New manifests record the current schema version
+    assert_eq!(
+        crate::manifest::version::schema_version(&serde_json::to_value(&manifest)?),
+        crate::manifest::version::SCHEMA_VERSION
+    );
+
+    let verify = || {
+        handle_model_command(
+            ModelCommands::Verify {
+                id: Some(id.clone()),
+                file: None,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(storage_url.clone()),
+                strict_cc: false,
+                tsa_ca: None,
+                allow_dangling: false,
+                offline: false,
+                trust_list: None,
+                allow_expired: false,
+                policy: None,
+                strict: false,
+                continue_on_error: false,
+            },
+            &Config::default(),
+        )
+    };
+
+    // Recompressing with other settings keeps the manifest valid
+    std::fs::write(&model_path, zstd::encode_all(&weights[..], 19)?)?;
+    verify()?;
+
+    std::fs::write(&model_path, zstd::encode_all(&b"other weights"[..], 3)?)?;
+    assert!(verify().is_err());
+
+    Ok(())
+}
+
+// Test that handler errors map to distinct process exit codes
+#[test]
+fn test_error_exit_codes() -> Result<()> {
+    use crate::error::{EXIT_FAILURE, EXIT_SIGNING, EXIT_STORAGE, EXIT_VALIDATION};
+
+    let dir = tempdir()?;
+    let storage_url = dir.path().join("storage").to_string_lossy().to_string();
+    let verify = |storage_type: &str| {
+        handle_model_command(
+            ModelCommands::Verify {
+                id: Some("urn:c2pa:missing".to_string()),
+                file: None,
+                storage_type: Box::new(storage_type.to_string()),
+                storage_url: Box::new(storage_url.clone()),
+                strict_cc: false,
+                tsa_ca: None,
+                allow_dangling: false,
+                offline: false,
+                trust_list: None,
+                allow_expired: false,
+                policy: None,
+                strict: false,
+                continue_on_error: false,
+            },
+            &Config::default(),
+        )
+    };
+
+    assert_eq!(verify("local-fs").unwrap_err().exit_code(), EXIT_STORAGE);
+    assert_eq!(verify("unknown").unwrap_err().exit_code(), EXIT_VALIDATION);
+    assert_eq!(Error::Signing("bad key".into()).exit_code(), EXIT_SIGNING);
+    assert_eq!(
+        Error::Serialization("bad json".into()).exit_code(),
+        EXIT_FAILURE
+    );
+
+    Ok(())
+}
+
+// Test that a batch creates every valid entry, links by entry name and
+// reports failing entries without stopping
+#[test]
+fn test_batch_create() -> Result<()> {
+    use crate::cli::commands::{BatchCommands, BatchCreateArgs};
+    use crate::cli::handlers::handle_batch_command;
+
+    let dir = tempdir()?;
+    std::fs::write(dir.path().join("train.csv"), b"a,b\n1,2\n")?;
+    std::fs::write(dir.path().join("model.onnx"), b"model weights")?;
+    let spec_path = dir.path().join("specs.json");
+    std::fs::write(
+        &spec_path,
+        serde_json::to_vec(&serde_json::json!([
+            {"type": "dataset", "name": "Training Data", "paths": ["train.csv"]},
+            {"name": "Missing Model", "paths": ["missing.onnx"]},
+            {
+                "type": "model",
+                "name": "Classifier",
+                "paths": ["model.onnx"],
+                "ingredient_names": ["Classifier Weights"],
+                "linked_manifests": ["Training Data"],
+            },
+        ]))?,
+    )?;
+    let storage_dir = dir.path().join("storage");
+
+    let result = handle_batch_command(
+        BatchCommands::Create(BatchCreateArgs {
+            spec: spec_path,
+            key: None,
+            hash_alg: HashAlgorithmChoice::Sha384,
+            min_key_bits: 2048,
+            author_org: Some("Example Org".to_string()),
+            tags: vec![],
+            no_schema_check: false,
+            deterministic: false,
+            storage_type: "local-fs".to_string(),
+            storage_url: storage_dir.to_string_lossy().to_string(),
+        }),
+        &Config::default(),
+    );
+    assert!(matches!(result, Err(Error::Validation(msg)) if msg.contains("Missing Model")));
+
+    let storage = FilesystemStorage::new(&storage_dir)?;
+    let manifests = storage.list_manifests()?;
+    assert_eq!(manifests.len(), 2);
+    let dataset_id = &manifests
+        .iter()
+        .find(|m| m.name == "Training Data")
+        .unwrap()
+        .id;
+    let model_id = &manifests
+        .iter()
+        .find(|m| m.name == "Classifier")
+        .unwrap()
+        .id;
+
+    let model = storage.retrieve_manifest(model_id)?;
+    assert_eq!(model.ingredients[0].title, "Classifier Weights");
+    assert_eq!(model.cross_references.len(), 1);
+    assert!(
+        model.cross_references[0]
+            .manifest_url
+            .contains(dataset_id.as_str())
+    );
+
+    Ok(())
+}
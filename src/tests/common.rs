@@ -68,7 +68,8 @@ impl StorageBackend for MockStorageBackend {
                 } else {
                     ManifestType::Model
                 },
-                created_at: manifest.created_at.0.to_string(),
+                created_at: crate::manifest::utils::manifest_created_at(manifest),
+                tags: vec![],
             })
             .collect())
     }
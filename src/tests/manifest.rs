@@ -13,6 +13,7 @@ use atlas_c2pa_lib::datetime_wrapper::OffsetDateTimeWrapper;
 use atlas_c2pa_lib::ingredient::{Ingredient, IngredientData};
 use atlas_c2pa_lib::manifest::Manifest;
 use std::io::Write;
+use std::path::PathBuf;
 use tempfile::tempdir;
 use time::OffsetDateTime;
 use uuid::Uuid;
@@ -125,6 +126,327 @@ fn test_model_verification() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_model_verification_fetches_remote_ingredients() -> Result<()> {
+    use crate::hash::calculate_hash_with_algorithm;
+    use crate::manifest::config::VerificationConfig;
+    use crate::manifest::report::CheckStatus;
+    use atlas_c2pa_lib::cose::HashAlgorithm;
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("remote_model.onnx");
+    std::fs::write(&model_path, b"remote model data")?;
+
+    let mut server = mockito::Server::new();
+    let weights = server
+        .mock("GET", "/bucket/model.onnx")
+        .with_body("remote model data")
+        .create();
+
+    let mut ingredient = create_test_ingredient_internal(
+        &model_path,
+        "Remote Model",
+        AssetType::ModelOnnx,
+        "application/onnx",
+    )?;
+    ingredient.data.url = format!("{}/bucket/model.onnx", server.url());
+    ingredient.data.alg = "sha384".to_string();
+    ingredient.data.hash =
+        calculate_hash_with_algorithm(b"remote model data", &HashAlgorithm::Sha384);
+
+    let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+    let manifest = create_test_manifest_internal(
+        manifest_id.clone(),
+        vec![ingredient.clone()],
+        "Remote Model Manifest",
+        AssetKind::Model,
+    )?;
+    let storage = MockStorageBackend::new(manifest);
+
+    let report = model::verify_model_manifest(&manifest_id, &storage)?;
+    assert_eq!(report.ingredients[0].status, CheckStatus::Verified);
+
+    // Changed remote content fails verification unless fetching is disabled
+    weights.remove();
+    let _changed = server
+        .mock("GET", "/bucket/model.onnx")
+        .with_body("tampered model data")
+        .create();
+    assert!(model::verify_model_manifest(&manifest_id, &storage).is_err());
+
+    let config = VerificationConfig {
+        offline: true,
+        ..Default::default()
+    };
+    let report = model::verify_model_manifest_with_config(&manifest_id, &storage, &config)?;
+    assert_eq!(report.ingredients[0].status, CheckStatus::Warning);
+
+    Ok(())
+}
+
+#[test]
+fn test_model_verification_with_policy() -> Result<()> {
+    use crate::error::Error;
+    use crate::manifest::config::VerificationConfig;
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"model data")?;
+
+    let ingredient = create_test_ingredient_internal(
+        &model_path,
+        "Model",
+        AssetType::ModelOnnx,
+        "application/onnx",
+    )?;
+    let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+    let manifest = create_test_manifest_internal(
+        manifest_id.clone(),
+        vec![ingredient],
+        "Policy Model Manifest",
+        AssetKind::Model,
+    )?;
+    let storage = MockStorageBackend::new(manifest);
+
+    let policy_path = dir.path().join("policy.json");
+    let verify = |rules: serde_json::Value| {
+        std::fs::write(&policy_path, rules.to_string())?;
+        let config = VerificationConfig {
+            policy: Some(policy_path.clone()),
+            ..Default::default()
+        };
+        model::verify_model_manifest_with_config(&manifest_id, &storage, &config)
+    };
+
+    let org_rule = serde_json::json!({
+        "path": "$.claim.created_assertions[*].CreativeWork.author[*]['@type']",
+        "equals": "Organization",
+        "applies_to": ["model"],
+        "message": "Model manifests must name an author organization"
+    });
+    let report = verify(serde_json::json!([
+        org_rule,
+        {
+            "path": "$..version",
+            "applies_to": ["software"],
+            "message": "Software manifests must declare a version"
+        }
+    ]))?;
+    assert_eq!(report.policy.len(), 1);
+    assert_eq!(report.policy[0].message, org_rule["message"]);
+
+    // Every violated rule is reported
+    let result = verify(serde_json::json!([
+        {"path": "$..license", "message": "Manifests must declare a license"},
+        {"path": "$.title", "equals": "Other", "message": "Title must be Other"},
+        org_rule
+    ]));
+    assert!(matches!(
+        result,
+        Err(Error::Validation(msg)) if msg.contains("license") && msg.contains("Title must be Other")
+    ));
+
+    // Invalid paths are rejected when the policy is loaded
+    assert!(verify(serde_json::json!([{"path": "title", "message": "no root"}])).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_model_verification_of_ingredient_formats() -> Result<()> {
+    use crate::error::Error;
+    use crate::manifest::config::VerificationConfig;
+    use crate::manifest::report::CheckStatus;
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"model data")?;
+
+    let verify = |format: &str, asset_type: AssetType, strict: bool| {
+        let ingredient =
+            create_test_ingredient_internal(&model_path, "Weights", asset_type, format)?;
+        let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+        let manifest = create_test_manifest_internal(
+            manifest_id.clone(),
+            vec![ingredient],
+            "Format Model Manifest",
+            AssetKind::Model,
+        )?;
+        let config = VerificationConfig {
+            strict,
+            ..Default::default()
+        };
+        model::verify_model_manifest_with_config(
+            &manifest_id,
+            &MockStorageBackend::new(manifest),
+            &config,
+        )
+    };
+    let format_check = |report: &crate::manifest::report::VerificationReport| {
+        report
+            .asset_checks
+            .iter()
+            .find(|check| check.subject == "Weights")
+            .map(|check| check.status)
+    };
+
+    let report = verify("application/onnx", AssetType::ModelOnnx, true)?;
+    assert_eq!(format_check(&report), Some(CheckStatus::Verified));
+
+    // A format that does not match the extension is a warning...
+    let report = verify("application/json", AssetType::ModelOnnx, false)?;
+    assert_eq!(format_check(&report), Some(CheckStatus::Warning));
+    let report = verify("application/onnx", AssetType::ModelPytorch, false)?;
+    assert_eq!(format_check(&report), Some(CheckStatus::Warning));
+
+    // ...and an error with --strict
+    assert!(matches!(
+        verify("application/json", AssetType::ModelOnnx, true),
+        Err(Error::Validation(msg)) if msg.contains("application/onnx")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_model_verification_of_sampled_hash() -> Result<()> {
+    use crate::hash::sample::SAMPLE_ALG;
+    use crate::manifest::common::create_ingredient_from_path_sampled;
+    use crate::manifest::report::CheckStatus;
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("huge.onnx");
+    std::fs::write(&model_path, b"huge model data")?;
+
+    let ingredient = create_ingredient_from_path_sampled(
+        &model_path,
+        "Huge Model",
+        AssetType::ModelOnnx,
+        "application/onnx".to_string(),
+    )?;
+    assert_eq!(ingredient.data.alg, SAMPLE_ALG);
+
+    let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+    let manifest = create_test_manifest_internal(
+        manifest_id.clone(),
+        vec![ingredient],
+        "Sampled Model Manifest",
+        AssetKind::Model,
+    )?;
+    crate::manifest::schema::validate_manifest_schema(&manifest)?;
+    let storage = MockStorageBackend::new(manifest);
+
+    // A matching sampled hash is never reported as fully verified
+    let report = model::verify_model_manifest(&manifest_id, &storage)?;
+    assert_eq!(report.ingredients[0].status, CheckStatus::Warning);
+    assert_eq!(report.status(), CheckStatus::Warning);
+
+    std::fs::write(&model_path, b"huge model data, changed")?;
+    assert!(model::verify_model_manifest(&manifest_id, &storage).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn test_symlinked_ingredients_follow_policy() -> Result<()> {
+    use crate::manifest::common::create_ingredient_from_path_with_options;
+    use crate::manifest::config::VerificationConfig;
+    use atlas_c2pa_lib::cose::HashAlgorithm;
+
+    // Symlinks may only point into an allowed root, by default the current
+    // directory
+    let cache = tempfile::tempdir_in(std::env::current_dir()?)?;
+    let target = cache.path().join("weights.onnx");
+    std::fs::write(&target, b"cached weights")?;
+    let link = cache.path().join("model.onnx");
+    std::os::unix::fs::symlink(&target, &link)?;
+
+    let create = |path: &std::path::Path, follow_symlinks| {
+        create_ingredient_from_path_with_options(
+            path,
+            "Model",
+            AssetType::ModelOnnx,
+            "application/onnx".to_string(),
+            HashAlgorithm::Sha384,
+            false,
+            follow_symlinks,
+        )
+    };
+    assert!(create(&link, false).is_err());
+    let ingredient = create(&link, true)?;
+    assert_eq!(
+        ingredient.data.url,
+        format!("file://{}", link.to_string_lossy())
+    );
+
+    // A target outside the allowed roots is rejected even when following
+    let outside = tempdir()?;
+    let outside_target = outside.path().join("weights.onnx");
+    std::fs::write(&outside_target, b"cached weights")?;
+    let outside_link = cache.path().join("outside.onnx");
+    std::os::unix::fs::symlink(&outside_target, &outside_link)?;
+    assert!(create(&outside_link, true).is_err());
+
+    let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+    let manifest = create_test_manifest_internal(
+        manifest_id.clone(),
+        vec![ingredient],
+        "Symlinked Model Manifest",
+        AssetKind::Model,
+    )?;
+    let storage = MockStorageBackend::new(manifest);
+
+    assert!(model::verify_model_manifest(&manifest_id, &storage).is_err());
+    let config = VerificationConfig {
+        follow_symlinks: true,
+        ..Default::default()
+    };
+    model::verify_model_manifest_with_config(&manifest_id, &storage, &config)?;
+
+    // Configured roots replace the current directory
+    let config = VerificationConfig {
+        symlink_roots: vec![outside.path().to_path_buf()],
+        ..config
+    };
+    assert!(model::verify_model_manifest_with_config(&manifest_id, &storage, &config).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_model_verification_skips_stdin_ingredients() -> Result<()> {
+    use crate::manifest::common::STDIN_URL_SCHEME;
+    use crate::manifest::report::CheckStatus;
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("streamed.onnx");
+    std::fs::write(&model_path, b"streamed model data")?;
+
+    let mut ingredient = create_test_ingredient_internal(
+        &model_path,
+        "streamed.onnx",
+        AssetType::ModelOnnx,
+        "application/onnx",
+    )?;
+    ingredient.data.url = format!("{STDIN_URL_SCHEME}streamed.onnx");
+
+    let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+    let manifest = create_test_manifest_internal(
+        manifest_id.clone(),
+        vec![ingredient],
+        "Streamed Model Manifest",
+        AssetKind::Model,
+    )?;
+    let storage = MockStorageBackend::new(manifest);
+
+    let report = model::verify_model_manifest(&manifest_id, &storage)?;
+    assert_eq!(report.ingredients[0].status, CheckStatus::Warning);
+    assert!(report.ingredients[0].message.contains("stdin"));
+
+    Ok(())
+}
+
 fn create_test_ingredient_internal(
     path: &std::path::Path,
     title: &str,
@@ -275,7 +597,7 @@ fn test_cross_reference_verification() -> Result<()> {
 
     // Create cross-reference from model to dataset
     use sha2::{Digest, Sha256};
-    let dataset_json = serde_json::to_string(&dataset_manifest).unwrap();
+    let dataset_json = crate::manifest::canonical::to_canonical_json(&dataset_manifest).unwrap();
     let dataset_hash = hex::encode(Sha256::digest(dataset_json.as_bytes()));
 
     // Add cross-reference to model manifest
@@ -308,6 +630,84 @@ fn test_cross_reference_verification() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_cross_reference_with_legacy_hash_verifies_with_warning() -> Result<()> {
+    use crate::manifest::report::CheckStatus;
+    use sha2::{Digest, Sha256};
+
+    let dir = tempdir()?;
+    let dataset_path = dir.path().join("legacy_dataset.csv");
+    let model_path = dir.path().join("legacy_model.onnx");
+    std::fs::write(&dataset_path, b"a,b\n1,2")?;
+    std::fs::write(&model_path, b"model data")?;
+
+    let dataset_id = format!("dataset_{}", Uuid::new_v4());
+    let dataset_manifest = create_test_manifest_internal(
+        dataset_id.clone(),
+        vec![create_test_ingredient_internal(
+            &dataset_path,
+            "Legacy Dataset",
+            AssetType::Dataset,
+            "text/csv",
+        )?],
+        "Legacy Dataset Manifest",
+        AssetKind::Dataset,
+    )?;
+    let model_id = format!("model_{}", Uuid::new_v4());
+    let mut model_manifest = create_test_manifest_internal(
+        model_id.clone(),
+        vec![create_test_ingredient_internal(
+            &model_path,
+            "Legacy Model",
+            AssetType::ModelOnnx,
+            "application/onnx",
+        )?],
+        "Legacy Model Manifest",
+        AssetKind::Model,
+    )?;
+
+    // Linked by a release that hashed the plain serde_json serialization
+    let legacy_json = serde_json::to_string(&dataset_manifest)?;
+    model_manifest
+        .cross_references
+        .push(atlas_c2pa_lib::cross_reference::CrossReference {
+            manifest_url: dataset_id.clone(),
+            manifest_hash: hex::encode(Sha256::digest(legacy_json.as_bytes())),
+            media_type: Some("application/json".to_string()),
+        });
+    let mut storage = MockStorageBackend::new(dataset_manifest.clone());
+    storage.add_manifest(model_manifest);
+
+    let report = model::verify_model_manifest(&model_id, &storage)?;
+    assert_eq!(report.cross_references[0].status, CheckStatus::Warning);
+    assert!(crate::manifest::verify_manifest_link(
+        &model_id,
+        &dataset_id,
+        &storage
+    )?);
+    let checks = crate::manifest::validate_linked_manifests(&model_id, &storage)?;
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].status, CheckStatus::Warning);
+
+    // A changed target still fails under either scheme
+    let mut modified = dataset_manifest;
+    modified.title = "Modified Dataset Title".to_string();
+    storage.add_manifest(modified);
+    assert!(model::verify_model_manifest(&model_id, &storage).is_err());
+    match crate::manifest::validate_linked_manifests(&model_id, &storage) {
+        Err(crate::error::Error::Validation(msg)) => {
+            assert!(
+                msg.contains(&format!("{dataset_id}: Hash mismatch")),
+                "{msg}"
+            )
+        }
+        other => panic!("expected a validation error, got {other:?}"),
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_evaluation_with_both_conditions() -> Result<()> {
     use crate::storage::traits::StorageBackend;
@@ -524,8 +924,7 @@ fn test_evaluation_with_both_conditions() -> Result<()> {
 
     // Add model cross-reference
     if let Ok(model_manifest) = storage.retrieve_manifest(&model_id) {
-        let model_json = serde_json::to_string(&model_manifest)
-            .map_err(|e| crate::error::Error::Serialization(e.to_string()))?;
+        let model_json = crate::manifest::canonical::to_canonical_json(&model_manifest)?;
         let model_hash = hex::encode(Sha256::digest(model_json.as_bytes()));
 
         cross_references.push(atlas_c2pa_lib::cross_reference::CrossReference {
@@ -537,8 +936,7 @@ fn test_evaluation_with_both_conditions() -> Result<()> {
 
     // Add dataset cross-reference
     if let Ok(dataset_manifest) = storage.retrieve_manifest(&dataset_id) {
-        let dataset_json = serde_json::to_string(&dataset_manifest)
-            .map_err(|e| crate::error::Error::Serialization(e.to_string()))?;
+        let dataset_json = crate::manifest::canonical::to_canonical_json(&dataset_manifest)?;
         let dataset_hash = hex::encode(Sha256::digest(dataset_json.as_bytes()));
 
         cross_references.push(atlas_c2pa_lib::cross_reference::CrossReference {
@@ -578,15 +976,1601 @@ fn test_evaluation_with_both_conditions() -> Result<()> {
     println!("\n=== PERFORMING VERIFICATION ===");
     println!("Attempting to verify evaluation manifest with ID: {eval_id}");
 
-    match crate::manifest::evaluation::verify_evaluation_manifest(&eval_id, &storage) {
-        Ok(_) => {
+    let report = match crate::manifest::evaluation::verify_evaluation_manifest(&eval_id, &storage) {
+        Ok(report) => {
             println!("✓ Evaluation verification successful");
+            report
         }
         Err(e) => {
             println!("✗ Evaluation verification failed: {e}");
             return Err(e);
         }
+    };
+
+    // The rendered success output uses a real check mark, not mojibake
+    let output = crate::cli::handlers::format_verification_report(&report);
+    assert!(
+        output.contains("✓ Manifest verification successful"),
+        "{output}"
+    );
+    assert!(!output.contains("âœ"), "{output}");
+    assert!(
+        report
+            .asset_checks
+            .iter()
+            .any(|c| c.message == "references a model and a dataset")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_show_manifest_formats() -> Result<()> {
+    let dir = tempdir()?;
+    let model_path = dir.path().join("show_model.onnx");
+    std::fs::write(&model_path, b"show model data")?;
+
+    let ingredient = create_test_ingredient_internal(
+        &model_path,
+        "Show Model",
+        AssetType::ModelOnnx,
+        "application/onnx",
+    )?;
+    let manifest_id = format!("urn:c2pa:{}", Uuid::new_v4());
+    let manifest = create_test_manifest_internal(
+        manifest_id.clone(),
+        vec![ingredient],
+        "Show Model Manifest",
+        AssetKind::Model,
+    )?;
+
+    // JSON serialization round-trips
+    let json = crate::manifest::common::serialize_manifest(&manifest, "json")?;
+    let decoded: Manifest = serde_json::from_str(&json)?;
+    assert_eq!(decoded.title, manifest.title);
+
+    // CBOR serialization is hex-encoded and round-trips
+    let cbor = crate::manifest::common::serialize_manifest(&manifest, "cbor")?;
+    let decoded: Manifest = serde_cbor::from_slice(&hex::decode(cbor)?)
+        .map_err(|e| crate::error::Error::Serialization(e.to_string()))?;
+    assert_eq!(decoded.instance_id, manifest.instance_id);
+
+    // YAML serialization round-trips to an equivalent manifest
+    let yaml = crate::manifest::common::serialize_manifest(&manifest, "yaml")?;
+    let decoded: Manifest = serde_yaml::from_str(&yaml)
+        .map_err(|e| crate::error::Error::Serialization(e.to_string()))?;
+    assert_eq!(
+        serde_json::to_value(&decoded)?,
+        serde_json::to_value(&manifest)?
+    );
+
+    let storage = MockStorageBackend::new(manifest);
+    for format in ["text", "json", "cbor", "yaml"] {
+        crate::manifest::show_manifest_with_format(&manifest_id, &storage, format)?;
+    }
+
+    // Unknown formats are rejected
+    assert!(matches!(
+        crate::manifest::show_manifest_with_format(&manifest_id, &storage, "xml"),
+        Err(crate::error::Error::Validation(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_write_manifest_cbor_round_trip() -> Result<()> {
+    use crate::manifest::common::write_manifest;
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("cbor_model.onnx");
+    std::fs::write(&model_path, b"cbor model")?;
+
+    let ingredient = create_test_ingredient_internal(
+        &model_path,
+        "CBOR Model",
+        AssetType::ModelOnnx,
+        "application/onnx",
+    )?;
+    let manifest = create_test_manifest_internal(
+        format!("urn:c2pa:{}", Uuid::new_v4()),
+        vec![ingredient],
+        "CBOR Model Manifest",
+        AssetKind::Model,
+    )?;
+
+    // CBOR is emitted as raw bytes that decode back to the same manifest
+    let mut raw = Vec::new();
+    write_manifest(&mut raw, &manifest, "cbor", false)?;
+    let decoded: Manifest = serde_cbor::from_slice(&raw)
+        .map_err(|e| crate::error::Error::Serialization(e.to_string()))?;
+    assert_eq!(
+        serde_json::to_value(&decoded)?,
+        serde_json::to_value(&manifest)?
+    );
+
+    // --hex prints the same bytes hex-encoded
+    let mut hex_output = Vec::new();
+    write_manifest(&mut hex_output, &manifest, "cbor", true)?;
+    assert_eq!(
+        String::from_utf8_lossy(&hex_output).trim_end(),
+        hex::encode(&raw)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_export_bundle_follows_links_and_handles_cycles() -> Result<()> {
+    use atlas_c2pa_lib::cross_reference::CrossReference;
+
+    let dir = tempdir()?;
+
+    let eval_id = format!("urn:c2pa:{}", Uuid::new_v4());
+    let model_id = format!("urn:c2pa:{}", Uuid::new_v4());
+    let dataset_id = format!("urn:c2pa:{}", Uuid::new_v4());
+
+    let mut eval = create_test_manifest_internal(
+        eval_id.clone(),
+        vec![],
+        "Bundle Evaluation",
+        AssetKind::Evaluation,
+    )?;
+    let mut model =
+        create_test_manifest_internal(model_id.clone(), vec![], "Bundle Model", AssetKind::Model)?;
+    let mut dataset = create_test_manifest_internal(
+        dataset_id.clone(),
+        vec![],
+        "Bundle Dataset",
+        AssetKind::Dataset,
+    )?;
+
+    // evaluation -> model -> dataset -> model forms a cycle
+    eval.cross_references = vec![CrossReference::new(model_id.clone(), "00".repeat(32))];
+    model.cross_references = vec![CrossReference::new(dataset_id.clone(), "00".repeat(32))];
+    dataset.cross_references = vec![CrossReference::new(model_id.clone(), "00".repeat(32))];
+
+    let mut storage = MockStorageBackend::new(eval);
+    storage.add_manifest(model);
+    storage.add_manifest(dataset);
+
+    let out_dir = dir.path().join("bundle");
+    let index = crate::manifest::export_bundle(&eval_id, &storage, &out_dir)?;
+
+    assert_eq!(index.root_id, eval_id);
+    assert_eq!(index.manifests.len(), 3);
+
+    // Every manifest in the index is written and decodes back
+    for (id, file_name) in &index.manifests {
+        let content = std::fs::read_to_string(out_dir.join(file_name))?;
+        let manifest: Manifest = serde_json::from_str(&content)?;
+        assert_eq!(&manifest.instance_id, id);
+    }
+
+    // The index file maps IDs to file names
+    let index_json = std::fs::read_to_string(out_dir.join(crate::manifest::BUNDLE_INDEX_FILE))?;
+    let written: crate::manifest::BundleIndex = serde_json::from_str(&index_json)?;
+    assert_eq!(written.manifests, index.manifests);
+
+    Ok(())
+}
+
+#[test]
+fn test_export_bundle_keeps_ids_that_sanitize_alike_apart() -> Result<()> {
+    use atlas_c2pa_lib::cross_reference::CrossReference;
+
+    let dir = tempdir()?;
+    // Both IDs sanitize to `urn_c2pa_a.json`, and `index` to the index file
+    let root_id = "urn:c2pa:a".to_string();
+    let other_id = "urn/c2pa/a".to_string();
+    let index_id = "index".to_string();
+
+    let mut root =
+        create_test_manifest_internal(root_id.clone(), vec![], "Root", AssetKind::Model)?;
+    root.cross_references = vec![
+        CrossReference::new(other_id.clone(), "00".repeat(32)),
+        CrossReference::new(index_id.clone(), "00".repeat(32)),
+    ];
+    let other = create_test_manifest_internal(other_id, vec![], "Other", AssetKind::Dataset)?;
+    let index_manifest =
+        create_test_manifest_internal(index_id, vec![], "Index", AssetKind::Dataset)?;
+    let mut storage = MockStorageBackend::new(root);
+    storage.add_manifest(other);
+    storage.add_manifest(index_manifest);
+
+    let out_dir = dir.path().join("bundle");
+    let index = crate::manifest::export_bundle(&root_id, &storage, &out_dir)?;
+
+    let file_names: std::collections::HashSet<&String> = index.manifests.values().collect();
+    assert_eq!(file_names.len(), 3);
+    assert!(!file_names.contains(&crate::manifest::BUNDLE_INDEX_FILE.to_string()));
+    for (id, file_name) in &index.manifests {
+        let content = std::fs::read_to_string(out_dir.join(file_name))?;
+        let manifest: Manifest = serde_json::from_str(&content)?;
+        assert_eq!(&manifest.instance_id, id);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_manifests() -> Result<()> {
+    use crate::manifest::diff::{diff_manifests, diff_manifests_with_options};
+    use atlas_c2pa_lib::cross_reference::CrossReference;
+
+    let dir = tempdir()?;
+    let weights_path = dir.path().join("weights.onnx");
+    let config_path = dir.path().join("config.json");
+    std::fs::write(&weights_path, b"weights v1")?;
+    std::fs::write(&config_path, b"{}")?;
+
+    let weights_v1 = create_test_ingredient_internal(
+        &weights_path,
+        "Weights",
+        AssetType::ModelOnnx,
+        "application/onnx",
+    )?;
+    let config = create_test_ingredient_internal(
+        &config_path,
+        "Config",
+        AssetType::Model,
+        "application/json",
+    )?;
+
+    let old = create_test_manifest_internal(
+        format!("urn:c2pa:{}", Uuid::new_v4()),
+        vec![weights_v1.clone(), config],
+        "Model",
+        AssetKind::Model,
+    )?;
+
+    // Regenerating identical content only changes volatile fields
+    let regenerated = create_test_manifest_internal(
+        format!("urn:c2pa:{}", Uuid::new_v4()),
+        old.ingredients.clone(),
+        "Model",
+        AssetKind::Model,
+    )?;
+    assert!(diff_manifests(&old, &regenerated).is_empty());
+    let volatile = diff_manifests_with_options(&old, &regenerated, true);
+    assert!(volatile.fields.iter().any(|c| c.field == "instance_id"));
+
+    // Retrained weights, dropped config, new tokenizer and a new link
+    std::fs::write(&weights_path, b"weights v2")?;
+    let tokenizer_path = dir.path().join("tokenizer.json");
+    std::fs::write(&tokenizer_path, b"tokens")?;
+    let weights_v2 = create_test_ingredient_internal(
+        &weights_path,
+        "Weights",
+        AssetType::ModelOnnx,
+        "application/onnx",
+    )?;
+    let tokenizer = create_test_ingredient_internal(
+        &tokenizer_path,
+        "Tokenizer",
+        AssetType::Model,
+        "application/json",
+    )?;
+    let mut new = create_test_manifest_internal(
+        format!("urn:c2pa:{}", Uuid::new_v4()),
+        vec![weights_v2.clone(), tokenizer],
+        "Model",
+        AssetKind::Model,
+    )?;
+    new.cross_references = vec![CrossReference::new(
+        "urn:c2pa:dataset".to_string(),
+        "00".repeat(32),
+    )];
+
+    let diff = diff_manifests(&old, &new);
+    assert_eq!(diff.ingredients_added.len(), 1);
+    assert_eq!(diff.ingredients_added[0].title, "Tokenizer");
+    assert_eq!(diff.ingredients_removed.len(), 1);
+    assert_eq!(diff.ingredients_removed[0].title, "Config");
+    assert_eq!(diff.ingredients_changed.len(), 1);
+    assert_eq!(diff.ingredients_changed[0].changes[0].field, "hash");
+    assert_eq!(
+        diff.ingredients_changed[0].changes[0].old,
+        weights_v1.data.hash
+    );
+    assert_eq!(
+        diff.ingredients_changed[0].changes[0].new,
+        weights_v2.data.hash
+    );
+    assert_eq!(diff.cross_references_added, vec!["urn:c2pa:dataset"]);
+    assert!(diff.assertions_added.is_empty());
+
+    // The diff serializes for scripting
+    let json = serde_json::to_value(&diff)?;
+    assert_eq!(json["ingredients_added"][0]["title"], "Tokenizer");
+
+    Ok(())
+}
+
+#[test]
+fn test_serialization_errors_name_the_manifest() -> Result<()> {
+    use crate::error::Error;
+    use crate::manifest::common::serialize_manifest;
+    use crate::storage::filesystem::FilesystemStorage;
+    use crate::storage::traits::StorageBackend;
+    use time::UtcOffset;
+
+    // RFC 3339 timestamps cannot represent offsets with a seconds component
+    let offset = UtcOffset::from_hms(1, 0, 30).unwrap();
+    let created_at = OffsetDateTimeWrapper(OffsetDateTime::now_utc().to_offset(offset));
+    let claim = ClaimV2 {
+        created_at: created_at.clone(),
+        ..super::common::create_default_claim()
+    };
+    let manifest = Manifest {
+        claim_generator: "test".to_string(),
+        title: "Unserializable Model".to_string(),
+        instance_id: "urn:c2pa:unserializable".to_string(),
+        ingredients: vec![],
+        claim: claim.clone(),
+        created_at,
+        cross_references: vec![],
+        claim_v2: Some(claim),
+        is_active: true,
+    };
+
+    for format in ["json", "cbor", "yaml"] {
+        match serialize_manifest(&manifest, format) {
+            Err(Error::Serialization(msg)) => {
+                assert!(msg.contains("Unserializable Model"), "{msg}");
+                assert!(msg.contains("urn:c2pa:unserializable"), "{msg}");
+                assert!(
+                    msg.contains(&format!("writing {format} output for manifest")),
+                    "{msg}"
+                );
+            }
+            other => panic!("expected a serialization error, got {other:?}"),
+        }
+    }
+
+    let dir = tempdir()?;
+    let storage = FilesystemStorage::new(dir.path())?;
+    match storage.store_manifest(&manifest) {
+        Err(Error::Serialization(msg)) => {
+            assert!(
+                msg.starts_with("storing manifest 'Unserializable Model'"),
+                "{msg}"
+            );
+        }
+        other => panic!("expected a serialization error, got {other:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_storage_records_evaluation_manifest_type() -> Result<()> {
+    use crate::storage::filesystem::FilesystemStorage;
+    use crate::storage::traits::{ManifestType, StorageBackend};
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"weights")?;
+    let ingredient = create_test_ingredient_internal(
+        &model_path,
+        "Model",
+        AssetType::ModelOnnx,
+        "application/onnx",
+    )?;
+
+    // The evaluation carries a model ingredient and no "Evaluation" in its title
+    let model = create_test_manifest_internal(
+        format!("urn:c2pa:{}", Uuid::new_v4()),
+        vec![ingredient.clone()],
+        "Classifier",
+        AssetKind::Model,
+    )?;
+    let evaluation = create_test_manifest_internal(
+        format!("urn:c2pa:{}", Uuid::new_v4()),
+        vec![ingredient],
+        "Classifier accuracy",
+        AssetKind::Evaluation,
+    )?;
+
+    let storage = FilesystemStorage::new(dir.path().join("storage"))?;
+    storage.store_manifest(&model)?;
+    storage.store_manifest(&evaluation)?;
+
+    let mut types: Vec<_> = storage
+        .list_manifests()?
+        .into_iter()
+        .map(|m| (m.name, m.manifest_type))
+        .collect();
+    types.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        types,
+        vec![
+            ("Classifier".to_string(), ManifestType::Model),
+            ("Classifier accuracy".to_string(), ManifestType::Evaluation),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_build_manifest_returns_manifest_without_storing() -> Result<()> {
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::build_manifest;
+    use crate::manifest::config::ManifestCreationConfig;
+    use crate::storage::traits::StorageBackend;
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("built_model.onnx");
+    std::fs::write(&model_path, b"built model")?;
+
+    let storage: &'static MockStorageBackend = Box::leak(Box::new(MockStorageBackend::new_empty()));
+    let config = ManifestCreationConfig {
+        paths: vec![model_path],
+        ingredient_names: vec!["Built Model".to_string()],
+        name: "Built Manifest".to_string(),
+        author_org: None,
+        author_name: None,
+        description: None,
+        linked_manifests: Some(vec!["urn:c2pa:missing".to_string()]),
+        storage: Some(storage),
+        print: false,
+        output_format: "json".to_string(),
+        key_path: None,
+        hash_alg: DigestAlgorithm::Sha384,
+        with_cc: false,
+        software_type: None,
+        version: None,
+        custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
+    };
+
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert_eq!(manifest.title, "Built Manifest");
+    assert_eq!(manifest.ingredients.len(), 1);
+    // The unresolved link is left out rather than failing the build
+    assert!(manifest.cross_references.is_empty());
+    // Nothing is stored
+    assert!(storage.list_manifests()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_build_manifest_with_ingredient_relationships() -> Result<()> {
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::build_manifest;
+    use crate::manifest::config::ManifestCreationConfig;
+
+    let dir = tempdir()?;
+    let base_path = dir.path().join("base.onnx");
+    let tuned_path = dir.path().join("tuned.onnx");
+    std::fs::write(&base_path, b"base model")?;
+    std::fs::write(&tuned_path, b"fine-tuned model")?;
+
+    let mut config = ManifestCreationConfig {
+        paths: vec![base_path, tuned_path],
+        ingredient_names: vec!["Base".to_string(), "Tuned".to_string()],
+        name: "Fine-tuned Model".to_string(),
+        author_org: None,
+        author_name: None,
+        description: None,
+        linked_manifests: None,
+        storage: None,
+        print: false,
+        output_format: "json".to_string(),
+        key_path: None,
+        hash_alg: DigestAlgorithm::Sha384,
+        with_cc: false,
+        software_type: None,
+        version: None,
+        custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
+    };
+
+    // Ingredients default to componentOf
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert!(
+        manifest
+            .ingredients
+            .iter()
+            .all(|ingredient| ingredient.relationship == "componentOf")
+    );
+
+    config.ingredient_relationships = vec!["parentOf".to_string(), "componentOf".to_string()];
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert_eq!(manifest.ingredients[0].relationship, "parentOf");
+    assert_eq!(manifest.ingredients[1].relationship, "componentOf");
+
+    // Unknown relationships and a count that does not match are rejected
+    config.ingredient_relationships = vec!["derivedFrom".to_string(), "componentOf".to_string()];
+    assert!(build_manifest(&config, AssetKind::Model).is_err());
+    config.ingredient_relationships = vec!["inputTo".to_string()];
+    assert!(build_manifest(&config, AssetKind::Model).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_derived_model_lineage() -> Result<()> {
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::config::ManifestCreationConfig;
+    use crate::manifest::lineage::{self, LineageStatus};
+    use crate::manifest::{build_manifest, verify_manifest};
+    use crate::storage::traits::StorageBackend;
+
+    let dir = tempdir()?;
+    let storage: &'static MockStorageBackend = Box::leak(Box::new(MockStorageBackend::new_empty()));
+    let create = |name: &str, bases: Vec<String>| -> Result<String> {
+        let path = dir.path().join(format!("{name}.onnx"));
+        std::fs::write(&path, name.as_bytes())?;
+        let config = ManifestCreationConfig {
+            paths: vec![path],
+            ingredient_names: vec![name.to_string()],
+            name: name.to_string(),
+            author_org: None,
+            author_name: None,
+            description: None,
+            linked_manifests: None,
+            storage: Some(storage),
+            print: false,
+            output_format: "json".to_string(),
+            key_path: None,
+            hash_alg: DigestAlgorithm::Sha384,
+            with_cc: false,
+            software_type: None,
+            version: None,
+            custom_fields: None,
+            asset_type: None,
+            recursive: false,
+            ignore_patterns: vec![],
+            show_progress: false,
+            tsa_url: None,
+            dry_run: false,
+            tags: vec![],
+            min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+            creative_type_override: None,
+            digital_source_type_override: None,
+            skip_schema_check: false,
+            no_sniff: false,
+            output_path: None,
+            hex_output: false,
+            deterministic: false,
+            created_at: None,
+            hash_decompressed: false,
+            ingredient_relationships: Vec::new(),
+            base_manifests: bases,
+            claim_generator: None,
+            valid_from: None,
+            valid_until: None,
+            quick_hash: false,
+            follow_symlinks: false,
+            trained_on: Vec::new(),
+            ingredient_urls: Vec::new(),
+            record_environment: false,
+            redact_hostname: false,
+            signer: None,
+            model_card: None,
+            model_card_hash: None,
+            symlink_roots: Vec::new(),
+            hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+            audit_log: None,
+        };
+        storage.store_manifest(&build_manifest(&config, AssetKind::Model)?)
+    };
+
+    let base_id = create("Base", vec![])?;
+    let tuned_id = create("Tuned", vec![base_id.clone()])?;
+    let distilled_id = create("Distilled", vec![tuned_id.clone()])?;
+
+    // The base is cross-referenced and checked during verification
+    let report = verify_manifest(&tuned_id, storage)?;
+    assert_eq!(report.cross_references.len(), 1);
+    assert_eq!(report.cross_references[0].subject, base_id);
+    assert!(report.cross_references[0].message.contains("base manifest"));
+
+    let tree = lineage::build_lineage(&distilled_id, storage)?;
+    assert_eq!(tree.title, "Distilled");
+    assert_eq!(tree.parents[0].title, "Tuned");
+    assert_eq!(tree.parents[0].parents[0].title, "Base");
+    assert!(tree.parents[0].parents[0].parents.is_empty());
+    assert_eq!(
+        lineage::format_lineage(&tree),
+        format!("{distilled_id} (Distilled)\n└── {tuned_id} (Tuned)\n    └── {base_id} (Base)\n")
+    );
+
+    // A base that lists one of its descendants is reported as a cycle
+    let mut base = storage.retrieve_manifest(&base_id)?;
+    if let Some(claim) = base.claim_v2.as_mut() {
+        claim
+            .created_assertions
+            .push(lineage::lineage_assertion(std::slice::from_ref(
+                &distilled_id,
+            )));
+    }
+    storage.store_manifest(&base)?;
+    let tree = lineage::build_lineage(&distilled_id, storage)?;
+    let cycle = &tree.parents[0].parents[0].parents[0];
+    assert_eq!(cycle.id, distilled_id);
+    assert_eq!(cycle.status, LineageStatus::Cycle);
+
+    // Deriving from a manifest that does not exist fails
+    assert!(create("Orphan", vec!["urn:c2pa:missing".to_string()]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_model_training_provenance() -> Result<()> {
+    use crate::error::Error;
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::config::{ManifestCreationConfig, VerificationConfig};
+    use crate::manifest::training;
+    use crate::manifest::{build_manifest, verify_manifest, verify_manifest_with_config};
+    use crate::storage::traits::StorageBackend;
+
+    let dir = tempdir()?;
+    let storage: &'static MockStorageBackend = Box::leak(Box::new(MockStorageBackend::new_empty()));
+    let create = |name: &str, asset_kind: AssetKind, trained_on: Vec<String>| -> Result<String> {
+        let path = dir.path().join(format!("{name}.csv"));
+        std::fs::write(&path, name.as_bytes())?;
+        let config = ManifestCreationConfig {
+            paths: vec![path],
+            ingredient_names: vec![name.to_string()],
+            name: name.to_string(),
+            author_org: None,
+            author_name: None,
+            description: None,
+            linked_manifests: None,
+            storage: Some(storage),
+            print: false,
+            output_format: "json".to_string(),
+            key_path: None,
+            hash_alg: DigestAlgorithm::Sha384,
+            with_cc: false,
+            software_type: None,
+            version: None,
+            custom_fields: None,
+            asset_type: None,
+            recursive: false,
+            ignore_patterns: vec![],
+            show_progress: false,
+            tsa_url: None,
+            dry_run: false,
+            tags: vec![],
+            min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+            creative_type_override: None,
+            digital_source_type_override: None,
+            skip_schema_check: false,
+            no_sniff: false,
+            output_path: None,
+            hex_output: false,
+            deterministic: false,
+            created_at: None,
+            hash_decompressed: false,
+            ingredient_relationships: Vec::new(),
+            base_manifests: Vec::new(),
+            claim_generator: None,
+            valid_from: None,
+            valid_until: None,
+            quick_hash: false,
+            follow_symlinks: false,
+            trained_on,
+            ingredient_urls: Vec::new(),
+            record_environment: false,
+            redact_hostname: false,
+            signer: None,
+            model_card: None,
+            model_card_hash: None,
+            symlink_roots: Vec::new(),
+            hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+            audit_log: None,
+        };
+        storage.store_manifest(&build_manifest(&config, asset_kind)?)
+    };
+
+    let train_id = create("Train", AssetKind::Dataset, vec![])?;
+    let eval_id = create("Eval", AssetKind::Dataset, vec![])?;
+    let model_id = create(
+        "Model",
+        AssetKind::Model,
+        vec![train_id.clone(), eval_id.clone()],
+    )?;
+
+    let model = storage.retrieve_manifest(&model_id)?;
+    let datasets = training::training_datasets(&model)?;
+    assert_eq!(datasets.len(), 2);
+    assert_eq!(datasets[0].manifest_id, train_id);
+
+    let report = verify_manifest(&model_id, storage)?;
+    assert_eq!(report.training_data.len(), 2);
+
+    // Only dataset manifests can be training data
+    assert!(matches!(
+        create("Stacked", AssetKind::Model, vec![model_id.clone()]),
+        Err(Error::Validation(msg)) if msg.contains("not a dataset manifest")
+    ));
+
+    // A changed training dataset fails verification
+    let mut train = storage.retrieve_manifest(&train_id)?;
+    train.title = "Relabelled".to_string();
+    storage.store_manifest(&train)?;
+    assert!(matches!(
+        verify_manifest(&model_id, storage),
+        Err(Error::Validation(msg)) if msg.contains("has changed")
+    ));
+
+    // A missing one fails too, unless dangling references are allowed
+    storage.delete_manifest(&train_id)?;
+    assert!(verify_manifest(&model_id, storage).is_err());
+    let config = VerificationConfig {
+        allow_dangling: true,
+        ..Default::default()
+    };
+    let report = verify_manifest_with_config(&model_id, storage, &config)?;
+    assert_eq!(report.warnings().count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_model_ingredient_urls() -> Result<()> {
+    use crate::error::Error;
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::build_manifest;
+    use crate::manifest::config::ManifestCreationConfig;
+    use crate::manifest::report::CheckStatus;
+    use crate::manifest::verify_manifest;
+    use crate::storage::traits::StorageBackend;
+
+    let dir = tempdir()?;
+    let weights = dir.path().join("weights");
+    std::fs::create_dir_all(weights.join("layers"))?;
+    std::fs::write(weights.join("layers/0.bin"), b"layer")?;
+    let config_path = dir.path().join("config.json");
+    std::fs::write(&config_path, b"{}")?;
+
+    let storage: &'static MockStorageBackend = Box::leak(Box::new(MockStorageBackend::new_empty()));
+    let mut config = ManifestCreationConfig {
+        paths: vec![config_path, weights],
+        ingredient_names: vec!["Config".to_string(), "Weights".to_string()],
+        name: "Published Model".to_string(),
+        author_org: None,
+        author_name: None,
+        description: None,
+        linked_manifests: None,
+        storage: Some(storage),
+        print: false,
+        output_format: "json".to_string(),
+        key_path: None,
+        hash_alg: DigestAlgorithm::Sha384,
+        with_cc: false,
+        software_type: None,
+        version: None,
+        custom_fields: None,
+        asset_type: None,
+        recursive: true,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
+    };
+
+    // Local inputs get file:// URLs and verify by re-hashing the files
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert!(
+        manifest
+            .ingredients
+            .iter()
+            .all(|ingredient| ingredient.data.url.starts_with("file://"))
+    );
+    let id = storage.store_manifest(&manifest)?;
+    let report = verify_manifest(&id, storage)?;
+    assert_eq!(report.ingredients.len(), 2);
+    assert!(
+        report
+            .ingredients
+            .iter()
+            .all(|check| check.status == CheckStatus::Verified)
+    );
+
+    // Overrides are recorded instead; a directory's URL is its files' base
+    config.ingredient_urls = vec![
+        "https://example.com/model/config.json".to_string(),
+        "s3://models/weights/".to_string(),
+    ];
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    let urls: Vec<&str> = manifest
+        .ingredients
+        .iter()
+        .map(|ingredient| ingredient.data.url.as_str())
+        .collect();
+    assert_eq!(
+        urls,
+        [
+            "https://example.com/model/config.json",
+            "s3://models/weights/layers/0.bin"
+        ]
+    );
+
+    // Only URLs that verification can fetch are accepted, one per ingredient
+    for urls in [
+        vec!["ftp://example.com/config.json", "s3://models/weights"],
+        vec!["https://example.com/config.json"],
+    ] {
+        config.ingredient_urls = urls.into_iter().map(str::to_string).collect();
+        assert!(matches!(
+            build_manifest(&config, AssetKind::Model),
+            Err(Error::Validation(_))
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_manifest_value_without_storage() -> Result<()> {
+    use crate::error::Error;
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::config::ManifestCreationConfig;
+    use crate::manifest::report::CheckStatus;
+    use crate::manifest::{build_manifest, verify_manifest_value};
+    use crate::storage::traits::StorageBackend;
+
+    let dir = tempdir()?;
+    let storage: &'static MockStorageBackend = Box::leak(Box::new(MockStorageBackend::new_empty()));
+    let build = |name: &str, asset_kind: AssetKind, linked: Option<Vec<String>>| {
+        let path = dir.path().join(format!("{name}.csv"));
+        std::fs::write(&path, name.as_bytes())?;
+        let config = ManifestCreationConfig {
+            paths: vec![path],
+            ingredient_names: vec![name.to_string()],
+            name: name.to_string(),
+            author_org: None,
+            author_name: None,
+            description: None,
+            linked_manifests: linked,
+            storage: Some(storage),
+            print: false,
+            output_format: "json".to_string(),
+            key_path: None,
+            hash_alg: DigestAlgorithm::Sha384,
+            with_cc: false,
+            software_type: None,
+            version: None,
+            custom_fields: None,
+            asset_type: None,
+            recursive: false,
+            ignore_patterns: vec![],
+            show_progress: false,
+            tsa_url: None,
+            dry_run: false,
+            tags: vec![],
+            min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+            creative_type_override: None,
+            digital_source_type_override: None,
+            skip_schema_check: false,
+            no_sniff: false,
+            output_path: None,
+            hex_output: false,
+            deterministic: false,
+            created_at: None,
+            hash_decompressed: false,
+            ingredient_relationships: Vec::new(),
+            ingredient_urls: Vec::new(),
+            base_manifests: Vec::new(),
+            claim_generator: None,
+            valid_from: None,
+            valid_until: None,
+            quick_hash: false,
+            follow_symlinks: false,
+            trained_on: Vec::new(),
+            record_environment: false,
+            redact_hostname: false,
+            signer: None,
+            model_card: None,
+            model_card_hash: None,
+            symlink_roots: Vec::new(),
+            hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+            audit_log: None,
+        };
+        build_manifest(&config, asset_kind)
+    };
+
+    let dataset_id = storage.store_manifest(&build("Data", AssetKind::Dataset, None)?)?;
+    let model = build("Weights", AssetKind::Model, Some(vec![dataset_id.clone()]))?;
+
+    // Without storage, ingredients are still verified and the link is only
+    // reported as unchecked
+    let report = verify_manifest_value(&model, None)?;
+    assert_eq!(report.manifest_id, model.instance_id);
+    assert_eq!(report.ingredients[0].status, CheckStatus::Verified);
+    assert_eq!(report.cross_references.len(), 1);
+    assert_eq!(report.cross_references[0].status, CheckStatus::Warning);
+
+    // With storage, the link is resolved
+    let report = verify_manifest_value(&model, Some(storage))?;
+    assert_eq!(report.cross_references[0].status, CheckStatus::Verified);
+
+    // A modified ingredient fails either way
+    std::fs::write(dir.path().join("Weights.csv"), b"changed")?;
+    assert!(matches!(
+        verify_manifest_value(&model, None),
+        Err(Error::Validation(msg)) if msg.contains("Hash verification failed")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_build_manifest_claim_generator() -> Result<()> {
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::build_manifest;
+    use crate::manifest::common::DEFAULT_CLAIM_GENERATOR;
+    use crate::manifest::config::ManifestCreationConfig;
+
+    // The default generator must follow the version in Cargo.toml
+    let cargo_version = include_str!("../../Cargo.toml")
+        .lines()
+        .find_map(|line| line.strip_prefix("version = "))
+        .map(|version| version.trim_matches('"'))
+        .unwrap();
+    assert_eq!(
+        DEFAULT_CLAIM_GENERATOR,
+        format!("atlas-cli/{cargo_version}")
+    );
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"model")?;
+    let mut config = ManifestCreationConfig {
+        paths: vec![model_path],
+        ingredient_names: vec!["Model".to_string()],
+        name: "Generated Model".to_string(),
+        author_org: None,
+        author_name: None,
+        description: None,
+        linked_manifests: None,
+        storage: None,
+        print: false,
+        output_format: "json".to_string(),
+        key_path: None,
+        hash_alg: DigestAlgorithm::Sha384,
+        with_cc: false,
+        software_type: None,
+        version: None,
+        custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
+    };
+
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert_eq!(manifest.claim_generator, DEFAULT_CLAIM_GENERATOR);
+    assert_eq!(manifest.claim_v2.unwrap().claim_generator_info, "atlas-cli");
+
+    // A custom generator is recorded in both the manifest and the claim
+    config.claim_generator = Some("registry-sync/2.3.1".to_string());
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert_eq!(manifest.claim_generator, "registry-sync/2.3.1");
+    assert_eq!(manifest.claim.claim_generator_info, "registry-sync");
+    assert_eq!(
+        manifest.claim_v2.unwrap().claim_generator_info,
+        "registry-sync"
+    );
+
+    config.claim_generator = Some(" ".to_string());
+    assert!(build_manifest(&config, AssetKind::Model).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_build_manifest_records_environment() -> Result<()> {
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::build_manifest;
+    use crate::manifest::config::ManifestCreationConfig;
+    use crate::manifest::environment::Environment;
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"model")?;
+    let mut config = ManifestCreationConfig {
+        paths: vec![model_path],
+        ingredient_names: vec!["Model".to_string()],
+        name: "Reproducible Model".to_string(),
+        author_org: None,
+        author_name: None,
+        description: None,
+        linked_manifests: None,
+        storage: None,
+        print: false,
+        output_format: "json".to_string(),
+        key_path: None,
+        hash_alg: DigestAlgorithm::Sha384,
+        with_cc: false,
+        software_type: None,
+        version: None,
+        custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
+    };
+
+    // Nothing is recorded unless requested
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert!(Environment::from_manifest(&manifest)?.is_none());
+
+    config.record_environment = true;
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    let environment = Environment::from_manifest(&manifest)?.unwrap();
+    assert_eq!(environment.os, std::env::consts::OS);
+    assert_eq!(environment.arch, std::env::consts::ARCH);
+    assert_eq!(environment.cli_version, env!("CARGO_PKG_VERSION"));
+    assert!(environment.hostname.is_some());
+
+    config.redact_hostname = true;
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert!(
+        Environment::from_manifest(&manifest)?
+            .unwrap()
+            .hostname
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_build_manifest_with_custom_signer() -> Result<()> {
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::build_manifest;
+    use crate::manifest::config::ManifestCreationConfig;
+    use crate::manifest::signatures::signed_claim_bytes;
+    use crate::signing::keygen::{KeyType, generate_private_key, public_key};
+    use crate::signing::signer::{LocalSigner, Signer};
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Stands in for a remote signer such as a KMS key
+    struct CountingSigner {
+        inner: LocalSigner,
+        calls: AtomicUsize,
+    }
+
+    impl Signer for CountingSigner {
+        fn sign(&self, data: &[u8], alg: DigestAlgorithm) -> Result<Vec<u8>> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.inner.sign(data, alg)
+        }
+
+        fn description(&self) -> String {
+            "counting signer".to_string()
+        }
+    }
+
+    let key = generate_private_key(KeyType::EcP256)?;
+    let public = public_key(&key)?;
+    let signer = Arc::new(CountingSigner {
+        inner: LocalSigner::new(key, "test key"),
+        calls: AtomicUsize::new(0),
+    });
+
+    let dir = tempdir()?;
+    let model_path = dir.path().join("model.onnx");
+    std::fs::write(&model_path, b"model")?;
+    let mut config = ManifestCreationConfig {
+        paths: vec![model_path],
+        ingredient_names: vec!["Model".to_string()],
+        name: "KMS Signed Model".to_string(),
+        author_org: None,
+        author_name: None,
+        description: None,
+        linked_manifests: None,
+        storage: None,
+        print: false,
+        output_format: "json".to_string(),
+        key_path: None,
+        hash_alg: DigestAlgorithm::Sha256,
+        with_cc: false,
+        software_type: None,
+        version: None,
+        custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: Some(signer.clone()),
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
+    };
+
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert_eq!(signer.calls.load(Ordering::Relaxed), 1);
+
+    let claim = manifest.claim_v2.as_ref().unwrap();
+    let signature = STANDARD.decode(claim.signature.as_ref().unwrap()).unwrap();
+    assert!(crate::signing::verify_signature_with_algorithm(
+        &signed_claim_bytes(claim)?,
+        &signature,
+        &public,
+        DigestAlgorithm::Sha256
+    )?);
+
+    // A key file and a signer cannot both sign
+    config.key_path = Some(dir.path().join("key.pem"));
+    assert!(build_manifest(&config, AssetKind::Model).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_continue_on_error_reports_every_failure() -> Result<()> {
+    use crate::error::Error;
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::config::{ManifestCreationConfig, VerificationConfig};
+    use crate::manifest::{build_manifest, verify_manifest_value_with_config};
+
+    let dir = tempdir()?;
+    let names = ["shard-0", "shard-1", "shard-2", "shard-3"];
+    let paths: Vec<PathBuf> = names
+        .iter()
+        .map(|name| dir.path().join(format!("{name}.csv")))
+        .collect();
+    for (path, name) in paths.iter().zip(names) {
+        std::fs::write(path, name)?;
     }
+    let config = ManifestCreationConfig {
+        paths: paths.clone(),
+        ingredient_names: names.iter().map(|name| name.to_string()).collect(),
+        name: "Sharded Dataset".to_string(),
+        author_org: None,
+        author_name: None,
+        description: None,
+        linked_manifests: None,
+        storage: None,
+        print: false,
+        output_format: "json".to_string(),
+        key_path: None,
+        hash_alg: DigestAlgorithm::Sha384,
+        with_cc: false,
+        software_type: None,
+        version: None,
+        custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        ingredient_urls: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
+    };
+    let manifest = build_manifest(&config, AssetKind::Dataset)?;
+
+    // One shard is corrupt and another is gone
+    std::fs::write(&paths[1], b"corrupt")?;
+    std::fs::remove_file(&paths[3])?;
+
+    // By default verification stops at the first failure
+    let Err(Error::Validation(first)) =
+        verify_manifest_value_with_config(&manifest, None, &VerificationConfig::default())
+    else {
+        panic!("expected a validation error");
+    };
+    assert!(first.contains("shard-1") && !first.contains("shard-3"));
+
+    let config = VerificationConfig {
+        continue_on_error: true,
+        ..Default::default()
+    };
+    let Err(Error::Validation(all)) = verify_manifest_value_with_config(&manifest, None, &config)
+    else {
+        panic!("expected a validation error");
+    };
+    assert!(
+        all.starts_with("2 of 4 ingredients failed verification"),
+        "{all}"
+    );
+    assert!(all.contains("shard-1") && all.contains("shard-3"));
+    assert!(!all.contains("shard-0") && !all.contains("shard-2"));
+
+    // With every ingredient intact the mode changes nothing
+    std::fs::write(&paths[1], "shard-1")?;
+    std::fs::write(&paths[3], "shard-3")?;
+    assert_eq!(
+        verify_manifest_value_with_config(&manifest, None, &config)?
+            .ingredients
+            .len(),
+        4
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_export_checksums_lists_matching_ingredients() -> Result<()> {
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::checksums::export_checksums;
+
+    let dir = tempdir()?;
+    let weights = dir.path().join("weights.bin");
+    let config = dir.path().join("config.json");
+    std::fs::write(&weights, b"weights")?;
+    std::fs::write(&config, b"{}")?;
+
+    let mut sha512_ingredient =
+        create_test_ingredient_internal(&config, "Config", AssetType::ModelOpenVino, "json")?;
+    sha512_ingredient.data.alg = "sha512".to_string();
+    sha512_ingredient.data.hash = hex::encode(openssl::sha::sha512(b"{}"));
+    let mut remote_ingredient =
+        create_test_ingredient_internal(&weights, "Mirror", AssetType::ModelOpenVino, "bin")?;
+    remote_ingredient.data.url = "https://example.com/weights.bin".to_string();
+    let mut sha256_ingredient =
+        create_test_ingredient_internal(&weights, "Weights", AssetType::ModelOpenVino, "bin")?;
+    sha256_ingredient.data.hash = hex::encode(openssl::sha::sha256(b"weights"));
+    let ingredients = vec![
+        sha256_ingredient.clone(),
+        sha512_ingredient,
+        remote_ingredient,
+    ];
+    let manifest = create_test_manifest_internal(
+        "urn:c2pa:checksums".to_string(),
+        ingredients,
+        "Checksummed Model",
+        AssetKind::Model,
+    )?;
+
+    let checksums = export_checksums(&manifest, DigestAlgorithm::Sha256);
+    assert_eq!(checksums.listed, 1);
+    assert_eq!(
+        checksums.content,
+        format!("{}  {}\n", sha256_ingredient.data.hash, weights.display())
+    );
+    let skipped: Vec<&str> = checksums
+        .skipped
+        .iter()
+        .map(|(title, _)| title.as_str())
+        .collect();
+    assert_eq!(skipped, ["Config", "Mirror"]);
+    assert!(checksums.skipped[0].1.contains("sha512"));
+
+    // The SHA-512 ingredient gets its own file
+    let checksums = export_checksums(&manifest, DigestAlgorithm::Sha512);
+    assert_eq!(checksums.listed, 1);
+    assert!(
+        checksums
+            .content
+            .ends_with(&format!("  {}\n", config.display()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_model_card_changes_are_warnings_unless_strict() -> Result<()> {
+    use crate::error::Error;
+    use crate::hash::DigestAlgorithm;
+    use crate::manifest::config::{ManifestCreationConfig, VerificationConfig};
+    use crate::manifest::model_card::{MODEL_CARD_RELATIONSHIP, ModelCardLink};
+    use crate::manifest::report::CheckStatus;
+    use crate::manifest::{build_manifest, verify_manifest_value_with_config};
+
+    let dir = tempdir()?;
+    let model = dir.path().join("model.onnx");
+    let card = dir.path().join("MODEL_CARD.md");
+    std::fs::write(&model, b"weights")?;
+    std::fs::write(&card, b"# Model card")?;
+    let mut config = ManifestCreationConfig {
+        paths: vec![model],
+        ingredient_names: vec!["Model".to_string()],
+        name: "Documented Model".to_string(),
+        author_org: None,
+        author_name: None,
+        description: None,
+        linked_manifests: None,
+        storage: None,
+        print: false,
+        output_format: "json".to_string(),
+        key_path: None,
+        hash_alg: DigestAlgorithm::Sha384,
+        with_cc: false,
+        software_type: None,
+        version: None,
+        custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        ingredient_urls: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: Some(card.to_string_lossy().into_owned()),
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
+    };
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert_eq!(manifest.ingredients.len(), 2);
+    assert_eq!(
+        manifest.ingredients[1].relationship,
+        MODEL_CARD_RELATIONSHIP
+    );
+    let report =
+        verify_manifest_value_with_config(&manifest, None, &VerificationConfig::default())?;
+    assert!(report.warnings().next().is_none());
+
+    // An edited model card is a warning, or an error with --strict
+    std::fs::write(&card, b"# Model card, revised")?;
+    let report =
+        verify_manifest_value_with_config(&manifest, None, &VerificationConfig::default())?;
+    assert_eq!(report.ingredients[1].status, CheckStatus::Warning);
+    let strict = VerificationConfig {
+        strict: true,
+        ..Default::default()
+    };
+    assert!(matches!(
+        verify_manifest_value_with_config(&manifest, None, &strict),
+        Err(Error::Validation(_))
+    ));
+
+    // A URL is linked instead, and a hash is only accepted for URLs
+    config.model_card_hash = Some("ab".repeat(32));
+    assert!(build_manifest(&config, AssetKind::Model).is_err());
+    config.model_card = Some("https://example.com/model-card.md".to_string());
+    let manifest = build_manifest(&config, AssetKind::Model)?;
+    assert_eq!(manifest.ingredients.len(), 1);
+    let link = ModelCardLink::from_manifest(&manifest)?.unwrap();
+    assert_eq!(link.url, "https://example.com/model-card.md");
+    assert_eq!(link.alg.as_deref(), Some("sha256"));
+
+    // Offline, the linked content is not fetched
+    let offline = VerificationConfig {
+        offline: true,
+        strict: true,
+        ..Default::default()
+    };
+    let report = verify_manifest_value_with_config(&manifest, None, &offline)?;
+    assert!(
+        report
+            .asset_checks
+            .iter()
+            .any(|check| check.subject == "Model card" && check.status == CheckStatus::Warning)
+    );
 
     Ok(())
 }
@@ -1,10 +1,10 @@
 use crate::error::Result;
+use crate::hash::DigestAlgorithm;
 use crate::manifest::common::{AssetKind, create_manifest};
 use crate::manifest::config::ManifestCreationConfig;
 use crate::storage::traits::StorageBackend;
 use crate::tests::common::MockStorageBackend;
 use crate::utils::safe_create_file;
-use atlas_c2pa_lib::cose::HashAlgorithm;
 use std::io::Write;
 use tempfile::tempdir;
 
@@ -43,11 +43,45 @@ fn test_manifest_with_cc_attestation() -> Result<()> {
         print: true,
         output_format: "json".to_string(),
         key_path: None,
-        hash_alg: HashAlgorithm::Sha384,
+        hash_alg: DigestAlgorithm::Sha384,
         with_cc,
         software_type: None,
         version: None,
         custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
     };
 
     // Create the manifest with CC attestation enabled
@@ -101,11 +135,45 @@ fn test_manifest_without_cc_attestation() -> Result<()> {
         print: true,
         output_format: "json".to_string(),
         key_path: None,
-        hash_alg: HashAlgorithm::Sha384,
+        hash_alg: DigestAlgorithm::Sha384,
         with_cc,
         software_type: None,
         version: None,
         custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
     };
 
     // Create the manifest without CC attestation
@@ -163,11 +231,45 @@ fn test_compare_manifests_with_and_without_attestation() -> Result<()> {
         print: false,
         output_format: "json".to_string(),
         key_path: None,
-        hash_alg: HashAlgorithm::Sha384,
+        hash_alg: DigestAlgorithm::Sha384,
         with_cc: true,
         software_type: None,
         version: None,
         custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
     };
     create_manifest(config_with_cc, AssetKind::Model)?;
 
@@ -184,11 +286,45 @@ fn test_compare_manifests_with_and_without_attestation() -> Result<()> {
         print: false,
         output_format: "json".to_string(),
         key_path: None,
-        hash_alg: HashAlgorithm::Sha384,
+        hash_alg: DigestAlgorithm::Sha384,
         with_cc: false,
         software_type: None,
         version: None,
         custom_fields: None,
+        asset_type: None,
+        recursive: false,
+        ignore_patterns: vec![],
+        show_progress: false,
+        tsa_url: None,
+        dry_run: false,
+        tags: vec![],
+        min_key_bits: crate::signing::DEFAULT_MIN_RSA_KEY_BITS,
+        creative_type_override: None,
+        digital_source_type_override: None,
+        skip_schema_check: false,
+        no_sniff: false,
+        output_path: None,
+        hex_output: false,
+        deterministic: false,
+        created_at: None,
+        hash_decompressed: false,
+        ingredient_relationships: Vec::new(),
+        base_manifests: Vec::new(),
+        claim_generator: None,
+        valid_from: None,
+        valid_until: None,
+        quick_hash: false,
+        follow_symlinks: false,
+        trained_on: Vec::new(),
+        ingredient_urls: Vec::new(),
+        record_environment: false,
+        redact_hostname: false,
+        signer: None,
+        model_card: None,
+        model_card_hash: None,
+        symlink_roots: Vec::new(),
+        hash_chunk_size: crate::hash::DEFAULT_CHUNK_SIZE,
+        audit_log: None,
     };
     create_manifest(config_without_cc, AssetKind::Model)?;
 
@@ -1,4 +1,5 @@
 mod cc_attestation;
+mod cli;
 mod common;
 mod manifest;
 mod manifest_attestation;
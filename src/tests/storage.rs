@@ -1,7 +1,8 @@
 use super::common::{MockStorageBackend, create_default_claim};
+use crate::Config;
 use crate::cli::commands::DatasetCommands;
 use crate::cli::handlers::handle_dataset_command;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::storage::filesystem::FilesystemStorage;
 use crate::storage::traits::ArtifactLocation;
 use crate::storage::traits::StorageBackend;
@@ -128,6 +129,225 @@ fn test_filesystem_storage() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_filesystem_storage_refuses_overwrite() -> Result<()> {
+    let dir = tempdir()?;
+    let fs_storage = FilesystemStorage::new(dir.path())?;
+
+    let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+    let mut manifest = Manifest {
+        claim_generator: "test".to_string(),
+        title: "Original".to_string(),
+        instance_id: manifest_id.clone(),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: None,
+        is_active: true,
+    };
+    fs_storage.store_manifest(&manifest)?;
+
+    // Retrying the same store succeeds without changing anything
+    assert_eq!(fs_storage.store_manifest(&manifest)?, manifest_id);
+
+    // A different manifest under the same ID is rejected
+    manifest.title = "Replacement".to_string();
+    assert!(matches!(
+        fs_storage.store_manifest(&manifest),
+        Err(Error::Storage(msg)) if msg.contains("already exists")
+    ));
+    assert_eq!(
+        fs_storage.retrieve_manifest(&manifest_id)?.title,
+        "Original"
+    );
+
+    // Updates replace the stored manifest
+    fs_storage.update_manifest(&manifest)?;
+    assert_eq!(
+        fs_storage.retrieve_manifest(&manifest_id)?.title,
+        "Replacement"
+    );
+    assert_eq!(fs_storage.list_manifests()?.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_storage_stats() -> Result<()> {
+    use crate::storage::memory::MemoryStorage;
+    use crate::storage::stats::collect_stats;
+    use crate::storage::traits::ManifestType;
+    use time::Duration;
+    use time::format_description::well_known::Rfc3339;
+
+    let dir = tempdir()?;
+    let fs_storage = FilesystemStorage::new(dir.path())?;
+    let memory_storage = MemoryStorage::new();
+
+    let oldest = OffsetDateTime::parse("2024-01-01T00:00:00Z", &Rfc3339).unwrap();
+    for (index, days) in [60, 0, 30].into_iter().enumerate() {
+        let manifest = Manifest {
+            claim_generator: "test".to_string(),
+            title: format!("Manifest {index}"),
+            instance_id: format!("test_manifest_{}", Uuid::new_v4()),
+            ingredients: Vec::new(),
+            claim: create_default_claim(),
+            created_at: OffsetDateTimeWrapper(oldest + Duration::days(days)),
+            cross_references: vec![],
+            claim_v2: None,
+            is_active: true,
+        };
+        fs_storage.store_manifest(&manifest)?;
+        memory_storage.store_manifest(&manifest)?;
+    }
+
+    let stats = collect_stats(&fs_storage)?;
+    assert_eq!(stats.total_manifests, 3);
+    assert_eq!(stats.by_type.len(), 1);
+    assert_eq!(stats.by_type[0].manifest_type, ManifestType::Unknown);
+    assert_eq!(stats.by_type[0].count, 3);
+    assert_eq!(stats.total_bytes, fs_storage.get_total_storage_size()?);
+    assert!(stats.total_bytes.unwrap_or_default() > 0);
+    assert_eq!(stats.oldest.as_deref(), Some("2024-01-01T00:00:00Z"));
+    assert_eq!(stats.newest.as_deref(), Some("2024-03-01T00:00:00Z"));
+
+    // Backends that can't measure their size report counts only
+    let memory_stats = collect_stats(&memory_storage)?;
+    assert_eq!(memory_stats.total_manifests, 3);
+    assert_eq!(memory_stats.total_bytes, None);
+    assert_eq!(memory_stats.oldest, stats.oldest);
+
+    Ok(())
+}
+
+#[test]
+fn test_filesystem_storage_interrupted_write() -> Result<()> {
+    let dir = tempdir()?;
+    let fs_storage = FilesystemStorage::new(dir.path())?;
+
+    let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+    let mut manifest = Manifest {
+        claim_generator: "test".to_string(),
+        title: "Version 1".to_string(),
+        instance_id: manifest_id.clone(),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: None,
+        is_active: true,
+    };
+    fs_storage.store_manifest(&manifest)?;
+
+    // Simulate a write that was interrupted after some bytes reached the
+    // temporary file
+    let temp_path = FilesystemStorage::temp_path(&fs_storage.manifest_path(&manifest_id));
+    fs::write(&temp_path, b"{\"claim_generator\": \"te")?;
+
+    // The last good version is still returned and listed
+    assert_eq!(
+        fs_storage.retrieve_manifest(&manifest_id)?.title,
+        "Version 1"
+    );
+    assert_eq!(fs_storage.list_manifests()?.len(), 1);
+
+    // A later write replaces the stale temporary file
+    manifest.title = "Version 2".to_string();
+    fs_storage.update_manifest(&manifest)?;
+    assert_eq!(
+        fs_storage.retrieve_manifest(&manifest_id)?.title,
+        "Version 2"
+    );
+    assert!(!temp_path.exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_filesystem_storage_content_addressed() -> Result<()> {
+    let dir = tempdir()?;
+    let fs_storage = FilesystemStorage::new_content_addressed(dir.path())?;
+
+    let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+    let manifest = Manifest {
+        claim_generator: "test".to_string(),
+        title: "Content Addressed".to_string(),
+        instance_id: manifest_id.clone(),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: None,
+        is_active: true,
+    };
+
+    // The returned ID is the reproducible content hash
+    let content_id = fs_storage.store_manifest(&manifest)?;
+    assert_eq!(content_id, FilesystemStorage::content_hash(&manifest)?);
+    assert_ne!(content_id, manifest_id);
+
+    // Storing identical content deduplicates
+    assert_eq!(fs_storage.store_manifest(&manifest)?, content_id);
+    let manifests = fs_storage.list_manifests()?;
+    assert_eq!(manifests.len(), 1);
+    assert_eq!(manifests[0].id, content_id);
+
+    // Either ID retrieves the manifest
+    assert_eq!(
+        fs_storage.retrieve_manifest(&content_id)?.instance_id,
+        manifest_id
+    );
+    assert_eq!(
+        fs_storage.retrieve_manifest(&manifest_id)?.instance_id,
+        manifest_id
+    );
+
+    fs_storage.delete_manifest(&manifest_id)?;
+    assert!(fs_storage.retrieve_manifest(&content_id).is_err());
+    assert!(fs_storage.retrieve_manifest(&manifest_id).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_filesystem_storage_content_addressed_legacy_key() -> Result<()> {
+    use sha2::Digest;
+
+    let dir = tempdir()?;
+    let fs_storage = FilesystemStorage::new_content_addressed(dir.path())?;
+    let manifest = Manifest {
+        claim_generator: "test".to_string(),
+        title: "Stored by an earlier release".to_string(),
+        instance_id: format!("test_manifest_{}", Uuid::new_v4()),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: None,
+        is_active: true,
+    };
+
+    // Earlier releases keyed manifests by the hash of their plain JSON
+    let legacy_key = hex::encode(sha2::Sha256::digest(serde_json::to_vec(&manifest)?));
+    std::fs::write(
+        fs_storage.manifest_path(&legacy_key),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+
+    // It is listed under the key it can be retrieved by, and not stored twice
+    let manifests = fs_storage.list_manifests()?;
+    assert_eq!(manifests[0].id, legacy_key);
+    assert_eq!(
+        fs_storage.retrieve_manifest(&legacy_key)?.title,
+        manifest.title
+    );
+    assert_eq!(fs_storage.store_manifest(&manifest)?, legacy_key);
+    assert_eq!(fs_storage.list_manifests()?.len(), 1);
+
+    Ok(())
+}
+
 #[test]
 fn test_filesystem_storage_extended() -> Result<()> {
     // Create a temporary directory for storage
@@ -168,7 +388,9 @@ fn test_filesystem_storage_extended() -> Result<()> {
     println!("Verified manifest_exists functionality");
 
     // Test manifest size functionality
-    let size = fs_storage.get_manifest_size(&manifest_id)?;
+    let size = fs_storage
+        .get_manifest_size(&manifest_id)?
+        .unwrap_or_default();
     assert!(size > 0, "Manifest file size should be greater than 0");
     println!("Manifest size: {size} bytes");
 
@@ -192,7 +414,7 @@ fn test_filesystem_storage_extended() -> Result<()> {
     println!("Exported {export_count} manifests");
 
     // Test total storage size
-    let total_size = fs_storage.get_total_storage_size()?;
+    let total_size = fs_storage.get_total_storage_size()?.unwrap_or_default();
     assert!(
         total_size > 0,
         "Total storage size should be greater than 0"
@@ -250,7 +472,7 @@ fn test_filesystem_storage_linking() -> Result<()> {
     println!("Stored both manifests");
 
     // Create a cross-reference from model to dataset
-    let dataset_json = serde_json::to_string(&dataset_manifest)?;
+    let dataset_json = crate::manifest::canonical::to_canonical_json(&dataset_manifest)?;
     let dataset_hash = hex::encode(sha2::Sha256::digest(dataset_json.as_bytes()));
 
     let cross_ref = atlas_c2pa_lib::cross_reference::CrossReference {
@@ -264,7 +486,7 @@ fn test_filesystem_storage_linking() -> Result<()> {
     println!("Added cross-reference from model to dataset");
 
     // Update model manifest in storage
-    fs_storage.store_manifest(&model_manifest)?;
+    fs_storage.update_manifest(&model_manifest)?;
     println!("Updated model manifest with cross-reference");
 
     // Retrieve the model manifest and verify cross-reference
@@ -283,7 +505,7 @@ fn test_filesystem_storage_linking() -> Result<()> {
 
     // Verify cross-reference hash by retrieving dataset
     let retrieved_dataset = fs_storage.retrieve_manifest(&dataset_id)?;
-    let retrieved_dataset_json = serde_json::to_string(&retrieved_dataset)?;
+    let retrieved_dataset_json = crate::manifest::canonical::to_canonical_json(&retrieved_dataset)?;
     let calculated_hash = hex::encode(sha2::Sha256::digest(retrieved_dataset_json.as_bytes()));
 
     assert_eq!(
@@ -322,9 +544,12 @@ fn test_cli_handler_storage_selection() -> Result<()> {
         let cmd = DatasetCommands::List {
             storage_type: Box::new(storage_type.to_string()),
             storage_url: Box::new(storage_url.to_string()),
+            tags: vec![],
+            since: None,
+            until: None,
         };
 
-        let result = handle_dataset_command(cmd);
+        let result = handle_dataset_command(cmd, &Config::default());
 
         // The connection errors are expected, we're just making sure we don't get type errors
         match result {
@@ -335,6 +560,7 @@ fn test_cli_handler_storage_selection() -> Result<()> {
 
                 // These are all expected connection errors
                 let expected_errors = [
+                    "storage service not ready",
                     "Connection refused",
                     "No such file or directory",
                     "Failed to connect",
@@ -360,3 +586,993 @@ fn test_cli_handler_storage_selection() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_create_storage_reports_deprecated_aliases() -> Result<()> {
+    use crate::storage::config::StorageConfig;
+    use crate::storage::create_storage_with_options;
+
+    let dir = tempdir()?;
+    let url = dir.path().to_string_lossy().to_string();
+    let deny = StorageConfig {
+        deny_deprecated: true,
+        ..Default::default()
+    };
+
+    let (_, resolution) = create_storage_with_options("local-fs", url.clone(), &deny)?;
+    assert_eq!(resolution.storage_type, "local-fs");
+    assert!(resolution.warning().is_none());
+
+    let (storage, resolution) =
+        create_storage_with_options("filesystem", url.clone(), &StorageConfig::default())?;
+    assert_eq!(resolution.storage_type, "local-fs");
+    assert_eq!(resolution.deprecated_alias, Some("filesystem"));
+    assert!(
+        resolution
+            .warning()
+            .unwrap()
+            .contains("Use 'local-fs' instead")
+    );
+    assert!(storage.list_manifests()?.is_empty());
+
+    match create_storage_with_options("filesystem", url, &deny) {
+        Err(Error::Validation(msg)) => assert!(msg.contains("deprecated"), "{msg}"),
+        other => panic!(
+            "expected a validation error, got {:?}",
+            other.err().map(|e| e.to_string())
+        ),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_storage() -> Result<()> {
+    use crate::storage::create_storage;
+
+    // The URL is ignored for in-memory storage
+    let storage = create_storage("memory", String::new(), &Default::default())?;
+
+    let manifest_id = format!("test_manifest_{}", Uuid::new_v4());
+    let manifest = Manifest {
+        claim_generator: "test".to_string(),
+        title: "Test Memory Storage".to_string(),
+        instance_id: manifest_id.clone(),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: None,
+        is_active: true,
+    };
+
+    assert_eq!(storage.store_manifest(&manifest)?, manifest_id);
+    assert_eq!(
+        storage.retrieve_manifest(&manifest_id)?.title,
+        "Test Memory Storage"
+    );
+
+    let manifests = storage.list_manifests()?;
+    assert_eq!(manifests.len(), 1);
+    assert_eq!(manifests[0].id, manifest_id);
+
+    storage.delete_manifest(&manifest_id)?;
+    assert!(storage.retrieve_manifest(&manifest_id).is_err());
+    assert!(storage.delete_manifest(&manifest_id).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_migrate_manifests_between_backends() -> Result<()> {
+    use crate::cli::commands::MigrateArgs;
+    use crate::cli::handlers::handle_migrate_command;
+    use crate::storage::migrate::migrate_manifests;
+
+    let dir = tempdir()?;
+    let source_dir = dir.path().join("source");
+    let destination_dir = dir.path().join("destination");
+    let source = FilesystemStorage::new(&source_dir)?;
+
+    let mut ids = Vec::new();
+    for title in ["First", "Second"] {
+        let manifest = Manifest {
+            claim_generator: "test".to_string(),
+            title: title.to_string(),
+            instance_id: format!("urn:c2pa:{}", Uuid::new_v4()),
+            ingredients: Vec::new(),
+            claim: create_default_claim(),
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+            cross_references: vec![],
+            claim_v2: None,
+            is_active: true,
+        };
+        ids.push(source.store_manifest(&manifest)?);
+    }
+
+    let migrate = |skip_existing: bool| {
+        handle_migrate_command(
+            MigrateArgs {
+                from: format!("local-fs:{}", source_dir.display()),
+                to: format!("local-fs:{}", destination_dir.display()),
+                skip_existing,
+            },
+            &Config::default(),
+        )
+    };
+    migrate(false)?;
+
+    // IDs are preserved
+    let destination = FilesystemStorage::new(&destination_dir)?;
+    for id in &ids {
+        assert_eq!(
+            destination.retrieve_manifest(id)?.title,
+            source.retrieve_manifest(id)?.title
+        );
+    }
+
+    // Re-running with --skip-existing copies nothing
+    migrate(true)?;
+    let summary = migrate_manifests(&source, &destination, true, false)?;
+    assert_eq!((summary.copied, summary.skipped), (0, 2));
+    assert_eq!(destination.list_manifests()?.len(), 2);
+
+    let result = handle_migrate_command(
+        MigrateArgs {
+            from: "local-fs".to_string(),
+            to: "memory".to_string(),
+            skip_existing: false,
+        },
+        &Config::default(),
+    );
+    assert!(matches!(result, Err(crate::error::Error::Validation(_))));
+
+    Ok(())
+}
+
+#[test]
+fn test_database_storage_filters_tags_server_side() -> Result<()> {
+    use crate::storage::database::DatabaseStorage;
+    use serde_json::json;
+
+    let entry = json!({
+        "manifest_id": "urn:c2pa:tagged",
+        "manifest_type": "model",
+        "manifest": {"manifest_id": "urn:c2pa:tagged", "manifest": {"title": "Tagged Model"}},
+        "created_at": "2025-01-23T12:00:00Z",
+        "tags": ["env=prod", "team=vision"],
+    });
+
+    let mut server = mockito::Server::new();
+    let filtered = server
+        .mock("GET", "/manifests")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "tag".into(),
+            "env=prod,team=vision".into(),
+        ))
+        .with_body(json!([entry]).to_string())
+        .create();
+
+    let storage = DatabaseStorage::new(server.url())?;
+    let manifests =
+        storage.list_manifests_with_tags(&["env=prod".to_string(), "team=vision".to_string()])?;
+
+    filtered.assert();
+    assert_eq!(manifests.len(), 1);
+    assert_eq!(manifests[0].name, "Tagged Model");
+    assert_eq!(manifests[0].tags, vec!["env=prod", "team=vision"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_database_storage_connect_timeout() -> Result<()> {
+    use crate::storage::config::StorageConfig;
+    use crate::storage::database::DatabaseStorage;
+    use std::time::{Duration, Instant};
+
+    let config = StorageConfig {
+        connect_timeout: Duration::from_millis(500),
+        request_timeout: Duration::from_secs(2),
+        ..Default::default()
+    };
+    // A non-routable address, so the connection neither succeeds nor is refused
+    let storage =
+        DatabaseStorage::new_with_config("http://10.255.255.1:8080".to_string(), &config)?;
+
+    let started = Instant::now();
+    assert!(storage.list_manifests().is_err());
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    Ok(())
+}
+
+#[test]
+fn test_filesystem_storage_migrates_old_manifests() -> Result<()> {
+    let dir = tempdir()?;
+    let storage = FilesystemStorage::new(dir.path())?;
+
+    let manifest_id = format!("urn:c2pa:{}", Uuid::new_v4());
+    let manifest = Manifest {
+        claim_generator: "c2pa-cli/0.1.0".to_string(),
+        title: "Old Manifest".to_string(),
+        instance_id: manifest_id.clone(),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: None,
+        is_active: true,
+    };
+    storage.store_manifest(&manifest)?;
+
+    // Rewrite the stored file in the shape of an unversioned manifest
+    let path = fs::read_dir(dir.path())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| fs::read_to_string(path).is_ok_and(|c| c.contains(&manifest_id)))
+        .unwrap();
+    let mut old: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    let fields = old.as_object_mut().unwrap();
+    fields.remove("cross_references");
+    fields.remove("is_active");
+    fs::write(&path, old.to_string())?;
+
+    let retrieved = storage.retrieve_manifest(&manifest_id)?;
+    assert_eq!(retrieved.title, "Old Manifest");
+    assert!(retrieved.is_active);
+    assert_eq!(storage.list_manifests()?.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_filesystem_storage_filters_by_creation_time() -> Result<()> {
+    use crate::storage::traits::ManifestFilter;
+    use time::format_description::well_known::Rfc3339;
+
+    let dir = tempdir()?;
+    let storage = FilesystemStorage::new(dir.path())?;
+
+    for (title, created_at) in [
+        ("December", "2023-12-31T23:59:59Z"),
+        ("January", "2024-01-15T08:30:00+02:00"),
+        ("February", "2024-02-01T00:00:00Z"),
+    ] {
+        storage.store_manifest(&Manifest {
+            claim_generator: "test".to_string(),
+            title: title.to_string(),
+            instance_id: format!("urn:c2pa:{}", Uuid::new_v4()),
+            ingredients: Vec::new(),
+            claim: create_default_claim(),
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::parse(created_at, &Rfc3339).unwrap()),
+            cross_references: vec![],
+            claim_v2: None,
+            is_active: true,
+        })?;
+    }
+
+    let names = |filter: &ManifestFilter| -> Result<Vec<String>> {
+        let mut names: Vec<String> = storage
+            .list_manifests_filtered(filter)?
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        names.sort();
+        Ok(names)
+    };
+
+    let january = ManifestFilter::new(vec![], Some("2024-01-01"), Some("2024-02-01"))?;
+    assert_eq!(names(&january)?, vec!["January"]);
+
+    let since = ManifestFilter::new(vec![], Some("2024-01-15T06:30:00Z"), None)?;
+    assert_eq!(names(&since)?, vec!["February", "January"]);
+
+    assert_eq!(names(&ManifestFilter::default())?.len(), 3);
+
+    for (since, until) in [
+        (Some("2024-13-01"), None),
+        (Some("2024-02-01"), Some("2024-01-01")),
+    ] {
+        assert!(matches!(
+            ManifestFilter::new(vec![], since, until),
+            Err(crate::error::Error::Validation(_))
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_database_storage_filters_time_server_side() -> Result<()> {
+    use crate::storage::database::DatabaseStorage;
+    use crate::storage::traits::ManifestFilter;
+    use serde_json::json;
+
+    let entry = |id: &str, created_at: &str| {
+        json!({
+            "manifest_id": id,
+            "manifest_type": "model",
+            "manifest": {"manifest_id": id, "manifest": {"title": id}},
+            "created_at": created_at,
+            "tags": [],
+        })
+    };
+
+    let mut server = mockito::Server::new();
+    let filtered = server
+        .mock("GET", "/manifests")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("since".into(), "2024-01-01T00:00:00+00:00".into()),
+            mockito::Matcher::UrlEncoded("until".into(), "2024-02-01T00:00:00+00:00".into()),
+        ]))
+        .with_body(
+            json!([
+                entry("urn:c2pa:january", "2024-01-15T08:30:00.123456+00:00"),
+                // String comparison on the service is coarse; exact bounds
+                // are applied again by the client
+                entry("urn:c2pa:late", "2024-02-01T00:00:00+00:00"),
+            ])
+            .to_string(),
+        )
+        .create();
+
+    let storage = DatabaseStorage::new(server.url())?;
+    let filter = ManifestFilter::new(vec![], Some("2024-01-01"), Some("2024-02-01"))?;
+    let manifests = storage.list_manifests_filtered(&filter)?;
+
+    filtered.assert();
+    assert_eq!(manifests.len(), 1);
+    assert_eq!(manifests[0].id, "urn:c2pa:january");
+
+    Ok(())
+}
+
+#[test]
+fn test_database_storage_sends_manifest_type_and_reports_rejections() -> Result<()> {
+    use crate::manifest::utils::{determine_manifest_type, manifest_type_to_string};
+    use crate::storage::database::DatabaseStorage;
+    use serde_json::json;
+
+    let manifest = Manifest {
+        claim_generator: "test".to_string(),
+        title: "Typed Manifest".to_string(),
+        instance_id: format!("urn:c2pa:{}", Uuid::new_v4()),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: None,
+        is_active: true,
+    };
+    let path = format!("/manifests/{}", manifest.instance_id);
+    let manifest_type = manifest_type_to_string(&determine_manifest_type(&manifest));
+
+    let mut server = mockito::Server::new();
+    let _missing = server.mock("GET", path.as_str()).with_status(404).create();
+    let accepted = server
+        .mock("POST", path.as_str())
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "manifest_type": manifest_type }),
+        ))
+        .with_status(201)
+        .create();
+
+    let storage = DatabaseStorage::new(server.url())?;
+    assert_eq!(storage.store_manifest(&manifest)?, manifest.instance_id);
+    accepted.assert();
+    accepted.remove();
+
+    let _rejected = server
+        .mock("POST", path.as_str())
+        .with_status(400)
+        .with_body("manifest_type is required")
+        .create();
+    match storage.store_manifest(&manifest) {
+        Err(crate::error::Error::Storage(msg)) => {
+            assert!(msg.contains("manifest_type is required"), "{msg}")
+        }
+        other => panic!("expected a rejected store, got {other:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_database_storage_reads_entries_as_the_service_stores_them() -> Result<()> {
+    use crate::storage::database::DatabaseStorage;
+    use crate::storage::protocol::{ManifestEntry, StoreRequest};
+
+    let manifest = Manifest {
+        claim_generator: "test".to_string(),
+        title: "Round Trip".to_string(),
+        instance_id: format!("urn:c2pa:{}", Uuid::new_v4()),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: None,
+        is_active: true,
+    };
+    let path = format!("/manifests/{}", manifest.instance_id);
+
+    // The service stores the request it was sent as the entry's manifest
+    let request = StoreRequest {
+        manifest_id: manifest.instance_id.clone(),
+        manifest_type: "Unknown".to_string(),
+        manifest: serde_json::to_value(&manifest)?,
+        created_at: "2025-01-23T12:00:00+00:00".to_string(),
+        tags: Vec::new(),
+    };
+    let entry = ManifestEntry {
+        manifest_id: manifest.instance_id.clone(),
+        manifest_type: request.manifest_type.clone(),
+        manifest: serde_json::to_value(&request)?,
+        created_at: request.created_at.clone(),
+        tags: Vec::new(),
+    };
+
+    let mut server = mockito::Server::new();
+    let _get = server
+        .mock("GET", path.as_str())
+        .with_body(serde_json::to_string(&entry)?)
+        .create();
+    let _list = server
+        .mock("GET", "/manifests")
+        .with_body(serde_json::to_string(&[&entry])?)
+        .create();
+
+    let storage = DatabaseStorage::new(server.url())?;
+    assert_eq!(
+        storage.retrieve_manifest(&manifest.instance_id)?.title,
+        "Round Trip"
+    );
+    assert_eq!(storage.list_manifests()?[0].name, "Round Trip");
+
+    Ok(())
+}
+
+#[test]
+fn test_database_storage_verifies_signatures_from_claims_only() -> Result<()> {
+    use crate::manifest::common::{verify_all_signatures, verify_signature_only};
+    use crate::manifest::config::VerificationConfig;
+    use crate::manifest::report::CheckStatus;
+    use crate::manifest::signatures::add_signature;
+    use crate::signing::keygen::{KeyType, generate_private_key};
+    use crate::storage::database::DatabaseStorage;
+    use crate::storage::protocol::ClaimResponse;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde_json::json;
+
+    let manifest = |id: &str| Manifest {
+        claim_generator: "test".to_string(),
+        title: id.to_string(),
+        instance_id: id.to_string(),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: Some(create_default_claim()),
+        is_active: true,
+    };
+    let mut signed = manifest("urn:c2pa:signed");
+    add_signature(
+        &mut signed,
+        &generate_private_key(KeyType::EcP256)?,
+        "auditor@example.com",
+    )?;
+    let unsigned = manifest("urn:c2pa:unsigned");
+    // A claim signature does not record its key, so it cannot be checked
+    // without a trust list
+    let mut claim_signed = manifest("urn:c2pa:claim-signed");
+    claim_signed.claim_v2.as_mut().unwrap().signature = Some(STANDARD.encode(b"signature"));
+
+    let mut server = mockito::Server::new();
+    let entries: Vec<_> = [&signed, &unsigned, &claim_signed]
+        .iter()
+        .map(|m| {
+            json!({
+                "manifest_id": m.instance_id,
+                "manifest_type": "Unknown",
+                "manifest": {"manifest_id": m.instance_id},
+                "created_at": "2025-01-23T12:00:00+00:00",
+            })
+        })
+        .collect();
+    let _list = server
+        .mock("GET", "/manifests")
+        .with_body(json!(entries).to_string())
+        .create();
+    let mut claims = Vec::new();
+    for m in [&signed, &unsigned, &claim_signed] {
+        let response = ClaimResponse {
+            manifest_id: m.instance_id.clone(),
+            claim: serde_json::to_value(m.claim_v2.as_ref().unwrap())?,
+        };
+        claims.push(
+            server
+                .mock(
+                    "GET",
+                    format!("/manifests/{}/claim", m.instance_id).as_str(),
+                )
+                .with_body(serde_json::to_string(&response)?)
+                .create(),
+        );
+    }
+    // Whole manifests are never downloaded
+    let full = server
+        .mock(
+            "GET",
+            mockito::Matcher::Regex("^/manifests/[^/]+$".to_string()),
+        )
+        .expect(0)
+        .create();
+
+    let storage = DatabaseStorage::new(server.url())?;
+    let summary = verify_all_signatures(&storage, &VerificationConfig::default())?;
+    assert_eq!(summary.passed, 1);
+    assert_eq!(summary.failed.len(), 2);
+    assert_eq!(summary.failed[0].0, "urn:c2pa:unsigned");
+    assert!(summary.failed[0].1.contains("is not signed"));
+    assert_eq!(summary.failed[1].0, "urn:c2pa:claim-signed");
+    assert!(summary.failed[1].1.contains("--trust-list"));
+
+    for claim in claims {
+        claim.assert();
+    }
+    full.assert();
+
+    // The countersignature verifies, but its key is reported as untrusted
+    let report =
+        verify_signature_only("urn:c2pa:signed", &storage, &VerificationConfig::default())?;
+    assert_eq!(report.countersignatures.len(), 1);
+    assert_eq!(report.trusted_signers.len(), 1);
+    assert!(
+        report
+            .trusted_signers
+            .iter()
+            .all(|check| check.status == CheckStatus::Warning)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_database_storage_bulk_deletes() -> Result<()> {
+    use crate::storage::database::DatabaseStorage;
+    use crate::storage::traits::ManifestType;
+    use serde_json::json;
+
+    let mut server = mockito::Server::new();
+    let by_type = server
+        .mock("DELETE", "/manifests")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "type".into(),
+            "Dataset".into(),
+        ))
+        .with_body(json!({"deleted": 2}).to_string())
+        .create();
+    let by_id = server
+        .mock("DELETE", "/manifests")
+        .match_query(mockito::Matcher::Missing)
+        .match_body(mockito::Matcher::Json(json!(["urn:c2pa:a", "urn:c2pa:b"])))
+        .with_body(json!({"deleted": 1}).to_string())
+        .create();
+
+    let storage = DatabaseStorage::new(server.url())?;
+    assert_eq!(storage.delete_by_type(&ManifestType::Dataset)?, 2);
+    assert_eq!(
+        storage.delete_many(&["urn:c2pa:a".to_string(), "urn:c2pa:b".to_string()])?,
+        1
+    );
+    assert_eq!(storage.delete_many(&[])?, 0);
+
+    by_type.assert();
+    by_id.assert();
+
+    Ok(())
+}
+
+#[test]
+fn test_manifest_prune_command() -> Result<()> {
+    use crate::cli::commands::ManifestCommands;
+    use crate::cli::handlers::handle_manifest_command;
+
+    let dir = tempdir()?;
+    let storage = FilesystemStorage::new(dir.path())?;
+    let mut ids = Vec::new();
+    for title in ["First", "Second", "Third"] {
+        ids.push(storage.store_manifest(&Manifest {
+            claim_generator: "test".to_string(),
+            title: title.to_string(),
+            instance_id: format!("urn:c2pa:{}", Uuid::new_v4()),
+            ingredients: Vec::new(),
+            claim: create_default_claim(),
+            created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+            cross_references: vec![],
+            claim_v2: None,
+            is_active: true,
+        })?);
+    }
+
+    let prune = |manifest_type: Option<&str>, ids: Vec<String>| {
+        handle_manifest_command(
+            ManifestCommands::Prune {
+                manifest_type: manifest_type.map(str::to_string),
+                ids,
+                storage_type: Box::new("local-fs".to_string()),
+                storage_url: Box::new(dir.path().to_string_lossy().to_string()),
+            },
+            &Config::default(),
+        )
+    };
+
+    // Unknown IDs are skipped
+    prune(None, vec![ids[0].clone(), "urn:c2pa:missing".to_string()])?;
+    assert_eq!(storage.list_manifests()?.len(), 2);
+
+    assert!(prune(Some("checkpoint"), vec![]).is_err());
+    prune(Some("dataset"), vec![])?;
+    assert_eq!(storage.list_manifests()?.len(), 2);
+
+    prune(Some("unknown"), vec![])?;
+    assert!(storage.list_manifests()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_database_storage_readiness_check() -> Result<()> {
+    use crate::storage::DatabaseStorage;
+
+    let mut server = mockito::Server::new();
+    let not_ready = server
+        .mock("GET", "/ready")
+        .with_status(503)
+        .with_body("database unavailable")
+        .create();
+    let list = server
+        .mock("GET", "/manifests")
+        .with_body("[]")
+        .expect(3)
+        .create();
+
+    // Without the check the service is called directly
+    let storage = DatabaseStorage::new(server.url())?;
+    assert!(storage.list_manifests()?.is_empty());
+
+    let storage = storage.with_readiness_check();
+    match storage.list_manifests() {
+        Err(crate::error::Error::Storage(msg)) => {
+            assert!(msg.contains("storage service not ready"), "{msg}");
+            assert!(msg.contains("database unavailable"), "{msg}");
+        }
+        Err(e) => panic!("expected a readiness error, got {e}"),
+        Ok(_) => panic!("expected a readiness error"),
+    }
+    not_ready.remove();
+
+    // Once ready, the service is only probed for the first operation
+    let ready = server.mock("GET", "/ready").expect(1).create();
+    assert!(storage.list_manifests()?.is_empty());
+    assert!(storage.list_manifests_with_tags(&[])?.is_empty());
+    ready.assert();
+    list.assert();
+
+    Ok(())
+}
+
+#[test]
+fn test_rekor_storage_against_mock_log() -> Result<()> {
+    use crate::storage::rekor::{ATLAS_PREDICATE_TYPE, RekorStorage};
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde_json::json;
+
+    let manifest_id = format!("urn:c2pa:{}", Uuid::new_v4());
+    let manifest = Manifest {
+        claim_generator: "test".to_string(),
+        title: "Test Rekor Storage".to_string(),
+        instance_id: manifest_id.clone(),
+        ingredients: Vec::new(),
+        claim: create_default_claim(),
+        created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+        cross_references: vec![],
+        claim_v2: None,
+        is_active: true,
+    };
+
+    let statement = json!({
+        "_type": "https://in-toto.io/Statement/v1",
+        "predicateType": ATLAS_PREDICATE_TYPE,
+        "predicate": manifest,
+    });
+    let payload_hash = hex::encode(sha2::Sha256::digest(statement.to_string()));
+    let body = STANDARD.encode(
+        json!({
+            "apiVersion": "0.0.2",
+            "kind": "intoto",
+            "spec": {"content": {"payloadHash": {"algorithm": "sha256", "value": payload_hash}}},
+        })
+        .to_string(),
+    );
+    let atlas_uuid = "24296fb24b8ad77a0001";
+    let atlas_entry = json!({
+        atlas_uuid: {
+            "body": body,
+            "logIndex": 1,
+            "attestation": { "data": STANDARD.encode(statement.to_string()) },
+        }
+    });
+    let other_entry = json!({
+        "24296fb24b8ad77a0002": { "body": body, "logIndex": 2 }
+    });
+
+    let mut server = mockito::Server::new();
+    let _entry = server
+        .mock("GET", format!("/api/v1/log/entries/{atlas_uuid}").as_str())
+        .with_body(atlas_entry.to_string())
+        .create();
+    let _by_index = server
+        .mock("GET", "/api/v1/log/entries")
+        .match_query(mockito::Matcher::UrlEncoded("logIndex".into(), "1".into()))
+        .with_body(atlas_entry.to_string())
+        .create();
+    let _log = server
+        .mock("GET", "/api/v1/log")
+        .with_body(json!({"treeSize": 2}).to_string())
+        .create();
+    let _retrieve = server
+        .mock("POST", "/api/v1/log/entries/retrieve")
+        .match_body(mockito::Matcher::Json(json!({"logIndexes": [0, 1]})))
+        .with_body(json!([atlas_entry, other_entry]).to_string())
+        .create();
+    let _missing = server
+        .mock("GET", "/api/v1/log/entries/unknown")
+        .with_status(404)
+        .create();
+
+    let storage = RekorStorage::new_with_url(server.url())?;
+
+    // Retrieve by UUID and by log index
+    assert_eq!(
+        storage.retrieve_manifest(atlas_uuid)?.instance_id,
+        manifest_id
+    );
+    assert_eq!(storage.retrieve_manifest("1")?.instance_id, manifest_id);
+
+    // Only entries carrying Atlas manifests are listed
+    let manifests = storage.list_manifests()?;
+    assert_eq!(manifests.len(), 1);
+    assert_eq!(manifests[0].id, atlas_uuid);
+    assert_eq!(manifests[0].name, "Test Rekor Storage");
+
+    // Failures map to storage errors
+    assert!(matches!(
+        storage.retrieve_manifest("unknown"),
+        Err(crate::error::Error::Storage(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_rekor_inclusion_proof() -> Result<()> {
+    use crate::hash::DigestAlgorithm;
+    use crate::signing::sign_data_with_algorithm;
+    use crate::storage::rekor::{ATLAS_PREDICATE_TYPE, RekorStorage};
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::nid::Nid;
+    use openssl::pkey::PKey;
+    use serde_json::json;
+
+    fn leaf(body: &[u8]) -> [u8; 32] {
+        sha2::Sha256::new()
+            .chain_update([0x00])
+            .chain_update(body)
+            .finalize()
+            .into()
+    }
+    fn node(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+        sha2::Sha256::new()
+            .chain_update([0x01])
+            .chain_update(left)
+            .chain_update(right)
+            .finalize()
+            .into()
+    }
+    // RFC 6962 tree hash and audit path
+    fn split(n: usize) -> usize {
+        n.next_power_of_two() / 2
+    }
+    fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+        match leaves.len() {
+            1 => leaves[0],
+            n => node(root(&leaves[..split(n)]), root(&leaves[split(n)..])),
+        }
+    }
+    fn path(m: usize, leaves: &[[u8; 32]]) -> Vec<[u8; 32]> {
+        let n = leaves.len();
+        if n == 1 {
+            return vec![];
+        }
+        let k = split(n);
+        if m < k {
+            let mut path = path(m, &leaves[..k]);
+            path.push(root(&leaves[k..]));
+            path
+        } else {
+            let mut path = path(m - k, &leaves[k..]);
+            path.push(root(&leaves[..k]));
+            path
+        }
+    }
+
+    let new_key = || {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    };
+    let log_key = new_key();
+    let checkpoint = |key: &PKey<openssl::pkey::Private>, size: usize, root: [u8; 32]| {
+        let text = format!("rekor.test - 1\n{size}\n{}\n", STANDARD.encode(root));
+        let mut signature = vec![0u8; 4];
+        signature.extend(
+            sign_data_with_algorithm(text.as_bytes(), key, DigestAlgorithm::Sha256).unwrap(),
+        );
+        format!(
+            "{text}\n\u{2014} rekor.test {}\n",
+            STANDARD.encode(signature)
+        )
+    };
+
+    // Each entry logs an in-toto statement carrying a manifest
+    let statements: Vec<String> = (0..5)
+        .map(|i| {
+            let manifest = Manifest {
+                claim_generator: "test".to_string(),
+                title: format!("entry {i}"),
+                instance_id: format!("urn:c2pa:{}", Uuid::new_v4()),
+                ingredients: Vec::new(),
+                claim: create_default_claim(),
+                created_at: OffsetDateTimeWrapper(OffsetDateTime::now_utc()),
+                cross_references: vec![],
+                claim_v2: None,
+                is_active: true,
+            };
+            json!({
+                "_type": "https://in-toto.io/Statement/v1",
+                "predicateType": ATLAS_PREDICATE_TYPE,
+                "predicate": manifest,
+            })
+            .to_string()
+        })
+        .collect();
+    let bodies: Vec<Vec<u8>> = statements
+        .iter()
+        .map(|statement| {
+            let payload_hash = hex::encode(sha2::Sha256::digest(statement));
+            json!({
+                "apiVersion": "0.0.2",
+                "kind": "intoto",
+                "spec": {"content": {"payloadHash": {"algorithm": "sha256", "value": payload_hash}}},
+            })
+            .to_string()
+            .into_bytes()
+        })
+        .collect();
+    let leaves: Vec<[u8; 32]> = bodies.iter().map(|body| leaf(body)).collect();
+    let tree_root = root(&leaves);
+
+    let entry_with_attestation =
+        |index: usize, hashes: Vec<[u8; 32]>, checkpoint: String, attestation: &str| {
+            let uuid = format!("24296fb24b8ad77a{}", hex::encode(leaves[index]));
+            let entry = json!({
+                uuid.clone(): {
+                    "body": STANDARD.encode(&bodies[index]),
+                    "logIndex": index,
+                    "attestation": { "data": STANDARD.encode(attestation) },
+                    "verification": {
+                        "inclusionProof": {
+                            "logIndex": index,
+                            "treeSize": leaves.len(),
+                            "rootHash": hex::encode(tree_root),
+                            "hashes": hashes.iter().map(hex::encode).collect::<Vec<_>>(),
+                            "checkpoint": checkpoint,
+                        }
+                    }
+                }
+            });
+            (uuid, entry)
+        };
+    let entry = |index: usize, hashes: Vec<[u8; 32]>, checkpoint: String| {
+        entry_with_attestation(index, hashes, checkpoint, &statements[index])
+    };
+
+    let mut server = mockito::Server::new();
+    let storage = RekorStorage::new_with_url(server.url())?
+        .with_log_public_key(&log_key.public_key_to_pem().unwrap())?;
+
+    // Every entry of the tree is proven to be included
+    let signed_head = checkpoint(&log_key, leaves.len(), tree_root);
+    let mut mocks = Vec::new();
+    for index in 0..leaves.len() {
+        let (uuid, entry) = entry(index, path(index, &leaves), signed_head.clone());
+        mocks.push(
+            server
+                .mock("GET", format!("/api/v1/log/entries/{uuid}").as_str())
+                .with_body(entry.to_string())
+                .create(),
+        );
+        let proof = storage.verify_inclusion(&uuid)?;
+        assert_eq!(proof.log_index, index as u64);
+        assert_eq!(proof.tree_size, leaves.len() as u64);
+
+        // The manifest is read from the proven entry
+        let (manifest, proof) = storage.retrieve_proven_manifest(&uuid)?;
+        assert_eq!(manifest.title, format!("entry {index}"));
+        assert_eq!(proof.log_index, index as u64);
+    }
+
+    // A logged entry whose attestation was swapped for another manifest is
+    // still included, but its attestation does not match its body
+    let (uuid, swapped) =
+        entry_with_attestation(2, path(2, &leaves), signed_head.clone(), &statements[4]);
+    let _swapped = server
+        .mock("GET", format!("/api/v1/log/entries/{uuid}").as_str())
+        .with_body(swapped.to_string())
+        .create();
+    storage.verify_inclusion(&uuid)?;
+    for result in [
+        storage.retrieve_proven_manifest(&uuid).map(|_| ()),
+        storage.retrieve_manifest(&uuid).map(|_| ()),
+    ] {
+        match result {
+            Err(crate::error::Error::Validation(msg)) => {
+                assert!(msg.contains("does not match the hash recorded"), "{msg}")
+            }
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+    }
+
+    // A wrong audit path or a tree head not signed by the log is rejected
+    let mut wrong_path = path(3, &leaves);
+    wrong_path[0] = leaves[0];
+    let other_key = new_key();
+    for (hashes, signed_head) in [
+        (wrong_path, signed_head.clone()),
+        (
+            path(3, &leaves),
+            checkpoint(&other_key, leaves.len(), tree_root),
+        ),
+    ] {
+        let (uuid, entry) = entry(3, hashes, signed_head);
+        let _entry = server
+            .mock("GET", format!("/api/v1/log/entries/{uuid}").as_str())
+            .with_body(entry.to_string())
+            .create();
+        assert!(matches!(
+            storage.verify_inclusion(&uuid),
+            Err(crate::error::Error::Validation(_))
+        ));
+    }
+
+    // Without a pinned key the checkpoint cannot be trusted
+    let (uuid, entry) = entry(0, path(0, &leaves), signed_head);
+    let _entry = server
+        .mock("GET", format!("/api/v1/log/entries/{uuid}").as_str())
+        .with_body(entry.to_string())
+        .create();
+    match RekorStorage::new_with_url(server.url())?.verify_inclusion(&uuid) {
+        Err(crate::error::Error::Validation(msg)) => {
+            assert!(msg.contains("--rekor-public-key"), "{msg}")
+        }
+        other => panic!("expected a validation error, got {:?}", other.map(|_| ())),
+    }
+
+    // The public Sigstore log's built-in key is pinned by default
+    RekorStorage::new()?;
+
+    Ok(())
+}
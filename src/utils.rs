@@ -2,8 +2,21 @@ use crate::error::{Error, Result};
 use std::fs::{self, File, OpenOptions};
 use std::path::{Path, PathBuf};
 
+/// Directories symlink targets must resolve into: the `configured` roots, or
+/// the current working directory if none were configured
+pub fn symlink_roots(configured: &[PathBuf]) -> Vec<PathBuf> {
+    if !configured.is_empty() {
+        return configured.to_vec();
+    }
+    std::env::current_dir()
+        .map(|cwd| vec![cwd])
+        .unwrap_or_default()
+}
+
 /// Ensures a file path is safe to use (not a symlink or hard link unless allowed)
 ///
+/// Allowed symlinks must point into the current working directory.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -22,6 +35,16 @@ use std::path::{Path, PathBuf};
 /// let _ = safe_file_path(&path, true);
 /// ```
 pub fn safe_file_path(path: &Path, allow_symlinks: bool) -> Result<PathBuf> {
+    safe_file_path_with_roots(path, allow_symlinks, &[])
+}
+
+/// Like [`safe_file_path`], but symlinks may only point into `allowed_roots`
+/// instead of the current working directory; see [`symlink_roots`]
+pub fn safe_file_path_with_roots(
+    path: &Path,
+    allow_symlinks: bool,
+    allowed_roots: &[PathBuf],
+) -> Result<PathBuf> {
     // Check if the file exists
     if path.exists() {
         // Check if it's a symlink
@@ -33,12 +56,15 @@ pub fn safe_file_path(path: &Path, allow_symlinks: bool) -> Result<PathBuf> {
                 )));
             }
 
-            // If symlinks are allowed, check the target is valid
+            // If symlinks are allowed, check the target is valid. Relative
+            // targets are relative to the directory holding the link
             let target = fs::read_link(path)?;
+            let target = match path.parent() {
+                Some(parent) if target.is_relative() => parent.join(target),
+                _ => target,
+            };
 
-            // Validate the target path (customize this logic based on your requirements)
-            // For example, ensure it's within a specific directory
-            if !is_safe_symlink_target(&target) {
+            if !is_safe_symlink_target(&target, &symlink_roots(allowed_roots)) {
                 return Err(Error::Validation(format!(
                     "Security error: Symlink target {} is not in an allowed location",
                     target.display()
@@ -49,15 +75,14 @@ pub fn safe_file_path(path: &Path, allow_symlinks: bool) -> Result<PathBuf> {
         }
 
         // Check for hard links (files with multiple links)
-        #[cfg(unix)]
+        #[cfg(any(unix, windows))]
         {
-            use std::os::unix::fs::MetadataExt;
-            let metadata = fs::metadata(path)?;
-            if metadata.nlink() > 1 {
+            let links = hard_link_count(path)?;
+            if links > 1 {
                 return Err(Error::Validation(format!(
                     "Security error: Path {} has multiple hard links ({})",
                     path.display(),
-                    metadata.nlink()
+                    links
                 )));
             }
         }
@@ -67,14 +92,47 @@ pub fn safe_file_path(path: &Path, allow_symlinks: bool) -> Result<PathBuf> {
     Ok(path.to_path_buf())
 }
 
-/// Checks if a symlink target is in an allowed location
-fn is_safe_symlink_target(target: &Path) -> bool {
-    if let Ok(canonical) = target.canonicalize() {
-        // Only allow /tmp or /var/app/data for now
-        canonical.starts_with("/tmp") || canonical.starts_with("/var/app/data")
-    } else {
-        false
+/// Number of hard links to the file at `path`
+#[cfg(unix)]
+fn hard_link_count(path: &Path) -> Result<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Ok(fs::metadata(path)?.nlink())
+}
+
+/// Number of hard links to the file at `path`
+#[cfg(windows)]
+fn hard_link_count(path: &Path) -> Result<u64> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS, GetFileInformationByHandle,
+    };
+
+    // Open without read access so metadata can be read from locked files, and
+    // with backup semantics so directories can be opened too
+    let file = OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)?;
+
+    // SAFETY: the handle stays valid while `file` is alive and `info` is a
+    // correctly sized, writable output buffer
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return Err(std::io::Error::last_os_error().into());
     }
+    Ok(u64::from(info.nNumberOfLinks))
+}
+
+/// Checks if a symlink target resolves into one of the allowed roots
+fn is_safe_symlink_target(target: &Path, allowed_roots: &[PathBuf]) -> bool {
+    let Ok(canonical) = target.canonicalize() else {
+        return false;
+    };
+    allowed_roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| canonical.starts_with(root))
 }
 
 /// Safely opens a file for reading
@@ -127,9 +185,86 @@ pub fn safe_create_file(path: &Path, allow_symlinks: bool) -> Result<File> {
 }
 
 /// Safely opens a file with custom options
-pub fn safe_open_options(path: &Path, allow_symlinks: bool) -> Result<OpenOptions> {
-    let _safe_path = safe_file_path(path, allow_symlinks)?;
-    Ok(OpenOptions::new())
+///
+/// The returned [`SafeOpenOptions`] opens the validated path, so the symlink
+/// and hard link checks cannot be bypassed by opening a different path.
+///
+/// # Examples
+///
+/// ```no_run
+/// use atlas_cli::utils::safe_open_options;
+/// use std::io::Write;
+/// use std::path::Path;
+///
+/// let mut file = safe_open_options(Path::new("output.log"), false)?
+///     .append(true)
+///     .create(true)
+///     .open()?;
+/// file.write_all(b"appended line\n")?;
+/// # Ok::<(), atlas_cli::error::Error>(())
+/// ```
+pub fn safe_open_options(path: &Path, allow_symlinks: bool) -> Result<SafeOpenOptions> {
+    Ok(SafeOpenOptions {
+        path: safe_file_path(path, allow_symlinks)?,
+        options: OpenOptions::new(),
+    })
+}
+
+/// [`OpenOptions`] bound to a path that passed [`safe_file_path`]
+#[derive(Debug, Clone)]
+pub struct SafeOpenOptions {
+    path: PathBuf,
+    options: OpenOptions,
+}
+
+impl SafeOpenOptions {
+    /// The validated path that [`open`](Self::open) will use
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn read(mut self, read: bool) -> Self {
+        self.options.read(read);
+        self
+    }
+
+    pub fn write(mut self, write: bool) -> Self {
+        self.options.write(write);
+        self
+    }
+
+    pub fn append(mut self, append: bool) -> Self {
+        self.options.append(append);
+        self
+    }
+
+    pub fn truncate(mut self, truncate: bool) -> Self {
+        self.options.truncate(truncate);
+        self
+    }
+
+    pub fn create(mut self, create: bool) -> Self {
+        self.options.create(create);
+        self
+    }
+
+    pub fn create_new(mut self, create_new: bool) -> Self {
+        self.options.create_new(create_new);
+        self
+    }
+
+    /// Permission bits for a newly created file
+    #[cfg(unix)]
+    pub fn mode(mut self, mode: u32) -> Self {
+        use std::os::unix::fs::OpenOptionsExt;
+        self.options.mode(mode);
+        self
+    }
+
+    /// Opens the validated path with the configured options
+    pub fn open(self) -> Result<File> {
+        self.options.open(&self.path).map_err(Error::from)
+    }
 }
 
 #[cfg(test)]
@@ -186,8 +321,8 @@ mod tests {
         let result = safe_file_path(&symlink_path, false);
         assert!(result.is_err(), "Should reject symlinks when not allowed");
 
-        // Test with symlinks allowed
-        let result = safe_file_path(&symlink_path, true)?;
+        // Test with symlinks allowed into the temporary directory
+        let result = safe_file_path_with_roots(&symlink_path, true, &[dir.path().to_path_buf()])?;
 
         // Should return the target path when symlinks are allowed
         assert_eq!(result, target_path);
@@ -239,6 +374,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(windows)]
+    fn test_safe_file_path_hardlink_windows() -> Result<()> {
+        let dir = tempdir()?;
+        let target_path = dir.path().join("target_file.txt");
+        let hardlink_path = dir.path().join("hardlink_file.txt");
+
+        let mut file = File::create(&target_path)?;
+        file.write_all(b"target file content")?;
+        drop(file);
+
+        // A single link is accepted
+        assert!(safe_file_path(&target_path, false).is_ok());
+
+        // NTFS hard links are detected through the file's link count
+        std::fs::hard_link(&target_path, &hardlink_path)?;
+        match safe_file_path(&hardlink_path, false) {
+            Err(Error::Validation(msg)) => assert!(msg.contains("multiple hard links"), "{msg}"),
+            other => panic!("expected a hard link error, got {other:?}"),
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_safe_open_file() -> Result<()> {
         // Create a temporary directory and file
@@ -292,17 +451,28 @@ mod tests {
 
         // Test creating with OpenOptions
         {
-            let mut options = safe_open_options(&file_path, false)?;
-            let mut file = options.write(true).create(true).open(&file_path)?;
+            let options = safe_open_options(&file_path, false)?;
+            assert_eq!(options.path(), file_path);
+            let mut file = options.write(true).create(true).open()?;
             file.write_all(b"options content")?;
         }
 
+        // Appending goes through the same validated path
+        {
+            let mut file = safe_open_options(&file_path, false)?.append(true).open()?;
+            file.write_all(b" appended")?;
+        }
+
         // Verify the file was created with the content
         let mut content = String::new();
         let mut file = File::open(&file_path)?;
         file.read_to_string(&mut content)?;
 
-        assert_eq!(content, "options content");
+        assert_eq!(content, "options content appended");
+
+        // Hard-linked files are rejected before any options are applied
+        std::fs::hard_link(&file_path, dir.path().join("linked.txt"))?;
+        assert!(safe_open_options(&file_path, false).is_err());
 
         Ok(())
     }
@@ -333,34 +503,39 @@ mod tests {
 
     #[test]
     fn test_is_safe_symlink_target() {
-        let check_path = |path: &str| -> bool {
-            let path = Path::new(path);
-            if let Ok(canonical) = path.canonicalize() {
-                canonical.starts_with("/tmp") || canonical.starts_with("/var/app/data")
-            } else {
-                // Simulate behavior for paths that can't be canonicalized
-                false
-            }
-        };
-
-        // Test a path that exists and should be allowed (tmpdir)
         let tmp_dir = tempdir().unwrap();
+        let inside = tmp_dir.path().join("model.onnx");
+        File::create(&inside).unwrap();
+        let roots = [tmp_dir.path().to_path_buf()];
+
+        // Targets inside an allowed root are accepted
         assert!(
-            check_path(tmp_dir.path().to_str().unwrap()),
-            "Temporary directory should be considered safe"
+            is_safe_symlink_target(&inside, &roots),
+            "Files in an allowed root should be considered safe"
         );
 
-        // Test paths that should not be allowed
+        // Targets outside every root, or that don't exist, are rejected
         assert!(
-            !check_path("/etc/passwd"),
+            !is_safe_symlink_target(Path::new("/etc/passwd"), &roots),
             "/etc/passwd should not be considered safe"
         );
         assert!(
-            !check_path("/home/user/file.txt"),
-            "/home/user/file.txt should not be considered safe"
+            !is_safe_symlink_target(&tmp_dir.path().join("missing.txt"), &roots),
+            "Missing targets should not be considered safe"
+        );
+        assert!(
+            !is_safe_symlink_target(&inside, &[]),
+            "Nothing is safe without allowed roots"
         );
     }
 
+    #[test]
+    fn test_symlink_roots_default_to_current_dir() {
+        assert_eq!(symlink_roots(&[]), vec![std::env::current_dir().unwrap()]);
+        let roots = vec![PathBuf::from("/srv/models")];
+        assert_eq!(symlink_roots(&roots), roots);
+    }
+
     #[test]
     fn test_safe_open_file_comprehensive() -> Result<()> {
         // Create a temporary directory and file
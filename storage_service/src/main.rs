@@ -1,58 +1,145 @@
-use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web::{error, web, App, HttpResponse, HttpServer};
 use mongodb::{Client, Database};
-use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use log::{debug, error, info}; 
 
+// Request and response bodies shared with the CLI's DatabaseStorage client
+#[path = "../../src/storage/protocol.rs"]
+mod protocol;
+mod verify;
+
+use protocol::{
+    ClaimResponse, DeleteQuery, DeleteResponse, InsertedId, ListQuery, ManifestEntry,
+    StatusResponse, StoreRequest, MANIFEST_TYPES,
+};
+
+// Default limit on request bodies, overridable with MAX_BODY_BYTES
+const DEFAULT_MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
 #[derive(Clone)]
 struct AppState {
     db: Arc<Database>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ManifestEntry {
-    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
-    id: Option<mongodb::bson::oid::ObjectId>,
-    manifest_id: String,
-    manifest_type: String, // "model" or "dataset"
-    manifest: serde_json::Value,
-    created_at: String,
+// Liveness: the process is up and serving requests
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().json(StatusResponse {
+        status: "ok".to_string(),
+        error: None,
+    })
+}
+
+// Readiness: MongoDB answers a ping
+async fn ready(state: web::Data<AppState>) -> HttpResponse {
+    match state
+        .db
+        .run_command(mongodb::bson::doc! { "ping": 1 }, None)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().json(StatusResponse {
+            status: "ready".to_string(),
+            error: None,
+        }),
+        Err(e) => {
+            error!("Readiness check failed: {:?}", e);
+            HttpResponse::ServiceUnavailable().json(StatusResponse {
+                status: "unavailable".to_string(),
+                error: Some(e.to_string()),
+            })
+        }
+    }
+}
+
+// Check that the manifest has a claim and an ingredients array, so
+// malformed documents never reach the collection
+fn validate_manifest_shape(manifest: &serde_json::Value) -> Result<(), String> {
+    let manifest = manifest
+        .as_object()
+        .ok_or("manifest must be a JSON object")?;
+
+    match manifest.get("claim") {
+        Some(serde_json::Value::Object(_)) => {}
+        Some(_) => return Err("manifest.claim must be a JSON object".to_string()),
+        None => return Err("manifest.claim is missing".to_string()),
+    }
+
+    match manifest.get("ingredients") {
+        Some(serde_json::Value::Array(_)) => {}
+        Some(_) => return Err("manifest.ingredients must be an array".to_string()),
+        None => return Err("manifest.ingredients is missing".to_string()),
+    }
+
+    Ok(())
+}
+
+// Describe JSON payload errors instead of returning actix's bare status
+fn json_error_handler(err: error::JsonPayloadError, _req: &actix_web::HttpRequest) -> error::Error {
+    let response = match &err {
+        error::JsonPayloadError::Overflow { limit } | error::JsonPayloadError::OverflowKnownLength { limit, .. } => {
+            HttpResponse::PayloadTooLarge().body(format!("Request body exceeds the {} byte limit", limit))
+        }
+        error::JsonPayloadError::ContentType => {
+            HttpResponse::BadRequest().body("Content-Type must be application/json")
+        }
+        _ => HttpResponse::BadRequest().body(format!("Invalid JSON body: {}", err)),
+    };
+    error::InternalError::from_response(err, response).into()
 }
 
 // Store manifest
 async fn store_manifest(
     state: web::Data<AppState>,
-    manifest: web::Json<serde_json::Value>,
+    body: web::Json<serde_json::Value>,
     path: web::Path<String>,
 ) -> HttpResponse {
     let collection = state.db.collection::<ManifestEntry>("manifests");
 
-    debug!("Received manifest: {}", serde_json::to_string_pretty(&manifest).unwrap_or_default());
-    
-    let manifest_type = manifest.get("manifest")
-        .and_then(|m| m.get("manifest_type"))
-        .or_else(|| manifest.get("manifest_type"))
-        .and_then(|v| v.as_str())
-        .unwrap_or_else(|| {
-            debug!("Could not find manifest_type in expected locations");
-            "unknown"
-        })
-        .to_string();
-    
-    info!("Extracted manifest_type: {}", manifest_type);
+    debug!("Received manifest: {}", serde_json::to_string_pretty(&body).unwrap_or_default());
 
+    // The client derives the type from the claim assertions and ingredients,
+    // so it must be sent explicitly rather than guessed here
+    if body.get("manifest_type").is_none() {
+        debug!("Rejecting manifest without manifest_type");
+        return HttpResponse::BadRequest().body("manifest_type is required");
+    }
+    let request: StoreRequest = match serde_json::from_value(body.into_inner()) {
+        Ok(request) => request,
+        Err(e) => {
+            return HttpResponse::BadRequest().body(format!("Invalid store request: {}", e));
+        }
+    };
+    if !MANIFEST_TYPES.contains(&request.manifest_type.to_lowercase().as_str()) {
+        return HttpResponse::BadRequest()
+            .body(format!("Unknown manifest_type '{}'", request.manifest_type));
+    }
+
+    if let Err(reason) = validate_manifest_shape(&request.manifest) {
+        debug!("Rejecting malformed manifest: {}", reason);
+        return HttpResponse::BadRequest().body(format!("Invalid manifest: {}", reason));
+    }
+
+    info!("Storing manifest_type: {}", request.manifest_type);
+
+    let manifest = match serde_json::to_value(&request) {
+        Ok(manifest) => manifest,
+        Err(e) => return HttpResponse::InternalServerError().body(e.to_string()),
+    };
     let entry = ManifestEntry {
-        id: None,
         manifest_id: path.into_inner(),
-        manifest_type,
-        manifest: manifest.into_inner(),
+        manifest_type: request.manifest_type,
+        manifest,
         created_at: chrono::Utc::now().to_rfc3339(),
+        tags: request.tags,
     };
 
     match collection.insert_one(entry, None).await {
         Ok(result) => {
             info!("Successfully stored manifest with ID: {}", result.inserted_id);
-            HttpResponse::Created().json(result.inserted_id)
+            let oid = match result.inserted_id.as_object_id() {
+                Some(oid) => oid.to_hex(),
+                None => result.inserted_id.to_string(),
+            };
+            HttpResponse::Created().json(InsertedId { oid })
         }
         Err(e) => {
             error!("Failed to store manifest: {:?}", e);
@@ -62,10 +149,34 @@ async fn store_manifest(
 }
 
 // List manifests
-async fn list_manifests(state: web::Data<AppState>) -> HttpResponse {
+async fn list_manifests(
+    state: web::Data<AppState>,
+    query: web::Query<ListQuery>,
+) -> HttpResponse {
     let collection = state.db.collection::<ManifestEntry>("manifests");
-    
-    match collection.find(None, None).await {
+
+    let mut filter = mongodb::bson::Document::new();
+    if let Some(tag) = &query.tag {
+        let tags: Vec<&str> = tag.split(',').filter(|t| !t.is_empty()).collect();
+        filter.insert("tags", mongodb::bson::doc! { "$all": tags });
+    }
+
+    // created_at holds UTC RFC 3339 strings, which sort chronologically
+    let mut created_at = mongodb::bson::Document::new();
+    for (operator, bound) in [("$gte", &query.since), ("$lt", &query.until)] {
+        if let Some(bound) = bound {
+            if chrono::DateTime::parse_from_rfc3339(bound).is_err() {
+                return HttpResponse::BadRequest()
+                    .body(format!("Invalid date '{}': expected an RFC 3339 timestamp", bound));
+            }
+            created_at.insert(operator, bound.as_str());
+        }
+    }
+    if !created_at.is_empty() {
+        filter.insert("created_at", created_at);
+    }
+
+    match collection.find(Some(filter), None).await {
         Ok(cursor) => {
             match futures::stream::TryStreamExt::try_collect::<Vec<_>>(cursor).await {
                 Ok(manifests) => HttpResponse::Ok().json(manifests),
@@ -106,6 +217,114 @@ async fn get_manifest(
     }
 }
 
+// Get only the claim of a manifest, projected by MongoDB so that
+// ingredients and cross-references are never sent
+async fn get_claim(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let collection = state.db.collection::<mongodb::bson::Document>("manifests");
+    let manifest_id = path.into_inner();
+
+    let pipeline = vec![
+        mongodb::bson::doc! { "$match": { "manifest_id": &manifest_id } },
+        mongodb::bson::doc! { "$limit": 1 },
+        mongodb::bson::doc! { "$project": {
+            "_id": 0,
+            "claim": { "$ifNull": ["$manifest.manifest.claim_v2", "$manifest.manifest.claim"] },
+        } },
+    ];
+    let found = match collection.aggregate(pipeline, None).await {
+        Ok(cursor) => futures::stream::TryStreamExt::try_collect::<Vec<_>>(cursor).await,
+        Err(e) => Err(e),
+    };
+    let document = match found {
+        Ok(mut documents) if !documents.is_empty() => documents.remove(0),
+        Ok(_) => {
+            return HttpResponse::NotFound()
+                .body(format!("Manifest not found for ID: {}", manifest_id));
+        }
+        Err(e) => {
+            error!("Error fetching claim of manifest {}: {:?}", manifest_id, e);
+            return HttpResponse::InternalServerError()
+                .body(format!("Error fetching claim: {}", e));
+        }
+    };
+
+    match document.get("claim") {
+        Some(claim) if claim.as_document().is_some() => HttpResponse::Ok().json(ClaimResponse {
+            manifest_id,
+            claim: claim.clone().into_relaxed_extjson(),
+        }),
+        _ => HttpResponse::NotFound().body(format!("Manifest {} has no claim", manifest_id)),
+    }
+}
+
+// Verify a stored manifest's structure and the hashes of the manifests it
+// cross-references, returning a JSON report
+async fn verify_manifest(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let collection = state.db.collection::<ManifestEntry>("manifests");
+    let manifest_id = path.into_inner();
+
+    let entry = match collection
+        .find_one(mongodb::bson::doc! { "manifest_id": &manifest_id }, None)
+        .await
+    {
+        Ok(Some(entry)) => entry,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .body(format!("Manifest not found for ID: {}", manifest_id));
+        }
+        Err(e) => {
+            error!("Error fetching manifest {}: {:?}", manifest_id, e);
+            return HttpResponse::InternalServerError()
+                .body(format!("Error fetching manifest: {}", e));
+        }
+    };
+
+    // Stored documents wrap the manifest alongside its metadata
+    let manifest = entry.c2pa_manifest().unwrap_or(&serde_json::Value::Null);
+    let (structure, references) = verify::check_structure(&manifest_id, manifest);
+
+    let target_ids: Vec<&str> = references.iter().map(|r| r.manifest_url.as_str()).collect();
+    let targets: Vec<ManifestEntry> = if target_ids.is_empty() {
+        Vec::new()
+    } else {
+        let found = match collection
+            .find(mongodb::bson::doc! { "manifest_id": { "$in": target_ids } }, None)
+            .await
+        {
+            Ok(cursor) => futures::stream::TryStreamExt::try_collect::<Vec<_>>(cursor).await,
+            Err(e) => Err(e),
+        };
+        match found {
+            Ok(targets) => targets,
+            Err(e) => {
+                error!("Error fetching cross-referenced manifests: {:?}", e);
+                return HttpResponse::InternalServerError()
+                    .body(format!("Error fetching cross-referenced manifests: {}", e));
+            }
+        }
+    };
+
+    let cross_references = references
+        .iter()
+        .map(|reference| {
+            let target = targets
+                .iter()
+                .find(|t| t.manifest_id == reference.manifest_url)
+                .and_then(|t| t.c2pa_manifest());
+            verify::check_cross_reference(reference, target)
+        })
+        .collect();
+
+    let report = verify::VerificationReport::new(manifest_id, structure, cross_references);
+    info!("Verified manifest {}: {:?}", report.manifest_id, report.status);
+    HttpResponse::Ok().json(report)
+}
 
 // Delete manifest
 async fn delete_manifest(
@@ -124,6 +343,52 @@ async fn delete_manifest(
     }
 }
 
+// Delete all manifests of a type (?type=dataset) or those whose IDs are
+// given as a JSON array in the body
+async fn delete_manifests(
+    state: web::Data<AppState>,
+    query: web::Query<DeleteQuery>,
+    body: web::Bytes,
+) -> HttpResponse {
+    let collection = state.db.collection::<ManifestEntry>("manifests");
+
+    let filter = match (&query.manifest_type, body.is_empty()) {
+        (Some(manifest_type), true) => {
+            if !MANIFEST_TYPES.contains(&manifest_type.to_lowercase().as_str()) {
+                return HttpResponse::BadRequest()
+                    .body(format!("Unknown manifest type '{}'", manifest_type));
+            }
+            mongodb::bson::doc! {
+                "manifest_type": { "$regex": format!("^{}$", manifest_type), "$options": "i" }
+            }
+        }
+        (None, false) => match serde_json::from_slice::<Vec<String>>(&body) {
+            Ok(ids) => mongodb::bson::doc! { "manifest_id": { "$in": ids } },
+            Err(e) => {
+                return HttpResponse::BadRequest()
+                    .body(format!("Expected a JSON array of manifest IDs: {}", e));
+            }
+        },
+        _ => {
+            return HttpResponse::BadRequest()
+                .body("Specify either ?type= or a JSON array of manifest IDs");
+        }
+    };
+
+    match collection.delete_many(filter, None).await {
+        Ok(result) => {
+            info!("Deleted {} manifests", result.deleted_count);
+            HttpResponse::Ok().json(DeleteResponse {
+                deleted: result.deleted_count,
+            })
+        }
+        Err(e) => {
+            error!("Failed to delete manifests: {:?}", e);
+            HttpResponse::InternalServerError().body(e.to_string())
+        }
+    }
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     env_logger::init();
@@ -137,15 +402,31 @@ async fn main() -> std::io::Result<()> {
     let db = Arc::new(client.database("c2pa_manifests"));
     let state = web::Data::new(AppState { db });
 
+    let max_body_bytes = std::env::var("MAX_BODY_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_BODY_BYTES);
+
     println!("Starting server at http://localhost:8080");
     
     HttpServer::new(move || {
         App::new()
             .app_data(state.clone())
+            .app_data(
+                web::JsonConfig::default()
+                    .limit(max_body_bytes)
+                    .error_handler(json_error_handler),
+            )
+            .app_data(web::PayloadConfig::new(max_body_bytes))
+            .route("/health", web::get().to(health))
+            .route("/ready", web::get().to(ready))
             .route("/manifests", web::get().to(list_manifests))
+            .route("/manifests", web::delete().to(delete_manifests))
             .route("/manifests/{id}", web::post().to(store_manifest))
             .route("/manifests/{id}", web::get().to(get_manifest))
             .route("/manifests/{id}", web::delete().to(delete_manifest))
+            .route("/manifests/{id}/claim", web::get().to(get_claim))
+            .route("/manifests/{id}/verify", web::get().to(verify_manifest))
     })
     .bind("0.0.0.0:8080")?
     .run()
@@ -0,0 +1,241 @@
+// Server-side manifest verification: structural checks and cross-reference
+// hash checks against the manifests already stored in MongoDB. Hashes follow
+// the CLI, SHA-256 over the canonical JSON of the referenced manifest (compact,
+// object keys sorted). Like the CLI, a hash over the manifest's plain JSON, as
+// recorded by releases before canonical JSON, is accepted with a warning; the
+// stored documents keep the CLI's field order (serde_json's preserve_order).
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Verified,
+    Warning,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerificationCheck {
+    pub subject: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl VerificationCheck {
+    fn verified(subject: &str, message: impl Into<String>) -> Self {
+        Self {
+            subject: subject.to_string(),
+            status: CheckStatus::Verified,
+            message: message.into(),
+        }
+    }
+
+    fn warning(subject: &str, message: impl Into<String>) -> Self {
+        Self {
+            subject: subject.to_string(),
+            status: CheckStatus::Warning,
+            message: message.into(),
+        }
+    }
+
+    fn failed(subject: &str, message: impl Into<String>) -> Self {
+        Self {
+            subject: subject.to_string(),
+            status: CheckStatus::Failed,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerificationReport {
+    pub manifest_id: String,
+    pub status: CheckStatus,
+    pub structure: Vec<VerificationCheck>,
+    pub cross_references: Vec<VerificationCheck>,
+}
+
+// A cross-reference read from a manifest, before its target is looked up
+pub struct CrossReference {
+    pub manifest_url: String,
+    pub manifest_hash: String,
+}
+
+// Check the fields every manifest needs and collect its well-formed
+// cross-references for hash checking
+pub fn check_structure(
+    manifest_id: &str,
+    manifest: &Value,
+) -> (Vec<VerificationCheck>, Vec<CrossReference>) {
+    let mut checks = Vec::new();
+    let mut cross_references = Vec::new();
+
+    let Some(manifest) = manifest.as_object() else {
+        checks.push(VerificationCheck::failed("manifest", "manifest is not a JSON object"));
+        return (checks, cross_references);
+    };
+
+    match manifest.get("instance_id").and_then(Value::as_str) {
+        Some(instance_id) if instance_id == manifest_id => {
+            checks.push(VerificationCheck::verified("instance_id", "matches the manifest ID"))
+        }
+        Some(instance_id) => checks.push(VerificationCheck::failed(
+            "instance_id",
+            format!("'{}' does not match the manifest ID", instance_id),
+        )),
+        None => checks.push(VerificationCheck::failed("instance_id", "missing")),
+    }
+
+    for (field, expected) in [
+        ("title", "a string"),
+        ("claim_generator", "a string"),
+        ("claim", "an object"),
+        ("ingredients", "an array"),
+        ("cross_references", "an array"),
+    ] {
+        let ok = match manifest.get(field) {
+            Some(Value::String(_)) => expected == "a string",
+            Some(Value::Object(_)) => expected == "an object",
+            Some(Value::Array(_)) => expected == "an array",
+            _ => false,
+        };
+        if ok {
+            checks.push(VerificationCheck::verified(field, format!("is {}", expected)));
+        } else {
+            checks.push(VerificationCheck::failed(field, format!("must be {}", expected)));
+        }
+    }
+
+    let references = manifest
+        .get("cross_references")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for (index, reference) in references.iter().enumerate() {
+        let url = reference.get("manifest_url").and_then(Value::as_str);
+        let hash = reference.get("manifest_hash").and_then(Value::as_str);
+        match (url, hash) {
+            (Some(url), Some(hash)) => cross_references.push(CrossReference {
+                manifest_url: url.to_string(),
+                manifest_hash: hash.to_string(),
+            }),
+            _ => checks.push(VerificationCheck::failed(
+                &format!("cross_references[{}]", index),
+                "manifest_url and manifest_hash must be strings",
+            )),
+        }
+    }
+
+    (checks, cross_references)
+}
+
+// Compare a cross-reference's recorded hash with its stored target
+pub fn check_cross_reference(
+    reference: &CrossReference,
+    target: Option<&Value>,
+) -> VerificationCheck {
+    let Some(target) = target else {
+        return VerificationCheck::failed(&reference.manifest_url, "referenced manifest not found");
+    };
+
+    let calculated = hex::encode(Sha256::digest(canonical_json(target).as_bytes()));
+    let legacy = hex::encode(Sha256::digest(target.to_string().as_bytes()));
+    if calculated == reference.manifest_hash {
+        VerificationCheck::verified(&reference.manifest_url, "hash matches")
+    } else if legacy == reference.manifest_hash {
+        VerificationCheck::warning(
+            &reference.manifest_url,
+            "hash matches the legacy non-canonical JSON of the linked manifest; link it again to record a canonical hash",
+        )
+    } else {
+        VerificationCheck::failed(
+            &reference.manifest_url,
+            format!(
+                "hash mismatch: stored={}, calculated={}",
+                reference.manifest_hash, calculated
+            ),
+        )
+    }
+}
+
+impl VerificationReport {
+    pub fn new(
+        manifest_id: String,
+        structure: Vec<VerificationCheck>,
+        cross_references: Vec<VerificationCheck>,
+    ) -> Self {
+        let failed = structure
+            .iter()
+            .chain(&cross_references)
+            .any(|check| check.status == CheckStatus::Failed);
+        Self {
+            manifest_id,
+            status: if failed { CheckStatus::Failed } else { CheckStatus::Verified },
+            structure,
+            cross_references,
+        }
+    }
+}
+
+// Compact JSON with object keys sorted, matching the CLI's canonical form
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            let entries: Vec<String> = entries
+                .into_iter()
+                .map(|(key, item)| format!("{}:{}", Value::String(key.clone()), canonical_json(item)))
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        }
+        scalar => scalar.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn reference_to(hash: String) -> CrossReference {
+        CrossReference {
+            manifest_url: "urn:c2pa:target".to_string(),
+            manifest_hash: hash,
+        }
+    }
+
+    #[test]
+    fn test_cross_reference_hash_schemes() {
+        // Field order as the CLI serializes manifests, not sorted
+        let target = json!({"title": "Target", "instance_id": "urn:c2pa:target", "claim": {}});
+        let sha256 = |text: &str| hex::encode(Sha256::digest(text.as_bytes()));
+
+        let canonical = sha256(&canonical_json(&target));
+        let check = check_cross_reference(&reference_to(canonical.clone()), Some(&target));
+        assert_eq!(check.status, CheckStatus::Verified);
+
+        // Releases before canonical JSON hashed the plain serialization
+        let legacy = sha256(&serde_json::to_string(&target).unwrap());
+        assert_ne!(legacy, canonical);
+        let check = check_cross_reference(&reference_to(legacy), Some(&target));
+        assert_eq!(check.status, CheckStatus::Warning);
+        let report = VerificationReport::new("urn:c2pa:source".to_string(), vec![], vec![check]);
+        assert_eq!(report.status, CheckStatus::Verified);
+
+        // Hashes are compared exactly, as lowercase hex
+        for hash in [canonical.to_uppercase(), sha256("other")] {
+            let check = check_cross_reference(&reference_to(hash), Some(&target));
+            assert_eq!(check.status, CheckStatus::Failed);
+        }
+        let check = check_cross_reference(&reference_to(canonical), None);
+        assert_eq!(check.status, CheckStatus::Failed);
+    }
+}